@@ -0,0 +1,41 @@
+// Bytes arbitrários como corpo de cada rota POST do router completo, com
+// todos os middlewares: cobre a desserialização de todos os payloads, não só
+// os do registro. As rotas são as do registro, então um workload novo entra
+// sozinho, mais as POST que não são workloads.
+#![no_main]
+
+use axum::{body::Body, http::Request};
+use demo_lambda_axum::registry::WORKLOADS;
+use libfuzzer_sys::fuzz_target;
+use once_cell::sync::Lazy;
+use tower::ServiceExt;
+
+// POST fora do registro; /external (rede de verdade) e /samples/flush (grava
+// arquivos) ficam de fora
+const OTHER_ROUTES: &[&str] = &["/echo", "/mixed", "/sweep", "/paired", "/power-tuning", "/poll", "/validate", "/compare"];
+
+static ROUTES: Lazy<Vec<String>> = Lazy::new(|| {
+    WORKLOADS
+        .iter()
+        .map(|workload| format!("/{}", workload.name))
+        .chain(OTHER_ROUTES.iter().map(|route| route.to_string()))
+        .collect()
+});
+
+static RUNTIME: Lazy<tokio::runtime::Runtime> =
+    Lazy::new(|| tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap());
+
+thread_local! {
+    // O Router não é Sync; o libFuzzer roda numa thread só
+    static ROUTER: axum::Router = demo_lambda_axum::create_router();
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Some((selector, body)) = data.split_first() else { return };
+    let request = Request::post(ROUTES[*selector as usize % ROUTES.len()].as_str())
+        .header("Content-Type", "application/json")
+        .body(Body::from(body.to_vec()))
+        .unwrap();
+    let router = ROUTER.with(Clone::clone);
+    RUNTIME.block_on(router.oneshot(request)).unwrap();
+});
@@ -0,0 +1,18 @@
+// Bytes arbitrários como payload de cada workload do registro: o primeiro
+// byte escolhe o workload, o resto tem que ser JSON (senão nem chega ao
+// handler, como na rota HTTP).
+#![no_main]
+
+use demo_lambda_axum::registry::WORKLOADS;
+use libfuzzer_sys::fuzz_target;
+use once_cell::sync::Lazy;
+
+static RUNTIME: Lazy<tokio::runtime::Runtime> =
+    Lazy::new(|| tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap());
+
+fuzz_target!(|data: &[u8]| {
+    let Some((selector, body)) = data.split_first() else { return };
+    let workload = &WORKLOADS[*selector as usize % WORKLOADS.len()];
+    let Ok(payload) = serde_json::from_slice::<serde_json::Value>(body) else { return };
+    RUNTIME.block_on((workload.run)(payload));
+});
@@ -0,0 +1,280 @@
+// ======================
+// MIDDLEWARE: LIMITE DE CONCORRÊNCIA ADAPTATIVO
+// ======================
+//
+// Experimento no estilo do concurrency-limits da Netflix: em vez de um limite
+// fixo por rota (concurrency.rs), um limite global de requests em andamento
+// que se ajusta pela latência observada. `BFF_ADAPTIVE_LIMIT` escolhe o
+// algoritmo:
+//
+// - `aimd`: cresce 1 a cada resposta rápida enquanto o limite está sendo
+//   usado (em andamento >= metade do limite) e cai para 90% a cada resposta
+//   acima de `BFF_ADAPTIVE_LATENCY_MS` ou com status 5xx
+// - `vegas`: estima a fila como `limite × (1 - rtt_min / rtt)`, com o menor
+//   rtt já visto como a latência sem carga; cresce log10(limite) se a fila é
+//   menor que 3·log10(limite) (e o limite está sendo usado, como no aimd) e
+//   cai o mesmo tanto se passa de 6·log10(limite)
+//
+// O limite começa em `BFF_ADAPTIVE_INITIAL` e fica entre `BFF_ADAPTIVE_MIN` e
+// `BFF_ADAPTIVE_MAX`. Diferente do limite fixo, quem chega com o limite cheio
+// não espera: leva 503 na hora, que é o que protege o serviço sob sobrecarga.
+// Cada mudança da parte inteira do limite vira uma linha JSON no log
+// (`"event": "adaptive_limit"`) e o /stats mostra o estado atual.
+//
+// Sob sobrecarga, cortar tudo por igual não é o que um serviço de verdade faz:
+// `BFF_SHED_ROUTES=/image=heavy,/burn=heavy,/echo=light` põe rotas em classes
+// e `BFF_SHED_CLASSES=heavy=0.5,light=1` diz que fração do limite cada classe
+// pode ocupar. Com o limite em 20, uma request `heavy` já leva 503 com 10 em
+// andamento, enquanto `light` entra até 20: as pesadas são cortadas primeiro.
+// Rotas sem classe ficam em `default` e classes sem fração usam o limite
+// inteiro. O /stats conta admitidas e cortadas por classe.
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    body::BoxBody,
+    extract::{Json, MatchedPath},
+    http::{Request, Response, StatusCode},
+    response::IntoResponse,
+};
+use once_cell::sync::Lazy;
+use tower::{Layer, Service};
+
+use crate::config::CONFIG;
+
+struct State {
+    limit: f64,
+    min_rtt: Option<Duration>,
+}
+
+static STATE: Lazy<Mutex<State>> = Lazy::new(|| {
+    Mutex::new(State { limit: CONFIG.adaptive_initial.clamp(CONFIG.adaptive_min, CONFIG.adaptive_max) as f64, min_rtt: None })
+});
+
+static IN_FLIGHT: AtomicU64 = AtomicU64::new(0);
+static ADMITTED: AtomicU64 = AtomicU64::new(0);
+static REJECTED: AtomicU64 = AtomicU64::new(0);
+static INCREASES: AtomicU64 = AtomicU64::new(0);
+static DECREASES: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Default)]
+struct Class {
+    admitted: u64,
+    shed: u64,
+}
+
+static CLASSES: Lazy<Mutex<HashMap<String, Class>>> = Lazy::new(Default::default);
+
+fn enabled() -> bool {
+    matches!(CONFIG.adaptive_limit.as_str(), "aimd" | "vegas")
+}
+
+fn class(route: &str) -> &'static str {
+    CONFIG
+        .shed_routes
+        .iter()
+        .find(|(path, _)| path == route)
+        .map_or("default", |(_, class)| class.as_str())
+}
+
+/// Fração do limite que a classe pode ocupar.
+fn share(class: &str) -> f64 {
+    CONFIG.shed_classes.iter().find(|(name, _)| name == class).map_or(1.0, |(_, share)| *share)
+}
+
+fn count(class: &str, shed: bool) {
+    let mut classes = CLASSES.lock().unwrap();
+    let counters = match classes.get_mut(class) {
+        Some(counters) => counters,
+        None => classes.entry(class.to_string()).or_default(),
+    };
+    if shed {
+        counters.shed += 1;
+    } else {
+        counters.admitted += 1;
+    }
+}
+
+pub fn snapshot() -> serde_json::Value {
+    let classes: serde_json::Map<String, serde_json::Value> = CLASSES
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(name, counters)| {
+            let value = serde_json::json!({
+                "share": share(name),
+                "admitted": counters.admitted,
+                "shed": counters.shed,
+            });
+            (name.clone(), value)
+        })
+        .collect();
+    let state = STATE.lock().unwrap();
+    serde_json::json!({
+        "algorithm": if enabled() { Some(CONFIG.adaptive_limit.as_str()) } else { None },
+        "limit": state.limit as u64,
+        "in_flight": IN_FLIGHT.load(Ordering::Relaxed),
+        "admitted": ADMITTED.load(Ordering::Relaxed),
+        "rejected": REJECTED.load(Ordering::Relaxed),
+        "increases": INCREASES.load(Ordering::Relaxed),
+        "decreases": DECREASES.load(Ordering::Relaxed),
+        "min_rtt_ms": state.min_rtt.map(|rtt| rtt.as_secs_f64() * 1000.0),
+        "classes": classes,
+    })
+}
+
+/// Novo limite (ainda sem os limites mínimo e máximo) depois de uma amostra.
+fn next_limit(state: &mut State, rtt: Duration, failed: bool, in_flight: u64) -> (f64, &'static str) {
+    let limit = state.limit;
+    if CONFIG.adaptive_limit == "aimd" {
+        if failed || rtt > CONFIG.adaptive_latency {
+            return (limit * 0.9, if failed { "error" } else { "slow" });
+        }
+        if in_flight as f64 * 2.0 >= limit {
+            return (limit + 1.0, "fast");
+        }
+        return (limit, "idle");
+    }
+
+    let min_rtt = state.min_rtt.map_or(rtt, |min| min.min(rtt));
+    state.min_rtt = Some(min_rtt);
+    if failed {
+        return (limit * 0.9, "error");
+    }
+    let queue = limit * (1.0 - min_rtt.as_secs_f64() / rtt.as_secs_f64().max(f64::EPSILON));
+    let step = limit.log10().max(1.0);
+    if queue < 3.0 * step {
+        if in_flight as f64 * 2.0 >= limit {
+            (limit + step, "queue_short")
+        } else {
+            (limit, "idle")
+        }
+    } else if queue > 6.0 * step {
+        (limit - step, "queue_long")
+    } else {
+        (limit, "steady")
+    }
+}
+
+fn sample(rtt: Duration, failed: bool, in_flight: u64) {
+    let mut state = STATE.lock().unwrap();
+    let previous = state.limit;
+    let (limit, reason) = next_limit(&mut state, rtt, failed, in_flight);
+    state.limit = limit.clamp(CONFIG.adaptive_min as f64, CONFIG.adaptive_max as f64);
+    if state.limit as u64 == previous as u64 {
+        return;
+    }
+    if state.limit > previous {
+        INCREASES.fetch_add(1, Ordering::Relaxed);
+    } else {
+        DECREASES.fetch_add(1, Ordering::Relaxed);
+    }
+    let mut event = serde_json::json!({
+        "event": "adaptive_limit",
+        "algorithm": CONFIG.adaptive_limit,
+        "previous": previous as u64,
+        "limit": state.limit as u64,
+        "reason": reason,
+        "rtt_ms": rtt.as_secs_f64() * 1000.0,
+        "min_rtt_ms": state.min_rtt.map(|rtt| rtt.as_secs_f64() * 1000.0),
+        "in_flight": in_flight,
+    });
+    drop(state);
+    crate::run::annotate(&mut event);
+    println!("{}", event);
+}
+
+/// Devolve a vaga mesmo se o cliente desconectar no meio da request.
+struct Slot;
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        IN_FLIGHT.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[derive(Clone)]
+pub struct AdaptiveLayer;
+
+#[derive(Clone)]
+pub struct AdaptiveService<S> {
+    inner: S,
+}
+
+impl<S> Layer<S> for AdaptiveLayer {
+    type Service = AdaptiveService<S>;
+    fn layer(&self, inner: S) -> Self::Service {
+        AdaptiveService { inner }
+    }
+}
+
+impl<S, ReqBody> Service<Request<ReqBody>> for AdaptiveService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+{
+    type Response = Response<BoxBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        let mut service = self.inner.clone();
+        if !enabled() {
+            return Box::pin(service.call(req));
+        }
+
+        let route = match req.extensions().get::<MatchedPath>() {
+            Some(path) => path.as_str(),
+            None => req.uri().path(),
+        };
+        let class = class(route);
+        let limit = STATE.lock().unwrap().limit as u64;
+        // Pelo menos uma vaga, para a classe não ficar cortada de vez com o
+        // limite baixo
+        let cap = ((limit as f64 * share(class)) as u64).max(1);
+        let in_flight = IN_FLIGHT.fetch_add(1, Ordering::Relaxed) + 1;
+        let slot = Slot;
+        if in_flight > cap {
+            drop(slot);
+            REJECTED.fetch_add(1, Ordering::Relaxed);
+            count(class, true);
+            let error = if cap == limit {
+                format!("Adaptive concurrency limit reached ({})", limit)
+            } else {
+                format!("Adaptive concurrency limit reached for {} ({} of {})", class, cap, limit)
+            };
+            return Box::pin(async move {
+                Ok((
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    Json(serde_json::json!({ "error": error }))
+                )
+                .into_response())
+            });
+        }
+        ADMITTED.fetch_add(1, Ordering::Relaxed);
+        count(class, false);
+
+        Box::pin(async move {
+            let start = Instant::now();
+            let response = service.call(req).await?;
+            sample(start.elapsed(), response.status().is_server_error(), IN_FLIGHT.load(Ordering::Relaxed));
+            drop(slot);
+            Ok(response)
+        })
+    }
+}
@@ -0,0 +1,149 @@
+// ======================
+// SERIALIZAÇÃO AVRO
+// ======================
+//
+// Gera `records` eventos de pedido (com seed) e os serializa com o esquema
+// embutido em order_event.avsc, um datum por evento, sem o cabeçalho de
+// arquivo: é o que vai no valor de uma mensagem Kafka (o registro de esquemas
+// fica de fora). Depois lê tudo de volta para as structs. Cada sentido é
+// medido à parte, em eventos e em MB por segundo; `round_trip` confere que os
+// eventos lidos são os gerados.
+
+use std::time::{Duration, Instant};
+
+use apache_avro::{from_avro_datum, from_value, to_avro_datum, to_value, Schema};
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use crate::rng::{self, SplitMix64};
+
+const MAX_RECORDS: usize = 200_000;
+
+static SCHEMA: Lazy<Schema> =
+    Lazy::new(|| Schema::parse_str(include_str!("order_event.avsc")).expect("order_event.avsc inválido"));
+
+#[derive(Deserialize)]
+pub struct AvroPayload {
+    records: Option<usize>,
+    seed: Option<u64>,
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+enum EventKind {
+    Created,
+    Paid,
+    Shipped,
+    Cancelled,
+}
+
+#[derive(PartialEq, Serialize, Deserialize)]
+struct OrderEvent {
+    id: i64,
+    order_id: i64,
+    customer_id: i64,
+    kind: EventKind,
+    amount: f64,
+    currency: String,
+    occurred_at: i64,
+    tags: Vec<String>,
+    coupon: Option<String>,
+}
+
+const KINDS: [EventKind; 4] = [EventKind::Created, EventKind::Paid, EventKind::Shipped, EventKind::Cancelled];
+const CURRENCIES: &[&str] = &["EUR", "USD", "BRL", "GBP"];
+const TAGS: &[&str] = &["gift", "express", "fragile", "bulk", "promo"];
+
+fn generate(count: usize, seed: u64) -> Vec<OrderEvent> {
+    let mut rng = SplitMix64::new(seed);
+    // 2024-01-01T00:00:00Z, mais até um ano
+    let epoch_ms = 1_704_067_200_000;
+    (0..count as i64)
+        .map(|id| OrderEvent {
+            id,
+            order_id: rng.next_below(1_000_000) as i64,
+            customer_id: rng.next_below(10_000) as i64,
+            kind: KINDS[rng.next_below(KINDS.len() as u64) as usize],
+            amount: (rng.next_below(100_000) + 100) as f64 / 100.0,
+            currency: CURRENCIES[rng.next_below(CURRENCIES.len() as u64) as usize].to_string(),
+            occurred_at: epoch_ms + rng.next_below(365 * 86_400_000) as i64,
+            tags: (0..rng.next_below(3)).map(|_| TAGS[rng.next_below(TAGS.len() as u64) as usize].to_string()).collect(),
+            coupon: (rng.next_below(4) == 0).then(|| format!("SAVE{}", rng.next_below(50) + 5)),
+        })
+        .collect()
+}
+
+fn bad_request(error: String) -> Response<BoxBody> {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({ "error": error }))
+    )
+    .into_response()
+}
+
+fn encode(events: &[OrderEvent]) -> Result<Vec<Vec<u8>>, String> {
+    let mut data = Vec::with_capacity(events.len());
+    for event in events {
+        let value = to_value(event).map_err(|e| e.to_string())?;
+        data.push(to_avro_datum(&SCHEMA, value).map_err(|e| e.to_string())?);
+    }
+    Ok(data)
+}
+
+fn decode(data: &[Vec<u8>]) -> Result<Vec<OrderEvent>, String> {
+    let mut events = Vec::with_capacity(data.len());
+    for datum in data {
+        let value = from_avro_datum(&SCHEMA, &mut datum.as_slice(), None).map_err(|e| e.to_string())?;
+        events.push(from_value(&value).map_err(|e| e.to_string())?);
+    }
+    Ok(events)
+}
+
+fn rates(records: usize, bytes: usize, duration: Duration) -> serde_json::Value {
+    serde_json::json!({
+        "ms": duration.as_secs_f64() * 1000.0,
+        "records_per_second": records as f64 / duration.as_secs_f64(),
+        "mb_per_second": bytes as f64 / 1e6 / duration.as_secs_f64(),
+    })
+}
+
+pub async fn avro(Json(payload): Json<AvroPayload>) -> Response<BoxBody> {
+    let records = payload.records.unwrap_or(1_000);
+    if records == 0 || records > MAX_RECORDS {
+        return bad_request(format!("records must be between 1 and {}", MAX_RECORDS));
+    }
+    let events = generate(records, rng::seed(payload.seed));
+
+    let start = Instant::now();
+    let data = match encode(&events) {
+        Ok(data) => data,
+        Err(error) => return bad_request(format!("Cannot encode Avro: {}", error)),
+    };
+    let encode_duration = start.elapsed();
+    let start = Instant::now();
+    let decoded = match decode(&data) {
+        Ok(decoded) => decoded,
+        Err(error) => return bad_request(format!("Cannot decode Avro: {}", error)),
+    };
+    let decode_duration = start.elapsed();
+    let bytes: usize = data.iter().map(Vec::len).sum();
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "records": records,
+            "bytes": bytes,
+            "bytes_per_record": bytes as f64 / records as f64,
+            "round_trip": decoded == events,
+            "encode": rates(records, bytes, encode_duration),
+            "decode": rates(records, bytes, decode_duration),
+        }))
+    )
+    .into_response()
+}
@@ -0,0 +1,138 @@
+// ======================
+// AWS SDK (feature "aws")
+// ======================
+//
+// Configuração compartilhada do SDK. Carregá-la resolve região e monta a
+// cadeia de credenciais (env, perfil, IMDS/container...), então é carregada
+// uma única vez por processo e reaproveitada pelos workloads que usam AWS.
+//
+// A cadeia de credenciais padrão vai embrulhada em `ObservedCredentials`. O
+// SDK guarda as credenciais em cache e só chama o provider quando precisa
+// resolver ou renovar, então cada chamada observada é uma resolução de
+// verdade: ela é logada e contada, para atribuir picos de latência a
+// refreshes de credencial.
+
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Instant, SystemTime},
+};
+
+use aws_config::{default_provider::credentials::DefaultCredentialsChain, BehaviorVersion, SdkConfig};
+use aws_credential_types::provider::{future, ProvideCredentials};
+use once_cell::sync::Lazy;
+use tokio::sync::OnceCell;
+
+static SHARED_CONFIG: OnceCell<SdkConfig> = OnceCell::const_new();
+
+/// Carrega uma configuração nova, sem passar pelo cache do processo.
+pub async fn load_config() -> SdkConfig {
+    let chain = DefaultCredentialsChain::builder().build().await;
+    aws_config::defaults(BehaviorVersion::latest())
+        .credentials_provider(ObservedCredentials { inner: chain })
+        .load()
+        .await
+}
+
+pub async fn shared_config() -> &'static SdkConfig {
+    SHARED_CONFIG.get_or_init(load_config).await
+}
+
+/// Mensagem com a cadeia de causas inteira (o Display dos erros do SDK é genérico).
+pub fn describe<E: std::error::Error>(error: E) -> String {
+    aws_sdk_s3::error::DisplayErrorContext(error).to_string()
+}
+
+// ------------
+// observação das credenciais
+// ------------
+static RESOLUTIONS: AtomicU64 = AtomicU64::new(0);
+static FAILURES: AtomicU64 = AtomicU64::new(0);
+static TOTAL_US: AtomicU64 = AtomicU64::new(0);
+static MAX_US: AtomicU64 = AtomicU64::new(0);
+
+struct LastResolution {
+    at: Instant,
+    duration_us: u64,
+    expiry: Option<SystemTime>,
+    error: Option<String>,
+}
+
+static LAST: Lazy<Mutex<Option<LastResolution>>> = Lazy::new(|| Mutex::new(None));
+
+pub fn snapshot() -> serde_json::Value {
+    let resolutions = RESOLUTIONS.load(Ordering::Relaxed);
+    let last = LAST.lock().unwrap();
+    serde_json::json!({
+        "resolutions": resolutions,
+        "failures": FAILURES.load(Ordering::Relaxed),
+        "mean_ms": if resolutions == 0 { 0.0 } else { TOTAL_US.load(Ordering::Relaxed) as f64 / resolutions as f64 / 1000.0 },
+        "max_ms": MAX_US.load(Ordering::Relaxed) as f64 / 1000.0,
+        "last": last.as_ref().map(|last| serde_json::json!({
+            "ago_ms": last.at.elapsed().as_secs_f64() * 1000.0,
+            "duration_ms": last.duration_us as f64 / 1000.0,
+            "expires_in_secs": last.expiry.map(seconds_until),
+            "error": last.error,
+        })),
+    })
+}
+
+fn seconds_until(time: SystemTime) -> i64 {
+    match time.duration_since(SystemTime::now()) {
+        Ok(remaining) => remaining.as_secs() as i64,
+        Err(past) => -(past.duration().as_secs() as i64),
+    }
+}
+
+#[derive(Debug)]
+struct ObservedCredentials {
+    inner: DefaultCredentialsChain,
+}
+
+impl ProvideCredentials for ObservedCredentials {
+    fn provide_credentials<'a>(&'a self) -> future::ProvideCredentials<'a>
+    where
+        Self: 'a,
+    {
+        future::ProvideCredentials::new(async move {
+            let start = Instant::now();
+            let result = self.inner.provide_credentials().await;
+            let duration_us = start.elapsed().as_micros() as u64;
+
+            let count = RESOLUTIONS.fetch_add(1, Ordering::Relaxed) + 1;
+            TOTAL_US.fetch_add(duration_us, Ordering::Relaxed);
+            MAX_US.fetch_max(duration_us, Ordering::Relaxed);
+
+            let (expiry, error) = match &result {
+                Ok(credentials) => (credentials.expiry(), None),
+                Err(error) => {
+                    FAILURES.fetch_add(1, Ordering::Relaxed);
+                    (None, Some(describe(error)))
+                }
+            };
+            match &error {
+                None => eprintln!(
+                    "aws credentials resolved (#{}) in {:.3} ms, expires in {}",
+                    count,
+                    duration_us as f64 / 1000.0,
+                    expiry.map_or("never".to_string(), |e| format!("{} s", seconds_until(e))),
+                ),
+                Some(error) => eprintln!(
+                    "aws credentials resolution (#{}) failed after {:.3} ms: {}",
+                    count,
+                    duration_us as f64 / 1000.0,
+                    error
+                ),
+            }
+            *LAST.lock().unwrap() = Some(LastResolution { at: Instant::now(), duration_us, expiry, error });
+
+            result
+        })
+    }
+
+    fn fallback_on_interrupt(&self) -> Option<aws_credential_types::Credentials> {
+        self.inner.fallback_on_interrupt()
+    }
+}
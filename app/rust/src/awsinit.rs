@@ -0,0 +1,131 @@
+// ======================
+// COLD INIT DO AWS SDK (feature "aws")
+// ======================
+//
+// Mede, separadamente, o custo de carregar a configuração do SDK, de construir
+// cada client (S3, DynamoDB, SSM) e, opcionalmente, da primeira chamada de
+// cada um, que é quando credenciais, endpoint e conexão TLS são resolvidos de
+// fato. Sem `shared_config`, a configuração é carregada do zero a cada request,
+// como num cold start. Os clients são descartados no fim, a menos que
+// `keep: true`, caso em que ficam guardados e a próxima request os reutiliza.
+
+use std::{sync::Mutex, time::Instant};
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+
+use crate::aws;
+
+const CLIENTS: &[&str] = &["s3", "dynamodb", "ssm"];
+
+#[derive(Deserialize)]
+pub struct AwsInitPayload {
+    clients: Option<Vec<String>>,
+    first_call: Option<bool>,
+    shared_config: Option<bool>,
+    keep: Option<bool>,
+}
+
+enum Client {
+    S3(aws_sdk_s3::Client),
+    DynamoDb(aws_sdk_dynamodb::Client),
+    Ssm(aws_sdk_ssm::Client),
+}
+
+impl Client {
+    fn build(name: &str, config: &aws_config::SdkConfig) -> Client {
+        match name {
+            "s3" => Client::S3(aws_sdk_s3::Client::new(config)),
+            "dynamodb" => Client::DynamoDb(aws_sdk_dynamodb::Client::new(config)),
+            _ => Client::Ssm(aws_sdk_ssm::Client::new(config)),
+        }
+    }
+
+    // A chamada mais barata de cada serviço; o resultado em si não importa
+    async fn first_call(&self) -> Result<(), String> {
+        match self {
+            Client::S3(client) => client.list_buckets().send().await.map(|_| ()).map_err(aws::describe),
+            Client::DynamoDb(client) => client.list_tables().limit(1).send().await.map(|_| ()).map_err(aws::describe),
+            Client::Ssm(client) => client.describe_parameters().max_results(1).send().await.map(|_| ()).map_err(aws::describe),
+        }
+    }
+}
+
+static KEPT: Lazy<Mutex<Vec<(String, Client)>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+pub async fn awsinit(Json(payload): Json<AwsInitPayload>) -> Response<BoxBody> {
+    let names = payload
+        .clients
+        .unwrap_or_else(|| CLIENTS.iter().map(|c| c.to_string()).collect());
+    if let Some(unknown) = names.iter().find(|name| !CLIENTS.contains(&name.as_str())) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": format!("Unknown client: {}", unknown) }))
+        )
+        .into_response();
+    }
+
+    let start = Instant::now();
+    let shared = payload.shared_config.unwrap_or(false);
+    let config_start = Instant::now();
+    let owned_config;
+    let config = if shared {
+        aws::shared_config().await
+    } else {
+        owned_config = aws::load_config().await;
+        &owned_config
+    };
+    let config_ms = config_start.elapsed().as_secs_f64() * 1000.0;
+
+    let mut kept = std::mem::take(&mut *KEPT.lock().unwrap());
+    let mut results = serde_json::Map::new();
+    let mut clients = Vec::with_capacity(names.len());
+    for name in names {
+        let build_start = Instant::now();
+        let reused = kept.iter().position(|(k, _)| *k == name).map(|i| kept.swap_remove(i).1);
+        let was_reused = reused.is_some();
+        let client = reused.unwrap_or_else(|| Client::build(&name, config));
+        let build_ms = build_start.elapsed().as_secs_f64() * 1000.0;
+        results.insert(
+            name.clone(),
+            serde_json::json!({ "build_ms": build_ms, "reused": was_reused }),
+        );
+        clients.push((name, client));
+    }
+
+    if payload.first_call.unwrap_or(false) {
+        for (name, client) in &clients {
+            let call_start = Instant::now();
+            let outcome = client.first_call().await;
+            let entry = &mut results[name.as_str()];
+            entry["first_call_ms"] = serde_json::json!(call_start.elapsed().as_secs_f64() * 1000.0);
+            entry["first_call_ok"] = serde_json::json!(outcome.is_ok());
+            if let Err(error) = outcome {
+                entry["first_call_error"] = serde_json::json!(error);
+            }
+        }
+    }
+
+    if payload.keep.unwrap_or(false) {
+        clients.extend(kept);
+        KEPT.lock().unwrap().extend(clients);
+    }
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "region": config.region().map(|r| r.to_string()),
+            "shared_config": shared,
+            "config_ms": config_ms,
+            "clients": results,
+            "duration_ms": start.elapsed().as_secs_f64() * 1000.0,
+        }))
+    )
+    .into_response()
+}
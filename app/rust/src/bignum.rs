@@ -0,0 +1,129 @@
+// ======================
+// INTEIROS GRANDES
+// ======================
+//
+// fibonacci(n) ou n! com precisão arbitrária (num-bigint). O /math trabalha
+// com i64 e não aloca nada; aqui cada passo do laço produz um número maior que
+// o anterior, então o custo é dominado por realocar e copiar buffers que
+// crescem. As duas contas são iterativas de propósito (sem fast doubling nem
+// produto em árvore), para serem fáceis de repetir nas outras linguagens.
+//
+// A conversão para decimal, necessária para contar os dígitos, é medida à
+// parte: para números com centenas de milhares de dígitos ela custa mais que
+// a própria conta. A resposta traz os primeiros e os últimos 20 dígitos, e o
+// valor inteiro só com `return_value`.
+
+use std::time::Instant;
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use num_bigint::BigUint;
+use serde::Deserialize;
+
+use crate::{
+    estimate::{self, Estimate},
+    progress::Progress,
+};
+
+const MAX_FIBONACCI: u64 = 1_000_000;
+const MAX_FACTORIAL: u64 = 100_000;
+const EDGE_DIGITS: usize = 20;
+
+#[derive(Deserialize)]
+pub struct BignumPayload {
+    operation: Option<String>,
+    n: Option<u64>,
+    return_value: Option<bool>,
+    dry_run: Option<bool>,
+}
+
+fn bad_request(error: String) -> Response<BoxBody> {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({ "error": error }))
+    )
+    .into_response()
+}
+
+fn fibonacci(n: u64, progress: &mut Progress) -> BigUint {
+    let mut a = BigUint::from(0u32);
+    let mut b = BigUint::from(1u32);
+    for i in 0..n {
+        let next = &a + &b;
+        a = std::mem::replace(&mut b, next);
+        progress.tick(i + 1);
+    }
+    a
+}
+
+fn factorial(n: u64, progress: &mut Progress) -> BigUint {
+    let mut result = BigUint::from(1u32);
+    for i in 1..=n {
+        result *= i;
+        progress.tick(i);
+    }
+    result
+}
+
+pub async fn bignum(Json(payload): Json<BignumPayload>) -> Response<BoxBody> {
+    let operation = payload.operation.as_deref().unwrap_or("fibonacci");
+    let (n, max, bits) = match operation {
+        // log2(φ) ≈ 0.694 bits por termo
+        "fibonacci" => {
+            let n = payload.n.unwrap_or(10_000);
+            (n, MAX_FIBONACCI, n as f64 * 0.694)
+        }
+        // Stirling: log2(n!) ≈ n·(ln n − 1) / ln 2
+        "factorial" => {
+            let n = payload.n.unwrap_or(1_000);
+            (n, MAX_FACTORIAL, (n as f64 * ((n as f64).ln() - 1.0) / std::f64::consts::LN_2).max(1.0))
+        }
+        other => return bad_request(format!("operation must be fibonacci or factorial, got {}", other)),
+    };
+    if n > max {
+        return bad_request(format!("n must be at most {} for {}", max, operation));
+    }
+    let return_value = payload.return_value.unwrap_or(false);
+
+    if payload.dry_run.unwrap_or(false) {
+        let digits = (bits * std::f64::consts::LOG10_2) as u64 + 1;
+        return estimate::response(Estimate {
+            iterations: n,
+            unit: if operation == "fibonacci" { "big-integer additions" } else { "big-integer multiplications" },
+            allocation_bytes: 3 * (bits as u64 / 8 + 1),
+            output_bytes: 250 + if return_value { digits } else { 0 },
+        });
+    }
+
+    let mut progress = Progress::new(n);
+    let start = Instant::now();
+    let value = if operation == "fibonacci" { fibonacci(n, &mut progress) } else { factorial(n, &mut progress) };
+    let compute_duration = start.elapsed();
+
+    let start = Instant::now();
+    let decimal = value.to_string();
+    let to_string_duration = start.elapsed();
+
+    let edge = EDGE_DIGITS.min(decimal.len());
+    let mut body = serde_json::json!({
+        "operation": operation,
+        "n": n,
+        "digits": decimal.len(),
+        "bits": value.bits(),
+        "leading": &decimal[..edge],
+        "trailing": &decimal[decimal.len() - edge..],
+        "compute_ms": compute_duration.as_secs_f64() * 1000.0,
+        "to_string_ms": to_string_duration.as_secs_f64() * 1000.0,
+    });
+    if return_value {
+        body["value"] = serde_json::json!(decimal);
+    }
+    if let Some(report) = progress.report() {
+        body["progress"] = report;
+    }
+    (StatusCode::OK, Json(body)).into_response()
+}
@@ -0,0 +1,115 @@
+// ======================
+// MIDDLEWARE: ALINHAMENTO COM A COBRANÇA DA LAMBDA
+// ======================
+//
+// A Lambda cobra a duração arredondada para cima na granularidade de cobrança
+// (hoje 1 ms; `BFF_BILLING_GRANULARITY_MS` reproduz outra, como os 100 ms de
+// antes de 2020). Comparando tamanhos de memória pelo custo, parte da
+// diferença é só arredondamento, e esta camada deixa isso visível nos dados.
+// Com `BFF_BILLING_ALIGN`:
+//
+// - `off` (padrão): nada muda
+// - `report`: a resposta leva `X-Billed-Duration-Ms` (a duração medida aqui,
+//   arredondada para cima) e `X-Billing-Slack-Ms`, quanto faltava para a
+//   próxima fronteira, ou seja, o que foi pago sem ser usado
+// - `pad`: além disso, segura a resposta até perto da fronteira, para que a
+//   execução use o tempo que vai ser cobrado de qualquer jeito; o quanto
+//   esperou vai em `X-Billing-Padded-Ms`
+//
+// A duração é a desta camada, a mais externa da pilha padrão: na Lambda fica
+// perto da cobrada, que ainda inclui o runtime. Por isso o padding para
+// `PAD_MARGIN` antes da fronteira e não a passa por causa do caminho de volta.
+// O resto até a fronteira é dormido e os últimos 2 ms, em espera ativa,
+// porque o timer do tokio tem resolução de 1 ms e pode acordar depois.
+
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    body::BoxBody,
+    http::{HeaderValue, Request, Response},
+};
+use tower::{Layer, Service};
+
+use crate::config::CONFIG;
+
+const PAD_MARGIN: Duration = Duration::from_micros(100);
+const SPIN: Duration = Duration::from_millis(2);
+
+/// Duração arredondada para cima na granularidade de cobrança, no mínimo uma.
+pub fn billed(elapsed: Duration) -> Duration {
+    let granularity = Duration::from_millis(CONFIG.billing_granularity_ms.max(1));
+    let units = elapsed.as_nanos().div_ceil(granularity.as_nanos()).max(1);
+    granularity * units as u32
+}
+
+fn ms(duration: Duration) -> HeaderValue {
+    HeaderValue::from_str(&format!("{:.3}", duration.as_secs_f64() * 1000.0)).unwrap()
+}
+
+async fn wait_until(deadline: Instant) {
+    if let Some(rest) = deadline.checked_duration_since(Instant::now()).and_then(|d| d.checked_sub(SPIN)) {
+        tokio::time::sleep(rest).await;
+    }
+    while Instant::now() < deadline {
+        std::hint::spin_loop();
+    }
+}
+
+#[derive(Clone)]
+pub struct BillingLayer;
+
+#[derive(Clone)]
+pub struct BillingService<S> {
+    inner: S,
+}
+
+impl<S> Layer<S> for BillingLayer {
+    type Service = BillingService<S>;
+    fn layer(&self, inner: S) -> Self::Service {
+        BillingService { inner }
+    }
+}
+
+impl<S, ReqBody> Service<Request<ReqBody>> for BillingService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+{
+    type Response = Response<BoxBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        let mut service = self.inner.clone();
+        let mode = CONFIG.billing_align.as_str();
+
+        Box::pin(async move {
+            let start = Instant::now();
+            let mut response = service.call(req).await?;
+            if mode != "report" && mode != "pad" {
+                return Ok(response);
+            }
+            let elapsed = start.elapsed();
+            let billed = billed(elapsed);
+            let headers = response.headers_mut();
+            headers.insert("X-Billed-Duration-Ms", ms(billed));
+            headers.insert("X-Billing-Slack-Ms", ms(billed - elapsed));
+            if mode == "pad" {
+                let deadline = start + billed.saturating_sub(PAD_MARGIN);
+                wait_until(deadline).await;
+                response.headers_mut().insert("X-Billing-Padded-Ms", ms(start.elapsed().saturating_sub(elapsed)));
+            }
+            Ok(response)
+        })
+    }
+}
@@ -0,0 +1,275 @@
+// ======================
+// PARSE DE PROTOCOLO BINÁRIO (FIXED-WIDTH / TLV)
+// ======================
+//
+// Gera (ou recebe em base64) um buffer de registros e faz o decode manual,
+// validando cada campo e agregando os valores por moeda.
+//
+// Layout "fixed" (32 bytes, little-endian):
+//   magic u16 (0xB1F0) | version u8 | flags u8 | id u64 | timestamp_ms u64 |
+//   amount_cents i64 | currency [u8; 3] | xor de todos os 31 bytes anteriores
+//
+// Layout "tlv": sequência de (tipo u8, tamanho u16 LE, valor), terminada por
+// tipo 0 com tamanho 0. Tipos: 1 id u64, 2 timestamp u64, 3 amount i64,
+// 4 currency [u8; 3], 5 tags UTF-8, 6 flags u8. Tipos desconhecidos são pulados.
+
+use std::{collections::BTreeMap, time::Instant};
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+
+use crate::rng::{self, SplitMix64};
+
+const FIXED_RECORD_SIZE: usize = 32;
+const MAGIC: u16 = 0xB1F0;
+const MAX_RECORDS: usize = 2_000_000;
+const CURRENCIES: &[&[u8; 3]] = &[b"USD", b"EUR", b"BRL", b"JPY"];
+
+#[derive(Deserialize)]
+pub struct BinparsePayload {
+    format: Option<String>,
+    records: Option<usize>,
+    seed: Option<u64>,
+    corrupt_rate: Option<f64>,
+    data: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Fixed,
+    Tlv,
+}
+
+struct Record {
+    timestamp: u64,
+    amount: i64,
+    currency: [u8; 3],
+}
+
+#[derive(Default)]
+struct Aggregate {
+    valid: usize,
+    invalid: usize,
+    totals: BTreeMap<String, i64>,
+    min_timestamp: Option<u64>,
+    max_timestamp: Option<u64>,
+}
+
+impl Aggregate {
+    fn add(&mut self, record: Record) {
+        self.valid += 1;
+        let currency = String::from_utf8_lossy(&record.currency).into_owned();
+        let total = self.totals.entry(currency).or_default();
+        *total = total.wrapping_add(record.amount);
+        self.min_timestamp = Some(self.min_timestamp.map_or(record.timestamp, |t| t.min(record.timestamp)));
+        self.max_timestamp = Some(self.max_timestamp.map_or(record.timestamp, |t| t.max(record.timestamp)));
+    }
+}
+
+fn valid_currency(currency: &[u8]) -> bool {
+    currency.len() == 3 && currency.iter().all(u8::is_ascii_uppercase)
+}
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes.try_into().unwrap())
+}
+
+// ------------
+// geração
+// ------------
+fn generate(format: Format, records: usize, corrupt_rate: f64, seed: u64) -> Vec<u8> {
+    let mut rng = SplitMix64::new(seed);
+    let mut buf = Vec::with_capacity(records * 48);
+    for i in 0..records {
+        let id = i as u64;
+        let timestamp = 1_700_000_000_000 + rng.next_below(86_400_000 * 30);
+        let amount = rng.next_below(1_000_000) as i64 - 100_000;
+        let currency = *CURRENCIES[rng.next_below(CURRENCIES.len() as u64) as usize];
+        let corrupt = rng.next_f64() < corrupt_rate;
+        match format {
+            Format::Fixed => {
+                let start = buf.len();
+                buf.extend_from_slice(&MAGIC.to_le_bytes());
+                buf.push(1);
+                buf.push(0);
+                buf.extend_from_slice(&id.to_le_bytes());
+                buf.extend_from_slice(&timestamp.to_le_bytes());
+                buf.extend_from_slice(&amount.to_le_bytes());
+                buf.extend_from_slice(&currency);
+                let xor = buf[start..].iter().fold(0u8, |acc, b| acc ^ b);
+                buf.push(xor);
+                if corrupt {
+                    // Estraga o valor: o checksum deixa de bater
+                    buf[start + 20] ^= 0xFF;
+                }
+            }
+            Format::Tlv => {
+                let currency = if corrupt { currency.map(|c| c.to_ascii_lowercase()) } else { currency };
+                let tags = format!("batch-{}", i % 97);
+                let fields: [(u8, &[u8]); 6] = [
+                    (1, &id.to_le_bytes()),
+                    (2, &timestamp.to_le_bytes()),
+                    (3, &amount.to_le_bytes()),
+                    (4, &currency),
+                    (5, tags.as_bytes()),
+                    (6, &[0]),
+                ];
+                for (kind, value) in fields {
+                    buf.push(kind);
+                    buf.extend_from_slice(&(value.len() as u16).to_le_bytes());
+                    buf.extend_from_slice(value);
+                }
+                buf.extend_from_slice(&[0, 0, 0]);
+            }
+        }
+    }
+    buf
+}
+
+// ------------
+// parsers
+// ------------
+fn parse_fixed(data: &[u8]) -> Result<Aggregate, String> {
+    if !data.len().is_multiple_of(FIXED_RECORD_SIZE) {
+        return Err("Buffer length is not a multiple of the record size".into());
+    }
+    let mut aggregate = Aggregate::default();
+    for record in data.chunks_exact(FIXED_RECORD_SIZE) {
+        let xor = record[..31].iter().fold(0u8, |acc, b| acc ^ b);
+        let valid = u16::from_le_bytes([record[0], record[1]]) == MAGIC
+            && record[2] == 1
+            && xor == record[31]
+            && valid_currency(&record[28..31]);
+        if !valid {
+            aggregate.invalid += 1;
+            continue;
+        }
+        aggregate.add(Record {
+            timestamp: read_u64(&record[12..20]),
+            amount: read_u64(&record[20..28]) as i64,
+            currency: [record[28], record[29], record[30]],
+        });
+    }
+    Ok(aggregate)
+}
+
+fn parse_tlv(data: &[u8]) -> Result<Aggregate, String> {
+    let mut aggregate = Aggregate::default();
+    let mut pos = 0;
+    while pos < data.len() {
+        let (mut id, mut timestamp, mut amount, mut currency) = (None, None, None, None);
+        let mut valid = true;
+        loop {
+            if pos + 3 > data.len() {
+                return Err(format!("Truncated TLV header at offset {}", pos));
+            }
+            let kind = data[pos];
+            let len = u16::from_le_bytes([data[pos + 1], data[pos + 2]]) as usize;
+            pos += 3;
+            if kind == 0 {
+                break;
+            }
+            let Some(value) = data.get(pos..pos + len) else {
+                return Err(format!("TLV value overruns buffer at offset {}", pos));
+            };
+            pos += len;
+            match (kind, len) {
+                (1, 8) => id = Some(read_u64(value)),
+                (2, 8) => timestamp = Some(read_u64(value)),
+                (3, 8) => amount = Some(read_u64(value) as i64),
+                (4, 3) if valid_currency(value) => currency = Some([value[0], value[1], value[2]]),
+                (5, _) => valid &= std::str::from_utf8(value).is_ok(),
+                (6, 1) => {}
+                (1..=6, _) => valid = false,
+                _ => {}
+            }
+        }
+        match (id, timestamp, amount, currency) {
+            (Some(_), Some(timestamp), Some(amount), Some(currency)) if valid => {
+                aggregate.add(Record { timestamp, amount, currency })
+            }
+            _ => aggregate.invalid += 1,
+        }
+    }
+    Ok(aggregate)
+}
+
+pub async fn binparse(Json(payload): Json<BinparsePayload>) -> Response<BoxBody> {
+    let format = match payload.format.as_deref().unwrap_or("fixed") {
+        "fixed" => Format::Fixed,
+        "tlv" => Format::Tlv,
+        _ => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Unsupported format" }))
+            )
+            .into_response()
+        }
+    };
+
+    let data = match &payload.data {
+        Some(encoded) => {
+            use base64::{engine::general_purpose, Engine as _};
+            match general_purpose::STANDARD.decode(encoded) {
+                Ok(data) => data,
+                Err(_) => {
+                    return (
+                        StatusCode::BAD_REQUEST,
+                        Json(serde_json::json!({ "error": "data must be valid base64" }))
+                    )
+                    .into_response()
+                }
+            }
+        }
+        None => {
+            let records = payload.records.unwrap_or(100_000);
+            if records > MAX_RECORDS {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({ "error": "Too many records" }))
+                )
+                .into_response();
+            }
+            let corrupt_rate = payload.corrupt_rate.unwrap_or(0.01).clamp(0.0, 1.0);
+            generate(format, records, corrupt_rate, rng::seed(payload.seed))
+        }
+    };
+
+    let start = Instant::now();
+    let result = match format {
+        Format::Fixed => parse_fixed(&data),
+        Format::Tlv => parse_tlv(&data),
+    };
+    let duration = start.elapsed();
+
+    let aggregate = match result {
+        Ok(aggregate) => aggregate,
+        Err(error) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": error }))
+            )
+            .into_response()
+        }
+    };
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "bytes": data.len(),
+            "valid": aggregate.valid,
+            "invalid": aggregate.invalid,
+            "totals": aggregate.totals,
+            "min_timestamp": aggregate.min_timestamp,
+            "max_timestamp": aggregate.max_timestamp,
+            "duration_ms": duration.as_secs_f64() * 1000.0,
+            "mb_per_second": data.len() as f64 / duration.as_secs_f64() / 1e6,
+        }))
+    )
+    .into_response()
+}
@@ -0,0 +1,236 @@
+// ======================
+// ESTRUTURAS PROBABILÍSTICAS (BLOOM / HYPERLOGLOG)
+// ======================
+//
+// `bloom`: insere N chaves, confirma que nenhuma sumiu e mede a taxa real de
+// falsos positivos com chaves que nunca foram inseridas.
+// `hll`: estima a cardinalidade de um fluxo com repetições e mede o erro.
+//
+// O hash é FNV-1a seguido do finalizador do SplitMix64, fácil de portar.
+
+use std::time::Instant;
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+
+use crate::rng::{self, SplitMix64};
+
+const MAX_ITEMS: usize = 5_000_000;
+// Abaixo disso m e k explodem (5e-324 com 5M itens daria ~970 MB de bits e
+// ~515 hashes por chave); em 1e-9 são ~27 MB e 30 hashes
+const MIN_FALSE_POSITIVE_RATE: f64 = 1e-9;
+
+#[derive(Deserialize)]
+pub struct BloomPayload {
+    structure: Option<String>,
+    items: Option<usize>,
+    queries: Option<usize>,
+    false_positive_rate: Option<f64>,
+    precision: Option<u8>,
+    duplicates: Option<usize>,
+    seed: Option<u64>,
+}
+
+fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+fn hash(key: &str) -> u64 {
+    let mut h: u64 = 0xCBF2_9CE4_8422_2325;
+    for byte in key.bytes() {
+        h ^= byte as u64;
+        h = h.wrapping_mul(0x0100_0000_01B3);
+    }
+    mix(h)
+}
+
+// ------------
+// bloom filter
+// ------------
+struct BloomFilter {
+    bits: Vec<u64>,
+    bit_count: u64,
+    hashes: u32,
+}
+
+impl BloomFilter {
+    /// Dimensiona m e k pelas fórmulas clássicas para n itens e taxa p.
+    fn with_rate(items: usize, rate: f64) -> BloomFilter {
+        let ln2 = std::f64::consts::LN_2;
+        let bit_count = (-(items as f64) * rate.ln() / (ln2 * ln2)).ceil().max(64.0) as u64;
+        let hashes = ((bit_count as f64 / items as f64) * ln2).round().max(1.0) as u32;
+        BloomFilter {
+            bits: vec![0; bit_count.div_ceil(64) as usize],
+            bit_count,
+            hashes,
+        }
+    }
+
+    // Double hashing (Kirsch-Mitzenmacher): k posições a partir de dois hashes
+    fn positions(&self, key: &str) -> impl Iterator<Item = u64> + use<> {
+        let h1 = hash(key);
+        let h2 = mix(h1 ^ 0x9E37_79B9_7F4A_7C15) | 1;
+        let bit_count = self.bit_count;
+        (0..self.hashes as u64).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % bit_count)
+    }
+
+    fn insert(&mut self, key: &str) {
+        for bit in self.positions(key) {
+            self.bits[(bit / 64) as usize] |= 1 << (bit % 64);
+        }
+    }
+
+    fn contains(&self, key: &str) -> bool {
+        self.positions(key)
+            .all(|bit| self.bits[(bit / 64) as usize] & (1 << (bit % 64)) != 0)
+    }
+}
+
+// ------------
+// hyperloglog
+// ------------
+struct HyperLogLog {
+    registers: Vec<u8>,
+    precision: u8,
+}
+
+impl HyperLogLog {
+    fn new(precision: u8) -> HyperLogLog {
+        HyperLogLog { registers: vec![0; 1 << precision], precision }
+    }
+
+    fn insert(&mut self, key: &str) {
+        let h = hash(key);
+        let index = (h >> (64 - self.precision)) as usize;
+        let rest = h << self.precision;
+        let rank = (rest.leading_zeros() as u8).min(64 - self.precision) + 1;
+        if rank > self.registers[index] {
+            self.registers[index] = rank;
+        }
+    }
+
+    fn estimate(&self) -> f64 {
+        let m = self.registers.len() as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self.registers.iter().map(|r| 2f64.powi(-(*r as i32))).sum();
+        let raw = alpha * m * m / sum;
+        let zeros = self.registers.iter().filter(|r| **r == 0).count();
+        // Correção para cardinalidades pequenas (linear counting)
+        if raw <= 2.5 * m && zeros > 0 {
+            m * (m / zeros as f64).ln()
+        } else {
+            raw
+        }
+    }
+}
+
+pub async fn bloom(Json(payload): Json<BloomPayload>) -> Response<BoxBody> {
+    let items = payload.items.unwrap_or(100_000);
+    let queries = payload.queries.unwrap_or(items);
+    let mut rng = SplitMix64::new(rng::seed(payload.seed));
+
+    if items == 0 || items > MAX_ITEMS || queries > MAX_ITEMS {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "Items and queries out of range" }))
+        )
+        .into_response();
+    }
+
+    match payload.structure.as_deref().unwrap_or("bloom") {
+        "bloom" => {
+            let rate = payload.false_positive_rate.unwrap_or(0.01);
+            if !(MIN_FALSE_POSITIVE_RATE..1.0).contains(&rate) {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({
+                        "error": format!("false_positive_rate must be in [{:e}, 1)", MIN_FALSE_POSITIVE_RATE)
+                    }))
+                )
+                .into_response();
+            }
+
+            let members: Vec<String> = (0..items).map(|_| format!("m:{:016x}", rng.next_u64())).collect();
+            let outsiders: Vec<String> = (0..queries).map(|_| format!("q:{:016x}", rng.next_u64())).collect();
+
+            let start = Instant::now();
+            let mut filter = BloomFilter::with_rate(items, rate);
+            for key in &members {
+                filter.insert(key);
+            }
+            let insert_duration = start.elapsed();
+
+            let query_start = Instant::now();
+            let false_negatives = members.iter().filter(|key| !filter.contains(key)).count();
+            let false_positives = outsiders.iter().filter(|key| filter.contains(key)).count();
+            let query_duration = query_start.elapsed();
+
+            (
+                StatusCode::OK,
+                Json(serde_json::json!({
+                    "structure": "bloom",
+                    "items": items,
+                    "bits": filter.bit_count,
+                    "hashes": filter.hashes,
+                    "false_negatives": false_negatives,
+                    "false_positives": false_positives,
+                    "target_false_positive_rate": rate,
+                    "observed_false_positive_rate": if queries == 0 { 0.0 } else { false_positives as f64 / queries as f64 },
+                    "insert_ms": insert_duration.as_secs_f64() * 1000.0,
+                    "query_ms": query_duration.as_secs_f64() * 1000.0,
+                    "duration_ms": (insert_duration + query_duration).as_secs_f64() * 1000.0,
+                }))
+            )
+            .into_response()
+        }
+        "hll" => {
+            let precision = payload.precision.unwrap_or(14);
+            if !(4..=18).contains(&precision) {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({ "error": "precision must be between 4 and 18" }))
+                )
+                .into_response();
+            }
+            // Cada chave aparece `duplicates` vezes no fluxo, como IDs repetidos
+            let duplicates = payload.duplicates.unwrap_or(3).clamp(1, 100);
+            let keys: Vec<String> = (0..items).map(|_| format!("m:{:016x}", rng.next_u64())).collect();
+
+            let start = Instant::now();
+            let mut hll = HyperLogLog::new(precision);
+            for _ in 0..duplicates {
+                for key in &keys {
+                    hll.insert(key);
+                }
+            }
+            let estimate = hll.estimate();
+            let duration = start.elapsed();
+
+            (
+                StatusCode::OK,
+                Json(serde_json::json!({
+                    "structure": "hll",
+                    "items": items,
+                    "stream_length": items * duplicates,
+                    "registers": hll.registers.len(),
+                    "estimate": estimate.round(),
+                    "relative_error": (estimate - items as f64).abs() / items as f64,
+                    "duration_ms": duration.as_secs_f64() * 1000.0,
+                }))
+            )
+            .into_response()
+        }
+        _ => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "Unsupported structure" }))
+        )
+        .into_response(),
+    }
+}
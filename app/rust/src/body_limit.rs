@@ -0,0 +1,63 @@
+// ======================
+// LIMITE DE CORPO NOS MIDDLEWARES
+// ======================
+//
+// Middlewares que precisam do corpo inteiro (dedup, idempotência, gravação...)
+// rodam por fora dos handlers, antes do limite do extractor do axum. Sem um
+// limite próprio, um POST de centenas de MB ficaria todo na memória só para
+// depois levar 413 do handler. Aqui o corpo é lido com o mesmo limite da rota:
+// o padrão de 2 MB do axum ou os 64 MB do /json/parse. Um `Content-Length`
+// acima dele é recusado sem ler nada; sem ele, a leitura para no limite.
+
+use axum::{
+    body::{Body, BoxBody, Bytes},
+    extract::Json,
+    http::{header::CONTENT_LENGTH, request::Parts, Request, Response, StatusCode},
+    response::IntoResponse,
+};
+use http_body::{LengthLimitError, Limited};
+
+/// Limite padrão de corpo do extractor do axum
+pub const DEFAULT: usize = 2 << 20;
+/// Limite do /json/parse, que recebe documentos de vários MB
+pub const JSON_PARSE: usize = 64 << 20;
+
+/// Limite de corpo da rota, o mesmo que o handler aplica.
+pub fn for_path(path: &str) -> usize {
+    match path.strip_prefix("/raw").unwrap_or(path) {
+        "/json/parse" => JSON_PARSE,
+        _ => DEFAULT,
+    }
+}
+
+fn too_large(limit: usize) -> Response<BoxBody> {
+    (
+        StatusCode::PAYLOAD_TOO_LARGE,
+        Json(serde_json::json!({ "error": format!("Request body larger than {} bytes", limit) }))
+    )
+    .into_response()
+}
+
+/// Lê o corpo inteiro da request, com o limite da rota.
+pub async fn read(req: Request<Body>) -> Result<(Parts, Bytes), Response<BoxBody>> {
+    let limit = for_path(req.uri().path());
+    let declared = req
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+    if declared.is_some_and(|length| length > limit as u64) {
+        return Err(too_large(limit));
+    }
+
+    let (parts, body) = req.into_parts();
+    match hyper::body::to_bytes(Limited::new(body, limit)).await {
+        Ok(bytes) => Ok((parts, bytes)),
+        Err(error) if error.downcast_ref::<LengthLimitError>().is_some() => Err(too_large(limit)),
+        Err(_) => Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "Failed to read request body" }))
+        )
+        .into_response()),
+    }
+}
@@ -0,0 +1,110 @@
+// ======================
+// CPU BURN CALIBRADO
+// ======================
+//
+// Ocupa a CPU por um tempo de relógio pedido. O laço gira em blocos de
+// iterações e só consulta o relógio entre blocos; o tamanho do bloco é
+// recalibrado a cada volta a partir do custo medido por iteração, mirando
+// `CHECK_INTERVAL` e nunca mais que o tempo restante. Assim o overshoot no
+// final fica na ordem de microssegundos, independente do tier de memória/CPU.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+
+const MAX_DURATION_MS: f64 = 60_000.0;
+const CHECK_INTERVAL: Duration = Duration::from_micros(100);
+
+#[derive(Deserialize)]
+pub struct BurnPayload {
+    duration_ms: Option<f64>,
+}
+
+// Uma iteração de trabalho: mistura barata que o compilador não consegue
+// eliminar por causa do black_box
+#[inline(always)]
+fn spin(state: u64, iterations: u64) -> u64 {
+    let mut x = state;
+    for _ in 0..iterations {
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        x = black_box(x);
+    }
+    x
+}
+
+struct BurnResult {
+    elapsed: Duration,
+    iterations: u64,
+    checks: u64,
+    ns_per_iteration: f64,
+}
+
+fn run(target: Duration) -> BurnResult {
+    let start = Instant::now();
+    let deadline = start + target;
+    let mut state = 0x9E37_79B9_7F4A_7C15u64;
+    let mut chunk = 1_024u64;
+    let mut ns_per_iteration = 0.0;
+    let mut iterations = 0;
+    let mut checks = 0;
+
+    loop {
+        let before = Instant::now();
+        checks += 1;
+        let Some(remaining) = deadline.checked_duration_since(before).filter(|r| !r.is_zero()) else {
+            break;
+        };
+
+        state = spin(state, chunk);
+        iterations += chunk;
+
+        // Média móvel do custo por iteração; o próximo bloco mira o menor
+        // entre o intervalo de checagem e o tempo que falta
+        let measured = before.elapsed().as_nanos() as f64 / chunk as f64;
+        ns_per_iteration = if ns_per_iteration == 0.0 { measured } else { 0.8 * ns_per_iteration + 0.2 * measured };
+        let budget = remaining.saturating_sub(before.elapsed()).min(CHECK_INTERVAL);
+        chunk = ((budget.as_nanos() as f64 / ns_per_iteration.max(0.01)) as u64).max(1);
+    }
+    black_box(state);
+
+    BurnResult { elapsed: start.elapsed(), iterations, checks, ns_per_iteration }
+}
+
+pub async fn burn(Json(payload): Json<BurnPayload>) -> Response<BoxBody> {
+    let requested = payload.duration_ms.unwrap_or(100.0);
+    if !(0.0..=MAX_DURATION_MS).contains(&requested) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "duration_ms must be between 0 and 60000" }))
+        )
+        .into_response();
+    }
+
+    let result = run(Duration::from_secs_f64(requested / 1000.0));
+    let actual_ms = result.elapsed.as_secs_f64() * 1000.0;
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "requested_ms": requested,
+            "actual_ms": actual_ms,
+            "drift_us": (actual_ms - requested) * 1000.0,
+            "iterations": result.iterations,
+            "checks": result.checks,
+            "ns_per_iteration": result.ns_per_iteration,
+            "duration_ms": actual_ms,
+        }))
+    )
+    .into_response()
+}
@@ -0,0 +1,302 @@
+// ======================
+// CALIBRAÇÃO DA MÁQUINA NO STARTUP
+// ======================
+//
+// A Lambda roda em gerações diferentes de hardware sem avisar, e um p50 só é
+// comparável com outro medido no mesmo tipo de CPU. No startup, uma vez por
+// processo, medimos a instância e o resultado vai para o `/info` (e, com ele,
+// para o evento `startup` do log e o documento do `/results`):
+//
+// - `cpu`: modelo (do /proc/cpuinfo), CPUs lógicas e as extensões que o
+//   código usa ou poderia usar (AVX2, AES, SHA...)
+// - `counter`: a frequência do TSC no x86_64, contada contra o relógio
+//   monotônico durante ~20 ms, e se ele é invariante; no aarch64, a do
+//   contador genérico (CNTFRQ_EL0), que o próprio registrador informa
+// - `memory_gbps`: banda de memória estimada com o triad do STREAM
+//   (`a = b + s·c`) em vetores de 8 MB, o melhor de três passadas
+// - `integer_mops`: operações inteiras por segundo num laço de xorshift
+//   dependente, de um núcleo
+// - `fingerprint`: hash do modelo, das extensões e do número de CPUs, para
+//   agrupar resultados por tipo de instância
+//
+// Tudo leva algumas dezenas de milissegundos, que na Lambda entram no tempo de
+// init; `BFF_CALIBRATION=false` desliga (o campo vira null).
+//
+// O host também pode mudar com o processo vivo: um snapshot do SnapStart, ou
+// um ambiente congelado e migrado, volta em outra máquina. Com
+// `BFF_CALIBRATION_INTERVAL_SECS` (0, o padrão, desliga), a cada intervalo e
+// logo depois de um freeze detectado (clock.rs) uma thread refaz a parte
+// barata (modelo, extensões, CPUs e a frequência do contador, com 1% de
+// tolerância). Ali a contagem do TSC dorme em vez de girar a CPU, para não
+// roubar tempo da request que está rodando numa Lambda de fração de vCPU. Se
+// mudou, a geração do host sobe, sai um evento `host_change` no log e as
+// respostas seguintes levam `X-Host-Generation`, que também vai para as
+// amostras cruas (samples.rs).
+
+use std::{
+    hint::black_box,
+    sync::{
+        atomic::{AtomicU32, AtomicU64, Ordering},
+        Mutex, Once,
+    },
+    thread::Thread,
+    time::Instant,
+};
+
+use once_cell::sync::{Lazy, OnceCell};
+
+use crate::config::CONFIG;
+
+const STREAM_LEN: usize = 1 << 20;
+const INTEGER_OPS: u64 = 20_000_000;
+const FREQUENCY_TOLERANCE: f64 = 0.01;
+
+pub static REPORT: Lazy<serde_json::Value> = Lazy::new(|| {
+    if !CONFIG.calibration {
+        return serde_json::Value::Null;
+    }
+    let start = Instant::now();
+    let cpu = cpu();
+    let counter = counter(true);
+    *HOST.lock().unwrap() = Some(Host::new(&cpu, &counter));
+    let memory_gbps = memory_gbps();
+    let integer_mops = integer_mops();
+    let fingerprint = {
+        use sha2::{Digest, Sha256};
+
+        let digest = Sha256::digest(format!("{}|{}|{}", cpu["model"], cpu["features"], cpu["logical_cpus"]));
+        digest.iter().take(8).map(|b| format!("{:02x}", b)).collect::<String>()
+    };
+    serde_json::json!({
+        "cpu": cpu,
+        "counter": counter,
+        "memory_gbps": memory_gbps,
+        "integer_mops": integer_mops,
+        "fingerprint": fingerprint,
+        "duration_ms": start.elapsed().as_secs_f64() * 1000.0,
+    })
+});
+
+/// O que identifica a máquina, sem as medidas ruidosas.
+#[derive(Clone)]
+struct Host {
+    cpu: serde_json::Value,
+    counter_hz: f64,
+}
+
+impl Host {
+    fn new(cpu: &serde_json::Value, counter: &serde_json::Value) -> Host {
+        Host { cpu: cpu.clone(), counter_hz: counter["hz"].as_f64().unwrap_or(0.0) }
+    }
+
+    fn differs(&self, other: &Host) -> bool {
+        let drift = (self.counter_hz - other.counter_hz).abs() / self.counter_hz.max(1.0);
+        self.cpu != other.cpu || drift > FREQUENCY_TOLERANCE
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "model": self.cpu["model"],
+            "features": self.cpu["features"],
+            "logical_cpus": self.cpu["logical_cpus"],
+            "counter_hz": self.counter_hz,
+        })
+    }
+}
+
+static HOST: Mutex<Option<Host>> = Mutex::new(None);
+static GENERATION: AtomicU32 = AtomicU32::new(0);
+static CHECKS: AtomicU64 = AtomicU64::new(0);
+static LAST_CHANGE: Mutex<Option<serde_json::Value>> = Mutex::new(None);
+static CHECKER: OnceCell<Thread> = OnceCell::new();
+static START: Once = Once::new();
+
+/// Quantas vezes o host mudou desde o startup.
+pub fn generation() -> u32 {
+    GENERATION.load(Ordering::Relaxed)
+}
+
+/// Inicia a thread que refaz a checagem do host.
+pub fn start() {
+    START.call_once(|| {
+        let interval = CONFIG.calibration_interval;
+        if REPORT.is_null() || interval.is_zero() {
+            return;
+        }
+        let thread = std::thread::Builder::new()
+            .name("calibration".to_string())
+            .spawn(move || loop {
+                std::thread::park_timeout(interval);
+                recheck();
+            })
+            .expect("falha ao criar a thread de calibração");
+        let _ = CHECKER.set(thread.thread().clone());
+    });
+}
+
+/// Pede uma checagem já (chamado quando um freeze é detectado).
+pub fn wake() {
+    if let Some(thread) = CHECKER.get() {
+        thread.unpark();
+    }
+}
+
+fn recheck() {
+    let current = Host::new(&cpu(), &counter(false));
+    CHECKS.fetch_add(1, Ordering::Relaxed);
+    let mut host = HOST.lock().unwrap();
+    let Some(previous) = host.as_ref() else {
+        return;
+    };
+    if !previous.differs(&current) {
+        return;
+    }
+    let generation = GENERATION.fetch_add(1, Ordering::Relaxed) + 1;
+    let change = serde_json::json!({
+        "generation": generation,
+        "at": chrono::Utc::now().to_rfc3339(),
+        "from": previous.to_json(),
+        "to": current.to_json(),
+    });
+    let mut event = change.clone();
+    event["event"] = serde_json::json!("host_change");
+    crate::run::annotate(&mut event);
+    println!("{}", event);
+    *LAST_CHANGE.lock().unwrap() = Some(change);
+    *host = Some(current);
+}
+
+/// Geração atual e a última mudança, para o /info.
+pub fn host_snapshot() -> serde_json::Value {
+    serde_json::json!({
+        "generation": generation(),
+        "checks": CHECKS.load(Ordering::Relaxed),
+        "interval_secs": CONFIG.calibration_interval.as_secs(),
+        "last_change": *LAST_CHANGE.lock().unwrap(),
+    })
+}
+
+fn cpu() -> serde_json::Value {
+    let cpuinfo = std::fs::read_to_string("/proc/cpuinfo").unwrap_or_default();
+    // x86 tem "model name"; no ARM só há os códigos do fabricante e da peça
+    let field = |name: &str| {
+        cpuinfo.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            (key.trim() == name).then(|| value.trim().to_string())
+        })
+    };
+    let model = field("model name").or_else(|| {
+        let implementer = field("CPU implementer")?;
+        Some(format!("implementer {} part {}", implementer, field("CPU part").unwrap_or_default()))
+    });
+    serde_json::json!({
+        "arch": std::env::consts::ARCH,
+        "model": model,
+        "logical_cpus": std::thread::available_parallelism().map_or(1, |n| n.get()),
+        "features": features(),
+    })
+}
+
+fn features() -> Vec<&'static str> {
+    #[allow(unused_mut)]
+    let mut found = Vec::new();
+    #[cfg(target_arch = "x86_64")]
+    {
+        macro_rules! detect {
+            ($($feature:tt),*) => {
+                $(if std::arch::is_x86_feature_detected!($feature) { found.push($feature); })*
+            };
+        }
+        detect!("sse4.2", "popcnt", "avx", "avx2", "fma", "bmi2", "avx512f", "aes", "pclmulqdq", "sha");
+    }
+    #[cfg(target_arch = "aarch64")]
+    {
+        macro_rules! detect {
+            ($($feature:tt),*) => {
+                $(if std::arch::is_aarch64_feature_detected!($feature) { found.push($feature); })*
+            };
+        }
+        detect!("neon", "aes", "pmull", "sha2", "sha3", "crc", "lse", "sve", "sve2");
+    }
+    found
+}
+
+/// Frequência do contador; com `spin`, a espera gira a CPU em vez de dormir.
+#[cfg(target_arch = "x86_64")]
+fn counter(spin: bool) -> serde_json::Value {
+    use std::arch::x86_64::{__cpuid, _rdtsc};
+
+    // CPUID 0x80000007, EDX bit 8: TSC invariante (não muda com a frequência)
+    let invariant = __cpuid(0x8000_0000).eax >= 0x8000_0007 && __cpuid(0x8000_0007).edx & (1 << 8) != 0;
+    // Leitura do TSC entre dois Instants; fica a de intervalo mais curto, para
+    // uma preempção no meio não distorcer a conta
+    let stamp = || {
+        (0..5)
+            .map(|_| {
+                let before = Instant::now();
+                let tsc = unsafe { _rdtsc() };
+                let width = before.elapsed();
+                (width, before + width / 2, tsc)
+            })
+            .min_by_key(|(width, _, _)| *width)
+            .map(|(_, at, tsc)| (at, tsc))
+            .unwrap()
+    };
+    let (start, tsc_start) = stamp();
+    if spin {
+        while start.elapsed().as_millis() < 20 {
+            std::hint::spin_loop();
+        }
+    } else {
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+    let (end, tsc_end) = stamp();
+    serde_json::json!({
+        "source": "tsc",
+        "hz": ((tsc_end - tsc_start) as f64 / (end - start).as_secs_f64()).round(),
+        "invariant": invariant,
+    })
+}
+
+#[cfg(target_arch = "aarch64")]
+fn counter(_spin: bool) -> serde_json::Value {
+    let hz: u64;
+    unsafe { std::arch::asm!("mrs {}, cntfrq_el0", out(reg) hz) };
+    serde_json::json!({ "source": "cntfrq_el0", "hz": hz })
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+fn counter(_spin: bool) -> serde_json::Value {
+    serde_json::Value::Null
+}
+
+fn memory_gbps() -> f64 {
+    let b = vec![1.0f64; STREAM_LEN];
+    let c = vec![2.0f64; STREAM_LEN];
+    let mut a = vec![0.0f64; STREAM_LEN];
+    let scalar = black_box(3.0);
+    let mut best = f64::MAX;
+    for _ in 0..3 {
+        let start = Instant::now();
+        for ((a, b), c) in a.iter_mut().zip(&b).zip(&c) {
+            *a = b + scalar * c;
+        }
+        black_box(&mut a);
+        best = best.min(start.elapsed().as_secs_f64());
+    }
+    // Lê b e c, escreve a
+    (3 * 8 * STREAM_LEN) as f64 / best / 1e9
+}
+
+fn integer_mops() -> f64 {
+    let mut x = black_box(0x9E37_79B9_7F4A_7C15u64);
+    let start = Instant::now();
+    // Cada passo são três xor e três shifts, e depende do anterior
+    for _ in 0..INTEGER_OPS / 6 {
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+    }
+    black_box(x);
+    INTEGER_OPS as f64 / start.elapsed().as_secs_f64() / 1e6
+}
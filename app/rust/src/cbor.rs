@@ -0,0 +1,57 @@
+// ======================
+// IDA E VOLTA POR CBOR
+// ======================
+//
+// O corpo da request, qualquer JSON, é o documento: codificado em CBOR (RFC
+// 8949) e decodificado de volta, uma vez. Devolve o tamanho em JSON compacto e
+// em CBOR, os tempos de cada sentido e se o documento voltou igual. Como não
+// há campos de opção, o corpo inteiro entra na medida; para repetir, use o
+// /sweep ou o /mixed.
+
+use std::time::Instant;
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use serde_json::Value;
+
+fn bad_request(error: String) -> Response<BoxBody> {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({ "error": error }))
+    )
+    .into_response()
+}
+
+pub async fn cbor(Json(document): Json<Value>) -> Response<BoxBody> {
+    let json_bytes = serde_json::to_vec(&document).map_or(0, |json| json.len());
+
+    let start = Instant::now();
+    let mut encoded = Vec::new();
+    if let Err(error) = ciborium::into_writer(&document, &mut encoded) {
+        return bad_request(format!("Cannot encode CBOR: {}", error));
+    }
+    let encode_duration = start.elapsed();
+    let start = Instant::now();
+    let decoded: Value = match ciborium::from_reader(encoded.as_slice()) {
+        Ok(decoded) => decoded,
+        Err(error) => return bad_request(format!("Cannot decode CBOR: {}", error)),
+    };
+    let decode_duration = start.elapsed();
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "json_bytes": json_bytes,
+            "cbor_bytes": encoded.len(),
+            "size_ratio": encoded.len() as f64 / json_bytes.max(1) as f64,
+            "round_trip": decoded == document,
+            "encode_ms": encode_duration.as_secs_f64() * 1000.0,
+            "decode_ms": decode_duration.as_secs_f64() * 1000.0,
+        }))
+    )
+    .into_response()
+}
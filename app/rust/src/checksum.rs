@@ -0,0 +1,151 @@
+// ======================
+// THROUGHPUT DE CHECKSUMS
+// ======================
+//
+// Roda CRC32, Adler-32, xxHash64 e CRC32C sobre um buffer gerado a partir da
+// seed e reporta GB/s de cada um. CRC32 (pclmulqdq) e CRC32C (sse4.2 / crc do
+// ARMv8) usam instruções de hardware quando a CPU oferece; o campo
+// `hardware` diz se isso aconteceu nesta máquina.
+
+use std::time::Instant;
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+
+use crate::{
+    estimate::{self, Estimate},
+    progress::Progress,
+    rng::{self, SplitMix64},
+};
+
+const MAX_SIZE_BYTES: usize = 256 * 1024 * 1024;
+const MAX_ITERATIONS: usize = 10_000;
+// Bytes processados no total (tamanho × iterações × algoritmos)
+const MAX_TOTAL_BYTES: usize = 16 * 1024 * 1024 * 1024;
+const ALGORITHMS: &[&str] = &["crc32", "adler32", "xxhash64", "crc32c"];
+
+#[derive(Deserialize)]
+pub struct ChecksumPayload {
+    size_bytes: Option<usize>,
+    iterations: Option<usize>,
+    algorithms: Option<Vec<String>>,
+    seed: Option<u64>,
+    dry_run: Option<bool>,
+}
+
+fn hardware_accelerated(algorithm: &str) -> bool {
+    #[cfg(target_arch = "x86_64")]
+    {
+        match algorithm {
+            "crc32" => is_x86_feature_detected!("pclmulqdq") && is_x86_feature_detected!("sse4.1"),
+            "crc32c" => is_x86_feature_detected!("sse4.2"),
+            _ => false,
+        }
+    }
+    #[cfg(target_arch = "aarch64")]
+    {
+        matches!(algorithm, "crc32" | "crc32c") && std::arch::is_aarch64_feature_detected!("crc")
+    }
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    {
+        let _ = algorithm;
+        false
+    }
+}
+
+fn compute(algorithm: &str, data: &[u8]) -> u64 {
+    match algorithm {
+        "crc32" => crc32fast::hash(data) as u64,
+        "adler32" => adler2::adler32_slice(data) as u64,
+        "xxhash64" => xxhash_rust::xxh64::xxh64(data, 0),
+        "crc32c" => crc32c::crc32c(data) as u64,
+        _ => unreachable!(),
+    }
+}
+
+pub async fn checksum(Json(payload): Json<ChecksumPayload>) -> Response<BoxBody> {
+    let size = payload.size_bytes.unwrap_or(1024 * 1024);
+    let iterations = payload.iterations.unwrap_or(10);
+    if size == 0 || size > MAX_SIZE_BYTES || iterations == 0 || iterations > MAX_ITERATIONS {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "size_bytes or iterations out of range" }))
+        )
+        .into_response();
+    }
+
+    let algorithms: Vec<String> = payload
+        .algorithms
+        .unwrap_or_else(|| ALGORITHMS.iter().map(|a| a.to_string()).collect());
+    if let Some(unknown) = algorithms.iter().find(|a| !ALGORITHMS.contains(&a.as_str())) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": format!("Unsupported algorithm: {}", unknown) }))
+        )
+        .into_response();
+    }
+
+    if size.saturating_mul(iterations).saturating_mul(algorithms.len()) > MAX_TOTAL_BYTES {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "size_bytes * iterations * algorithms exceeds 16 GiB" }))
+        )
+        .into_response();
+    }
+    if payload.dry_run.unwrap_or(false) {
+        return estimate::response(Estimate {
+            iterations: (iterations * algorithms.len()) as u64,
+            unit: "checksums",
+            allocation_bytes: size as u64,
+            output_bytes: 50 + 120 * algorithms.len() as u64,
+        });
+    }
+
+    let mut rng = SplitMix64::new(rng::seed(payload.seed));
+    let mut data = Vec::with_capacity(size + 8);
+    while data.len() < size {
+        data.extend_from_slice(&rng.next_u64().to_le_bytes());
+    }
+    data.truncate(size);
+
+    let mut results = serde_json::Map::new();
+    // Uma só contagem para todos os algoritmos, na ordem em que rodam
+    let mut progress = Progress::new((iterations * algorithms.len()) as u64);
+    let mut done = 0;
+    for algorithm in &algorithms {
+        let start = Instant::now();
+        let mut value = 0;
+        for _ in 0..iterations {
+            // black_box impede o compilador de colapsar as iterações repetidas
+            value = compute(algorithm, std::hint::black_box(&data));
+            done += 1;
+            progress.tick(done);
+        }
+        let duration = start.elapsed();
+        let bytes = (size * iterations) as f64;
+        results.insert(
+            algorithm.clone(),
+            serde_json::json!({
+                "checksum": format!("{:x}", value),
+                "hardware": hardware_accelerated(algorithm),
+                "duration_ms": duration.as_secs_f64() * 1000.0,
+                "gb_per_second": bytes / duration.as_secs_f64() / 1e9,
+            }),
+        );
+    }
+
+    let mut body = serde_json::json!({
+        "size_bytes": size,
+        "iterations": iterations,
+        "results": results,
+    });
+    if let Some(report) = progress.report() {
+        body["progress"] = report;
+    }
+    (StatusCode::OK, Json(body)).into_response()
+}
@@ -0,0 +1,148 @@
+// ======================
+// RELÓGIO DAS MEDIÇÕES
+// ======================
+//
+// O `Instant` do Rust usa CLOCK_MONOTONIC, que no Linux não anda enquanto o
+// sistema está suspenso. As durações reportadas (headers de timing, long poll,
+// modos sem HTTP) usam CLOCK_BOOTTIME, que continua contando durante a
+// suspensão; fora do Linux caem no `Instant`.
+//
+// O relógio de parede é lido junto no início da medição. Se no fim ele andou
+// diferente do monotônico além de `BFF_CLOCK_JUMP_THRESHOLD_MS` (ajuste do
+// NTP, freeze/thaw do ambiente da Lambda...), a medição é marcada: a duração
+// continua confiável, mas qualquer conta feita com os horários de parede não.
+//
+// Entre invocações a mesma comparação detecta o freeze do ambiente da Lambda:
+// enquanto o processo está congelado o relógio de parede segue, e os
+// monotônicos podem não seguir. Toda invocação (request HTTP ou evento) vira
+// um `Invocation`; se desde o fim da anterior o relógio de parede andou mais
+// que o CLOCK_MONOTONIC além de `BFF_FREEZE_THRESHOLD_MS`, a diferença conta
+// como tempo congelado. Um salto do NTP entre invocações aparece do mesmo
+// jeito, então o número é uma estimativa.
+
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::config::CONFIG;
+
+static JUMPS: AtomicU64 = AtomicU64::new(0);
+static MAX_JUMP_US: AtomicU64 = AtomicU64::new(0);
+
+static FREEZES: AtomicU64 = AtomicU64::new(0);
+static FROZEN_US: AtomicU64 = AtomicU64::new(0);
+static MAX_FROZEN_US: AtomicU64 = AtomicU64::new(0);
+
+// (monotônico, parede) no fim da última invocação
+static LAST_END: Mutex<Option<(Instant, SystemTime)>> = Mutex::new(None);
+
+#[cfg(target_os = "linux")]
+pub const SOURCE: &str = "boottime";
+#[cfg(not(target_os = "linux"))]
+pub const SOURCE: &str = "monotonic";
+
+/// Tempo desde uma origem fixa qualquer (o boot, no Linux).
+#[cfg(target_os = "linux")]
+pub fn now() -> Duration {
+    let mut ts = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+    // CLOCK_BOOTTIME existe desde o Linux 2.6.39
+    unsafe { libc::clock_gettime(libc::CLOCK_BOOTTIME, &mut ts) };
+    Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn now() -> Duration {
+    static ORIGIN: once_cell::sync::Lazy<std::time::Instant> = once_cell::sync::Lazy::new(std::time::Instant::now);
+    ORIGIN.elapsed()
+}
+
+pub fn snapshot() -> serde_json::Value {
+    serde_json::json!({
+        "source": SOURCE,
+        "jump_threshold_ms": CONFIG.clock_jump_threshold.as_secs_f64() * 1000.0,
+        "jumps": JUMPS.load(Ordering::Relaxed),
+        "max_jump_ms": MAX_JUMP_US.load(Ordering::Relaxed) as f64 / 1000.0,
+        "freeze_threshold_ms": CONFIG.freeze_threshold.as_secs_f64() * 1000.0,
+        "freezes": FREEZES.load(Ordering::Relaxed),
+        "frozen_ms": FROZEN_US.load(Ordering::Relaxed) as f64 / 1000.0,
+        "max_frozen_ms": MAX_FROZEN_US.load(Ordering::Relaxed) as f64 / 1000.0,
+    })
+}
+
+pub fn freezes() -> u64 {
+    FREEZES.load(Ordering::Relaxed)
+}
+
+/// Uma invocação em andamento; o fim é registrado no drop, inclusive quando
+/// ela termina em erro.
+pub struct Invocation {
+    /// Tempo congelado estimado desde o fim da invocação anterior.
+    pub frozen: Option<Duration>,
+}
+
+impl Invocation {
+    pub fn start() -> Self {
+        let last = *LAST_END.lock().unwrap();
+        let frozen = last.and_then(|(mono, wall)| {
+            let wall = SystemTime::now().duration_since(wall).ok()?;
+            let drift = wall.checked_sub(mono.elapsed())?;
+            (drift >= CONFIG.freeze_threshold).then_some(drift)
+        });
+        if let Some(frozen) = frozen {
+            let frozen_us = frozen.as_micros() as u64;
+            FREEZES.fetch_add(1, Ordering::Relaxed);
+            FROZEN_US.fetch_add(frozen_us, Ordering::Relaxed);
+            MAX_FROZEN_US.fetch_max(frozen_us, Ordering::Relaxed);
+        }
+        Invocation { frozen }
+    }
+}
+
+impl Drop for Invocation {
+    fn drop(&mut self) {
+        *LAST_END.lock().unwrap() = Some((Instant::now(), SystemTime::now()));
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Stopwatch {
+    start: Duration,
+    wall: SystemTime,
+}
+
+impl Stopwatch {
+    pub fn start() -> Self {
+        Stopwatch { start: now(), wall: SystemTime::now() }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        now().saturating_sub(self.start)
+    }
+
+    /// Horário de parede do início.
+    pub fn started_at(&self) -> SystemTime {
+        self.wall
+    }
+
+    /// Em ms, quanto o relógio de parede andou a mais (positivo) ou a menos
+    /// que o monotônico desde o início, se passou do limite. Chame uma vez por
+    /// medição: cada salto encontrado conta no /stats.
+    pub fn wall_jump(&self) -> Option<f64> {
+        let elapsed = self.elapsed().as_secs_f64();
+        let wall = match SystemTime::now().duration_since(self.wall) {
+            Ok(forward) => forward.as_secs_f64(),
+            Err(backward) => -backward.duration().as_secs_f64(),
+        };
+        let jump = wall - elapsed;
+        if jump.abs() < CONFIG.clock_jump_threshold.as_secs_f64() {
+            return None;
+        }
+        JUMPS.fetch_add(1, Ordering::Relaxed);
+        MAX_JUMP_US.fetch_max((jump.abs() * 1e6) as u64, Ordering::Relaxed);
+        Some(jump * 1000.0)
+    }
+}
@@ -0,0 +1,237 @@
+// ======================
+// MÉTRICAS NO CLOUDWATCH (feature "aws")
+// ======================
+//
+// Com `BFF_CLOUDWATCH_NAMESPACE`, cada request HTTP entra num agregado por
+// rota (o padrão da rota, ex. `/mock/:name`, e `<unmatched>` para as que não
+// casam com nenhuma, para não criar uma série por path) e o agregado vira
+// métricas customizadas via PutMetricData:
+//
+// - `Requests` e `Errors` (status >= 500), em Count
+// - `Latency`, em Milliseconds, como StatisticSet (amostras, soma, mínimo e
+//   máximo), então uma datum por rota basta para média e pico
+//
+// Todas com a dimensão `Route`, mais `RunId`, `Scenario` e `HarnessSha`
+// quando há metadados da execução no ambiente (run.rs; os headers `X-Run-Id`
+// e afins não entram, porque cada valor novo é mais uma métrica cobrada) e
+// `Region` e `AvailabilityZone` quando se sabe onde o processo roda
+// (region.rs). No servidor local o agregado é publicado a cada
+// `BFF_CLOUDWATCH_INTERVAL_SECS`; na Lambda, ao fim de cada invocação,
+// antes da resposta sair (o ambiente congela logo depois). As datums vão em
+// lotes de até 1000, o limite do PutMetricData, e um lote que falha é
+// descartado, só contado.
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    task::{Context, Poll},
+    time::{Instant, SystemTime},
+};
+
+use aws_sdk_cloudwatch::{
+    primitives::DateTime,
+    types::{Dimension, MetricDatum, StandardUnit, StatisticSet},
+};
+use axum::{
+    body::BoxBody,
+    http::{Request, Response},
+};
+use once_cell::sync::Lazy;
+use tower::{Layer, Service};
+
+use crate::{aws, config::CONFIG, results, run};
+
+const BATCH: usize = 1000;
+
+#[derive(Default)]
+struct Route {
+    requests: u64,
+    errors: u64,
+    latency_sum_ms: f64,
+    latency_min_ms: f64,
+    latency_max_ms: f64,
+}
+
+static PENDING: Lazy<Mutex<HashMap<String, Route>>> = Lazy::new(Default::default);
+
+static CALLS: AtomicU64 = AtomicU64::new(0);
+static DATUMS: AtomicU64 = AtomicU64::new(0);
+static FAILURES: AtomicU64 = AtomicU64::new(0);
+static LAST_ERROR: Lazy<Mutex<Option<String>>> = Lazy::new(Default::default);
+
+fn enabled() -> bool {
+    !CONFIG.cloudwatch_namespace.is_empty()
+}
+
+pub fn snapshot() -> serde_json::Value {
+    serde_json::json!({
+        "namespace": CONFIG.cloudwatch_namespace,
+        "interval_secs": CONFIG.cloudwatch_interval.as_secs(),
+        "pending_routes": PENDING.lock().unwrap().len(),
+        "put_calls": CALLS.load(Ordering::Relaxed),
+        "datums": DATUMS.load(Ordering::Relaxed),
+        "failures": FAILURES.load(Ordering::Relaxed),
+        "last_error": *LAST_ERROR.lock().unwrap(),
+    })
+}
+
+const RUN_DIMENSIONS: [&str; 3] = ["RunId", "Scenario", "HarnessSha"];
+const REGION_DIMENSIONS: [&str; 2] = ["Region", "AvailabilityZone"];
+
+fn record(route: String, status: u16, latency_ms: f64) {
+    let mut pending = PENDING.lock().unwrap();
+    let entry = pending.entry(route).or_default();
+    if entry.requests == 0 || latency_ms < entry.latency_min_ms {
+        entry.latency_min_ms = latency_ms;
+    }
+    entry.latency_max_ms = entry.latency_max_ms.max(latency_ms);
+    entry.latency_sum_ms += latency_ms;
+    entry.requests += 1;
+    if status >= 500 {
+        entry.errors += 1;
+    }
+}
+
+fn datums(routes: HashMap<String, Route>) -> Vec<MetricDatum> {
+    let timestamp = DateTime::from(SystemTime::now());
+    let run = run::from_env();
+    let region = crate::region::current();
+    routes
+        .into_iter()
+        .flat_map(|(route, stats)| {
+            let mut dimensions = vec![Dimension::builder().name("Route").value(route).build()];
+            let names = RUN_DIMENSIONS.iter().chain(&REGION_DIMENSIONS);
+            for (name, value) in names.zip(run.values().into_iter().chain(region.values())) {
+                if let Some(value) = value {
+                    dimensions.push(Dimension::builder().name(*name).value(value).build());
+                }
+            }
+            let count = |name: &str, value: u64| {
+                MetricDatum::builder()
+                    .metric_name(name)
+                    .set_dimensions(Some(dimensions.clone()))
+                    .timestamp(timestamp)
+                    .unit(StandardUnit::Count)
+                    .value(value as f64)
+                    .build()
+            };
+            let latency = MetricDatum::builder()
+                .metric_name("Latency")
+                .set_dimensions(Some(dimensions.clone()))
+                .timestamp(timestamp)
+                .unit(StandardUnit::Milliseconds)
+                .statistic_values(
+                    StatisticSet::builder()
+                        .sample_count(stats.requests as f64)
+                        .sum(stats.latency_sum_ms)
+                        .minimum(stats.latency_min_ms)
+                        .maximum(stats.latency_max_ms)
+                        .build(),
+                )
+                .build();
+            [count("Requests", stats.requests), count("Errors", stats.errors), latency]
+        })
+        .collect()
+}
+
+/// Publica e zera o que foi agregado desde a última publicação.
+pub async fn flush() {
+    let routes = std::mem::take(&mut *PENDING.lock().unwrap());
+    if routes.is_empty() {
+        return;
+    }
+    let client = aws_sdk_cloudwatch::Client::new(aws::shared_config().await);
+    for batch in datums(routes).chunks(BATCH) {
+        CALLS.fetch_add(1, Ordering::Relaxed);
+        let result = client
+            .put_metric_data()
+            .namespace(&CONFIG.cloudwatch_namespace)
+            .set_metric_data(Some(batch.to_vec()))
+            .send()
+            .await;
+        match result {
+            Ok(_) => {
+                DATUMS.fetch_add(batch.len() as u64, Ordering::Relaxed);
+            }
+            Err(error) => {
+                let error = aws::describe(error);
+                eprintln!("falha ao publicar métricas no CloudWatch: {}", error);
+                FAILURES.fetch_add(1, Ordering::Relaxed);
+                *LAST_ERROR.lock().unwrap() = Some(error);
+            }
+        }
+    }
+}
+
+/// Publicação periódica do servidor local; na Lambda quem publica é o layer.
+#[cfg(not(feature = "lambda"))]
+pub fn start() {
+    static START: std::sync::Once = std::sync::Once::new();
+    START.call_once(|| {
+        if !enabled() {
+            return;
+        }
+        tokio::spawn(async {
+            let mut interval = tokio::time::interval(CONFIG.cloudwatch_interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                flush().await;
+            }
+        });
+    });
+}
+
+#[derive(Clone)]
+pub struct CloudWatchLayer;
+
+#[derive(Clone)]
+pub struct CloudWatchService<S> {
+    inner: S,
+}
+
+impl<S> Layer<S> for CloudWatchLayer {
+    type Service = CloudWatchService<S>;
+    fn layer(&self, inner: S) -> Self::Service {
+        CloudWatchService { inner }
+    }
+}
+
+impl<S, ReqBody> Service<Request<ReqBody>> for CloudWatchService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+{
+    type Response = Response<BoxBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        let mut service = self.inner.clone();
+        if !enabled() {
+            return Box::pin(async move { service.call(req).await });
+        }
+
+        let route = results::route(&req);
+        let start = Instant::now();
+
+        Box::pin(async move {
+            let response = service.call(req).await?;
+            record(route, response.status().as_u16(), start.elapsed().as_secs_f64() * 1000.0);
+            #[cfg(feature = "lambda")]
+            flush().await;
+            Ok(response)
+        })
+    }
+}
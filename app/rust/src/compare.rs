@@ -0,0 +1,187 @@
+// ======================
+// POST /compare
+// ======================
+//
+// Manda o mesmo payload para as duas variantes configuradas em
+// `BFF_COMPARE_A` e `BFF_COMPARE_B` (uma depois da outra, para uma não disputar
+// CPU com a outra quando estão na mesma máquina) e compara as respostas JSON
+// campo a campo.
+//
+// Campos de tempo não entram na comparação: chaves terminadas em `_ms`, `_us`,
+// `_ns`, `_secs` ou `_per_second`, mais as que vierem em `ignore`. Números não
+// inteiros são comparados com 6 casas decimais, como no checksum de validação.
+// Só HTTP simples.
+
+use std::time::Instant;
+
+use axum::{
+    body::{BoxBody, Body},
+    extract::Json,
+    http::{header, Method, Request, Response, StatusCode},
+    response::IntoResponse,
+};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+
+use crate::{config::CONFIG, validation};
+
+const TIMING_SUFFIXES: &[&str] = &["_ms", "_us", "_ns", "_secs", "_per_second"];
+const MAX_MISMATCHES: usize = 100;
+
+static CLIENT: Lazy<hyper::Client<hyper::client::HttpConnector>> = Lazy::new(hyper::Client::new);
+
+#[derive(Deserialize)]
+pub struct ComparePayload {
+    /// Rota nas duas variantes, ex.: `/math`.
+    path: String,
+    method: Option<String>,
+    body: Option<serde_json::Value>,
+    /// Chaves extras a ignorar, em qualquer nível.
+    #[serde(default)]
+    ignore: Vec<String>,
+}
+
+struct Reply {
+    status: u16,
+    latency_ms: f64,
+    body: Result<serde_json::Value, String>,
+}
+
+async fn call(base: &str, method: &Method, path: &str, body: &serde_json::Value) -> Result<Reply, String> {
+    let request = Request::builder()
+        .method(method.clone())
+        .uri(format!("{}{}", base.trim_end_matches('/'), path))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .map_err(|e| e.to_string())?;
+
+    let start = Instant::now();
+    let (status, bytes) = tokio::time::timeout(CONFIG.compare_timeout, async {
+        let response = CLIENT.request(request).await?;
+        let status = response.status().as_u16();
+        hyper::body::to_bytes(response.into_body()).await.map(|bytes| (status, bytes))
+    })
+    .await
+    .map_err(|_| "Timed out".to_string())?
+    .map_err(|e| e.to_string())?;
+
+    Ok(Reply {
+        status,
+        latency_ms: start.elapsed().as_secs_f64() * 1000.0,
+        body: serde_json::from_slice(&bytes).map_err(|_| String::from_utf8_lossy(&bytes).into_owned()),
+    })
+}
+
+fn ignored(key: &str, ignore: &[String]) -> bool {
+    TIMING_SUFFIXES.iter().any(|suffix| key.ends_with(suffix)) || ignore.iter().any(|k| k == key)
+}
+
+fn diff(
+    path: &str,
+    a: &serde_json::Value,
+    b: &serde_json::Value,
+    ignore: &[String],
+    out: &mut Vec<serde_json::Value>,
+) {
+    use serde_json::Value;
+
+    if out.len() >= MAX_MISMATCHES {
+        return;
+    }
+    match (a, b) {
+        (Value::Object(a), Value::Object(b)) => {
+            let mut keys: Vec<&String> = a.keys().chain(b.keys().filter(|k| !a.contains_key(*k))).collect();
+            keys.sort();
+            for key in keys.into_iter().filter(|key| !ignored(key, ignore)) {
+                let child = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                diff(&child, a.get(key).unwrap_or(&Value::Null), b.get(key).unwrap_or(&Value::Null), ignore, out);
+            }
+        }
+        (Value::Array(a), Value::Array(b)) if a.len() == b.len() => {
+            for (i, (a, b)) in a.iter().zip(b).enumerate() {
+                diff(&format!("{}[{}]", path, i), a, b, ignore, out);
+            }
+        }
+        _ => {
+            let (a, b) = (validation::round(a), validation::round(b));
+            if a != b {
+                out.push(serde_json::json!({ "path": path, "a": a, "b": b }));
+            }
+        }
+    }
+}
+
+pub async fn compare(Json(payload): Json<ComparePayload>) -> Response<BoxBody> {
+    if CONFIG.compare_a.is_empty() || CONFIG.compare_b.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "Set BFF_COMPARE_A and BFF_COMPARE_B to the variant base URLs" }))
+        )
+        .into_response();
+    }
+    if !payload.path.starts_with('/') {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "Path must start with /" }))
+        )
+        .into_response();
+    }
+    let Ok(method) = payload.method.as_deref().unwrap_or("POST").parse::<Method>() else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "Invalid method" }))
+        )
+        .into_response();
+    };
+
+    let body = payload.body.unwrap_or_else(|| serde_json::json!({}));
+    let mut replies = Vec::with_capacity(2);
+    for (name, base) in [("a", &CONFIG.compare_a), ("b", &CONFIG.compare_b)] {
+        match call(base, &method, &payload.path, &body).await {
+            Ok(reply) => replies.push(reply),
+            Err(error) => {
+                return (
+                    StatusCode::BAD_GATEWAY,
+                    Json(serde_json::json!({ "error": format!("Variant {} ({}) failed: {}", name, base, error) }))
+                )
+                .into_response();
+            }
+        }
+    }
+    let (a, b) = (&replies[0], &replies[1]);
+
+    let mut mismatches = Vec::new();
+    if a.status != b.status {
+        mismatches.push(serde_json::json!({ "path": "(status)", "a": a.status, "b": b.status }));
+    }
+    match (&a.body, &b.body) {
+        (Ok(a), Ok(b)) => diff("", a, b, &payload.ignore, &mut mismatches),
+        (a, b) => {
+            // Pelo menos um lado não é JSON: compara o texto
+            let text = |body: &Result<serde_json::Value, String>| match body {
+                Ok(value) => value.to_string(),
+                Err(text) => text.clone(),
+            };
+            if text(a) != text(b) {
+                mismatches.push(serde_json::json!({ "path": "(body)", "a": text(a), "b": text(b) }));
+            }
+        }
+    }
+
+    let side = |url: &str, reply: &Reply| {
+        serde_json::json!({ "url": url, "status": reply.status, "latency_ms": reply.latency_ms })
+    };
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "path": payload.path,
+            "equal": mismatches.is_empty(),
+            "mismatches": mismatches,
+            "truncated": mismatches.len() >= MAX_MISMATCHES,
+            "a": side(&CONFIG.compare_a, a),
+            "b": side(&CONFIG.compare_b, b),
+            "latency_delta_ms": b.latency_ms - a.latency_ms,
+        }))
+    )
+    .into_response()
+}
@@ -0,0 +1,166 @@
+// ======================
+// MIDDLEWARE: LIMITE DE CONCORRÊNCIA POR ROTA
+// ======================
+//
+// `BFF_CONCURRENCY_LIMITS=/image=2,/compress=4` deixa no máximo N requests
+// da rota rodando ao mesmo tempo; as demais esperam na fila do semáforo (FIFO)
+// em vez de competir pela CPU. É o tipo de proteção que um endpoint pesado
+// costuma ter em produção, e a espera vira dado: a resposta de quem precisou
+// esperar leva `X-Queue-Time`, e o /stats acumula as esperas por rota.
+//
+// A vaga é liberada quando o handler devolve a resposta; um corpo em stream
+// continua sendo enviado depois disso.
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    sync::atomic::{AtomicI64, AtomicU64, Ordering},
+    task::{Context, Poll},
+    time::Instant,
+};
+
+use axum::{
+    body::{Body, BoxBody},
+    http::{HeaderValue, Request, Response},
+};
+use once_cell::sync::Lazy;
+use tokio::sync::Semaphore;
+use tower::{Layer, Service};
+
+use crate::config::CONFIG;
+
+struct Limit {
+    limit: usize,
+    semaphore: Semaphore,
+    queued: AtomicI64,
+    admitted: AtomicU64,
+    waited: AtomicU64,
+    total_wait_us: AtomicU64,
+    max_wait_us: AtomicU64,
+}
+
+static LIMITS: Lazy<HashMap<String, Limit>> = Lazy::new(|| {
+    CONFIG
+        .concurrency_limits
+        .iter()
+        .map(|(path, limit)| {
+            let state = Limit {
+                limit: *limit,
+                semaphore: Semaphore::new(*limit),
+                queued: AtomicI64::new(0),
+                admitted: AtomicU64::new(0),
+                waited: AtomicU64::new(0),
+                total_wait_us: AtomicU64::new(0),
+                max_wait_us: AtomicU64::new(0),
+            };
+            (path.clone(), state)
+        })
+        .collect()
+});
+
+/// Conta a request como na fila enquanto existir; cai junto se o cliente
+/// desistir (o future é descartado) antes de conseguir a vaga.
+struct QueuedGuard<'a>(&'a AtomicI64);
+
+impl<'a> QueuedGuard<'a> {
+    fn new(queued: &'a AtomicI64) -> Self {
+        queued.fetch_add(1, Ordering::Relaxed);
+        QueuedGuard(queued)
+    }
+}
+
+impl Drop for QueuedGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+pub fn snapshot() -> serde_json::Value {
+    let routes: serde_json::Map<String, serde_json::Value> = LIMITS
+        .iter()
+        .map(|(path, limit)| {
+            let waited = limit.waited.load(Ordering::Relaxed);
+            let value = serde_json::json!({
+                "limit": limit.limit,
+                "in_flight": limit.limit - limit.semaphore.available_permits(),
+                "queued": limit.queued.load(Ordering::Relaxed),
+                "admitted": limit.admitted.load(Ordering::Relaxed),
+                "waited": waited,
+                "mean_wait_ms": if waited == 0 { 0.0 } else { limit.total_wait_us.load(Ordering::Relaxed) as f64 / waited as f64 / 1000.0 },
+                "max_wait_ms": limit.max_wait_us.load(Ordering::Relaxed) as f64 / 1000.0,
+            });
+            (path.clone(), value)
+        })
+        .collect();
+    serde_json::Value::Object(routes)
+}
+
+#[derive(Clone)]
+pub struct ConcurrencyLayer;
+
+#[derive(Clone)]
+pub struct ConcurrencyService<S> {
+    inner: S,
+}
+
+impl<S> Layer<S> for ConcurrencyLayer {
+    type Service = ConcurrencyService<S>;
+    fn layer(&self, inner: S) -> Self::Service {
+        ConcurrencyService { inner }
+    }
+}
+
+impl<S> Service<Request<Body>> for ConcurrencyService<S>
+where
+    S: Service<Request<Body>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<BoxBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let mut service = self.inner.clone();
+
+        let Some(limit) = LIMITS.get(req.uri().path()) else {
+            return Box::pin(service.call(req));
+        };
+
+        Box::pin(async move {
+            let start = Instant::now();
+            let (permit, waited) = match limit.semaphore.try_acquire() {
+                Ok(permit) => (permit, None),
+                Err(_) => {
+                    let queued = QueuedGuard::new(&limit.queued);
+                    // O semáforo nunca é fechado
+                    let permit = limit.semaphore.acquire().await.unwrap();
+                    drop(queued);
+
+                    let waited = start.elapsed();
+                    let waited_us = waited.as_micros() as u64;
+                    limit.waited.fetch_add(1, Ordering::Relaxed);
+                    limit.total_wait_us.fetch_add(waited_us, Ordering::Relaxed);
+                    limit.max_wait_us.fetch_max(waited_us, Ordering::Relaxed);
+                    (permit, Some(waited))
+                }
+            };
+            limit.admitted.fetch_add(1, Ordering::Relaxed);
+
+            let mut response = service.call(req).await?;
+            drop(permit);
+
+            if let Some(waited) = waited {
+                response.headers_mut().insert(
+                    "X-Queue-Time",
+                    HeaderValue::from_str(&format!("{:?}", waited).replace('µ', "u")).unwrap(),
+                );
+            }
+            Ok(response)
+        })
+    }
+}
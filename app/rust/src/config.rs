@@ -0,0 +1,298 @@
+// ======================
+// CONFIGURAÇÃO (variáveis de ambiente)
+// ======================
+//
+// Tudo é lido uma única vez, na primeira vez que `CONFIG` é acessado. Na
+// Lambda as variáveis vêm do `environment_variables` do Terraform; localmente
+// basta exportá-las antes de rodar. Valores inválidos caem no padrão.
+
+use std::{str::FromStr, time::Duration};
+
+use once_cell::sync::Lazy;
+
+pub struct Config {
+    pub idempotency_ttl: Duration,
+    pub idempotency_max_entries: usize,
+    #[cfg(feature = "aws")]
+    pub idempotency_table: String,
+    pub dedup_window: Duration,
+    pub dedup_short_circuit: bool,
+    pub concurrency_limits: Vec<(String, usize)>,
+    pub latency_budgets: Vec<(String, Duration)>,
+    pub scheduler_slots: usize,
+    pub adaptive_limit: String,
+    pub adaptive_initial: usize,
+    pub adaptive_min: usize,
+    pub adaptive_max: usize,
+    pub adaptive_latency: Duration,
+    pub shed_routes: Vec<(String, String)>,
+    pub shed_classes: Vec<(String, f64)>,
+    pub memory_threshold_percent: f64,
+    pub memory_guarded_routes: Vec<String>,
+    pub progress_threshold: Duration,
+    pub progress_slices: u64,
+    pub intensity_schedule: Vec<crate::intensity::Step>,
+    pub middleware: Vec<String>,
+    pub timing_format: String,
+    pub clock_jump_threshold: Duration,
+    pub content_type: String,
+    pub payload_error_status: u16,
+    pub freeze_threshold: Duration,
+    pub run_id: String,
+    pub run_scenario: String,
+    pub harness_sha: String,
+    pub calibration: bool,
+    pub calibration_interval: Duration,
+    pub cost_memory_mb: u64,
+    pub billing_align: String,
+    pub billing_granularity_ms: u64,
+    pub region: String,
+    pub availability_zone: String,
+    pub results_secret: String,
+    pub response_metrics: bool,
+    pub record_file: String,
+    #[cfg(feature = "aws")]
+    pub record_s3_bucket: String,
+    #[cfg(feature = "aws")]
+    pub record_s3_prefix: String,
+    #[cfg(feature = "aws")]
+    pub record_s3_batch: usize,
+    pub samples_dir: String,
+    pub samples_batch: usize,
+    #[cfg(feature = "aws")]
+    pub samples_s3_bucket: String,
+    #[cfg(feature = "aws")]
+    pub samples_s3_prefix: String,
+    pub shadow_url: String,
+    pub shadow_sample: f64,
+    pub shadow_timeout: Duration,
+    pub shadow_max_in_flight: usize,
+    pub compare_a: String,
+    pub compare_b: String,
+    pub compare_timeout: Duration,
+    #[cfg(feature = "external")]
+    pub external_url: String,
+    #[cfg(feature = "external")]
+    pub external_cache_ttl: Duration,
+    #[cfg(feature = "external")]
+    pub external_timeout: Duration,
+    #[cfg(feature = "aws")]
+    pub ssm_parameters: Vec<String>,
+    #[cfg(feature = "aws")]
+    pub secret_ids: Vec<String>,
+    #[cfg(feature = "aws")]
+    pub remote_config_ttl: Duration,
+    #[cfg(feature = "aws")]
+    pub cloudwatch_namespace: String,
+    #[cfg(feature = "aws")]
+    pub cloudwatch_interval: Duration,
+    #[cfg(not(feature = "lambda"))]
+    pub runtime: String,
+    #[cfg(not(feature = "lambda"))]
+    pub workers: usize,
+    #[cfg(not(feature = "lambda"))]
+    pub tcp_nodelay: bool,
+    #[cfg(not(feature = "lambda"))]
+    pub listen_backlog: i32,
+    #[cfg(not(feature = "lambda"))]
+    pub recv_buffer: usize,
+    #[cfg(not(feature = "lambda"))]
+    pub send_buffer: usize,
+    #[cfg(not(feature = "lambda"))]
+    pub max_requests_per_connection: u64,
+    #[cfg(not(feature = "lambda"))]
+    pub max_connection_age: Duration,
+    #[cfg(all(feature = "hyper-server", not(feature = "lambda")))]
+    pub server: String,
+    #[cfg(all(feature = "uring", not(feature = "lambda")))]
+    pub uring_port: u16,
+    #[cfg(feature = "lambda")]
+    pub lambda_mode: String,
+    #[cfg(feature = "lambda")]
+    pub failure_rate: f64,
+    #[cfg(feature = "lambda")]
+    pub partial_batch: bool,
+    #[cfg(feature = "lambda")]
+    pub max_receive_count: u32,
+}
+
+pub static CONFIG: Lazy<Config> = Lazy::new(|| Config {
+    idempotency_ttl: Duration::from_secs(env_or("BFF_IDEMPOTENCY_TTL_SECS", 300)),
+    idempotency_max_entries: env_or("BFF_IDEMPOTENCY_MAX_ENTRIES", 10_000),
+    #[cfg(feature = "aws")]
+    idempotency_table: env_or("BFF_IDEMPOTENCY_TABLE", String::new()),
+    dedup_window: Duration::from_millis(env_or("BFF_DEDUP_WINDOW_MS", 1_000)),
+    dedup_short_circuit: env_or("BFF_DEDUP_SHORT_CIRCUIT", false),
+    concurrency_limits: env_pairs::<usize>("BFF_CONCURRENCY_LIMITS")
+        .into_iter()
+        .filter(|(_, limit)| *limit > 0)
+        .collect(),
+    latency_budgets: env_pairs::<u64>("BFF_LATENCY_BUDGETS")
+        .into_iter()
+        .map(|(route, ms)| (route, Duration::from_millis(ms)))
+        .collect(),
+    scheduler_slots: env_or("BFF_SCHEDULER_SLOTS", 0),
+    adaptive_limit: env_or("BFF_ADAPTIVE_LIMIT", String::new()),
+    adaptive_initial: env_or("BFF_ADAPTIVE_INITIAL", 20),
+    adaptive_min: env_or::<usize>("BFF_ADAPTIVE_MIN", 1).max(1),
+    adaptive_max: env_or("BFF_ADAPTIVE_MAX", 1000),
+    adaptive_latency: Duration::from_millis(env_or("BFF_ADAPTIVE_LATENCY_MS", 100)),
+    shed_routes: env_pairs("BFF_SHED_ROUTES"),
+    shed_classes: env_pairs::<f64>("BFF_SHED_CLASSES")
+        .into_iter()
+        .map(|(class, share)| (class, share.clamp(0.0, 1.0)))
+        .collect(),
+    memory_threshold_percent: env_or("BFF_MEMORY_THRESHOLD_PERCENT", 0.0),
+    memory_guarded_routes: match env_list("BFF_MEMORY_GUARDED_ROUTES") {
+        routes if routes.is_empty() => vec!["/image".to_string()],
+        routes => routes,
+    },
+    progress_threshold: Duration::from_millis(env_or("BFF_PROGRESS_THRESHOLD_MS", 1_000)),
+    progress_slices: env_or::<u64>("BFF_PROGRESS_SLICES", 10).clamp(1, 100),
+    intensity_schedule: {
+        let mut steps: Vec<_> = env_list("BFF_INTENSITY_SCHEDULE")
+            .iter()
+            .filter_map(|item| crate::intensity::parse_step(item))
+            .collect();
+        steps.sort_by_key(|step| step.at);
+        steps
+    },
+    middleware: env_list("BFF_MIDDLEWARE"),
+    timing_format: env_or("BFF_TIMING_FORMAT", "verbose".to_string()),
+    clock_jump_threshold: Duration::from_millis(env_or("BFF_CLOCK_JUMP_THRESHOLD_MS", 50)),
+    content_type: env_or("BFF_CONTENT_TYPE", "strict".to_string()),
+    payload_error_status: env_or("BFF_PAYLOAD_ERROR_STATUS", 422),
+    freeze_threshold: Duration::from_millis(env_or("BFF_FREEZE_THRESHOLD_MS", 50)),
+    run_id: env_or("BFF_RUN_ID", String::new()),
+    run_scenario: env_or("BFF_RUN_SCENARIO", String::new()),
+    harness_sha: env_or("BFF_HARNESS_SHA", String::new()),
+    calibration: env_or("BFF_CALIBRATION", true),
+    calibration_interval: Duration::from_secs(env_or("BFF_CALIBRATION_INTERVAL_SECS", 0)),
+    cost_memory_mb: env_or("BFF_COST_MEMORY_MB", 0),
+    billing_align: env_or("BFF_BILLING_ALIGN", "off".to_string()),
+    billing_granularity_ms: env_or("BFF_BILLING_GRANULARITY_MS", 1),
+    region: env_or(
+        "BFF_REGION",
+        std::env::var("AWS_REGION").or_else(|_| std::env::var("AWS_DEFAULT_REGION")).unwrap_or_default(),
+    ),
+    availability_zone: env_or("BFF_AVAILABILITY_ZONE", String::new()),
+    results_secret: env_or("BFF_RESULTS_SECRET", String::new()),
+    response_metrics: env_or("BFF_RESPONSE_METRICS", false),
+    record_file: env_or("BFF_RECORD_FILE", String::new()),
+    #[cfg(feature = "aws")]
+    record_s3_bucket: env_or("BFF_RECORD_S3_BUCKET", String::new()),
+    #[cfg(feature = "aws")]
+    record_s3_prefix: env_or("BFF_RECORD_S3_PREFIX", "recordings/".to_string()),
+    #[cfg(feature = "aws")]
+    record_s3_batch: env_or("BFF_RECORD_S3_BATCH", 100),
+    samples_dir: env_or("BFF_SAMPLES_DIR", String::new()),
+    samples_batch: env_or("BFF_SAMPLES_BATCH", 10_000),
+    #[cfg(feature = "aws")]
+    samples_s3_bucket: env_or("BFF_SAMPLES_S3_BUCKET", String::new()),
+    #[cfg(feature = "aws")]
+    samples_s3_prefix: env_or("BFF_SAMPLES_S3_PREFIX", "samples/".to_string()),
+    shadow_url: env_or("BFF_SHADOW_URL", String::new()),
+    shadow_sample: env_or("BFF_SHADOW_SAMPLE", 1.0),
+    shadow_timeout: Duration::from_millis(env_or("BFF_SHADOW_TIMEOUT_MS", 5000)),
+    shadow_max_in_flight: env_or("BFF_SHADOW_MAX_IN_FLIGHT", 64),
+    compare_a: env_or("BFF_COMPARE_A", String::new()),
+    compare_b: env_or("BFF_COMPARE_B", String::new()),
+    compare_timeout: Duration::from_millis(env_or("BFF_COMPARE_TIMEOUT_MS", 30000)),
+    #[cfg(feature = "external")]
+    external_url: env_or("BFF_EXTERNAL_URL", "https://httpbin.org/json".to_string()),
+    #[cfg(feature = "external")]
+    external_cache_ttl: Duration::from_secs(env_or("BFF_EXTERNAL_CACHE_TTL_SECS", 60)),
+    #[cfg(feature = "external")]
+    external_timeout: Duration::from_millis(env_or("BFF_EXTERNAL_TIMEOUT_MS", 5_000)),
+    #[cfg(feature = "aws")]
+    ssm_parameters: env_list("BFF_SSM_PARAMETERS"),
+    #[cfg(feature = "aws")]
+    secret_ids: env_list("BFF_SECRET_IDS"),
+    #[cfg(feature = "aws")]
+    remote_config_ttl: Duration::from_secs(env_or("BFF_REMOTE_CONFIG_TTL_SECS", 300)),
+    #[cfg(feature = "aws")]
+    cloudwatch_namespace: env_or("BFF_CLOUDWATCH_NAMESPACE", String::new()),
+    #[cfg(feature = "aws")]
+    cloudwatch_interval: Duration::from_secs(env_or::<u64>("BFF_CLOUDWATCH_INTERVAL_SECS", 60).max(1)),
+    #[cfg(not(feature = "lambda"))]
+    runtime: env_or("BFF_RUNTIME", "work-stealing".to_string()),
+    #[cfg(not(feature = "lambda"))]
+    workers: env_or("BFF_WORKERS", 0),
+    #[cfg(not(feature = "lambda"))]
+    tcp_nodelay: env_or("BFF_TCP_NODELAY", false),
+    #[cfg(not(feature = "lambda"))]
+    listen_backlog: env_or("BFF_LISTEN_BACKLOG", 1024),
+    #[cfg(not(feature = "lambda"))]
+    recv_buffer: env_or("BFF_RECV_BUFFER_BYTES", 0),
+    #[cfg(not(feature = "lambda"))]
+    send_buffer: env_or("BFF_SEND_BUFFER_BYTES", 0),
+    #[cfg(not(feature = "lambda"))]
+    max_requests_per_connection: env_or("BFF_MAX_REQUESTS_PER_CONNECTION", 0),
+    #[cfg(not(feature = "lambda"))]
+    max_connection_age: Duration::from_secs(env_or("BFF_MAX_CONNECTION_AGE_SECS", 0)),
+    #[cfg(all(feature = "hyper-server", not(feature = "lambda")))]
+    server: env_or("BFF_SERVER", "axum".to_string()),
+    #[cfg(all(feature = "uring", not(feature = "lambda")))]
+    uring_port: env_or("BFF_URING_PORT", 3001),
+    #[cfg(feature = "lambda")]
+    lambda_mode: env_or("BFF_LAMBDA_MODE", "http".to_string()),
+    #[cfg(feature = "lambda")]
+    failure_rate: env_or("BFF_FAILURE_RATE", 0.0),
+    #[cfg(feature = "lambda")]
+    partial_batch: env_or("BFF_PARTIAL_BATCH", true),
+    #[cfg(feature = "lambda")]
+    max_receive_count: env_or("BFF_MAX_RECEIVE_COUNT", 3),
+});
+
+fn env_or<T: FromStr>(name: &str, default: T) -> T {
+    std::env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Lista separada por vírgulas; vazia se a variável não existir.
+fn env_list(name: &str) -> Vec<String> {
+    std::env::var(name)
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Pares `chave=valor` separados por vírgulas; itens inválidos são ignorados.
+fn env_pairs<T: FromStr>(name: &str) -> Vec<(String, T)> {
+    env_list(name)
+        .into_iter()
+        .filter_map(|item| {
+            let (key, value) = item.split_once('=')?;
+            Some((key.trim().to_string(), value.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+/// Hash (SHA-256, 16 primeiros dígitos hex) de todas as variáveis `BFF_*`,
+/// ordenadas por nome. Só depende do ambiente, então as variantes das outras
+/// linguagens calculam o mesmo valor para a mesma configuração. A config
+/// remota (SSM/Secrets Manager) não entra: ela chega depois e pode mudar.
+pub static CONFIG_HASH: Lazy<String> = Lazy::new(|| {
+    use sha2::{Digest, Sha256};
+
+    // Os metadados da execução (run.rs) mudam a cada rodada e ficam de fora,
+    // assim como a região (region.rs)
+    let mut vars: Vec<(String, String)> = std::env::vars()
+        .filter(|(name, _)| {
+            name.starts_with("BFF_")
+                && !crate::run::ENV_VARS.contains(&name.as_str())
+                && !crate::region::ENV_VARS.contains(&name.as_str())
+        })
+        .collect();
+    vars.sort();
+    let mut hasher = Sha256::new();
+    for (name, value) in vars {
+        hasher.update(format!("{}={}\n", name, value));
+    }
+    hasher.finalize()[..8].iter().map(|b| format!("{:02x}", b)).collect()
+});
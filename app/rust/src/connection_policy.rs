@@ -0,0 +1,93 @@
+// ======================
+// POLÍTICA DE CONEXÕES KEEP-ALIVE
+// ======================
+//
+// Cada conexão aceita pelo servidor local ganha o seu próprio
+// `ConnectionPolicy` em volta do app. Ao atingir `BFF_MAX_REQUESTS_PER_CONNECTION`
+// requests ou `BFF_MAX_CONNECTION_AGE_SECS` segundos de vida, a resposta sai
+// com `Connection: close` e o hyper fecha a conexão depois de enviá-la. Assim
+// o load balancer (ou o cliente) precisa reconectar, e dá para estudar o
+// rebalanceamento em execuções longas. Zero desliga o limite.
+//
+// A idade só é conferida quando chega uma request: uma conexão ociosa além do
+// limite fecha na próxima resposta, não antes.
+
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::atomic::{AtomicU64, Ordering},
+    task::{Context, Poll},
+    time::Instant,
+};
+
+use axum::http::{HeaderValue, Request, Response};
+use tower::Service;
+
+use crate::config::CONFIG;
+
+static CONNECTIONS: AtomicU64 = AtomicU64::new(0);
+static CLOSED_BY_REQUESTS: AtomicU64 = AtomicU64::new(0);
+static CLOSED_BY_AGE: AtomicU64 = AtomicU64::new(0);
+
+pub fn snapshot() -> serde_json::Value {
+    serde_json::json!({
+        "accepted": CONNECTIONS.load(Ordering::Relaxed),
+        "closed_by_requests": CLOSED_BY_REQUESTS.load(Ordering::Relaxed),
+        "closed_by_age": CLOSED_BY_AGE.load(Ordering::Relaxed),
+        "max_requests": CONFIG.max_requests_per_connection,
+        "max_age_secs": CONFIG.max_connection_age.as_secs(),
+    })
+}
+
+#[derive(Clone)]
+pub struct ConnectionPolicy<S> {
+    inner: S,
+    opened: Instant,
+    requests: u64,
+}
+
+impl<S> ConnectionPolicy<S> {
+    /// Um por conexão aceita.
+    pub fn new(inner: S) -> Self {
+        CONNECTIONS.fetch_add(1, Ordering::Relaxed);
+        ConnectionPolicy { inner, opened: Instant::now(), requests: 0 }
+    }
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for ConnectionPolicy<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Send,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        self.requests += 1;
+        let max_requests = CONFIG.max_requests_per_connection;
+        let max_age = CONFIG.max_connection_age;
+        let close = if max_requests > 0 && self.requests >= max_requests {
+            CLOSED_BY_REQUESTS.fetch_add(1, Ordering::Relaxed);
+            true
+        } else if !max_age.is_zero() && self.opened.elapsed() >= max_age {
+            CLOSED_BY_AGE.fetch_add(1, Ordering::Relaxed);
+            true
+        } else {
+            false
+        };
+
+        let future = self.inner.call(req);
+        Box::pin(async move {
+            let mut response = future.await?;
+            if close {
+                response.headers_mut().insert("Connection", HeaderValue::from_static("close"));
+            }
+            Ok(response)
+        })
+    }
+}
@@ -0,0 +1,107 @@
+// ======================
+// MIDDLEWARE: CONTENT-TYPE E ERROS DE PAYLOAD
+// ======================
+//
+// O extractor `Json` do axum recusa corpo sem `Content-Type: application/json`
+// (415) e responde as rejeições em texto puro, com 400 para JSON inválido e
+// 422 para JSON que não bate com o payload. As outras variantes não fazem
+// igual: o gin do Go ignora o Content-Type e devolve 400 em JSON; o Flask
+// devolve 415 e 400. Para comparar os caminhos de erro entre linguagens:
+//
+// - `BFF_CONTENT_TYPE=strict` (padrão) mantém o 415; `lenient` trata qualquer
+//   corpo de POST/PUT/PATCH como JSON, como o Go
+// - `BFF_PAYLOAD_ERROR_STATUS` troca o 422 (ex.: 400, como Go e Python)
+// - as rejeições sempre viram `{"error": "..."}`, o mesmo formato dos erros
+//   dos handlers
+//
+// Só respostas de erro em `text/plain` são reescritas, e só os extractors do
+// axum respondem assim.
+
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{header, HeaderValue, Method, Request, Response, StatusCode},
+    response::IntoResponse,
+};
+use tower::{Layer, Service};
+
+use crate::config::CONFIG;
+
+fn is_json(value: &HeaderValue) -> bool {
+    let Ok(value) = value.to_str() else { return false };
+    let mime = value.split(';').next().unwrap_or("").trim().to_ascii_lowercase();
+    mime == "application/json" || (mime.starts_with("application/") && mime.ends_with("+json"))
+}
+
+async fn rewrite(response: Response<BoxBody>) -> Response<BoxBody> {
+    let status = response.status();
+    let plain = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/plain"));
+    if !plain || !matches!(status.as_u16(), 400 | 413 | 415 | 422) {
+        return response;
+    }
+
+    let message = match hyper::body::to_bytes(response.into_body()).await {
+        Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+        Err(_) => status.canonical_reason().unwrap_or("Invalid request").to_string(),
+    };
+    let status = if status == StatusCode::UNPROCESSABLE_ENTITY {
+        StatusCode::from_u16(CONFIG.payload_error_status).unwrap_or(status)
+    } else {
+        status
+    };
+    (status, Json(serde_json::json!({ "error": message }))).into_response()
+}
+
+#[derive(Clone)]
+pub struct ContentTypeLayer;
+
+#[derive(Clone)]
+pub struct ContentTypeService<S> {
+    inner: S,
+}
+
+impl<S> Layer<S> for ContentTypeLayer {
+    type Service = ContentTypeService<S>;
+    fn layer(&self, inner: S) -> Self::Service {
+        ContentTypeService { inner }
+    }
+}
+
+impl<S, ReqBody> Service<Request<ReqBody>> for ContentTypeService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+{
+    type Response = Response<BoxBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<ReqBody>) -> Self::Future {
+        let mut service = self.inner.clone();
+
+        let has_body = matches!(*req.method(), Method::POST | Method::PUT | Method::PATCH);
+        if CONFIG.content_type == "lenient" && has_body && !req.headers().get(header::CONTENT_TYPE).is_some_and(is_json) {
+            req.headers_mut().insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        }
+
+        Box::pin(async move {
+            let response = service.call(req).await?;
+            Ok(rewrite(response).await)
+        })
+    }
+}
@@ -0,0 +1,130 @@
+// ======================
+// CONVERSÃO ENTRE JSON, YAML E TOML
+// ======================
+//
+// Traduz `text` do formato `from` para o `to` (`json`, `yaml` ou `toml`),
+// passando por um `serde_json::Value`. Mede separadamente a leitura e a
+// escrita, que é onde os serializadores de cada linguagem se diferenciam.
+//
+// Nem tudo atravessa: TOML não tem null, e o documento precisa ser uma tabela
+// no topo; YAML com chaves que não são texto não vira JSON. Nesses casos a
+// resposta é 400. Datas do TOML viram strings RFC 3339. `pretty` vale para a
+// saída JSON (YAML e TOML já saem indentados).
+
+use std::time::Instant;
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use serde_json::Value;
+
+const SAMPLE: &str = r#"{"service":"checkout","replicas":3,"canary":false,"limits":{"cpu":0.5,"memory_mb":512},"routes":[{"path":"/cart","timeout_ms":800},{"path":"/pay","timeout_ms":2500}]}"#;
+
+const FORMATS: [&str; 3] = ["json", "yaml", "toml"];
+
+#[derive(Deserialize)]
+pub struct ConvertPayload {
+    text: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    pretty: Option<bool>,
+}
+
+fn bad_request(error: String) -> Response<BoxBody> {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({ "error": error }))
+    )
+    .into_response()
+}
+
+fn from_toml(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(text) => Value::String(text),
+        toml::Value::Integer(n) => n.into(),
+        toml::Value::Float(n) => n.into(),
+        toml::Value::Boolean(b) => b.into(),
+        toml::Value::Datetime(datetime) => Value::String(datetime.to_string()),
+        toml::Value::Array(items) => Value::Array(items.into_iter().map(from_toml).collect()),
+        toml::Value::Table(table) => Value::Object(table.into_iter().map(|(k, v)| (k, from_toml(v))).collect()),
+    }
+}
+
+fn read(format: &str, text: &str) -> Result<Value, String> {
+    match format {
+        "json" => serde_json::from_str(text).map_err(|e| format!("Invalid JSON: {}", e)),
+        "yaml" => serde_yaml::from_str(text).map_err(|e| format!("Invalid YAML: {}", e)),
+        _ => text
+            .parse::<toml::Table>()
+            .map(|table| from_toml(toml::Value::Table(table)))
+            .map_err(|e| format!("Invalid TOML: {}", e)),
+    }
+}
+
+fn has_null(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Array(items) => items.iter().any(has_null),
+        Value::Object(fields) => fields.values().any(has_null),
+        _ => false,
+    }
+}
+
+fn write(format: &str, value: &Value, pretty: bool) -> Result<String, String> {
+    match format {
+        "json" if pretty => serde_json::to_string_pretty(value).map_err(|e| e.to_string()),
+        "json" => serde_json::to_string(value).map_err(|e| e.to_string()),
+        "yaml" => serde_yaml::to_string(value).map_err(|e| format!("Cannot write YAML: {}", e)),
+        _ => {
+            if !value.is_object() {
+                return Err("Cannot write TOML: the document must be an object".to_string());
+            }
+            if has_null(value) {
+                return Err("Cannot write TOML: TOML has no null".to_string());
+            }
+            toml::to_string(value).map_err(|e| format!("Cannot write TOML: {}", e))
+        }
+    }
+}
+
+pub async fn convert(Json(payload): Json<ConvertPayload>) -> Response<BoxBody> {
+    let from = payload.from.as_deref().unwrap_or("json");
+    let to = payload.to.as_deref().unwrap_or("yaml");
+    for format in [from, to] {
+        if !FORMATS.contains(&format) {
+            return bad_request(format!("from and to must be json, yaml or toml, got {}", format));
+        }
+    }
+    let text = payload.text.as_deref().unwrap_or(SAMPLE);
+
+    let start = Instant::now();
+    let value = match read(from, text) {
+        Ok(value) => value,
+        Err(error) => return bad_request(error),
+    };
+    let parse_duration = start.elapsed();
+    let start = Instant::now();
+    let output = match write(to, &value, payload.pretty.unwrap_or(false)) {
+        Ok(output) => output,
+        Err(error) => return bad_request(error),
+    };
+    let serialize_duration = start.elapsed();
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "from": from,
+            "to": to,
+            "input_bytes": text.len(),
+            "output_bytes": output.len(),
+            "output": output,
+            "parse_ms": parse_duration.as_secs_f64() * 1000.0,
+            "serialize_ms": serialize_duration.as_secs_f64() * 1000.0,
+        }))
+    )
+    .into_response()
+}
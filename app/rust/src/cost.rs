@@ -0,0 +1,92 @@
+// ======================
+// ESTIMATIVA DE CUSTO POR INVOCAÇÃO
+// ======================
+//
+// O número que todo mundo pede no fim de um benchmark é quanto custa cada
+// request, e hoje ele sai de uma planilha. Aqui a conta é a da Lambda sob
+// demanda: duração cobrada (a do endpoint, arredondada para cima na
+// granularidade de cobrança de billing.rs, 1 ms por padrão) × memória em GB ×
+// preço do GB-segundo da arquitetura, mais o preço por request. Os preços
+// abaixo são os públicos de us-east-1, sem free tier nem desconto por volume;
+// outras regiões custam um pouco mais, então é uma estimativa para comparar,
+// não a fatura.
+//
+// A memória vem de `AWS_LAMBDA_FUNCTION_MEMORY_SIZE` na Lambda ou de
+// `BFF_COST_MEMORY_MB` fora dela (0, o padrão, desliga a estimativa). Com ela,
+// cada resposta leva `X-Estimated-Cost-USD`, o /results soma o custo por rota
+// e o modelo usado, e as amostras cruas ganham a coluna do custo.
+
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+
+use crate::config::CONFIG;
+
+/// Preço da Lambda de uma arquitetura, em USD.
+pub struct Pricing {
+    pub architecture: &'static str,
+    pub per_gb_second: f64,
+    pub per_request: f64,
+}
+
+pub const PRICING: [Pricing; 2] = [
+    Pricing { architecture: "x86_64", per_gb_second: 0.000_016_666_7, per_request: 0.000_000_2 },
+    Pricing { architecture: "arm64", per_gb_second: 0.000_013_333_4, per_request: 0.000_000_2 },
+];
+
+pub struct Model {
+    pub memory_mb: u64,
+    pub source: &'static str,
+    pub pricing: &'static Pricing,
+}
+
+impl Model {
+    /// Custo de uma invocação com `billed_ms` cobrados.
+    pub fn invocation_usd(&self, billed_ms: u64) -> f64 {
+        let gb_seconds = self.memory_mb as f64 / 1024.0 * billed_ms as f64 / 1000.0;
+        gb_seconds * self.pricing.per_gb_second + self.pricing.per_request
+    }
+}
+
+/// Preço da arquitetura em que o processo roda.
+pub fn pricing() -> &'static Pricing {
+    let architecture = if std::env::consts::ARCH == "aarch64" { "arm64" } else { "x86_64" };
+    PRICING.iter().find(|p| p.architecture == architecture).unwrap()
+}
+
+pub static MODEL: Lazy<Option<Model>> = Lazy::new(|| {
+    let lambda = std::env::var("AWS_LAMBDA_FUNCTION_MEMORY_SIZE").ok().and_then(|v| v.parse::<u64>().ok());
+    let (memory_mb, source) = match lambda {
+        Some(mb) => (mb, "lambda"),
+        None => (CONFIG.cost_memory_mb, "config"),
+    };
+    if memory_mb == 0 {
+        return None;
+    }
+    Some(Model { memory_mb, source, pricing: pricing() })
+});
+
+/// Duração cobrada, em ms.
+pub fn billed_ms(duration: Duration) -> u64 {
+    crate::billing::billed(duration).as_millis() as u64
+}
+
+/// Custo estimado de uma invocação com essa duração, se houver modelo.
+pub fn estimate(duration: Duration) -> Option<f64> {
+    MODEL.as_ref().map(|model| model.invocation_usd(billed_ms(duration)))
+}
+
+/// O modelo de custo em uso, para o /results.
+pub fn snapshot() -> serde_json::Value {
+    match MODEL.as_ref() {
+        Some(model) => serde_json::json!({
+            "memory_mb": model.memory_mb,
+            "memory_source": model.source,
+            "architecture": model.pricing.architecture,
+            "usd_per_gb_second": model.pricing.per_gb_second,
+            "usd_per_request": model.pricing.per_request,
+            "pricing_region": "us-east-1",
+        }),
+        None => serde_json::Value::Null,
+    }
+}
@@ -0,0 +1,292 @@
+// ======================
+// CRIPTOGRAFIA (AES-256-GCM E ED25519)
+// ======================
+//
+// POST /crypto/aes cifra `data` (ou `size` bytes gerados a partir da seed) com AES-256-GCM,
+// `iterations` vezes, cada uma com um nonce aleatório novo de 96 bits, e
+// decifra de volta o último texto cifrado outras `iterations` vezes, conferindo
+// que o resultado é igual ao original. `decrypt: false` mede só a cifragem.
+//
+// A chave vem de `key` (32 bytes em base64) ou da seed. O nonce sai do
+// gerador do sistema operacional, como em produção, então o texto cifrado
+// muda a cada request.
+//
+// POST /crypto/sign assina `message` com Ed25519 `iterations` vezes e confere
+// a assinatura. Com `mode: "static"` (padrão) o par de chaves é gerado uma vez
+// por processo, na primeira request, que é a única a pagar `keygen_ms`: o custo
+// de geração fica no cold start e o resto mede só assinatura. `mode: "fresh"`
+// gera um par novo a cada request. Com `public_key` e `signature` (base64),
+// só confere a assinatura recebida.
+
+use std::time::{Duration, Instant};
+
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit},
+    Aes256Gcm, Key,
+};
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use base64::{engine::general_purpose, Engine as _};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use once_cell::sync::OnceCell;
+use rand_core::OsRng;
+use serde::Deserialize;
+
+use crate::{
+    estimate::{self, Estimate},
+    progress::Progress,
+    rng::{self, SplitMix64},
+};
+
+const MAX_SIZE: usize = 16 * 1024 * 1024;
+const MAX_ITERATIONS: usize = 100_000;
+// Bytes cifrados no total (tamanho × iterações)
+const MAX_TOTAL_BYTES: usize = 4 * 1024 * 1024 * 1024;
+
+#[derive(Deserialize)]
+pub struct AesPayload {
+    data: Option<String>,
+    size: Option<usize>,
+    key: Option<String>,
+    decrypt: Option<bool>,
+    iterations: Option<usize>,
+    seed: Option<u64>,
+    dry_run: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct SignPayload {
+    message: Option<String>,
+    mode: Option<String>,
+    iterations: Option<usize>,
+    public_key: Option<String>,
+    signature: Option<String>,
+}
+
+pub async fn aes(Json(payload): Json<AesPayload>) -> Response<BoxBody> {
+    let iterations = payload.iterations.unwrap_or(1);
+    if iterations == 0 || iterations > MAX_ITERATIONS {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": format!("iterations must be between 1 and {}", MAX_ITERATIONS) }))
+        )
+        .into_response();
+    }
+
+    let mut rng = SplitMix64::new(rng::seed(payload.seed));
+    let plaintext = match payload.data {
+        Some(data) => data.into_bytes(),
+        None => {
+            let size = payload.size.unwrap_or(1024);
+            if size > MAX_SIZE {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({ "error": format!("size must be at most {}", MAX_SIZE) }))
+                )
+                .into_response();
+            }
+            (0..size).map(|_| rng.next_u64() as u8).collect()
+        }
+    };
+    if plaintext.len().saturating_mul(iterations) > MAX_TOTAL_BYTES {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "data size * iterations exceeds 4 GiB" }))
+        )
+        .into_response();
+    }
+
+    let key: [u8; 32] = match payload.key {
+        Some(key) => match general_purpose::STANDARD.decode(key).ok().and_then(|k| k.try_into().ok()) {
+            Some(key) => key,
+            None => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({ "error": "key must be 32 bytes in base64" }))
+                )
+                .into_response();
+            }
+        },
+        None => {
+            let mut key = [0u8; 32];
+            key.chunks_mut(8).for_each(|chunk| chunk.copy_from_slice(&rng.next_u64().to_le_bytes()));
+            key
+        }
+    };
+    if payload.dry_run.unwrap_or(false) {
+        let decrypt = payload.decrypt.unwrap_or(true);
+        let bytes = plaintext.len() as u64;
+        return estimate::response(Estimate {
+            iterations: bytes * iterations as u64 * if decrypt { 2 } else { 1 },
+            unit: "bytes processed",
+            // Texto, texto cifrado (+ tag de 16 bytes) e, no decrypt, o decifrado
+            allocation_bytes: bytes * if decrypt { 3 } else { 2 } + 16,
+            output_bytes: 300 + (bytes + 16).div_ceil(3) * 4,
+        });
+    }
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+
+    let decrypt = payload.decrypt.unwrap_or(true);
+    // Cifrar e decifrar contam como uma fila só de operações
+    let mut progress = Progress::new((iterations * if decrypt { 2 } else { 1 }) as u64);
+    let start = Instant::now();
+    let mut encrypted = None;
+    for i in 0..iterations {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        // Só falha com mais de 64 GiB de texto
+        encrypted = Some((cipher.encrypt(&nonce, plaintext.as_slice()).unwrap(), nonce));
+        progress.tick(i as u64 + 1);
+    }
+    let (ciphertext, nonce) = encrypted.unwrap();
+    let encrypt_duration = start.elapsed();
+
+    let mut verified = None;
+    let mut decrypt_ms = None;
+    if decrypt {
+        let start = Instant::now();
+        let mut matched = true;
+        for i in 0..iterations {
+            matched &= cipher.decrypt(&nonce, ciphertext.as_slice()).is_ok_and(|decrypted| decrypted == plaintext);
+            progress.tick((iterations + i) as u64 + 1);
+        }
+        decrypt_ms = Some(start.elapsed().as_secs_f64() * 1000.0);
+        verified = Some(matched);
+    }
+
+    let encrypt_ms = encrypt_duration.as_secs_f64() * 1000.0;
+    let mut body = serde_json::json!({
+        "bytes": plaintext.len(),
+        "iterations": iterations,
+        "ciphertext": general_purpose::STANDARD.encode(&ciphertext),
+        "nonce": general_purpose::STANDARD.encode(nonce),
+        "verified": verified,
+        "encrypt_ms": encrypt_ms,
+        "decrypt_ms": decrypt_ms,
+        "roundtrip_ms": encrypt_ms + decrypt_ms.unwrap_or(0.0),
+        "encrypt_mb_per_second": (plaintext.len() * iterations) as f64 / 1e6 / encrypt_duration.as_secs_f64(),
+    });
+    if let Some(report) = progress.report() {
+        body["progress"] = report;
+    }
+    (StatusCode::OK, Json(body)).into_response()
+}
+
+// ------------
+// Ed25519
+// ------------
+static STATIC_KEY: OnceCell<SigningKey> = OnceCell::new();
+
+fn generate_key() -> (SigningKey, Duration) {
+    let start = Instant::now();
+    let key = SigningKey::generate(&mut OsRng);
+    (key, start.elapsed())
+}
+
+fn verify_only(message: &[u8], public_key: &str, signature: &str) -> Response<BoxBody> {
+    let decode = |value: &str| general_purpose::STANDARD.decode(value).ok();
+    let public_key = decode(public_key)
+        .and_then(|bytes| bytes.try_into().ok())
+        .and_then(|bytes: [u8; 32]| VerifyingKey::from_bytes(&bytes).ok());
+    let signature = decode(signature).and_then(|bytes| Signature::from_slice(&bytes).ok());
+    let (Some(public_key), Some(signature)) = (public_key, signature) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "public_key must be 32 bytes and signature 64 bytes, in base64" }))
+        )
+        .into_response();
+    };
+
+    let start = Instant::now();
+    let verified = public_key.verify(message, &signature).is_ok();
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "verified": verified,
+            "verify_ms": start.elapsed().as_secs_f64() * 1000.0,
+        }))
+    )
+    .into_response()
+}
+
+pub async fn sign(Json(payload): Json<SignPayload>) -> Response<BoxBody> {
+    let message = payload.message.unwrap_or_else(|| "hello world".to_string());
+    match (&payload.public_key, &payload.signature) {
+        (Some(public_key), Some(signature)) => return verify_only(message.as_bytes(), public_key, signature),
+        (None, None) => {}
+        _ => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Pass both public_key and signature, or neither" }))
+            )
+            .into_response();
+        }
+    }
+    let iterations = payload.iterations.unwrap_or(1);
+    if iterations == 0 || iterations > MAX_ITERATIONS {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": format!("iterations must be between 1 and {}", MAX_ITERATIONS) }))
+        )
+        .into_response();
+    }
+
+    let mode = payload.mode.unwrap_or_else(|| "static".to_string());
+    // keygen só é Some para quem gerou a chave nesta request
+    let (key, keygen) = match mode.as_str() {
+        "static" => {
+            let mut keygen = None;
+            let key = STATIC_KEY.get_or_init(|| {
+                let (key, duration) = generate_key();
+                keygen = Some(duration);
+                key
+            });
+            (key.clone(), keygen)
+        }
+        "fresh" => {
+            let (key, duration) = generate_key();
+            (key, Some(duration))
+        }
+        other => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": format!("Unsupported mode: {}", other) }))
+            )
+            .into_response();
+        }
+    };
+
+    let start = Instant::now();
+    let mut signature = key.sign(message.as_bytes());
+    for _ in 1..iterations {
+        signature = key.sign(std::hint::black_box(message.as_bytes()));
+    }
+    let sign_duration = start.elapsed();
+
+    let public_key = key.verifying_key();
+    let start = Instant::now();
+    let mut verified = true;
+    for _ in 0..iterations {
+        verified &= public_key.verify(std::hint::black_box(message.as_bytes()), &signature).is_ok();
+    }
+    let verify_duration = start.elapsed();
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "mode": mode,
+            "iterations": iterations,
+            "public_key": general_purpose::STANDARD.encode(public_key.as_bytes()),
+            "signature": general_purpose::STANDARD.encode(signature.to_bytes()),
+            "verified": verified,
+            "generated_key": keygen.is_some(),
+            "keygen_ms": keygen.map(|d| d.as_secs_f64() * 1000.0).unwrap_or(0.0),
+            "sign_ms": sign_duration.as_secs_f64() * 1000.0,
+            "verify_ms": verify_duration.as_secs_f64() * 1000.0,
+        }))
+    )
+    .into_response()
+}
@@ -0,0 +1,227 @@
+// ======================
+// PARSING E TRANSFORMAÇÃO DE CSV
+// ======================
+//
+// Lê `text` como CSV (primeira linha é o cabeçalho, `delimiter` de um byte,
+// "," por padrão) e aplica, nesta ordem: `filter` (coluna, operação e valor),
+// `sort` (coluna, `descending`) e `aggregate` (sum, avg, min, max ou count de
+// uma coluna, opcionalmente por `group_by`). As linhas que sobram voltam como
+// objetos JSON, até `limit`. Ao contrário do /string, que é regex sobre um
+// texto só, aqui o custo é alocar um registro por linha e os campos dele.
+//
+// Os valores continuam texto. Comparação e agregação usam o número quando os
+// dois lados são numéricos e o texto caso contrário; na agregação, campo não
+// numérico é ignorado (só `count` conta todas as linhas).
+
+use std::{cmp::Ordering, collections::BTreeMap, time::Instant};
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+
+const MAX_LIMIT: usize = 10_000;
+
+const SAMPLE: &str = "region,product,units,price
+south,keyboard,12,49.90
+north,mouse,40,19.90
+south,monitor,3,899.00
+east,keyboard,7,49.90
+north,monitor,5,899.00
+east,mouse,22,19.90
+";
+
+#[derive(Deserialize)]
+pub struct CsvPayload {
+    text: Option<String>,
+    delimiter: Option<String>,
+    filter: Option<Filter>,
+    sort: Option<Sort>,
+    aggregate: Option<Aggregate>,
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct Filter {
+    column: String,
+    op: Option<String>,
+    value: String,
+}
+
+#[derive(Deserialize)]
+struct Sort {
+    column: String,
+    descending: Option<bool>,
+}
+
+#[derive(Deserialize)]
+struct Aggregate {
+    function: String,
+    column: Option<String>,
+    group_by: Option<String>,
+}
+
+fn bad_request(error: String) -> Response<BoxBody> {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({ "error": error }))
+    )
+    .into_response()
+}
+
+/// Número quando os dois lados são numéricos, texto caso contrário.
+fn compare(a: &str, b: &str) -> Ordering {
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        (Ok(x), Ok(y)) => x.total_cmp(&y),
+        _ => a.cmp(b),
+    }
+}
+
+fn aggregate(function: &str, values: &[&str]) -> serde_json::Value {
+    let numbers = values.iter().filter_map(|v| v.trim().parse::<f64>().ok());
+    match function {
+        "count" => values.len().into(),
+        "sum" => numbers.sum::<f64>().into(),
+        "avg" => {
+            let (sum, count) = numbers.fold((0.0, 0usize), |(sum, count), n| (sum + n, count + 1));
+            if count == 0 { serde_json::Value::Null } else { (sum / count as f64).into() }
+        }
+        "min" => numbers.reduce(f64::min).into(),
+        _ => numbers.reduce(f64::max).into(),
+    }
+}
+
+pub async fn csvparse(Json(payload): Json<CsvPayload>) -> Response<BoxBody> {
+    let text = payload.text.as_deref().unwrap_or(SAMPLE);
+    let delimiter = match payload.delimiter.as_deref().unwrap_or(",").as_bytes() {
+        [byte] => *byte,
+        _ => return bad_request("delimiter must be a single byte".to_string()),
+    };
+    let limit = payload.limit.unwrap_or(100);
+    if limit > MAX_LIMIT {
+        return bad_request(format!("limit must be at most {}", MAX_LIMIT));
+    }
+    if let Some(spec) = &payload.aggregate {
+        if !["sum", "avg", "min", "max", "count"].contains(&spec.function.as_str()) {
+            return bad_request(format!("aggregate function must be sum, avg, min, max or count, got {}", spec.function));
+        }
+        if spec.column.is_none() && spec.function != "count" {
+            return bad_request(format!("aggregate function {} needs a column", spec.function));
+        }
+    }
+
+    let start = Instant::now();
+    let mut reader = csv::ReaderBuilder::new().delimiter(delimiter).from_reader(text.as_bytes());
+    let columns: Vec<String> = match reader.headers() {
+        Ok(headers) => headers.iter().map(str::to_string).collect(),
+        Err(error) => return bad_request(format!("Invalid CSV: {}", error)),
+    };
+    let mut records = Vec::new();
+    for record in reader.records() {
+        match record {
+            Ok(record) => records.push(record),
+            Err(error) => return bad_request(format!("Invalid CSV: {}", error)),
+        }
+    }
+    let parse_duration = start.elapsed();
+    let rows = records.len();
+
+    let index = |name: &str| columns.iter().position(|c| c == name).ok_or(format!("Unknown column: {}", name));
+    let start = Instant::now();
+    if let Some(filter) = &payload.filter {
+        let column = match index(&filter.column) {
+            Ok(column) => column,
+            Err(error) => return bad_request(error),
+        };
+        let op = filter.op.as_deref().unwrap_or("eq");
+        let keep: fn(&str, &str) -> bool = match op {
+            "eq" => |a, b| compare(a, b) == Ordering::Equal,
+            "ne" => |a, b| compare(a, b) != Ordering::Equal,
+            "lt" => |a, b| compare(a, b) == Ordering::Less,
+            "le" => |a, b| compare(a, b) != Ordering::Greater,
+            "gt" => |a, b| compare(a, b) == Ordering::Greater,
+            "ge" => |a, b| compare(a, b) != Ordering::Less,
+            "contains" => |a, b| a.contains(b),
+            other => return bad_request(format!("filter op must be eq, ne, lt, le, gt, ge or contains, got {}", other)),
+        };
+        records.retain(|record| keep(record.get(column).unwrap_or(""), &filter.value));
+    }
+    if let Some(sort) = &payload.sort {
+        let column = match index(&sort.column) {
+            Ok(column) => column,
+            Err(error) => return bad_request(error),
+        };
+        // Estável nos dois sentidos: empates ficam na ordem do arquivo
+        let descending = sort.descending.unwrap_or(false);
+        records.sort_by(|a, b| {
+            let order = compare(a.get(column).unwrap_or(""), b.get(column).unwrap_or(""));
+            if descending { order.reverse() } else { order }
+        });
+    }
+
+    let mut body = serde_json::json!({
+        "columns": columns,
+        "rows": rows,
+        "matched": records.len(),
+    });
+    if let Some(spec) = &payload.aggregate {
+        let lookup = |name: &Option<String>| name.as_deref().map(index).transpose();
+        let (column, group_by) = match (lookup(&spec.column), lookup(&spec.group_by)) {
+            (Ok(column), Ok(group_by)) => (column, group_by),
+            (Err(error), _) | (_, Err(error)) => return bad_request(error),
+        };
+        let mut groups: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for record in &records {
+            let key = group_by.map_or("", |g| record.get(g).unwrap_or(""));
+            groups.entry(key).or_default().push(column.map_or("", |c| record.get(c).unwrap_or("")));
+        }
+        let result: Vec<_> = groups
+            .iter()
+            .map(|(key, values)| {
+                let mut group = serde_json::json!({ "count": values.len(), "value": aggregate(&spec.function, values) });
+                if let Some(name) = &spec.group_by {
+                    group[name.as_str()] = serde_json::json!(key);
+                }
+                group
+            })
+            .collect();
+        body["aggregate"] = serde_json::json!(result);
+    } else {
+        let result: Vec<serde_json::Map<String, serde_json::Value>> = records
+            .iter()
+            .take(limit)
+            .map(|record| columns.iter().cloned().zip(record.iter().map(serde_json::Value::from)).collect())
+            .collect();
+        body["result"] = serde_json::json!(result);
+    }
+    let transform_duration = start.elapsed();
+
+    body["parse_ms"] = serde_json::json!(parse_duration.as_secs_f64() * 1000.0);
+    body["transform_ms"] = serde_json::json!(transform_duration.as_secs_f64() * 1000.0);
+    body["rows_per_second"] = serde_json::json!(rows as f64 / parse_duration.as_secs_f64());
+    (StatusCode::OK, Json(body)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_compare_numerically() {
+        assert_eq!(compare("9", "10"), Ordering::Less);
+        assert_eq!(compare(" 2.5", "2.50 "), Ordering::Equal);
+        assert_eq!(compare("-1", "0"), Ordering::Less);
+        assert_eq!(compare("1e3", "999"), Ordering::Greater);
+    }
+
+    #[test]
+    fn anything_else_compares_as_text() {
+        assert_eq!(compare("9", "10a"), Ordering::Greater);
+        assert_eq!(compare("apple", "banana"), Ordering::Less);
+        assert_eq!(compare("", "0"), Ordering::Less);
+        assert_eq!(compare("b", "b"), Ordering::Equal);
+    }
+}
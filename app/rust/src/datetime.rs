@@ -0,0 +1,158 @@
+// ======================
+// PARSE DE DATAS E CONVERSÃO DE FUSO
+// ======================
+//
+// Recebe (ou gera a partir da seed) um lote de timestamps em formatos
+// misturados, converte cada um para os fusos pedidos e formata de volta.
+
+use std::time::Instant;
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::Deserialize;
+
+use crate::rng::{self, SplitMix64};
+
+const MAX_TIMESTAMPS: usize = 1_000_000;
+
+#[derive(Deserialize)]
+pub struct DatetimePayload {
+    timestamps: Option<Vec<String>>,
+    count: Option<usize>,
+    seed: Option<u64>,
+    timezones: Option<Vec<String>>,
+    format: Option<String>,
+}
+
+/// Tenta, em ordem: RFC 3339, RFC 2822, epoch em segundos e alguns formatos
+/// "ingênuos" (sem fuso), que são interpretados como UTC.
+fn parse_timestamp(input: &str) -> Option<DateTime<Utc>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(input) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc2822(input) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    if !input.is_empty() && input.bytes().all(|b| b.is_ascii_digit()) {
+        return input.parse().ok().and_then(|secs| Utc.timestamp_opt(secs, 0).single());
+    }
+    for format in ["%Y-%m-%d %H:%M:%S", "%d/%m/%Y %H:%M", "%Y%m%dT%H%M%S"] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(input, format) {
+            return Some(Utc.from_utc_datetime(&naive));
+        }
+    }
+    NaiveDate::parse_from_str(input, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|naive| Utc.from_utc_datetime(&naive))
+}
+
+/// Gera timestamps entre 2000 e 2030 alternando entre os formatos aceitos.
+fn generate_timestamps(count: usize, seed: u64) -> Vec<String> {
+    let mut rng = SplitMix64::new(seed);
+    let start = 946_684_800; // 2000-01-01T00:00:00Z
+    let span = 946_684_800; // ~30 anos
+    (0..count)
+        .map(|i| {
+            let secs = start + (rng.next_f64() * span as f64) as i64;
+            let dt = Utc.timestamp_opt(secs, 0).unwrap();
+            match i % 6 {
+                0 => dt.to_rfc3339(),
+                1 => dt.to_rfc2822(),
+                2 => secs.to_string(),
+                3 => dt.format("%Y-%m-%d %H:%M:%S").to_string(),
+                4 => dt.format("%d/%m/%Y %H:%M").to_string(),
+                _ => dt.format("%Y%m%dT%H%M%S").to_string(),
+            }
+        })
+        .collect()
+}
+
+pub async fn datetime(Json(payload): Json<DatetimePayload>) -> Response<BoxBody> {
+    let timezone_names = payload.timezones.clone().unwrap_or_else(|| {
+        ["UTC", "America/Sao_Paulo", "Europe/Berlin", "Asia/Tokyo"]
+            .iter()
+            .map(|tz| tz.to_string())
+            .collect()
+    });
+    let mut timezones = Vec::with_capacity(timezone_names.len());
+    for name in &timezone_names {
+        match name.parse::<Tz>() {
+            Ok(tz) => timezones.push(tz),
+            Err(_) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({ "error": format!("Unknown timezone: {}", name) }))
+                )
+                .into_response()
+            }
+        }
+    }
+
+    let count = payload
+        .timestamps
+        .as_ref()
+        .map(|t| t.len())
+        .unwrap_or(payload.count.unwrap_or(10_000));
+    if count > MAX_TIMESTAMPS {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "Too many timestamps" }))
+        )
+        .into_response();
+    }
+    let timestamps = payload
+        .timestamps
+        .unwrap_or_else(|| generate_timestamps(count, rng::seed(payload.seed)));
+
+    let format = payload.format.as_deref().unwrap_or("%Y-%m-%dT%H:%M:%S%:z");
+    // Valida o formato antes do loop: o chrono só acusa erro na hora de formatar
+    if chrono::format::StrftimeItems::new(format).any(|item| item == chrono::format::Item::Error) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "Invalid format string" }))
+        )
+        .into_response();
+    }
+
+    let start = Instant::now();
+    let mut parsed = 0usize;
+    let mut failed = 0usize;
+    let mut output_bytes = 0usize;
+    let mut sample = Vec::new();
+    for input in &timestamps {
+        let Some(dt) = parse_timestamp(input.trim()) else {
+            failed += 1;
+            continue;
+        };
+        parsed += 1;
+        for tz in &timezones {
+            let formatted = dt.with_timezone(tz).format(format).to_string();
+            output_bytes += formatted.len();
+            if sample.len() < 5 {
+                sample.push(formatted);
+            }
+        }
+    }
+    let duration = start.elapsed();
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "parsed": parsed,
+            "failed": failed,
+            "conversions": parsed * timezones.len(),
+            "output_bytes": output_bytes,
+            "sample": sample,
+            "duration_ms": duration.as_secs_f64() * 1000.0,
+            "timestamps_per_second": timestamps.len() as f64 / duration.as_secs_f64(),
+        }))
+    )
+    .into_response()
+}
@@ -0,0 +1,138 @@
+// ======================
+// ARITMÉTICA DECIMAL (moeda)
+// ======================
+//
+// Soma, cálculo de imposto e conversão de moeda com `rust_decimal`, sempre
+// arredondando para centavos. Os totais devolvidos servem de checksum para
+// comparar o resultado com as outras linguagens.
+
+use std::{str::FromStr, time::Instant};
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::Deserialize;
+
+use crate::rng::{self, SplitMix64};
+
+const MAX_AMOUNTS: usize = 1_000_000;
+
+#[derive(Deserialize)]
+pub struct DecimalPayload {
+    amounts: Option<Vec<String>>,
+    count: Option<usize>,
+    seed: Option<u64>,
+    vat_rate: Option<String>,
+    fx_rate: Option<String>,
+    rounding: Option<String>,
+}
+
+pub async fn decimal(Json(payload): Json<DecimalPayload>) -> Response<BoxBody> {
+    let vat_rate = payload.vat_rate.as_deref().unwrap_or("0.23");
+    let fx_rate = payload.fx_rate.as_deref().unwrap_or("5.4321");
+    let (Ok(vat_rate), Ok(fx_rate)) = (Decimal::from_str(vat_rate), Decimal::from_str(fx_rate)) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "Invalid vat_rate or fx_rate" }))
+        )
+        .into_response();
+    };
+
+    let rounding = match payload.rounding.as_deref().unwrap_or("half_even") {
+        "half_even" => RoundingStrategy::MidpointNearestEven,
+        "half_up" => RoundingStrategy::MidpointAwayFromZero,
+        "down" => RoundingStrategy::ToZero,
+        _ => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Unsupported rounding" }))
+            )
+            .into_response()
+        }
+    };
+
+    let count = payload
+        .amounts
+        .as_ref()
+        .map(|a| a.len())
+        .unwrap_or(payload.count.unwrap_or(100_000));
+    if count == 0 || count > MAX_AMOUNTS {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "Between 1 and 1000000 amounts are required" }))
+        )
+        .into_response();
+    }
+
+    let amounts: Vec<Decimal> = match &payload.amounts {
+        Some(amounts) => match amounts.iter().map(|a| Decimal::from_str(a)).collect() {
+            Ok(amounts) => amounts,
+            Err(_) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({ "error": "Invalid amount" }))
+                )
+                .into_response()
+            }
+        },
+        None => {
+            let mut rng = SplitMix64::new(rng::seed(payload.seed));
+            // Valores entre 0,01 e 1.000,00
+            (0..count)
+                .map(|_| Decimal::new(1 + (rng.next_f64() * 100_000.0) as i64, 2))
+                .collect()
+        }
+    };
+
+    let start = Instant::now();
+    // Operações checadas: valores enviados pelo cliente podem estourar os 96 bits
+    let totals = amounts.iter().try_fold(
+        (Decimal::ZERO, Decimal::ZERO, Decimal::ZERO),
+        |(net, vat, converted), amount| {
+            // Cada linha é arredondada antes de somar, como numa nota fiscal
+            let line_vat = amount.checked_mul(vat_rate)?.round_dp_with_strategy(2, rounding);
+            let line_converted = amount
+                .checked_add(line_vat)?
+                .checked_mul(fx_rate)?
+                .round_dp_with_strategy(2, rounding);
+            Some((
+                net.checked_add(*amount)?,
+                vat.checked_add(line_vat)?,
+                converted.checked_add(line_converted)?,
+            ))
+        },
+    );
+    let Some((net, vat, converted)) = totals else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "Decimal overflow" }))
+        )
+        .into_response();
+    };
+    let Some(gross) = net.checked_add(vat) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "Decimal overflow" }))
+        )
+        .into_response();
+    };
+    let duration = start.elapsed();
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "count": amounts.len(),
+            "net": net.to_string(),
+            "vat": vat.to_string(),
+            "gross": gross.to_string(),
+            "converted": converted.to_string(),
+            "duration_ms": duration.as_secs_f64() * 1000.0,
+            "operations_per_second": (amounts.len() * 4) as f64 / duration.as_secs_f64(),
+        }))
+    )
+    .into_response()
+}
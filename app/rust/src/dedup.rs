@@ -0,0 +1,155 @@
+// ======================
+// MIDDLEWARE: DETECÇÃO DE DUPLICADAS
+// ======================
+//
+// Guarda o hash (método + path + corpo) de cada request de escrita numa janela
+// deslizante de `BFF_DEDUP_WINDOW_MS`. Uma request cujo hash já está na janela
+// conta como duplicada; com `BFF_DEDUP_SHORT_CIRCUIT=true` ela é respondida com
+// 409 sem chegar ao handler. Serve tanto como feature quanto para conferir se o
+// gerador de carga está mandando corpos variados de verdade.
+
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, VecDeque},
+    future::Future,
+    hash::{Hash, Hasher},
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    task::{Context, Poll},
+    time::Instant,
+};
+
+use axum::{
+    body::{Body, BoxBody},
+    extract::Json,
+    http::{Method, Request, Response, StatusCode},
+    response::IntoResponse,
+};
+use once_cell::sync::Lazy;
+use tower::{Layer, Service};
+
+use crate::{body_limit, config::CONFIG};
+
+// Limite de hashes na janela, para não crescer sem controle sob carga alta
+const MAX_TRACKED: usize = 100_000;
+
+#[derive(Clone)]
+pub struct DedupLayer;
+
+#[derive(Clone)]
+pub struct DedupService<S> {
+    inner: S,
+}
+
+impl<S> Layer<S> for DedupLayer {
+    type Service = DedupService<S>;
+    fn layer(&self, inner: S) -> Self::Service {
+        DedupService { inner }
+    }
+}
+
+#[derive(Default)]
+struct Window {
+    order: VecDeque<(Instant, u64)>,
+    counts: HashMap<u64, u32>,
+}
+
+impl Window {
+    fn expire(&mut self, now: Instant) {
+        while let Some(&(seen_at, hash)) = self.order.front() {
+            if now.duration_since(seen_at) < CONFIG.dedup_window && self.order.len() < MAX_TRACKED {
+                break;
+            }
+            self.order.pop_front();
+            if let Some(count) = self.counts.get_mut(&hash) {
+                *count -= 1;
+                if *count == 0 {
+                    self.counts.remove(&hash);
+                }
+            }
+        }
+    }
+
+    /// Registra o hash e devolve se ele já estava na janela.
+    fn observe(&mut self, hash: u64) -> bool {
+        let now = Instant::now();
+        self.expire(now);
+        self.order.push_back((now, hash));
+        let count = self.counts.entry(hash).or_default();
+        *count += 1;
+        *count > 1
+    }
+}
+
+static WINDOW: Lazy<Mutex<Window>> = Lazy::new(|| Mutex::new(Window::default()));
+
+static SEEN: AtomicU64 = AtomicU64::new(0);
+static DUPLICATES: AtomicU64 = AtomicU64::new(0);
+static SHORT_CIRCUITED: AtomicU64 = AtomicU64::new(0);
+
+pub fn snapshot() -> serde_json::Value {
+    let seen = SEEN.load(Ordering::Relaxed);
+    let duplicates = DUPLICATES.load(Ordering::Relaxed);
+    serde_json::json!({
+        "window_ms": CONFIG.dedup_window.as_millis() as u64,
+        "short_circuit": CONFIG.dedup_short_circuit,
+        "tracked": WINDOW.lock().unwrap().order.len(),
+        "seen": seen,
+        "duplicates": duplicates,
+        "short_circuited": SHORT_CIRCUITED.load(Ordering::Relaxed),
+        "duplicate_rate": if seen == 0 { 0.0 } else { duplicates as f64 / seen as f64 },
+    })
+}
+
+impl<S> Service<Request<Body>> for DedupService<S>
+where
+    S: Service<Request<Body>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<BoxBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let mut service = self.inner.clone();
+
+        let safe_method = matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS);
+        if safe_method || CONFIG.dedup_window.is_zero() {
+            return Box::pin(service.call(req));
+        }
+
+        Box::pin(async move {
+            let (parts, body) = match body_limit::read(req).await {
+                Ok(read) => read,
+                Err(response) => return Ok(response),
+            };
+
+            let mut hasher = DefaultHasher::new();
+            parts.method.hash(&mut hasher);
+            parts.uri.path().hash(&mut hasher);
+            body.hash(&mut hasher);
+            let duplicate = WINDOW.lock().unwrap().observe(hasher.finish());
+
+            SEEN.fetch_add(1, Ordering::Relaxed);
+            if duplicate {
+                DUPLICATES.fetch_add(1, Ordering::Relaxed);
+                if CONFIG.dedup_short_circuit {
+                    SHORT_CIRCUITED.fetch_add(1, Ordering::Relaxed);
+                    return Ok((
+                        StatusCode::CONFLICT,
+                        Json(serde_json::json!({ "error": "Duplicate request within dedup window" }))
+                    )
+                    .into_response());
+                }
+            }
+
+            service.call(Request::from_parts(parts, Body::from(body))).await
+        })
+    }
+}
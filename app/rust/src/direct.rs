@@ -0,0 +1,105 @@
+// ======================
+// MODO DIRETO (feature "lambda")
+// ======================
+//
+// Com `BFF_LAMBDA_MODE=direct`, nem lambda_http nem o Router do axum entram
+// no caminho: o evento `{"workload": "...", "payload": {...}}` vai direto do
+// lambda_runtime para o registro de workloads, e a resposta da invocação é o
+// próprio corpo que a rota HTTP devolveria. Comparado ao modo `http` com o
+// mesmo workload, a diferença é o custo da camada de adaptação HTTP.
+//
+// Cada invocação loga uma linha JSON com o tempo esperando o próximo evento
+// (do fim da invocação anterior até esta começar: enviar a resposta e o long
+// poll do `/runtime/invocation/next`, incluindo o tempo congelado entre
+// invocações) e o tempo tratando, mais os totais acumulados de ocioso/ocupado.
+
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+
+use lambda_runtime::{service_fn, Error, LambdaEvent};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+
+use crate::{clock, registry};
+
+#[derive(Deserialize)]
+struct Invocation {
+    workload: String,
+    #[serde(default)]
+    payload: Option<serde_json::Value>,
+}
+
+static INVOCATIONS: AtomicU64 = AtomicU64::new(0);
+static IDLE_US: AtomicU64 = AtomicU64::new(0);
+static BUSY_US: AtomicU64 = AtomicU64::new(0);
+
+// Fim da última invocação (ou início do loop do runtime, antes da primeira)
+static LAST_END: Lazy<Mutex<Duration>> = Lazy::new(|| Mutex::new(clock::now()));
+
+pub fn snapshot() -> serde_json::Value {
+    let idle_us = IDLE_US.load(Ordering::Relaxed);
+    let busy_us = BUSY_US.load(Ordering::Relaxed);
+    let total_us = idle_us + busy_us;
+    serde_json::json!({
+        "invocations": INVOCATIONS.load(Ordering::Relaxed),
+        "idle_ms": idle_us as f64 / 1000.0,
+        "busy_ms": busy_us as f64 / 1000.0,
+        "idle_ratio": if total_us == 0 { 0.0 } else { idle_us as f64 / total_us as f64 },
+        "busy_ratio": if total_us == 0 { 0.0 } else { busy_us as f64 / total_us as f64 },
+    })
+}
+
+async fn handle(event: LambdaEvent<serde_json::Value>) -> Result<serde_json::Value, Error> {
+    let invocation = clock::Invocation::start();
+    let start = clock::now();
+    let poll_us = start.saturating_sub(*LAST_END.lock().unwrap()).as_micros() as u64;
+
+    let result = invoke(event).await;
+
+    let end = clock::now();
+    let handle_us = end.saturating_sub(start).as_micros() as u64;
+    *LAST_END.lock().unwrap() = end;
+    INVOCATIONS.fetch_add(1, Ordering::Relaxed);
+    IDLE_US.fetch_add(poll_us, Ordering::Relaxed);
+    BUSY_US.fetch_add(handle_us, Ordering::Relaxed);
+    let mut line = serde_json::json!({
+        "poll_ms": poll_us as f64 / 1000.0,
+        "handle_ms": handle_us as f64 / 1000.0,
+        "frozen_ms": invocation.frozen.map(|frozen| frozen.as_secs_f64() * 1000.0),
+        "totals": snapshot(),
+    });
+    crate::run::annotate(&mut line);
+    println!("{}", line);
+
+    result
+}
+
+async fn invoke(event: LambdaEvent<serde_json::Value>) -> Result<serde_json::Value, Error> {
+    let invocation: Invocation =
+        serde_json::from_value(event.payload).map_err(|e| format!("Invalid invocation: {}", e))?;
+    let workload =
+        registry::find(&invocation.workload).ok_or_else(|| format!("Unknown workload: {}", invocation.workload))?;
+    let payload = invocation
+        .payload
+        .unwrap_or_else(|| serde_json::from_str(workload.default_payload).unwrap());
+
+    let response = (workload.run)(payload).await;
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body()).await?;
+    let output = serde_json::from_slice(&body)
+        .unwrap_or_else(|_| serde_json::Value::String(String::from_utf8_lossy(&body).into_owned()));
+    if !status.is_success() {
+        return Err(format!("Workload {} returned {}: {}", workload.name, status, output).into());
+    }
+    Ok(output)
+}
+
+pub async fn run() -> Result<(), Error> {
+    Lazy::force(&LAST_END);
+    lambda_runtime::run(service_fn(handle)).await
+}
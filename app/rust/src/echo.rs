@@ -0,0 +1,87 @@
+// ======================
+// ECHO E STREAM
+// ======================
+//
+// Endpoints de I/O quase puro, para medir o caminho de rede do servidor:
+// - POST /echo devolve o corpo recebido, byte a byte
+// - GET  /stream?chunks=N&chunk_size=M responde com N chunks de M bytes
+//   (Transfer-Encoding: chunked), cada um enviado assim que fica pronto
+//
+// O servidor io_uring (feature "uring") responde os mesmos dois endpoints com
+// os mesmos limites.
+
+use axum::{
+    body::{boxed, BoxBody, Bytes, Full},
+    extract::{Json, Query},
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+
+pub const MAX_CHUNKS: usize = 100_000;
+pub const MAX_CHUNK_SIZE: usize = 1 << 20;
+pub const MAX_STREAM_BYTES: usize = 256 << 20;
+
+#[derive(Deserialize)]
+pub struct StreamQuery {
+    pub chunks: Option<usize>,
+    pub chunk_size: Option<usize>,
+}
+
+impl StreamQuery {
+    /// `(chunks, chunk_size)` com os padrões aplicados, ou o erro de validação.
+    pub fn resolve(&self) -> Result<(usize, usize), &'static str> {
+        let chunks = self.chunks.unwrap_or(16);
+        let chunk_size = self.chunk_size.unwrap_or(4096);
+        if chunks == 0 || chunks > MAX_CHUNKS {
+            return Err("chunks must be between 1 and 100000");
+        }
+        if chunk_size == 0 || chunk_size > MAX_CHUNK_SIZE {
+            return Err("chunk_size must be between 1 and 1048576");
+        }
+        if chunks * chunk_size > MAX_STREAM_BYTES {
+            return Err("chunks * chunk_size must be at most 256 MiB");
+        }
+        Ok((chunks, chunk_size))
+    }
+}
+
+/// Conteúdo de um chunk: bytes imprimíveis, diferentes a cada chunk.
+pub fn chunk(index: usize, size: usize) -> Bytes {
+    Bytes::from((0..size).map(|i| b'a' + ((index + i) % 26) as u8).collect::<Vec<_>>())
+}
+
+pub async fn echo(body: Bytes) -> Response<BoxBody> {
+    Response::builder()
+        .header("Content-Type", "application/octet-stream")
+        .body(boxed(Full::from(body)))
+        .unwrap()
+}
+
+pub async fn stream(Query(query): Query<StreamQuery>) -> Response<BoxBody> {
+    let (chunks, chunk_size) = match query.resolve() {
+        Ok(params) => params,
+        Err(error) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": error }))
+            )
+            .into_response()
+        }
+    };
+
+    let (mut sender, body) = hyper::Body::channel();
+    tokio::spawn(async move {
+        for i in 0..chunks {
+            // Cliente desconectou: não há para quem mandar o resto
+            if sender.send_data(chunk(i, chunk_size)).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    Response::builder()
+        .header("Content-Type", "application/octet-stream")
+        .body(boxed(body))
+        .unwrap()
+}
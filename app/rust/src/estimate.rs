@@ -0,0 +1,45 @@
+// ======================
+// DRY RUN: ESTIMATIVA SEM EXECUTAR
+// ======================
+//
+// Os workloads pesados aceitam `dry_run: true`: o payload passa pelas mesmas
+// validações (um payload inválido leva o mesmo 400) e, em vez de rodar, o
+// handler devolve uma estimativa do custo. O harness usa isso para conferir um
+// arquivo de cenário inteiro antes de uma execução cara na nuvem.
+//
+// - `iterations`: unidades de trabalho no laço principal (hashes, elementos
+//   ordenados, multiplicações, ...); `unit` diz qual
+// - `allocation_bytes`: memória que o workload aloca para os dados
+// - `output_bytes`: tamanho aproximado do corpo da resposta
+//
+// São ordens de grandeza para comparar cenários, não previsões exatas.
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+
+pub struct Estimate {
+    pub iterations: u64,
+    pub unit: &'static str,
+    pub allocation_bytes: u64,
+    pub output_bytes: u64,
+}
+
+pub fn response(estimate: Estimate) -> Response<BoxBody> {
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "dry_run": true,
+            "estimate": {
+                "iterations": estimate.iterations,
+                "unit": estimate.unit,
+                "allocation_bytes": estimate.allocation_bytes,
+                "output_bytes": estimate.output_bytes,
+            },
+        }))
+    )
+    .into_response()
+}
@@ -0,0 +1,246 @@
+// ======================
+// MODOS DE EVENTO: SQS / KINESIS (feature "lambda")
+// ======================
+//
+// Com `BFF_LAMBDA_MODE=sqs` ou `kinesis`, a Lambda não passa pelo lambda_http:
+// recebe o lote de registros direto do lambda_runtime. Cada registro traz
+// `{"workload": "...", "payload": {...}}` e é executado pelo registro de
+// workloads.
+//
+// Falhas, para medir o caminho de erro:
+// - `"poison": true` no registro sempre falha (mensagem venenosa)
+// - `BFF_FAILURE_RATE` faz falhar uma fração dos registros; o sorteio usa o id
+//   do registro e a tentativa, então uma nova tentativa pode dar certo
+//
+// Com `BFF_PARTIAL_BATCH=true` (padrão) a resposta é um
+// `batchItemFailures` (ReportBatchItemFailures) e só os registros que falharam
+// voltam; sem ela, qualquer falha derruba o lote inteiro. Um registro que
+// falha na tentativa `BFF_MAX_RECEIVE_COUNT` é contado como enviado à DLQ
+// (quem envia de fato é o SQS/event source mapping).
+
+use std::{
+    collections::HashMap,
+    hash::{Hash, Hasher},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::Instant,
+};
+
+use lambda_runtime::{service_fn, Error, LambdaEvent};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+
+use crate::{clock, config::CONFIG, registry, rng::SplitMix64};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Source {
+    Sqs,
+    Kinesis,
+}
+
+#[derive(Deserialize)]
+struct SqsEvent {
+    #[serde(rename = "Records")]
+    records: Vec<SqsRecord>,
+}
+
+#[derive(Deserialize)]
+struct SqsRecord {
+    #[serde(rename = "messageId")]
+    message_id: String,
+    body: String,
+    #[serde(default)]
+    attributes: HashMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct KinesisEvent {
+    #[serde(rename = "Records")]
+    records: Vec<KinesisRecord>,
+}
+
+#[derive(Deserialize)]
+struct KinesisRecord {
+    kinesis: KinesisData,
+}
+
+#[derive(Deserialize)]
+struct KinesisData {
+    #[serde(rename = "sequenceNumber")]
+    sequence_number: String,
+    data: String,
+}
+
+#[derive(Deserialize)]
+struct Task {
+    workload: String,
+    #[serde(default)]
+    payload: Option<serde_json::Value>,
+    #[serde(default)]
+    poison: bool,
+}
+
+// Registro já normalizado, independente da origem
+struct Item {
+    id: String,
+    attempt: u32,
+    body: Result<String, String>,
+}
+
+static PROCESSED: AtomicU64 = AtomicU64::new(0);
+static FAILED: AtomicU64 = AtomicU64::new(0);
+static RETRIES: AtomicU64 = AtomicU64::new(0);
+static DLQ_SENDS: AtomicU64 = AtomicU64::new(0);
+static BATCHES: AtomicU64 = AtomicU64::new(0);
+static BATCH_FAILURES: AtomicU64 = AtomicU64::new(0);
+
+// O Kinesis não informa a tentativa; contamos pelas vezes que o mesmo
+// sequence number falhou neste processo, até ele dar certo ou ir para a DLQ
+static KINESIS_ATTEMPTS: Lazy<Mutex<HashMap<String, u32>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+pub fn snapshot() -> serde_json::Value {
+    serde_json::json!({
+        "mode": CONFIG.lambda_mode,
+        "batches": BATCHES.load(Ordering::Relaxed),
+        "processed": PROCESSED.load(Ordering::Relaxed),
+        "failed": FAILED.load(Ordering::Relaxed),
+        "retries": RETRIES.load(Ordering::Relaxed),
+        "dlq_sends": DLQ_SENDS.load(Ordering::Relaxed),
+        "whole_batch_failures": BATCH_FAILURES.load(Ordering::Relaxed),
+    })
+}
+
+fn items(source: Source, event: serde_json::Value) -> Result<Vec<Item>, Error> {
+    match source {
+        Source::Sqs => {
+            let event: SqsEvent = serde_json::from_value(event)?;
+            Ok(event
+                .records
+                .into_iter()
+                .map(|record| Item {
+                    attempt: record
+                        .attributes
+                        .get("ApproximateReceiveCount")
+                        .and_then(|count| count.parse().ok())
+                        .unwrap_or(1),
+                    id: record.message_id,
+                    body: Ok(record.body),
+                })
+                .collect())
+        }
+        Source::Kinesis => {
+            use base64::{engine::general_purpose, Engine as _};
+
+            let event: KinesisEvent = serde_json::from_value(event)?;
+            let attempts = KINESIS_ATTEMPTS.lock().unwrap();
+            Ok(event
+                .records
+                .into_iter()
+                .map(|record| {
+                    let data = record.kinesis.data;
+                    let id = record.kinesis.sequence_number;
+                    Item {
+                        attempt: attempts.get(&id).map_or(1, |failures| failures + 1),
+                        body: general_purpose::STANDARD
+                            .decode(data)
+                            .map_err(|_| "data is not valid base64".to_string())
+                            .and_then(|bytes| String::from_utf8(bytes).map_err(|_| "data is not UTF-8".to_string())),
+                        id,
+                    }
+                })
+                .collect())
+        }
+    }
+}
+
+// Falha sorteada de forma determinística por (registro, tentativa)
+fn injected_failure(item: &Item) -> bool {
+    if CONFIG.failure_rate <= 0.0 {
+        return false;
+    }
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    item.id.hash(&mut hasher);
+    item.attempt.hash(&mut hasher);
+    SplitMix64::new(hasher.finish()).next_f64() < CONFIG.failure_rate
+}
+
+async fn process(item: &Item) -> Result<(), String> {
+    let task: Task = serde_json::from_str(item.body.as_ref()?).map_err(|e| format!("Invalid task: {}", e))?;
+    if task.poison {
+        return Err("Poison message".into());
+    }
+    if injected_failure(item) {
+        return Err("Injected failure".into());
+    }
+    let workload = registry::find(&task.workload).ok_or_else(|| format!("Unknown workload: {}", task.workload))?;
+    let payload = task
+        .payload
+        .unwrap_or_else(|| serde_json::from_str(workload.default_payload).unwrap());
+    let response = (workload.run)(payload).await;
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("Workload returned {}", response.status()))
+    }
+}
+
+async fn handle(source: Source, event: LambdaEvent<serde_json::Value>) -> Result<serde_json::Value, Error> {
+    let invocation = clock::Invocation::start();
+    let start = Instant::now();
+    let items = items(source, event.payload)?;
+    BATCHES.fetch_add(1, Ordering::Relaxed);
+
+    let mut failures = Vec::new();
+    for item in &items {
+        PROCESSED.fetch_add(1, Ordering::Relaxed);
+        if item.attempt > 1 {
+            RETRIES.fetch_add(1, Ordering::Relaxed);
+        }
+        let result = process(item).await;
+        if source == Source::Kinesis {
+            let mut attempts = KINESIS_ATTEMPTS.lock().unwrap();
+            // Depois da última tentativa o registro não volta mais
+            if result.is_ok() || item.attempt >= CONFIG.max_receive_count {
+                attempts.remove(&item.id);
+            } else {
+                attempts.insert(item.id.clone(), item.attempt);
+            }
+        }
+        if let Err(error) = result {
+            FAILED.fetch_add(1, Ordering::Relaxed);
+            if item.attempt >= CONFIG.max_receive_count {
+                DLQ_SENDS.fetch_add(1, Ordering::Relaxed);
+            }
+            eprintln!("record {} failed (attempt {}): {}", item.id, item.attempt, error);
+            failures.push(item.id.clone());
+        }
+    }
+
+    // Uma linha de métricas por lote, para o CloudWatch Logs
+    let mut line = serde_json::json!({
+        "records": items.len(),
+        "failed": failures.len(),
+        "duration_ms": start.elapsed().as_secs_f64() * 1000.0,
+        "frozen_ms": invocation.frozen.map(|frozen| frozen.as_secs_f64() * 1000.0),
+        "totals": snapshot(),
+    });
+    crate::run::annotate(&mut line);
+    println!("{}", line);
+
+    if !CONFIG.partial_batch && !failures.is_empty() {
+        BATCH_FAILURES.fetch_add(1, Ordering::Relaxed);
+        return Err(format!("{} of {} records failed", failures.len(), items.len()).into());
+    }
+    Ok(serde_json::json!({
+        "batchItemFailures": failures
+            .into_iter()
+            .map(|id| serde_json::json!({ "itemIdentifier": id }))
+            .collect::<Vec<_>>(),
+    }))
+}
+
+pub async fn run(source: Source) -> Result<(), Error> {
+    lambda_runtime::run(service_fn(move |event| handle(source, event))).await
+}
@@ -0,0 +1,164 @@
+// ======================
+// HTTP EXTERNO (feature "external")
+// ======================
+//
+// Chama a API configurada em `BFF_EXTERNAL_URL`, faz o parse do JSON e guarda
+// o resultado em cache por `BFF_EXTERNAL_CACHE_TTL_SECS`. É o único cenário
+// com I/O de internet de verdade: DNS + TLS + transferência + parse.
+//
+// - `cache: false` ignora o cache (mas ainda atualiza)
+// - `fresh_client: true` cria um client novo, sem conexões reaproveitadas,
+//   para medir o custo de DNS e handshake TLS de ponta a ponta
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::Instant,
+};
+
+use axum::{
+    body::{BoxBody, Bytes},
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+
+use crate::config::CONFIG;
+
+#[derive(Deserialize)]
+pub struct ExternalPayload {
+    cache: Option<bool>,
+    fresh_client: Option<bool>,
+}
+
+struct Cached {
+    fetched_at: Instant,
+    status: u16,
+    bytes: usize,
+    data: serde_json::Value,
+}
+
+static CLIENT: Lazy<reqwest::Client> = Lazy::new(build_client);
+static CACHE: Lazy<Mutex<HashMap<String, Cached>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+static CACHE_HITS: AtomicU64 = AtomicU64::new(0);
+static CACHE_MISSES: AtomicU64 = AtomicU64::new(0);
+static FAILURES: AtomicU64 = AtomicU64::new(0);
+
+fn build_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(CONFIG.external_timeout)
+        .build()
+        .expect("Failed to build HTTP client")
+}
+
+pub fn snapshot() -> serde_json::Value {
+    serde_json::json!({
+        "url": CONFIG.external_url,
+        "cache_hits": CACHE_HITS.load(Ordering::Relaxed),
+        "cache_misses": CACHE_MISSES.load(Ordering::Relaxed),
+        "failures": FAILURES.load(Ordering::Relaxed),
+    })
+}
+
+// Resumo do documento, para não devolver a resposta externa inteira
+fn shape(data: &serde_json::Value) -> serde_json::Value {
+    match data {
+        serde_json::Value::Object(map) => serde_json::json!({ "type": "object", "len": map.len() }),
+        serde_json::Value::Array(items) => serde_json::json!({ "type": "array", "len": items.len() }),
+        _ => serde_json::json!({ "type": "scalar" }),
+    }
+}
+
+async fn fetch(client: &reqwest::Client, url: &str) -> Result<(u16, Bytes, f64), String> {
+    let start = Instant::now();
+    let response = client.get(url).send().await.map_err(|e| e.to_string())?;
+    let status = response.status().as_u16();
+    let body = response.bytes().await.map_err(|e| e.to_string())?;
+    Ok((status, body, start.elapsed().as_secs_f64() * 1000.0))
+}
+
+pub async fn external(Json(payload): Json<ExternalPayload>) -> Response<BoxBody> {
+    let url = CONFIG.external_url.as_str();
+    let start = Instant::now();
+
+    if payload.cache.unwrap_or(true) {
+        let cache = CACHE.lock().unwrap();
+        if let Some(cached) = cache.get(url).filter(|c| c.fetched_at.elapsed() < CONFIG.external_cache_ttl) {
+            CACHE_HITS.fetch_add(1, Ordering::Relaxed);
+            return (
+                StatusCode::OK,
+                Json(serde_json::json!({
+                    "url": url,
+                    "status": cached.status,
+                    "bytes": cached.bytes,
+                    "cached": true,
+                    "age_ms": cached.fetched_at.elapsed().as_secs_f64() * 1000.0,
+                    "shape": shape(&cached.data),
+                    "duration_ms": start.elapsed().as_secs_f64() * 1000.0,
+                }))
+            )
+            .into_response();
+        }
+    }
+    CACHE_MISSES.fetch_add(1, Ordering::Relaxed);
+
+    let fresh_client = payload.fresh_client.unwrap_or(false);
+    let result = if fresh_client {
+        fetch(&build_client(), url).await
+    } else {
+        fetch(&CLIENT, url).await
+    };
+    let (status, body, fetch_ms) = match result {
+        Ok(result) => result,
+        Err(error) => {
+            FAILURES.fetch_add(1, Ordering::Relaxed);
+            return (
+                StatusCode::BAD_GATEWAY,
+                Json(serde_json::json!({ "error": format!("Upstream request failed: {}", error) }))
+            )
+            .into_response();
+        }
+    };
+
+    let parse_start = Instant::now();
+    let data: serde_json::Value = match serde_json::from_slice(&body) {
+        Ok(data) => data,
+        Err(_) => {
+            FAILURES.fetch_add(1, Ordering::Relaxed);
+            return (
+                StatusCode::BAD_GATEWAY,
+                Json(serde_json::json!({ "error": "Upstream response is not valid JSON", "status": status }))
+            )
+            .into_response();
+        }
+    };
+    let parse_ms = parse_start.elapsed().as_secs_f64() * 1000.0;
+    let summary = shape(&data);
+
+    CACHE.lock().unwrap().insert(
+        url.to_string(),
+        Cached { fetched_at: Instant::now(), status, bytes: body.len(), data },
+    );
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "url": url,
+            "status": status,
+            "bytes": body.len(),
+            "cached": false,
+            "fresh_client": fresh_client,
+            "shape": summary,
+            "fetch_ms": fetch_ms,
+            "parse_ms": parse_ms,
+            "duration_ms": start.elapsed().as_secs_f64() * 1000.0,
+        }))
+    )
+    .into_response()
+}
@@ -0,0 +1,136 @@
+// ======================
+// FFT
+// ======================
+//
+// FFT direta (rustfft) de um sinal gerado: a soma de senos nos bins de
+// `frequencies` (padrão 50 e 120) mais ruído uniforme de amplitude `noise`,
+// tirado da seed. `length` não precisa ser potência de 2; o rustfft escolhe
+// o algoritmo (radix-4, Bluestein, ...), o que por si só muda bastante o
+// tempo. O plano é calculado uma vez por request, fora do tempo medido, e a
+// transformada roda `iterations` vezes.
+//
+// A resposta resume o espectro da metade positiva (bins 0..length/2): os
+// `top_k` bins de maior magnitude, a magnitude média e máxima e a energia,
+// que pelo teorema de Parseval bate com a do sinal.
+
+use std::{f64::consts::TAU, time::Instant};
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use rustfft::{num_complex::Complex, FftPlanner};
+use serde::Deserialize;
+
+use crate::{
+    estimate::{self, Estimate},
+    progress::Progress,
+    rng::{self, SplitMix64},
+};
+
+const MAX_LENGTH: usize = 1 << 22;
+const MAX_ITERATIONS: usize = 1000;
+// Amostras transformadas no total (tamanho × iterações)
+const MAX_TOTAL_SAMPLES: usize = 1 << 28;
+
+#[derive(Deserialize)]
+pub struct FftPayload {
+    length: Option<usize>,
+    frequencies: Option<Vec<f64>>,
+    noise: Option<f64>,
+    iterations: Option<usize>,
+    top_k: Option<usize>,
+    seed: Option<u64>,
+    dry_run: Option<bool>,
+}
+
+fn bad_request(error: String) -> Response<BoxBody> {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({ "error": error }))
+    )
+    .into_response()
+}
+
+pub async fn fft(Json(payload): Json<FftPayload>) -> Response<BoxBody> {
+    let length = payload.length.unwrap_or(4096);
+    let iterations = payload.iterations.unwrap_or(1);
+    if !(2..=MAX_LENGTH).contains(&length) || iterations == 0 || iterations > MAX_ITERATIONS {
+        return bad_request(format!(
+            "length must be between 2 and {} and iterations between 1 and {}",
+            MAX_LENGTH, MAX_ITERATIONS
+        ));
+    }
+    if length * iterations > MAX_TOTAL_SAMPLES {
+        return bad_request("length * iterations exceeds the limit".to_string());
+    }
+    let frequencies = payload.frequencies.unwrap_or_else(|| vec![50.0, 120.0]);
+    if frequencies.iter().any(|f| !f.is_finite() || *f < 0.0 || *f > (length / 2) as f64) {
+        return bad_request(format!("frequencies must be between 0 and {}", length / 2));
+    }
+    let noise = payload.noise.unwrap_or(0.1);
+    if !noise.is_finite() || noise < 0.0 {
+        return bad_request("noise must be a non-negative number".to_string());
+    }
+    let top_k = payload.top_k.unwrap_or(5).min(length / 2);
+
+    if payload.dry_run.unwrap_or(false) {
+        // ~5·n·log2(n) operações por transformada, a estimativa clássica
+        let n = length as u64;
+        return estimate::response(Estimate {
+            iterations: 5 * n * (64 - n.leading_zeros() as u64) * iterations as u64,
+            unit: "floating-point operations",
+            allocation_bytes: 2 * n * 16,
+            output_bytes: 250 + 60 * top_k as u64,
+        });
+    }
+
+    let mut rng = SplitMix64::new(rng::seed(payload.seed));
+    let signal: Vec<Complex<f64>> = (0..length)
+        .map(|i| {
+            let t = i as f64 / length as f64;
+            let tone: f64 = frequencies.iter().map(|f| (TAU * f * t).sin()).sum();
+            Complex::new(tone + noise * (rng.next_f64() * 2.0 - 1.0), 0.0)
+        })
+        .collect();
+
+    let plan = FftPlanner::<f64>::new().plan_fft_forward(length);
+    let mut scratch = vec![Complex::default(); plan.get_inplace_scratch_len()];
+    let mut spectrum = signal.clone();
+    let mut progress = Progress::new(iterations as u64);
+    let start = Instant::now();
+    for i in 0..iterations {
+        spectrum.copy_from_slice(&signal);
+        plan.process_with_scratch(&mut spectrum, &mut scratch);
+        progress.tick(i as u64 + 1);
+    }
+    let elapsed = start.elapsed();
+
+    let half = &spectrum[..length / 2];
+    let magnitudes: Vec<f64> = half.iter().map(|c| c.norm()).collect();
+    let mut peaks: Vec<usize> = (0..magnitudes.len()).collect();
+    peaks.sort_unstable_by(|a, b| magnitudes[*b].total_cmp(&magnitudes[*a]).then(a.cmp(b)));
+    let peaks: Vec<_> = peaks
+        .into_iter()
+        .take(top_k)
+        .map(|bin| serde_json::json!({ "bin": bin, "magnitude": magnitudes[bin] }))
+        .collect();
+    let energy: f64 = spectrum.iter().map(|c| c.norm_sqr()).sum::<f64>() / length as f64;
+
+    let mut body = serde_json::json!({
+        "length": length,
+        "iterations": iterations,
+        "fft_ms": elapsed.as_secs_f64() * 1000.0,
+        "transforms_per_second": iterations as f64 / elapsed.as_secs_f64(),
+        "peaks": peaks,
+        "mean_magnitude": magnitudes.iter().sum::<f64>() / magnitudes.len() as f64,
+        "max_magnitude": magnitudes.iter().cloned().fold(0.0, f64::max),
+        "energy": energy,
+    });
+    if let Some(report) = progress.report() {
+        body["progress"] = report;
+    }
+    (StatusCode::OK, Json(body)).into_response()
+}
@@ -0,0 +1,239 @@
+// ======================
+// DISTÂNCIA DE EDIÇÃO (LEVENSHTEIN)
+// ======================
+//
+// O /string mede regex; aqui a carga de texto é algorítmica: a distância de
+// Levenshtein (inserções, remoções e trocas, custo 1 cada) pela programação
+// dinâmica clássica, com duas linhas da tabela em vez da tabela inteira. A
+// conta é sobre caracteres Unicode, não bytes, para bater entre linguagens.
+//
+// `mode: "distance"` (padrão) compara `a` e `b` e devolve a distância e a
+// similaridade, `1 - distância / maior tamanho` (1 para duas strings vazias).
+// `mode: "closest"` compara `query` com cada item de `candidates` e devolve
+// os `limit` mais próximos, empates pela ordem da lista. `ignore_case`
+// compara em minúsculas.
+
+use std::time::Instant;
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+
+use crate::estimate::{self, Estimate};
+
+const MAX_CHARS: usize = 10_000;
+const MAX_CANDIDATES: usize = 100_000;
+const MAX_LIMIT: usize = 100;
+
+#[derive(Deserialize)]
+pub struct FuzzyPayload {
+    mode: Option<String>,
+    a: Option<String>,
+    b: Option<String>,
+    query: Option<String>,
+    candidates: Option<Vec<String>>,
+    limit: Option<usize>,
+    ignore_case: Option<bool>,
+    dry_run: Option<bool>,
+}
+
+fn bad_request(error: String) -> Response<BoxBody> {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({ "error": error }))
+    )
+    .into_response()
+}
+
+fn chars(text: &str, ignore_case: bool) -> Vec<char> {
+    if ignore_case {
+        text.to_lowercase().chars().collect()
+    } else {
+        text.chars().collect()
+    }
+}
+
+/// Distância de Levenshtein com duas linhas de `b.len() + 1` posições.
+fn levenshtein(a: &[char], b: &[char], previous: &mut Vec<usize>, current: &mut Vec<usize>) -> usize {
+    previous.clear();
+    previous.extend(0..=b.len());
+    for (i, ca) in a.iter().enumerate() {
+        current.clear();
+        current.push(i + 1);
+        for (j, cb) in b.iter().enumerate() {
+            let replace = previous[j] + usize::from(ca != cb);
+            current.push(replace.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        std::mem::swap(previous, current);
+    }
+    previous[b.len()]
+}
+
+fn similarity(distance: usize, a: &[char], b: &[char]) -> f64 {
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        1.0
+    } else {
+        1.0 - distance as f64 / longest as f64
+    }
+}
+
+pub async fn fuzzy(Json(payload): Json<FuzzyPayload>) -> Response<BoxBody> {
+    let ignore_case = payload.ignore_case.unwrap_or(false);
+    let too_long = |text: &str| text.chars().count() > MAX_CHARS;
+
+    match payload.mode.as_deref().unwrap_or("distance") {
+        "distance" => {
+            let a = payload.a.unwrap_or_else(|| "kitten".to_string());
+            let b = payload.b.unwrap_or_else(|| "sitting".to_string());
+            if too_long(&a) || too_long(&b) {
+                return bad_request(format!("a and b must have at most {} characters", MAX_CHARS));
+            }
+            let (a, b) = (chars(&a, ignore_case), chars(&b, ignore_case));
+            let cells = a.len() as u64 * b.len() as u64;
+            if payload.dry_run.unwrap_or(false) {
+                return estimate::response(Estimate {
+                    iterations: cells,
+                    unit: "table cells",
+                    allocation_bytes: 2 * (b.len() as u64 + 1) * 8,
+                    output_bytes: 150,
+                });
+            }
+
+            let start = Instant::now();
+            let distance = levenshtein(&a, &b, &mut Vec::new(), &mut Vec::new());
+            let elapsed = start.elapsed();
+            (
+                StatusCode::OK,
+                Json(serde_json::json!({
+                    "mode": "distance",
+                    "a_chars": a.len(),
+                    "b_chars": b.len(),
+                    "distance": distance,
+                    "similarity": similarity(distance, &a, &b),
+                    "compute_ms": elapsed.as_secs_f64() * 1000.0,
+                }))
+            )
+            .into_response()
+        }
+        "closest" => {
+            let Some(query) = payload.query else {
+                return bad_request("closest mode needs a query".to_string());
+            };
+            let candidates = payload.candidates.unwrap_or_default();
+            let limit = payload.limit.unwrap_or(1);
+            if candidates.is_empty() || candidates.len() > MAX_CANDIDATES {
+                return bad_request(format!("candidates must have between 1 and {} items", MAX_CANDIDATES));
+            }
+            if limit == 0 || limit > MAX_LIMIT {
+                return bad_request(format!("limit must be between 1 and {}", MAX_LIMIT));
+            }
+            if too_long(&query) || candidates.iter().any(|c| too_long(c)) {
+                return bad_request(format!("query and candidates must have at most {} characters", MAX_CHARS));
+            }
+            let query_chars = chars(&query, ignore_case);
+            let lengths: Vec<u64> = candidates.iter().map(|c| c.chars().count() as u64).collect();
+            let cells: u64 = lengths.iter().map(|len| len * query_chars.len() as u64).sum();
+            if payload.dry_run.unwrap_or(false) {
+                let longest = lengths.iter().max().copied().unwrap_or(0);
+                return estimate::response(Estimate {
+                    iterations: cells,
+                    unit: "table cells",
+                    allocation_bytes: 2 * (longest + 1) * 8,
+                    output_bytes: 150 + limit.min(candidates.len()) as u64 * (100 + longest),
+                });
+            }
+
+            let start = Instant::now();
+            let (mut previous, mut current) = (Vec::new(), Vec::new());
+            let mut scored: Vec<(usize, usize, f64)> = candidates
+                .iter()
+                .enumerate()
+                .map(|(index, candidate)| {
+                    let candidate = chars(candidate, ignore_case);
+                    let distance = levenshtein(&query_chars, &candidate, &mut previous, &mut current);
+                    (index, distance, similarity(distance, &query_chars, &candidate))
+                })
+                .collect();
+            scored.sort_by_key(|(index, distance, _)| (*distance, *index));
+            let elapsed = start.elapsed();
+
+            let matches: Vec<_> = scored
+                .iter()
+                .take(limit)
+                .map(|(index, distance, similarity)| {
+                    serde_json::json!({
+                        "index": index,
+                        "candidate": candidates[*index],
+                        "distance": distance,
+                        "similarity": similarity,
+                    })
+                })
+                .collect();
+            (
+                StatusCode::OK,
+                Json(serde_json::json!({
+                    "mode": "closest",
+                    "query": query,
+                    "candidates": candidates.len(),
+                    "matches": matches,
+                    "cells": cells,
+                    "compute_ms": elapsed.as_secs_f64() * 1000.0,
+                    "cells_per_second": cells as f64 / elapsed.as_secs_f64(),
+                }))
+            )
+            .into_response()
+        }
+        other => bad_request(format!("mode must be distance or closest, got {}", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn distance(a: &str, b: &str) -> usize {
+        let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+        levenshtein(&a, &b, &mut Vec::new(), &mut Vec::new())
+    }
+
+    #[test]
+    fn classic_distances() {
+        assert_eq!(distance("kitten", "sitting"), 3);
+        assert_eq!(distance("flaw", "lawn"), 2);
+        assert_eq!(distance("saturday", "sunday"), 3);
+        assert_eq!(distance("rust", "rust"), 0);
+    }
+
+    #[test]
+    fn empty_side_costs_the_other_length() {
+        assert_eq!(distance("", ""), 0);
+        assert_eq!(distance("", "abc"), 3);
+        assert_eq!(distance("abc", ""), 3);
+    }
+
+    #[test]
+    fn distance_counts_chars_not_bytes() {
+        assert_eq!(distance("café", "cafe"), 1);
+        assert_eq!(distance("日本語", "日本"), 1);
+    }
+
+    #[test]
+    fn buffers_are_reused_between_calls() {
+        let (mut previous, mut current) = (Vec::new(), Vec::new());
+        let chars = |s: &str| s.chars().collect::<Vec<_>>();
+        assert_eq!(levenshtein(&chars("kitten"), &chars("sitting"), &mut previous, &mut current), 3);
+        assert_eq!(levenshtein(&chars("ab"), &chars("b"), &mut previous, &mut current), 1);
+    }
+
+    #[test]
+    fn similarity_is_relative_to_the_longest() {
+        let (a, b): (Vec<char>, Vec<char>) = ("kitten".chars().collect(), "sitting".chars().collect());
+        assert!((similarity(3, &a, &b) - 4.0 / 7.0).abs() < 1e-12);
+        assert_eq!(similarity(0, &[], &[]), 1.0);
+    }
+}
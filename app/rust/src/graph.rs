@@ -0,0 +1,264 @@
+// ======================
+// TRAVESSIA DE GRAFOS (BFS / DIJKSTRA)
+// ======================
+//
+// O grafo é gerado a partir da seed e guardado em CSR (offsets + arestas em
+// vetores contíguos), que é como uma implementação real evitaria ponteiros.
+// Mesmo assim o acesso às arestas é aleatório, então o custo é dominado por
+// cache misses.
+//
+// `adjacency` troca o grafo gerado por um enviado pelo cliente: uma lista por
+// nó com as arestas de saída, cada uma `alvo` (peso 1) ou `[alvo, peso]`.
+// Vai para o mesmo CSR, então a busca é a mesma nos dois casos.
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, VecDeque},
+    time::Instant,
+};
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+
+use crate::rng::{self, SplitMix64};
+
+const MAX_NODES: usize = 2_000_000;
+const MAX_EDGES: usize = 20_000_000;
+
+#[derive(Deserialize)]
+pub struct GraphPayload {
+    nodes: Option<usize>,
+    degree: Option<usize>,
+    max_weight: Option<u32>,
+    seed: Option<u64>,
+    algorithm: Option<String>,
+    source: Option<usize>,
+    target: Option<usize>,
+    include_path: Option<bool>,
+    adjacency: Option<Vec<Vec<Edge>>>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Edge {
+    Target(u32),
+    Weighted(u32, u32),
+}
+
+struct Graph {
+    offsets: Vec<usize>,
+    targets: Vec<u32>,
+    weights: Vec<u32>,
+}
+
+impl Graph {
+    fn generate(nodes: usize, degree: usize, max_weight: u32, seed: u64) -> Graph {
+        let mut rng = SplitMix64::new(seed);
+        let mut offsets = Vec::with_capacity(nodes + 1);
+        let mut targets = Vec::with_capacity(nodes * degree);
+        let mut weights = Vec::with_capacity(nodes * degree);
+        offsets.push(0);
+        for _ in 0..nodes {
+            for _ in 0..degree {
+                targets.push(rng.next_below(nodes as u64) as u32);
+                weights.push(1 + rng.next_below(max_weight as u64) as u32);
+            }
+            offsets.push(targets.len());
+        }
+        Graph { offsets, targets, weights }
+    }
+
+    /// `None` se alguma aresta aponta para fora do grafo.
+    fn from_adjacency(adjacency: Vec<Vec<Edge>>) -> Option<Graph> {
+        let nodes = adjacency.len();
+        let total = adjacency.iter().map(Vec::len).sum();
+        let mut offsets = Vec::with_capacity(nodes + 1);
+        let mut targets = Vec::with_capacity(total);
+        let mut weights = Vec::with_capacity(total);
+        offsets.push(0);
+        for edges in adjacency {
+            for edge in edges {
+                let (target, weight) = match edge {
+                    Edge::Target(target) => (target, 1),
+                    Edge::Weighted(target, weight) => (target, weight),
+                };
+                if target as usize >= nodes {
+                    return None;
+                }
+                targets.push(target);
+                weights.push(weight);
+            }
+            offsets.push(targets.len());
+        }
+        Some(Graph { offsets, targets, weights })
+    }
+
+    fn edges(&self, node: usize) -> impl Iterator<Item = (usize, u64)> + '_ {
+        let range = self.offsets[node]..self.offsets[node + 1];
+        self.targets[range.clone()]
+            .iter()
+            .zip(&self.weights[range])
+            .map(|(t, w)| (*t as usize, *w as u64))
+    }
+
+    fn nodes(&self) -> usize {
+        self.offsets.len() - 1
+    }
+}
+
+// Resultado comum: distância até o alvo, nós visitados e predecessores para
+// reconstruir o caminho
+struct Search {
+    distance: Option<u64>,
+    visited: usize,
+    previous: Vec<u32>,
+}
+
+const NO_PREVIOUS: u32 = u32::MAX;
+
+fn bfs(graph: &Graph, source: usize, target: usize) -> Search {
+    let mut previous = vec![NO_PREVIOUS; graph.nodes()];
+    let mut depth = vec![u64::MAX; graph.nodes()];
+    let mut queue = VecDeque::new();
+    let mut visited = 0;
+    depth[source] = 0;
+    queue.push_back(source);
+    while let Some(node) = queue.pop_front() {
+        visited += 1;
+        if node == target {
+            break;
+        }
+        for (next, _) in graph.edges(node) {
+            if depth[next] == u64::MAX {
+                depth[next] = depth[node] + 1;
+                previous[next] = node as u32;
+                queue.push_back(next);
+            }
+        }
+    }
+    let distance = (depth[target] != u64::MAX).then_some(depth[target]);
+    Search { distance, visited, previous }
+}
+
+fn dijkstra(graph: &Graph, source: usize, target: usize) -> Search {
+    let mut previous = vec![NO_PREVIOUS; graph.nodes()];
+    let mut dist = vec![u64::MAX; graph.nodes()];
+    let mut heap = BinaryHeap::new();
+    let mut visited = 0;
+    dist[source] = 0;
+    heap.push(Reverse((0u64, source)));
+    while let Some(Reverse((d, node))) = heap.pop() {
+        // Entrada antiga no heap (já achamos um caminho melhor)
+        if d > dist[node] {
+            continue;
+        }
+        visited += 1;
+        if node == target {
+            break;
+        }
+        for (next, weight) in graph.edges(node) {
+            let candidate = d + weight;
+            if candidate < dist[next] {
+                dist[next] = candidate;
+                previous[next] = node as u32;
+                heap.push(Reverse((candidate, next)));
+            }
+        }
+    }
+    let distance = (dist[target] != u64::MAX).then_some(dist[target]);
+    Search { distance, visited, previous }
+}
+
+fn reconstruct_path(previous: &[u32], source: usize, target: usize) -> Vec<usize> {
+    let mut path = vec![target];
+    let mut node = target;
+    while node != source {
+        node = previous[node] as usize;
+        path.push(node);
+    }
+    path.reverse();
+    path
+}
+
+pub async fn graph(Json(payload): Json<GraphPayload>) -> Response<BoxBody> {
+    let (nodes, edges) = match &payload.adjacency {
+        Some(adjacency) => (adjacency.len(), adjacency.iter().map(Vec::len).sum()),
+        None => {
+            let nodes = payload.nodes.unwrap_or(100_000);
+            (nodes, nodes.saturating_mul(payload.degree.unwrap_or(4)))
+        }
+    };
+    let max_weight = payload.max_weight.unwrap_or(100).max(1);
+    let source = payload.source.unwrap_or(0);
+    let target = payload.target.unwrap_or(nodes.saturating_sub(1));
+
+    if nodes == 0 || nodes > MAX_NODES || edges > MAX_EDGES {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "Graph size out of range" }))
+        )
+        .into_response();
+    }
+    if source >= nodes || target >= nodes {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "Source and target must be valid nodes" }))
+        )
+        .into_response();
+    }
+    let algorithm: fn(&Graph, usize, usize) -> Search = match payload.algorithm.as_deref().unwrap_or("dijkstra") {
+        "bfs" => bfs,
+        "dijkstra" => dijkstra,
+        _ => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Unsupported algorithm" }))
+            )
+            .into_response()
+        }
+    };
+
+    let build_start = Instant::now();
+    let graph = match payload.adjacency {
+        Some(adjacency) => match Graph::from_adjacency(adjacency) {
+            Some(graph) => graph,
+            None => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({ "error": "Adjacency list has an edge to a missing node" }))
+                )
+                .into_response()
+            }
+        },
+        None => Graph::generate(nodes, payload.degree.unwrap_or(4), max_weight, rng::seed(payload.seed)),
+    };
+    let build_duration = build_start.elapsed();
+
+    let start = Instant::now();
+    let search = algorithm(&graph, source, target);
+    let duration = start.elapsed();
+
+    let mut body = serde_json::json!({
+        "reachable": search.distance.is_some(),
+        "distance": search.distance,
+        "visited": search.visited,
+        "nodes": nodes,
+        "edges": graph.targets.len(),
+        "build_ms": build_duration.as_secs_f64() * 1000.0,
+        "duration_ms": duration.as_secs_f64() * 1000.0,
+    });
+    if search.distance.is_some() {
+        let path = reconstruct_path(&search.previous, source, target);
+        body["hops"] = serde_json::json!(path.len() - 1);
+        if payload.include_path.unwrap_or(false) {
+            body["path"] = serde_json::json!(path);
+        }
+    }
+
+    (StatusCode::OK, Json(body)).into_response()
+}
@@ -0,0 +1,99 @@
+// ======================
+// DIGESTS CRIPTOGRÁFICOS
+// ======================
+//
+// Calcula o digest de `text` com o algoritmo pedido, `iterations` vezes, e
+// devolve o hex e o tempo gasto só no hash. É CPU pura, sem alocação no laço,
+// para comparar com /math e /compress entre os runtimes.
+
+use std::time::Instant;
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use sha2::Digest;
+
+use crate::progress::Progress;
+
+const MAX_ITERATIONS: usize = 1_000_000;
+// Bytes processados no total (tamanho do texto × iterações)
+const MAX_TOTAL_BYTES: usize = 16 * 1024 * 1024 * 1024;
+const ALGORITHMS: &[&str] = &["sha256", "sha512", "md5", "blake3"];
+
+#[derive(Deserialize)]
+pub struct HashPayload {
+    text: String,
+    algorithm: Option<String>,
+    iterations: Option<usize>,
+}
+
+/// Escreve o digest em `out` e devolve o tamanho dele em bytes.
+fn digest(algorithm: &str, data: &[u8], out: &mut [u8; 64]) -> usize {
+    let mut put = |bytes: &[u8]| {
+        out[..bytes.len()].copy_from_slice(bytes);
+        bytes.len()
+    };
+    match algorithm {
+        "sha256" => put(&sha2::Sha256::digest(data)),
+        "sha512" => put(&sha2::Sha512::digest(data)),
+        "md5" => put(&md5::Md5::digest(data)),
+        "blake3" => put(blake3::hash(data).as_bytes()),
+        _ => unreachable!(),
+    }
+}
+
+pub async fn hash(Json(payload): Json<HashPayload>) -> Response<BoxBody> {
+    let algorithm = payload.algorithm.unwrap_or_else(|| "sha256".to_string());
+    if !ALGORITHMS.contains(&algorithm.as_str()) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": format!("Unsupported algorithm: {}", algorithm) }))
+        )
+        .into_response();
+    }
+    let iterations = payload.iterations.unwrap_or(1);
+    if iterations == 0 || iterations > MAX_ITERATIONS {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": format!("iterations must be between 1 and {}", MAX_ITERATIONS) }))
+        )
+        .into_response();
+    }
+    let data = payload.text.as_bytes();
+    if data.len().saturating_mul(iterations) > MAX_TOTAL_BYTES {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "text size * iterations exceeds 16 GiB" }))
+        )
+        .into_response();
+    }
+
+    let start = Instant::now();
+    let mut value = [0u8; 64];
+    let mut len = 0;
+    let mut progress = Progress::new(iterations as u64);
+    for i in 0..iterations {
+        // black_box impede o compilador de colapsar as iterações repetidas
+        len = digest(&algorithm, std::hint::black_box(data), &mut value);
+        progress.tick(i as u64 + 1);
+    }
+    let duration = start.elapsed();
+    let bytes = (data.len() * iterations) as f64;
+
+    let mut body = serde_json::json!({
+        "algorithm": algorithm,
+        "digest": value[..len].iter().map(|b| format!("{:02x}", b)).collect::<String>(),
+        "iterations": iterations,
+        "bytes": data.len(),
+        "duration_ms": duration.as_secs_f64() * 1000.0,
+        "mb_per_second": bytes / duration.as_secs_f64() / 1e6,
+    });
+    if let Some(report) = progress.report() {
+        body["progress"] = report;
+    }
+    (StatusCode::OK, Json(body)).into_response()
+}
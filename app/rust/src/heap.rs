@@ -0,0 +1,89 @@
+// ======================
+// PICO DE HEAP POR MEDIÇÃO
+// ======================
+//
+// Alocador global que embrulha o do sistema e, só enquanto alguma medição está
+// ativa, soma as alocações da thread que está medindo. Fora disso o custo é
+// um load atômico relaxado por alocação. O RSS do processo não serve para
+// isso: o malloc reaproveita páginas já liberadas e o pico some no meio do
+// resto.
+//
+// `measure` roda uma closure síncrona e devolve o maior volume de heap que ela
+// chegou a ter alocado ao mesmo tempo, contando só a thread atual (memória
+// liberada que veio de antes não desconta abaixo de zero).
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+struct Counting;
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+static ACTIVE: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static TRACKING: Cell<bool> = const { Cell::new(false) };
+    static CURRENT: Cell<isize> = const { Cell::new(0) };
+    static PEAK: Cell<isize> = const { Cell::new(0) };
+}
+
+fn track(delta: isize) {
+    if ACTIVE.load(Ordering::Relaxed) == 0 {
+        return;
+    }
+    // `try_with`: a thread pode estar sendo destruída
+    if TRACKING.try_with(Cell::get).unwrap_or(false) {
+        let current = CURRENT.with(|c| {
+            c.set(c.get() + delta);
+            c.get()
+        });
+        PEAK.with(|p| p.set(p.get().max(current)));
+    }
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            track(layout.size() as isize);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            track(layout.size() as isize);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        track(-(layout.size() as isize));
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = System.realloc(ptr, layout, new_size);
+        if !new.is_null() {
+            track(new_size as isize - layout.size() as isize);
+        }
+        new
+    }
+}
+
+/// Roda `f` e devolve o resultado e o pico de heap (em bytes) que ela usou.
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    CURRENT.with(|c| c.set(0));
+    PEAK.with(|p| p.set(0));
+    TRACKING.with(|t| t.set(true));
+    ACTIVE.fetch_add(1, Ordering::Relaxed);
+    let result = f();
+    ACTIVE.fetch_sub(1, Ordering::Relaxed);
+    TRACKING.with(|t| t.set(false));
+    (result, PEAK.with(Cell::get).max(0) as usize)
+}
@@ -0,0 +1,61 @@
+// ======================
+// SERVIDOR HYPER PURO (feature "hyper-server")
+// ======================
+//
+// Com `BFF_SERVER=hyper`, o servidor local troca o Router do axum por um
+// `service_fn` do hyper, sem layers, que despacha `POST /<workload>` direto
+// para o registro de workloads. Os handlers são os mesmos, então comparar com
+// o servidor padrão mede o custo do framework (roteamento, extractors, layers).
+//
+// Só os workloads do registro existem aqui; /mixed, /poll, /stats etc. ficam
+// no servidor axum. Toda resposta leva `X-Server: hyper` (e o `X-Runtime` e o `X-Config-Hash` de sempre).
+
+use std::{convert::Infallible, net::TcpListener};
+
+use axum::{
+    body::{boxed, BoxBody, Full},
+    http::{HeaderValue, Method, Request, Response, StatusCode},
+};
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Server,
+};
+
+use crate::{connection_policy::ConnectionPolicy, registry};
+
+fn error(status: StatusCode, message: String) -> Response<BoxBody> {
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .body(boxed(Full::from(serde_json::json!({ "error": message }).to_string())))
+        .unwrap()
+}
+
+async fn handle(req: Request<Body>) -> Result<Response<BoxBody>, Infallible> {
+    let mut response = match registry::find(req.uri().path().trim_start_matches('/')) {
+        None => error(StatusCode::NOT_FOUND, format!("No workload at {}", req.uri().path())),
+        Some(_) if req.method() != Method::POST => {
+            error(StatusCode::METHOD_NOT_ALLOWED, "Workloads only accept POST".to_string())
+        }
+        Some(workload) => match hyper::body::to_bytes(req.into_body()).await {
+            Err(e) => error(StatusCode::BAD_REQUEST, format!("Failed to read body: {}", e)),
+            Ok(body) => match serde_json::from_slice(&body) {
+                Ok(payload) => (workload.run)(payload).await,
+                Err(e) => error(StatusCode::BAD_REQUEST, format!("Invalid JSON: {}", e)),
+            },
+        },
+    };
+    response.headers_mut().insert("X-Server", HeaderValue::from_static("hyper"));
+    Ok(crate::info::tag(crate::thread_per_core::tag(response)))
+}
+
+pub async fn serve(listener: TcpListener) {
+    let make_service =
+        make_service_fn(|_| async { Ok::<_, Infallible>(ConnectionPolicy::new(service_fn(handle))) });
+    Server::from_tcp(listener)
+        .unwrap()
+        .tcp_nodelay(crate::config::CONFIG.tcp_nodelay)
+        .serve(make_service)
+        .await
+        .unwrap();
+}
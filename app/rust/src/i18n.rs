@@ -0,0 +1,497 @@
+// ======================
+// FORMATAÇÃO DE MENSAGENS i18n (estilo ICU)
+// ======================
+//
+// Implementa o subconjunto do ICU MessageFormat que um BFF costuma usar:
+// argumentos simples, `number`, `date`, `plural` (com `=N`, `offset:` e `#`)
+// e `select`. Os catálogos ficam embutidos em `i18n_catalog.json`.
+
+use std::{collections::BTreeMap, time::Instant};
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use chrono::{DateTime, TimeZone, Utc};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+const MAX_ITERATIONS: usize = 100_000;
+// Mensagens formatadas no total (iterações × locales × mensagens)
+const MAX_MESSAGES: usize = 5_000_000;
+
+#[derive(Deserialize)]
+pub struct I18nPayload {
+    locales: Option<Vec<String>>,
+    messages: Option<Vec<String>>,
+    args: Option<Map<String, Value>>,
+    iterations: Option<usize>,
+    parse_each_time: Option<bool>,
+}
+
+// ------------
+// dados de locale
+// ------------
+struct Locale {
+    grouping: &'static str,
+    decimal: &'static str,
+    date_format: &'static str,
+    plural: fn(f64) -> &'static str,
+}
+
+fn plural_one_if_one(n: f64) -> &'static str {
+    if n == 1.0 { "one" } else { "other" }
+}
+
+// pt-BR e fr: "one" para a parte inteira 0 ou 1
+fn plural_one_if_zero_or_one(n: f64) -> &'static str {
+    if (0.0..2.0).contains(&n) { "one" } else { "other" }
+}
+
+fn plural_slavic(n: f64) -> &'static str {
+    if n.fract() != 0.0 {
+        return "other";
+    }
+    let i = n.abs() as u64;
+    match (i % 10, i % 100) {
+        (1, r) if r != 11 => "one",
+        (2..=4, r) if !(12..=14).contains(&r) => "few",
+        _ => "many",
+    }
+}
+
+fn plural_other(_: f64) -> &'static str {
+    "other"
+}
+
+fn locale_data(locale: &str) -> Option<Locale> {
+    let (grouping, decimal, date_format, plural): (_, _, _, fn(f64) -> &'static str) = match locale {
+        "en" => (",", ".", "%m/%d/%Y", plural_one_if_one),
+        "pt-BR" => (".", ",", "%d/%m/%Y", plural_one_if_zero_or_one),
+        "de" => (".", ",", "%d.%m.%Y", plural_one_if_one),
+        "fr" => ("\u{202f}", ",", "%d/%m/%Y", plural_one_if_zero_or_one),
+        "ru" => ("\u{a0}", ",", "%d.%m.%Y", plural_slavic),
+        "ja" => (",", ".", "%Y/%m/%d", plural_other),
+        _ => return None,
+    };
+    Some(Locale { grouping, decimal, date_format, plural })
+}
+
+// ------------
+// parser
+// ------------
+enum Selector {
+    Exact(f64),
+    Keyword(String),
+}
+
+enum Node {
+    Text(String),
+    Arg(String),
+    Number(String),
+    Date(String),
+    Pound,
+    Plural { arg: String, offset: f64, cases: Vec<(Selector, Vec<Node>)> },
+    Select { arg: String, cases: Vec<(String, Vec<Node>)> },
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_whitespace();
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(format!("Expected '{}' at position {}", expected, self.pos))
+        }
+    }
+
+    fn word(&mut self) -> String {
+        self.skip_whitespace();
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| !c.is_whitespace() && !matches!(c, ',' | '{' | '}'))
+        {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    fn message(&mut self, in_plural: bool, nested: bool) -> Result<Vec<Node>, String> {
+        let mut nodes = Vec::new();
+        let mut text = String::new();
+        while let Some(c) = self.peek() {
+            match c {
+                '\'' => {
+                    self.pos += 1;
+                    match self.peek() {
+                        Some('\'') => {
+                            text.push('\'');
+                            self.pos += 1;
+                        }
+                        // Trecho entre apóstrofos é literal até o próximo apóstrofo simples
+                        Some('{') | Some('}') => self.quoted(&mut text),
+                        Some('#') if in_plural => self.quoted(&mut text),
+                        _ => text.push('\''),
+                    }
+                }
+                '{' => {
+                    if !text.is_empty() {
+                        nodes.push(Node::Text(std::mem::take(&mut text)));
+                    }
+                    nodes.push(self.argument(in_plural)?);
+                }
+                '}' if nested => break,
+                '}' => return Err(format!("Unbalanced '}}' at position {}", self.pos)),
+                '#' if in_plural => {
+                    if !text.is_empty() {
+                        nodes.push(Node::Text(std::mem::take(&mut text)));
+                    }
+                    nodes.push(Node::Pound);
+                    self.pos += 1;
+                }
+                _ => {
+                    text.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+        if !text.is_empty() {
+            nodes.push(Node::Text(text));
+        }
+        Ok(nodes)
+    }
+
+    fn quoted(&mut self, text: &mut String) {
+        while let Some(c) = self.peek() {
+            self.pos += 1;
+            if c != '\'' {
+                text.push(c);
+            } else if self.peek() == Some('\'') {
+                text.push('\'');
+                self.pos += 1;
+            } else {
+                return;
+            }
+        }
+    }
+
+    fn argument(&mut self, in_plural: bool) -> Result<Node, String> {
+        self.expect('{')?;
+        let name = self.word();
+        if name.is_empty() {
+            return Err(format!("Missing argument name at position {}", self.pos));
+        }
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.pos += 1;
+            return Ok(Node::Arg(name));
+        }
+        self.expect(',')?;
+        let kind = self.word();
+        let node = match kind.as_str() {
+            "number" | "date" => {
+                // Estilos (short, percent, ...) são aceitos mas ignorados
+                self.skip_whitespace();
+                if self.peek() == Some(',') {
+                    self.pos += 1;
+                    self.word();
+                }
+                if kind == "number" { Node::Number(name) } else { Node::Date(name) }
+            }
+            "plural" => {
+                self.expect(',')?;
+                let mut offset = 0.0;
+                let mut cases = Vec::new();
+                loop {
+                    self.skip_whitespace();
+                    if self.peek() == Some('}') || self.peek().is_none() {
+                        break;
+                    }
+                    let selector = self.word();
+                    if let Some(value) = selector.strip_prefix("offset:") {
+                        offset = value.parse().map_err(|_| "Invalid plural offset".to_string())?;
+                        continue;
+                    }
+                    let selector = match selector.strip_prefix('=') {
+                        Some(value) => Selector::Exact(
+                            value.parse().map_err(|_| "Invalid plural selector".to_string())?,
+                        ),
+                        None => Selector::Keyword(selector),
+                    };
+                    self.expect('{')?;
+                    let body = self.message(true, true)?;
+                    self.expect('}')?;
+                    cases.push((selector, body));
+                }
+                Node::Plural { arg: name, offset, cases }
+            }
+            "select" => {
+                self.expect(',')?;
+                let mut cases = Vec::new();
+                loop {
+                    self.skip_whitespace();
+                    if self.peek() == Some('}') || self.peek().is_none() {
+                        break;
+                    }
+                    let selector = self.word();
+                    self.expect('{')?;
+                    let body = self.message(in_plural, true)?;
+                    self.expect('}')?;
+                    cases.push((selector, body));
+                }
+                Node::Select { arg: name, cases }
+            }
+            _ => return Err(format!("Unsupported argument type: {}", kind)),
+        };
+        self.expect('}')?;
+        Ok(node)
+    }
+}
+
+fn parse(source: &str) -> Result<Vec<Node>, String> {
+    let mut parser = Parser { chars: source.chars().collect(), pos: 0 };
+    parser.message(false, false)
+}
+
+// ------------
+// formatação
+// ------------
+fn format_number(value: f64, locale: &Locale) -> String {
+    let negative = value < 0.0;
+    let value = value.abs();
+    let (integer, fraction) = if value.fract() == 0.0 {
+        (format!("{}", value as u64), None)
+    } else {
+        let fixed = format!("{:.2}", value);
+        let (integer, fraction) = fixed.split_once('.').unwrap();
+        (integer.to_string(), Some(fraction.to_string()))
+    };
+
+    let mut out = String::new();
+    if negative {
+        out.push('-');
+    }
+    for (i, digit) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            out.push_str(locale.grouping);
+        }
+        out.push(digit);
+    }
+    if let Some(fraction) = fraction {
+        out.push_str(locale.decimal);
+        out.push_str(&fraction);
+    }
+    out
+}
+
+fn format_date(value: &Value, locale: &Locale) -> Result<String, String> {
+    let dt = match value {
+        Value::Number(n) => n.as_i64().and_then(|secs| Utc.timestamp_opt(secs, 0).single()),
+        Value::String(s) => DateTime::parse_from_rfc3339(s).ok().map(|dt| dt.with_timezone(&Utc)),
+        _ => None,
+    };
+    dt.map(|dt| dt.format(locale.date_format).to_string())
+        .ok_or_else(|| "Invalid date argument".to_string())
+}
+
+fn format_nodes(
+    nodes: &[Node],
+    args: &Map<String, Value>,
+    locale: &Locale,
+    pound: Option<f64>,
+    out: &mut String,
+) -> Result<(), String> {
+    let arg = |name: &str| args.get(name).ok_or_else(|| format!("Missing argument: {}", name));
+    let number = |name: &str| {
+        arg(name)?
+            .as_f64()
+            .ok_or_else(|| format!("Argument is not a number: {}", name))
+    };
+
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Arg(name) => match arg(name)? {
+                Value::String(s) => out.push_str(s),
+                other => out.push_str(&other.to_string()),
+            },
+            Node::Number(name) => out.push_str(&format_number(number(name)?, locale)),
+            Node::Date(name) => out.push_str(&format_date(arg(name)?, locale)?),
+            Node::Pound => out.push_str(&format_number(pound.unwrap_or(0.0), locale)),
+            Node::Plural { arg: name, offset, cases } => {
+                let n = number(name)?;
+                let keyword = (locale.plural)(n - offset);
+                let case = cases
+                    .iter()
+                    .find(|(s, _)| matches!(s, Selector::Exact(v) if *v == n))
+                    .or_else(|| cases.iter().find(|(s, _)| matches!(s, Selector::Keyword(k) if k == keyword)))
+                    .or_else(|| cases.iter().find(|(s, _)| matches!(s, Selector::Keyword(k) if k == "other")))
+                    .ok_or_else(|| format!("No plural case for {}", name))?;
+                format_nodes(&case.1, args, locale, Some(n - offset), out)?;
+            }
+            Node::Select { arg: name, cases } => {
+                let value = match arg(name)? {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                let case = cases
+                    .iter()
+                    .find(|(k, _)| *k == value)
+                    .or_else(|| cases.iter().find(|(k, _)| k == "other"))
+                    .ok_or_else(|| format!("No select case for {}", name))?;
+                format_nodes(&case.1, args, locale, pound, out)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// ------------
+// catálogo embutido
+// ------------
+static CATALOG: Lazy<BTreeMap<String, BTreeMap<String, String>>> = Lazy::new(|| {
+    serde_json::from_str(include_str!("i18n_catalog.json")).expect("i18n_catalog.json inválido")
+});
+
+// Mensagens já parseadas, usadas quando `parse_each_time` é falso
+static PARSED: Lazy<BTreeMap<(String, String), Vec<Node>>> = Lazy::new(|| {
+    CATALOG
+        .iter()
+        .flat_map(|(locale, messages)| {
+            messages.iter().map(move |(key, source)| {
+                let nodes = parse(source).expect("mensagem inválida no catálogo");
+                ((locale.clone(), key.clone()), nodes)
+            })
+        })
+        .collect()
+});
+
+/// Argumentos gerados quando o cliente não manda `args`: variam a cada
+/// iteração para passar por todos os ramos de plural e select.
+fn generated_args(i: usize) -> Map<String, Value> {
+    let genders = ["female", "male", "other"];
+    let mut args = Map::new();
+    args.insert("name".into(), Value::from("Ana"));
+    args.insert("gender".into(), Value::from(genders[i % genders.len()]));
+    args.insert("count".into(), Value::from(i % 25));
+    args.insert("total".into(), Value::from(i as f64 * 1337.5 + 0.25));
+    args.insert("last_login".into(), Value::from(1_700_000_000 + i as i64 * 86_400));
+    args
+}
+
+pub async fn i18n(Json(payload): Json<I18nPayload>) -> Response<BoxBody> {
+    let locales = payload
+        .locales
+        .clone()
+        .unwrap_or_else(|| CATALOG.keys().cloned().collect());
+    let iterations = payload.iterations.unwrap_or(100);
+    let parse_each_time = payload.parse_each_time.unwrap_or(false);
+
+    if iterations == 0 || iterations > MAX_ITERATIONS {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "Iterations out of range" }))
+        )
+        .into_response();
+    }
+
+    let mut resolved = Vec::new();
+    for locale in &locales {
+        let (Some(data), Some(messages)) = (locale_data(locale), CATALOG.get(locale)) else {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": format!("Unsupported locale: {}", locale) }))
+            )
+            .into_response();
+        };
+        let keys: Vec<String> = match &payload.messages {
+            Some(keys) => keys.clone(),
+            None => messages.keys().cloned().collect(),
+        };
+        if let Some(missing) = keys.iter().find(|key| !messages.contains_key(*key)) {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": format!("Unknown message: {}", missing) }))
+            )
+            .into_response();
+        }
+        resolved.push((locale, data, keys));
+    }
+
+    let per_iteration: usize = resolved.iter().map(|(_, _, keys)| keys.len()).sum();
+    if per_iteration.saturating_mul(iterations) > MAX_MESSAGES {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "Too many messages for this many iterations" }))
+        )
+        .into_response();
+    }
+
+    // Garante que o parse inicial do catálogo não entre na medição
+    Lazy::force(&PARSED);
+
+    let start = Instant::now();
+    let mut formatted = 0usize;
+    let mut output_bytes = 0usize;
+    let mut sample = BTreeMap::new();
+    for i in 0..iterations {
+        let args = payload.args.clone().unwrap_or_else(|| generated_args(i));
+        for (locale, data, keys) in &resolved {
+            for key in keys {
+                let mut out = String::new();
+                let result = if parse_each_time {
+                    parse(&CATALOG[*locale][key])
+                        .and_then(|nodes| format_nodes(&nodes, &args, data, None, &mut out))
+                } else {
+                    let nodes = &PARSED[&((*locale).clone(), key.clone())];
+                    format_nodes(nodes, &args, data, None, &mut out)
+                };
+                if let Err(error) = result {
+                    return (
+                        StatusCode::BAD_REQUEST,
+                        Json(serde_json::json!({ "error": error }))
+                    )
+                    .into_response();
+                }
+                formatted += 1;
+                output_bytes += out.len();
+                if i == 0 {
+                    sample.insert(format!("{}.{}", locale, key), out);
+                }
+            }
+        }
+    }
+    let duration = start.elapsed();
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "formatted": formatted,
+            "output_bytes": output_bytes,
+            "sample": sample,
+            "duration_ms": duration.as_secs_f64() * 1000.0,
+            "messages_per_second": formatted as f64 / duration.as_secs_f64(),
+        }))
+    )
+    .into_response()
+}
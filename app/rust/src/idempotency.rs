@@ -0,0 +1,575 @@
+// ======================
+// MIDDLEWARE: Idempotency-Key
+// ======================
+//
+// Requests de escrita com o header `Idempotency-Key` têm a resposta guardada
+// em memória (com TTL). Uma repetição com a mesma chave recebe a resposta
+// original de volta, com `Idempotent-Replayed: true`, sem executar o handler.
+//
+// - mesma chave com corpo diferente: 422
+// - mesma chave enquanto a primeira ainda executa: 409
+// - respostas 5xx não são guardadas, para o cliente poder tentar de novo
+// - corpos acima do limite da rota (body_limit.rs): a request leva 413 e a
+//   resposta passa direto, sem ser guardada
+//
+// Com `BFF_IDEMPOTENCY_TABLE` (feature "aws") as chaves ficam numa tabela
+// DynamoDB em vez da memória, e valem entre instâncias. A tabela tem a chave
+// de partição `key` (String) e o TTL da tabela deve apontar para o atributo
+// `expires_at`. A execução começa com um put condicional (a chave não existe
+// ou já venceu); se a condição falha, o item antigo volta na própria resposta
+// e decide entre replay, 409 e 422 como na memória. O item tem limite de
+// 400 KB, então respostas acima de `dynamodb::MAX_BODY` passam direto sem
+// serem guardadas.
+
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeSet, HashMap},
+    future::Future,
+    hash::{Hash, Hasher},
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    task::{Context, Poll},
+    time::Instant,
+};
+
+use axum::{
+    body::{boxed, Body, BoxBody, Bytes, Full, HttpBody},
+    extract::Json,
+    http::{HeaderMap, HeaderValue, Method, Request, Response, StatusCode},
+    response::IntoResponse,
+};
+use once_cell::sync::Lazy;
+use tower::{Layer, Service};
+
+use crate::{body_limit, config::CONFIG};
+
+pub const HEADER: &str = "idempotency-key";
+
+#[derive(Clone)]
+pub struct IdempotencyLayer;
+
+#[derive(Clone)]
+pub struct IdempotencyService<S> {
+    inner: S,
+}
+
+impl<S> Layer<S> for IdempotencyLayer {
+    type Service = IdempotencyService<S>;
+    fn layer(&self, inner: S) -> Self::Service {
+        IdempotencyService { inner }
+    }
+}
+
+struct StoredResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+enum Entry {
+    InFlight { fingerprint: u64, started_at: Instant },
+    Done { fingerprint: u64, stored_at: Instant, response: StoredResponse },
+}
+
+impl Entry {
+    fn created_at(&self) -> Instant {
+        match self {
+            Entry::InFlight { started_at, .. } => *started_at,
+            Entry::Done { stored_at, .. } => *stored_at,
+        }
+    }
+}
+
+/// Entradas por chave, com um índice por idade para o despejo não varrer tudo.
+#[derive(Default)]
+struct Store {
+    entries: HashMap<String, Entry>,
+    by_age: BTreeSet<(Instant, String)>,
+}
+
+impl Store {
+    fn insert(&mut self, key: String, entry: Entry) {
+        if let Some(old) = self.entries.get(&key) {
+            self.by_age.remove(&(old.created_at(), key.clone()));
+        }
+        self.by_age.insert((entry.created_at(), key.clone()));
+        self.entries.insert(key, entry);
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(old) = self.entries.remove(key) {
+            self.by_age.remove(&(old.created_at(), key.to_string()));
+        }
+    }
+
+    /// Remove entradas vencidas e, se ainda estiver no limite, as mais antigas.
+    fn evict(&mut self) {
+        let before = self.entries.len();
+        while let Some((created_at, key)) = self.by_age.first().cloned() {
+            if created_at.elapsed() < CONFIG.idempotency_ttl
+                && self.entries.len() < CONFIG.idempotency_max_entries.max(1)
+            {
+                break;
+            }
+            self.remove(&key);
+        }
+        EVICTIONS.fetch_add((before - self.entries.len()) as u64, Ordering::Relaxed);
+    }
+}
+
+static STORE: Lazy<Mutex<Store>> = Lazy::new(|| Mutex::new(Store::default()));
+
+static HITS: AtomicU64 = AtomicU64::new(0);
+static MISSES: AtomicU64 = AtomicU64::new(0);
+static CONFLICTS: AtomicU64 = AtomicU64::new(0);
+static MISMATCHES: AtomicU64 = AtomicU64::new(0);
+static EVICTIONS: AtomicU64 = AtomicU64::new(0);
+
+pub fn snapshot() -> serde_json::Value {
+    let hits = HITS.load(Ordering::Relaxed);
+    let misses = MISSES.load(Ordering::Relaxed);
+    #[allow(unused_mut)]
+    let mut body = serde_json::json!({
+        "entries": STORE.lock().unwrap().entries.len(),
+        "hits": hits,
+        "misses": misses,
+        "conflicts": CONFLICTS.load(Ordering::Relaxed),
+        "mismatches": MISMATCHES.load(Ordering::Relaxed),
+        "evictions": EVICTIONS.load(Ordering::Relaxed),
+        "hit_rate": if hits + misses == 0 { 0.0 } else { hits as f64 / (hits + misses) as f64 },
+    });
+    #[cfg(feature = "aws")]
+    if !CONFIG.idempotency_table.is_empty() {
+        body["dynamodb"] = dynamodb::snapshot();
+    }
+    body
+}
+
+fn fingerprint(body: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    body.hash(&mut hasher);
+    hasher.finish()
+}
+
+enum Buffered {
+    Complete(Bytes),
+    // Acima do limite: o que já foi lido seguido do resto, para passar direto
+    TooLarge(BoxBody),
+    Failed,
+}
+
+/// Lê o corpo da resposta para guardar, até `limit`.
+async fn buffer(mut body: BoxBody, limit: usize) -> Buffered {
+    let mut buffered = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let Ok(chunk) = chunk else {
+            return Buffered::Failed;
+        };
+        if buffered.len() + chunk.len() > limit {
+            let (mut sender, rest) = Body::channel();
+            tokio::spawn(async move {
+                for chunk in [Bytes::from(buffered), chunk] {
+                    if sender.send_data(chunk).await.is_err() {
+                        return;
+                    }
+                }
+                while let Some(chunk) = body.data().await {
+                    let Ok(chunk) = chunk else {
+                        return sender.abort();
+                    };
+                    if sender.send_data(chunk).await.is_err() {
+                        return;
+                    }
+                }
+            });
+            return Buffered::TooLarge(boxed(rest));
+        }
+        buffered.extend_from_slice(&chunk);
+    }
+    Buffered::Complete(Bytes::from(buffered))
+}
+
+fn replay(stored: &StoredResponse) -> Response<BoxBody> {
+    let mut response = Response::new(boxed(Full::from(stored.body.clone())));
+    *response.status_mut() = stored.status;
+    *response.headers_mut() = stored.headers.clone();
+    response
+        .headers_mut()
+        .insert("Idempotent-Replayed", HeaderValue::from_static("true"));
+    response
+}
+
+fn error(status: StatusCode, message: &str) -> Response<BoxBody> {
+    (status, Json(serde_json::json!({ "error": message }))).into_response()
+}
+
+// O que fazer com a request depois de consultar o store
+enum Lookup {
+    Execute,
+    Respond(Response<BoxBody>),
+}
+
+async fn begin(key: &str, fingerprint: u64) -> Lookup {
+    #[cfg(feature = "aws")]
+    if !CONFIG.idempotency_table.is_empty() {
+        return dynamodb::begin(key, fingerprint).await;
+    }
+    lookup(key, fingerprint)
+}
+
+async fn forget(key: &str) {
+    #[cfg(feature = "aws")]
+    if !CONFIG.idempotency_table.is_empty() {
+        return dynamodb::forget(key).await;
+    }
+    STORE.lock().unwrap().remove(key);
+}
+
+async fn save(key: String, fingerprint: u64, response: StoredResponse) {
+    #[cfg(feature = "aws")]
+    if !CONFIG.idempotency_table.is_empty() {
+        return dynamodb::save(&key, fingerprint, &response).await;
+    }
+    STORE
+        .lock()
+        .unwrap()
+        .insert(key, Entry::Done { fingerprint, stored_at: Instant::now(), response });
+}
+
+/// Maior resposta que cabe no store em uso.
+fn response_limit(path: &str) -> usize {
+    #[cfg(feature = "aws")]
+    if !CONFIG.idempotency_table.is_empty() {
+        return body_limit::for_path(path).min(dynamodb::MAX_BODY);
+    }
+    body_limit::for_path(path)
+}
+
+fn lookup(key: &str, fingerprint: u64) -> Lookup {
+    let mut store = STORE.lock().unwrap();
+    let ttl = CONFIG.idempotency_ttl;
+    match store.entries.get(key) {
+        Some(entry) if entry.created_at().elapsed() >= ttl => {
+            // Vencida (ou execução abandonada): trata como chave nova
+            store.remove(key);
+            EVICTIONS.fetch_add(1, Ordering::Relaxed);
+        }
+        Some(Entry::Done { fingerprint: stored, .. }) | Some(Entry::InFlight { fingerprint: stored, .. })
+            if *stored != fingerprint =>
+        {
+            MISMATCHES.fetch_add(1, Ordering::Relaxed);
+            return Lookup::Respond(error(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "Idempotency-Key was already used with a different request body",
+            ));
+        }
+        Some(Entry::Done { response, .. }) => {
+            HITS.fetch_add(1, Ordering::Relaxed);
+            return Lookup::Respond(replay(response));
+        }
+        Some(Entry::InFlight { .. }) => {
+            CONFLICTS.fetch_add(1, Ordering::Relaxed);
+            return Lookup::Respond(error(
+                StatusCode::CONFLICT,
+                "A request with this Idempotency-Key is still in progress",
+            ));
+        }
+        None => {}
+    }
+
+    MISSES.fetch_add(1, Ordering::Relaxed);
+    store.evict();
+    store.insert(key.to_string(), Entry::InFlight { fingerprint, started_at: Instant::now() });
+    Lookup::Execute
+}
+
+impl<S> Service<Request<Body>> for IdempotencyService<S>
+where
+    S: Service<Request<Body>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<BoxBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let mut service = self.inner.clone();
+
+        let safe_method = matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS);
+        let key = req
+            .headers()
+            .get(HEADER)
+            .and_then(|value| value.to_str().ok())
+            .filter(|_| !safe_method)
+            .map(|value| format!("{} {} {}", req.method(), req.uri().path(), value));
+
+        let Some(key) = key else {
+            return Box::pin(service.call(req));
+        };
+
+        Box::pin(async move {
+            let limit = response_limit(req.uri().path());
+            let (parts, body) = match body_limit::read(req).await {
+                Ok(read) => read,
+                Err(response) => return Ok(response),
+            };
+
+            let fingerprint = fingerprint(&body);
+            if let Lookup::Respond(response) = begin(&key, fingerprint).await {
+                return Ok(response);
+            }
+
+            let response = service.call(Request::from_parts(parts, Body::from(body))).await?;
+            if response.status().is_server_error() {
+                forget(&key).await;
+                return Ok(response);
+            }
+
+            // Precisamos do corpo inteiro para guardar e devolver a resposta
+            let (parts, body) = response.into_parts();
+            let body = match buffer(body, limit).await {
+                Buffered::Complete(body) => body,
+                Buffered::TooLarge(passthrough) => {
+                    forget(&key).await;
+                    return Ok(Response::from_parts(parts, passthrough));
+                }
+                Buffered::Failed => {
+                    forget(&key).await;
+                    return Ok(error(StatusCode::INTERNAL_SERVER_ERROR, "Failed to buffer response body"));
+                }
+            };
+            let stored = StoredResponse {
+                status: parts.status,
+                headers: parts.headers.clone(),
+                body: body.clone(),
+            };
+            save(key, fingerprint, stored).await;
+
+            Ok(Response::from_parts(parts, boxed(Full::from(body))))
+        })
+    }
+}
+
+#[cfg(feature = "aws")]
+mod dynamodb {
+    use std::{
+        collections::HashMap,
+        sync::atomic::{AtomicU64, Ordering},
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    use aws_sdk_dynamodb::{
+        operation::put_item::PutItemError,
+        primitives::Blob,
+        types::{AttributeValue, ReturnValuesOnConditionCheckFailure},
+    };
+    use axum::{
+        body::Bytes,
+        http::{HeaderMap, HeaderName, HeaderValue, StatusCode},
+    };
+    use tokio::sync::OnceCell;
+
+    use super::{error, replay, Lookup, StoredResponse, CONFLICTS, HITS, MISMATCHES, MISSES};
+    use crate::{aws, config::CONFIG};
+
+    /// Limite do corpo guardado: o item inteiro precisa caber em 400 KB.
+    pub const MAX_BODY: usize = 384 << 10;
+
+    static CLIENT: OnceCell<aws_sdk_dynamodb::Client> = OnceCell::const_new();
+    static ERRORS: AtomicU64 = AtomicU64::new(0);
+
+    async fn client() -> &'static aws_sdk_dynamodb::Client {
+        CLIENT
+            .get_or_init(|| async { aws_sdk_dynamodb::Client::new(aws::shared_config().await) })
+            .await
+    }
+
+    pub fn snapshot() -> serde_json::Value {
+        serde_json::json!({
+            "table": CONFIG.idempotency_table,
+            "errors": ERRORS.load(Ordering::Relaxed),
+        })
+    }
+
+    fn now() -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+    }
+
+    fn expires_at() -> AttributeValue {
+        AttributeValue::N((now() + CONFIG.idempotency_ttl.as_secs()).to_string())
+    }
+
+    fn failed(operation: &str, key: &str, message: String) {
+        ERRORS.fetch_add(1, Ordering::Relaxed);
+        eprintln!("idempotency: {} de {:?} no DynamoDB falhou: {}", operation, key, message);
+    }
+
+    pub async fn begin(key: &str, fingerprint: u64) -> Lookup {
+        let result = client()
+            .await
+            .put_item()
+            .table_name(&CONFIG.idempotency_table)
+            .item("key", AttributeValue::S(key.to_string()))
+            .item("fingerprint", AttributeValue::N(fingerprint.to_string()))
+            .item("state", AttributeValue::S("in_flight".to_string()))
+            .item("expires_at", expires_at())
+            // O TTL do DynamoDB apaga com atraso, então o item vencido conta como ausente
+            .condition_expression("attribute_not_exists(#key) OR #expires_at < :now")
+            .expression_attribute_names("#key", "key")
+            .expression_attribute_names("#expires_at", "expires_at")
+            .expression_attribute_values(":now", AttributeValue::N(now().to_string()))
+            .return_values_on_condition_check_failure(ReturnValuesOnConditionCheckFailure::AllOld)
+            .send()
+            .await;
+
+        let existing = match result {
+            Ok(_) => {
+                MISSES.fetch_add(1, Ordering::Relaxed);
+                return Lookup::Execute;
+            }
+            Err(sdk_error) => match sdk_error.into_service_error() {
+                PutItemError::ConditionalCheckFailedException(conflict) => conflict.item,
+                other => {
+                    failed("put", key, aws::describe(other));
+                    return Lookup::Respond(error(StatusCode::SERVICE_UNAVAILABLE, "Idempotency store is unavailable"));
+                }
+            },
+        };
+
+        let Some(existing) = existing.as_ref().and_then(Existing::parse) else {
+            failed("leitura", key, "item sem os atributos esperados".to_string());
+            return Lookup::Respond(error(StatusCode::SERVICE_UNAVAILABLE, "Idempotency store is unavailable"));
+        };
+        if existing.fingerprint != fingerprint {
+            MISMATCHES.fetch_add(1, Ordering::Relaxed);
+            return Lookup::Respond(error(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "Idempotency-Key was already used with a different request body",
+            ));
+        }
+        match existing.response {
+            Some(response) => {
+                HITS.fetch_add(1, Ordering::Relaxed);
+                Lookup::Respond(replay(&response))
+            }
+            None => {
+                CONFLICTS.fetch_add(1, Ordering::Relaxed);
+                Lookup::Respond(error(
+                    StatusCode::CONFLICT,
+                    "A request with this Idempotency-Key is still in progress",
+                ))
+            }
+        }
+    }
+
+    pub async fn forget(key: &str) {
+        let result = client()
+            .await
+            .delete_item()
+            .table_name(&CONFIG.idempotency_table)
+            .key("key", AttributeValue::S(key.to_string()))
+            .send()
+            .await;
+        if let Err(sdk_error) = result {
+            failed("delete", key, aws::describe(sdk_error));
+        }
+    }
+
+    pub async fn save(key: &str, fingerprint: u64, response: &StoredResponse) {
+        let result = client()
+            .await
+            .put_item()
+            .table_name(&CONFIG.idempotency_table)
+            .set_item(Some(item(key, fingerprint, response)))
+            .send()
+            .await;
+        if let Err(sdk_error) = result {
+            failed("put", key, aws::describe(sdk_error));
+        }
+    }
+
+    fn item(key: &str, fingerprint: u64, response: &StoredResponse) -> HashMap<String, AttributeValue> {
+        // Pares (nome, valor) para não perder headers repetidos; valores que
+        // não são texto ficam de fora
+        let headers: Vec<(&str, &str)> = response
+            .headers
+            .iter()
+            .filter_map(|(name, value)| Some((name.as_str(), value.to_str().ok()?)))
+            .collect();
+        HashMap::from([
+            ("key".to_string(), AttributeValue::S(key.to_string())),
+            ("fingerprint".to_string(), AttributeValue::N(fingerprint.to_string())),
+            ("state".to_string(), AttributeValue::S("done".to_string())),
+            ("expires_at".to_string(), expires_at()),
+            ("status".to_string(), AttributeValue::N(response.status.as_u16().to_string())),
+            ("headers".to_string(), AttributeValue::S(serde_json::json!(headers).to_string())),
+            ("body".to_string(), AttributeValue::B(Blob::new(response.body.to_vec()))),
+        ])
+    }
+
+    /// Item que já estava na tabela; `response` só existe quando terminou.
+    struct Existing {
+        fingerprint: u64,
+        response: Option<StoredResponse>,
+    }
+
+    impl Existing {
+        fn parse(item: &HashMap<String, AttributeValue>) -> Option<Existing> {
+            let fingerprint = item.get("fingerprint")?.as_n().ok()?.parse().ok()?;
+            if item.get("state")?.as_s().ok()? != "done" {
+                return Some(Existing { fingerprint, response: None });
+            }
+
+            let status = StatusCode::from_u16(item.get("status")?.as_n().ok()?.parse().ok()?).ok()?;
+            let pairs: Vec<(String, String)> = serde_json::from_str(item.get("headers")?.as_s().ok()?).ok()?;
+            let mut headers = HeaderMap::new();
+            for (name, value) in pairs {
+                headers.append(HeaderName::try_from(name).ok()?, HeaderValue::try_from(value).ok()?);
+            }
+            let body = Bytes::from(item.get("body")?.as_b().ok()?.clone().into_inner());
+            Some(Existing { fingerprint, response: Some(StoredResponse { status, headers, body }) })
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn stored_response_round_trips_through_the_item() {
+            let mut headers = HeaderMap::new();
+            headers.insert("content-type", HeaderValue::from_static("application/json"));
+            headers.append("set-cookie", HeaderValue::from_static("a=1"));
+            headers.append("set-cookie", HeaderValue::from_static("b=2"));
+            let response = StoredResponse {
+                status: StatusCode::CREATED,
+                headers,
+                body: Bytes::from_static(b"{\"ok\":true}"),
+            };
+
+            let existing = Existing::parse(&item("POST /json/parse k1", 42, &response)).unwrap();
+            let parsed = existing.response.unwrap();
+            assert_eq!(existing.fingerprint, 42);
+            assert_eq!(parsed.status, StatusCode::CREATED);
+            assert_eq!(parsed.headers, response.headers);
+            assert_eq!(parsed.body, response.body);
+        }
+
+        #[test]
+        fn in_flight_item_has_no_response() {
+            let item = HashMap::from([
+                ("fingerprint".to_string(), AttributeValue::N("7".to_string())),
+                ("state".to_string(), AttributeValue::S("in_flight".to_string())),
+            ]);
+            let existing = Existing::parse(&item).unwrap();
+            assert_eq!(existing.fingerprint, 7);
+            assert!(existing.response.is_none());
+        }
+    }
+}
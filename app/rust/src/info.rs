@@ -0,0 +1,100 @@
+// ======================
+// GET /info
+// ======================
+//
+// O que foi compilado e como o processo está rodando: versão, features,
+// runtime e opções de socket. Serve para anotar cada resultado do benchmark
+// com a configuração que o produziu.
+//
+// O mesmo documento sai uma vez no log, como evento `startup`, junto com o
+// endereço do listener, e toda resposta HTTP leva `X-Config-Hash`: comparar o
+// hash entre as variantes de uma execução mostra se todas rodaram com a
+// mesma configuração.
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{HeaderValue, Response, StatusCode},
+    response::IntoResponse,
+};
+use once_cell::sync::Lazy;
+
+use crate::config::CONFIG_HASH;
+
+static CONFIG_HASH_HEADER: Lazy<HeaderValue> = Lazy::new(|| HeaderValue::from_str(&CONFIG_HASH).unwrap());
+
+/// Marca a resposta com o hash da configuração e a região.
+pub fn tag<B>(mut response: Response<B>) -> Response<B> {
+    response.headers_mut().insert("X-Config-Hash", CONFIG_HASH_HEADER.clone());
+    crate::region::tag(response.headers_mut());
+    response
+}
+
+/// Loga o evento de startup (uma linha JSON). Na Lambda não há listener.
+pub fn startup(listener: Option<std::net::SocketAddr>) {
+    let mut event = document();
+    event["event"] = serde_json::json!("startup");
+    if let Some(addr) = listener {
+        event["listener"] = serde_json::json!({ "addr": addr.to_string() });
+        #[cfg(all(feature = "uring", not(feature = "lambda")))]
+        {
+            event["listener"]["uring_port"] = serde_json::json!(crate::config::CONFIG.uring_port);
+        }
+    }
+    println!("{}", event);
+}
+
+pub async fn info() -> Response<BoxBody> {
+    (StatusCode::OK, Json(document())).into_response()
+}
+
+pub fn document() -> serde_json::Value {
+    let features: Vec<&str> = [
+        ("lambda", cfg!(feature = "lambda")),
+        ("external", cfg!(feature = "external")),
+        ("aws", cfg!(feature = "aws")),
+        ("hyper-server", cfg!(feature = "hyper-server")),
+        ("uring", cfg!(feature = "uring")),
+    ]
+    .into_iter()
+    .filter_map(|(name, enabled)| enabled.then_some(name))
+    .collect();
+
+    let mut body = serde_json::json!({
+        "name": env!("CARGO_PKG_NAME"),
+        "version": env!("CARGO_PKG_VERSION"),
+        "build": {
+            "profile": if cfg!(debug_assertions) { "debug" } else { "release" },
+            "target": format!("{}-{}", std::env::consts::ARCH, std::env::consts::OS),
+        },
+        "features": features,
+        "config_hash": *CONFIG_HASH,
+        "middleware": crate::middleware::snapshot(),
+        "calibration": *crate::calibration::REPORT,
+    });
+    if !body["calibration"].is_null() {
+        body["calibration"]["host"] = crate::calibration::host_snapshot();
+    }
+
+    #[cfg(not(feature = "lambda"))]
+    {
+        use crate::config::CONFIG;
+
+        body["runtime"] = serde_json::json!(CONFIG.runtime);
+        if CONFIG.runtime == "thread-per-core" {
+            body["workers"] = serde_json::json!(crate::thread_per_core::workers());
+        }
+        body["socket"] = crate::listener::snapshot();
+    }
+    #[cfg(all(feature = "hyper-server", not(feature = "lambda")))]
+    {
+        body["server"] = serde_json::json!(crate::config::CONFIG.server);
+    }
+    #[cfg(feature = "lambda")]
+    {
+        body["lambda_mode"] = serde_json::json!(crate::config::CONFIG.lambda_mode);
+    }
+    crate::run::annotate(&mut body);
+
+    body
+}
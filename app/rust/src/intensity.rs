@@ -0,0 +1,194 @@
+// ======================
+// ESCALA DE INTENSIDADE AO LONGO DA EXECUÇÃO
+// ======================
+//
+// `BFF_INTENSITY_SCHEDULE=600:/burn.duration_ms=2,1200:/burn.duration_ms=1`
+// agenda degraus: a partir de 600 s de processo, o campo numérico
+// `duration_ms` do corpo JSON de /burn é multiplicado por 2; a partir de
+// 1200 s, volta a 1. Cada degrau define o fator daquele campo dali em diante
+// (não acumula). Um controlador em background aplica os degraus na hora
+// certa e loga cada troca; o middleware só lê os fatores vigentes.
+//
+// A regra é só "multiplica o campo X da rota Y a partir de T", então é fácil
+// de repetir igual nas outras linguagens. Se a request não traz o campo, nada
+// muda: o harness deve mandar o valor explícito. Respostas escaladas levam
+// `X-Intensity` com os fatores aplicados.
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    sync::{Once, RwLock},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    body::{Body, BoxBody},
+    http::{HeaderValue, Request, Response},
+};
+use once_cell::sync::Lazy;
+use tower::{Layer, Service};
+
+use crate::{body_limit, config::CONFIG};
+
+pub struct Step {
+    pub at: Duration,
+    pub route: String,
+    pub field: String,
+    pub factor: f64,
+}
+
+/// `at_secs:/rota.campo=fator`; `None` se o item estiver malformado.
+pub fn parse_step(item: &str) -> Option<Step> {
+    let (at, rest) = item.split_once(':')?;
+    let (target, factor) = rest.split_once('=')?;
+    let (route, field) = target.rsplit_once('.')?;
+    let factor: f64 = factor.trim().parse().ok()?;
+    if !route.starts_with('/') || field.is_empty() || !factor.is_finite() || factor < 0.0 {
+        return None;
+    }
+    Some(Step {
+        at: Duration::from_secs(at.trim().parse().ok()?),
+        route: route.to_string(),
+        field: field.to_string(),
+        factor,
+    })
+}
+
+static STARTED: Lazy<Instant> = Lazy::new(Instant::now);
+static START: Once = Once::new();
+
+// (campo, fator) vigentes de uma rota
+type Factors = Vec<(String, f64)>;
+
+static FACTORS: Lazy<RwLock<HashMap<String, Factors>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+fn apply(step: &Step) {
+    let mut factors = FACTORS.write().unwrap();
+    let fields = factors.entry(step.route.clone()).or_default();
+    match fields.iter_mut().find(|(field, _)| *field == step.field) {
+        Some(entry) => entry.1 = step.factor,
+        None => fields.push((step.field.clone(), step.factor)),
+    }
+    let mut event = serde_json::json!({
+        "event": "intensity",
+        "elapsed_secs": STARTED.elapsed().as_secs(),
+        "route": step.route,
+        "field": step.field,
+        "factor": step.factor,
+    });
+    crate::run::annotate(&mut event);
+    println!("{}", event);
+}
+
+/// Sobe o controlador (uma vez por processo, no runtime atual).
+pub fn start() {
+    START.call_once(|| {
+        Lazy::force(&STARTED);
+        if CONFIG.intensity_schedule.is_empty() {
+            return;
+        }
+        tokio::spawn(async {
+            for step in CONFIG.intensity_schedule.iter() {
+                tokio::time::sleep_until((*STARTED + step.at).into()).await;
+                apply(step);
+            }
+        });
+    });
+}
+
+pub fn snapshot() -> serde_json::Value {
+    let elapsed = STARTED.elapsed();
+    let next = CONFIG.intensity_schedule.iter().find(|step| step.at > elapsed);
+    serde_json::json!({
+        "elapsed_secs": elapsed.as_secs(),
+        "factors": *FACTORS.read().unwrap(),
+        "next": next.map(|step| serde_json::json!({
+            "at_secs": step.at.as_secs(),
+            "route": step.route,
+            "field": step.field,
+            "factor": step.factor,
+        })),
+    })
+}
+
+fn scale(body: &mut serde_json::Value, fields: &[(String, f64)]) -> Vec<String> {
+    let mut applied = Vec::new();
+    for (field, factor) in fields {
+        let Some(value) = body.get_mut(field) else { continue };
+        let scaled = if let Some(n) = value.as_u64() {
+            serde_json::json!((n as f64 * factor).round() as u64)
+        } else if let Some(n) = value.as_f64() {
+            serde_json::json!(n * factor)
+        } else {
+            continue;
+        };
+        *value = scaled;
+        applied.push(format!("{}={}", field, factor));
+    }
+    applied
+}
+
+#[derive(Clone)]
+pub struct IntensityLayer;
+
+#[derive(Clone)]
+pub struct IntensityService<S> {
+    inner: S,
+}
+
+impl<S> Layer<S> for IntensityLayer {
+    type Service = IntensityService<S>;
+    fn layer(&self, inner: S) -> Self::Service {
+        IntensityService { inner }
+    }
+}
+
+impl<S> Service<Request<Body>> for IntensityService<S>
+where
+    S: Service<Request<Body>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<BoxBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let mut service = self.inner.clone();
+
+        let fields = match FACTORS.read().unwrap().get(req.uri().path()) {
+            Some(fields) => fields.clone(),
+            None => return Box::pin(service.call(req)),
+        };
+
+        Box::pin(async move {
+            let (parts, bytes) = match body_limit::read(req).await {
+                Ok(read) => read,
+                Err(response) => return Ok(response),
+            };
+
+            // Corpo que não é um objeto JSON segue intacto, e o handler decide
+            let mut applied = Vec::new();
+            let body = match serde_json::from_slice::<serde_json::Value>(&bytes) {
+                Ok(mut value) if value.is_object() => {
+                    applied = scale(&mut value, &fields);
+                    Body::from(value.to_string())
+                }
+                _ => Body::from(bytes),
+            };
+
+            let mut response = service.call(Request::from_parts(parts, body)).await?;
+            if !applied.is_empty() {
+                response
+                    .headers_mut()
+                    .insert("X-Intensity", HeaderValue::from_str(&applied.join(",")).unwrap());
+            }
+            Ok(response)
+        })
+    }
+}
@@ -0,0 +1,246 @@
+// ======================
+// PARSE DE JSON GRANDE
+// ======================
+//
+// Faz o parse de um documento de pedidos (`{"orders": [...]}`) com a
+// estratégia escolhida em `parser` e agrega o mesmo resumo em todas:
+//
+// - `dom`: `serde_json::Value` inteiro na memória, percorrido depois
+// - `typed`: direto para structs com serde
+// - `raw`: o array vira fatias `&RawValue` do texto original e cada pedido é
+//   desserializado e descartado, um de cada vez
+// - `stream`: NDJSON (um pedido por linha) lido com `StreamDeserializer`
+//
+// O documento vem em `document` (texto; NDJSON para `stream`) ou é gerado a
+// partir da seed com `orders` pedidos (~300 bytes cada). `peak_memory_bytes` é
+// o pico de heap do parse mais a agregação, medido pelo alocador (heap.rs);
+// o texto do documento fica de fora.
+
+use std::time::Instant;
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
+
+use crate::{
+    heap,
+    rng::{self, SplitMix64},
+};
+
+const MAX_ORDERS: usize = 200_000;
+
+#[derive(Deserialize)]
+pub struct JsonParsePayload {
+    parser: Option<String>,
+    orders: Option<usize>,
+    document: Option<String>,
+    seed: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Customer {
+    id: u64,
+    name: String,
+    email: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Item {
+    sku: String,
+    quantity: u32,
+    price: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Order {
+    id: u64,
+    customer: Customer,
+    status: String,
+    created_at: String,
+    items: Vec<Item>,
+    tags: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Document {
+    orders: Vec<Order>,
+}
+
+#[derive(Deserialize)]
+struct RawDocument<'a> {
+    #[serde(borrow)]
+    orders: Vec<&'a RawValue>,
+}
+
+#[derive(Default)]
+struct Summary {
+    orders: u64,
+    items: u64,
+    quantity: u64,
+    amount: f64,
+}
+
+impl Summary {
+    fn add(&mut self, order: &Order) {
+        self.orders += 1;
+        for item in &order.items {
+            self.items += 1;
+            self.quantity += item.quantity as u64;
+            self.amount += item.quantity as f64 * item.price;
+        }
+    }
+}
+
+const NAMES: &[&str] = &["Ana Souza", "Bruno Lima", "Carla Dias", "Diego Alves", "Elisa Rocha", "Fábio Nunes"];
+const STATUSES: &[&str] = &["pending", "paid", "shipped", "delivered", "cancelled"];
+const TAGS: &[&str] = &["gift", "express", "fragile", "bulk", "promo"];
+
+fn generate(count: usize, seed: u64) -> Vec<Order> {
+    let mut rng = SplitMix64::new(seed);
+    (0..count as u64)
+        .map(|id| {
+            let customer = rng.next_below(10_000);
+            let name = NAMES[rng.next_below(NAMES.len() as u64) as usize];
+            Order {
+                id,
+                customer: Customer {
+                    id: customer,
+                    name: name.to_string(),
+                    email: format!("customer{}@example.com", customer),
+                },
+                status: STATUSES[rng.next_below(STATUSES.len() as u64) as usize].to_string(),
+                created_at: format!(
+                    "2024-{:02}-{:02}T{:02}:{:02}:00Z",
+                    rng.next_below(12) + 1,
+                    rng.next_below(28) + 1,
+                    rng.next_below(24),
+                    rng.next_below(60)
+                ),
+                items: (0..rng.next_below(4) + 1)
+                    .map(|_| Item {
+                        sku: format!("SKU-{:05}", rng.next_below(100_000)),
+                        quantity: rng.next_below(5) as u32 + 1,
+                        price: (rng.next_below(100_000) + 100) as f64 / 100.0,
+                    })
+                    .collect(),
+                tags: (0..rng.next_below(3)).map(|_| TAGS[rng.next_below(TAGS.len() as u64) as usize].to_string()).collect(),
+            }
+        })
+        .collect()
+}
+
+/// O documento `{"orders": [...]}` gerado, em texto (usado também pelo /protobuf).
+pub fn generate_document(count: usize, seed: u64) -> String {
+    serde_json::to_string(&Document { orders: generate(count, seed) }).unwrap()
+}
+
+fn parse_dom(text: &str, summary: &mut Summary) -> Result<(), String> {
+    let document: serde_json::Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+    let orders = document.get("orders").and_then(|o| o.as_array()).ok_or("Missing orders array")?;
+    for order in orders {
+        summary.orders += 1;
+        for item in order.get("items").and_then(|i| i.as_array()).ok_or("Order without items array")? {
+            let quantity = item.get("quantity").and_then(|q| q.as_u64()).ok_or("Item without quantity")?;
+            let price = item.get("price").and_then(|p| p.as_f64()).ok_or("Item without price")?;
+            summary.items += 1;
+            summary.quantity += quantity;
+            summary.amount += quantity as f64 * price;
+        }
+    }
+    Ok(())
+}
+
+fn parse(parser: &str, text: &str, summary: &mut Summary) -> Result<(), String> {
+    match parser {
+        "dom" => parse_dom(text, summary),
+        "typed" => {
+            let document: Document = serde_json::from_str(text).map_err(|e| e.to_string())?;
+            document.orders.iter().for_each(|order| summary.add(order));
+            Ok(())
+        }
+        "raw" => {
+            let document: RawDocument = serde_json::from_str(text).map_err(|e| e.to_string())?;
+            for raw in document.orders {
+                let order: Order = serde_json::from_str(raw.get()).map_err(|e| e.to_string())?;
+                summary.add(&order);
+            }
+            Ok(())
+        }
+        _ => {
+            for order in serde_json::Deserializer::from_str(text).into_iter::<Order>() {
+                summary.add(&order.map_err(|e| e.to_string())?);
+            }
+            Ok(())
+        }
+    }
+}
+
+pub async fn jsonparse(Json(payload): Json<JsonParsePayload>) -> Response<BoxBody> {
+    let parser = payload.parser.unwrap_or_else(|| "typed".to_string());
+    if !["dom", "typed", "raw", "stream"].contains(&parser.as_str()) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "parser must be dom, typed, raw or stream" }))
+        )
+        .into_response();
+    }
+    let format = if parser == "stream" { "ndjson" } else { "document" };
+
+    let generate_start = Instant::now();
+    let text = match payload.document {
+        Some(document) => document,
+        None => {
+            let count = payload.orders.unwrap_or(10_000);
+            if count > MAX_ORDERS {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({ "error": format!("orders must be at most {}", MAX_ORDERS) }))
+                )
+                .into_response();
+            }
+            let orders = generate(count, rng::seed(payload.seed));
+            if format == "ndjson" {
+                orders.iter().map(|order| serde_json::to_string(order).unwrap() + "\n").collect()
+            } else {
+                serde_json::to_string(&Document { orders }).unwrap()
+            }
+        }
+    };
+    let generate_ms = generate_start.elapsed().as_secs_f64() * 1000.0;
+
+    let start = Instant::now();
+    let mut summary = Summary::default();
+    let (result, peak) = heap::measure(|| parse(&parser, &text, &mut summary));
+    let duration = start.elapsed();
+
+    if let Err(error) = result {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": format!("Invalid {}: {}", format, error) }))
+        )
+        .into_response();
+    }
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "parser": parser,
+            "format": format,
+            "bytes": text.len(),
+            "orders": summary.orders,
+            "items": summary.items,
+            "quantity": summary.quantity,
+            "amount": summary.amount,
+            "peak_memory_bytes": peak,
+            "generate_ms": generate_ms,
+            "parse_ms": duration.as_secs_f64() * 1000.0,
+            "mb_per_second": text.len() as f64 / 1e6 / duration.as_secs_f64(),
+        }))
+    )
+    .into_response()
+}
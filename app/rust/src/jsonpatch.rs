@@ -0,0 +1,434 @@
+// ======================
+// JSON PATCH (RFC 6902) E MERGE PATCH (RFC 7386)
+// ======================
+//
+// Aplica `patch` em `document` `iterations` vezes (cada vez sobre uma cópia
+// nova do documento) e devolve o resultado. Um array em `patch` é JSON Patch
+// (add, remove, replace, move, copy, test, com JSON Pointer); qualquer outro
+// valor é merge patch. `format` força um dos dois.
+//
+// Sem `document`/`patch`, ambos são gerados a partir da seed: `users` usuários
+// e `operations` operações de JSON Patch sobre eles. Como no RFC, o patch é
+// atômico: se uma operação falha, nada é devolvido além do erro.
+
+use std::time::Instant;
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::rng::{self, SplitMix64};
+
+const MAX_ITERATIONS: usize = 100_000;
+const MAX_GENERATED: usize = 100_000;
+/// Bytes de documento copiados no total (iterations x tamanho)
+const MAX_WORK: usize = 2_000_000_000;
+
+#[derive(Deserialize)]
+pub struct JsonPatchPayload {
+    document: Option<Value>,
+    patch: Option<Value>,
+    format: Option<String>,
+    iterations: Option<usize>,
+    users: Option<usize>,
+    operations: Option<usize>,
+    seed: Option<u64>,
+}
+
+// ------------
+// JSON Pointer (RFC 6901)
+// ------------
+fn tokens(pointer: &str) -> Result<Vec<String>, String> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    if !pointer.starts_with('/') {
+        return Err(format!("Invalid pointer: {}", pointer));
+    }
+    Ok(pointer[1..].split('/').map(|t| t.replace("~1", "/").replace("~0", "~")).collect())
+}
+
+fn index(token: &str, len: usize, allow_end: bool) -> Result<usize, String> {
+    if allow_end && token == "-" {
+        return Ok(len);
+    }
+    // Sem zeros à esquerda nem sinal, como manda o RFC
+    let digits = !token.is_empty() && token.bytes().all(|b| b.is_ascii_digit());
+    let i: usize = match token.parse() {
+        Ok(i) if digits && (token == "0" || !token.starts_with('0')) => i,
+        _ => return Err(format!("Invalid index: {}", token)),
+    };
+    if i > len || (i == len && !allow_end) {
+        return Err(format!("Index out of bounds: {}", token));
+    }
+    Ok(i)
+}
+
+fn resolve<'a>(document: &'a mut Value, tokens: &[String]) -> Result<&'a mut Value, String> {
+    tokens.iter().try_fold(document, |value, token| match value {
+        Value::Object(map) => map.get_mut(token).ok_or_else(|| format!("Path not found: {}", token)),
+        Value::Array(items) => {
+            let i = index(token, items.len(), false)?;
+            Ok(&mut items[i])
+        }
+        _ => Err(format!("Cannot descend into scalar at {}", token)),
+    })
+}
+
+fn get(document: &mut Value, pointer: &str) -> Result<Value, String> {
+    resolve(document, &tokens(pointer)?).map(|value| value.clone())
+}
+
+fn add(document: &mut Value, pointer: &str, value: Value) -> Result<(), String> {
+    let tokens = tokens(pointer)?;
+    let Some((last, parent)) = tokens.split_last() else {
+        *document = value;
+        return Ok(());
+    };
+    match resolve(document, parent)? {
+        Value::Object(map) => {
+            map.insert(last.clone(), value);
+            Ok(())
+        }
+        Value::Array(items) => {
+            let i = index(last, items.len(), true)?;
+            items.insert(i, value);
+            Ok(())
+        }
+        _ => Err(format!("Cannot add to scalar at {}", pointer)),
+    }
+}
+
+fn remove(document: &mut Value, pointer: &str) -> Result<Value, String> {
+    let tokens = tokens(pointer)?;
+    let (last, parent) = tokens.split_last().ok_or("Cannot remove the whole document")?;
+    match resolve(document, parent)? {
+        Value::Object(map) => map.remove(last).ok_or_else(|| format!("Path not found: {}", pointer)),
+        Value::Array(items) => {
+            let i = index(last, items.len(), false)?;
+            Ok(items.remove(i))
+        }
+        _ => Err(format!("Cannot remove from scalar at {}", pointer)),
+    }
+}
+
+fn apply_operation(document: &mut Value, operation: &Value) -> Result<(), String> {
+    let field = |name: &str| operation.get(name).and_then(Value::as_str).ok_or_else(|| format!("Missing {}", name));
+    let value = || operation.get("value").cloned().ok_or("Missing value");
+    let path = field("path")?;
+    match field("op")? {
+        "add" => add(document, path, value()?),
+        "remove" => remove(document, path).map(|_| ()),
+        "replace" => {
+            let target = resolve(document, &tokens(path)?)?;
+            *target = value()?;
+            Ok(())
+        }
+        "move" => {
+            let from = field("from")?;
+            if path.starts_with(from) && path[from.len()..].starts_with('/') {
+                return Err(format!("Cannot move {} into itself", from));
+            }
+            let moved = remove(document, from)?;
+            add(document, path, moved)
+        }
+        "copy" => {
+            let copied = get(document, field("from")?)?;
+            add(document, path, copied)
+        }
+        "test" => {
+            let expected = value()?;
+            if crate::validation::round(&get(document, path)?) == crate::validation::round(&expected) {
+                Ok(())
+            } else {
+                Err(format!("Test failed at {}", path))
+            }
+        }
+        other => Err(format!("Unknown op: {}", other)),
+    }
+}
+
+// ------------
+// Merge patch (RFC 7386)
+// ------------
+fn merge(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Default::default());
+    }
+    let map = target.as_object_mut().unwrap();
+    for (key, value) in patch {
+        if value.is_null() {
+            map.remove(key);
+        } else {
+            merge(map.entry(key.clone()).or_insert(Value::Null), value);
+        }
+    }
+}
+
+// ------------
+// Dados gerados
+// ------------
+const ROLES: &[&str] = &["admin", "editor", "viewer", "billing", "support"];
+const THEMES: &[&str] = &["light", "dark", "system"];
+
+fn generate(users: usize, operations: usize, seed: u64) -> (Value, Value) {
+    let mut rng = SplitMix64::new(seed);
+    let document = serde_json::json!({
+        "version": 1,
+        "users": (0..users).map(|id| serde_json::json!({
+            "id": id,
+            "name": format!("user{}", id),
+            "active": rng.next_below(2) == 0,
+            "roles": (0..rng.next_below(3) + 1).map(|_| ROLES[rng.next_below(ROLES.len() as u64) as usize]).collect::<Vec<_>>(),
+            "settings": { "theme": THEMES[rng.next_below(THEMES.len() as u64) as usize], "notifications": rng.next_below(2) == 0 },
+        })).collect::<Vec<_>>(),
+    });
+
+    // Operações sempre válidas: os índices ficam abaixo do menor tamanho que
+    // a lista de usuários pode ter depois das operações anteriores
+    let mut len = users;
+    let patch: Vec<Value> = (0..operations)
+        .map(|i| {
+            let user = rng.next_below(len.max(1) as u64);
+            match rng.next_below(6) {
+                0 if len > 1 => {
+                    len -= 1;
+                    serde_json::json!({ "op": "remove", "path": format!("/users/{}", user) })
+                }
+                1 => serde_json::json!({ "op": "add", "path": format!("/users/{}/roles/-", user), "value": ROLES[i % ROLES.len()] }),
+                2 => serde_json::json!({ "op": "replace", "path": format!("/users/{}/settings/theme", user), "value": THEMES[i % THEMES.len()] }),
+                3 => serde_json::json!({ "op": "copy", "from": format!("/users/{}/settings", user), "path": "/lastSettings" }),
+                4 => serde_json::json!({ "op": "move", "from": format!("/users/{}/roles/0", user), "path": format!("/users/{}/roles/-", user) }),
+                _ => serde_json::json!({ "op": "replace", "path": "/version", "value": i + 2 }),
+            }
+        })
+        .collect();
+    (document, Value::Array(patch))
+}
+
+pub async fn jsonpatch(Json(payload): Json<JsonPatchPayload>) -> Response<BoxBody> {
+    let iterations = payload.iterations.unwrap_or(1);
+    let users = payload.users.unwrap_or(100);
+    let operations = payload.operations.unwrap_or(20);
+    if iterations == 0 || iterations > MAX_ITERATIONS || users == 0 || users > MAX_GENERATED || operations > MAX_GENERATED {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "iterations, users or operations out of range" }))
+        )
+        .into_response();
+    }
+
+    let (document, patch) = match (payload.document, payload.patch) {
+        (Some(document), Some(patch)) => (document, patch),
+        (None, None) => generate(users, operations, rng::seed(payload.seed)),
+        _ => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Pass both document and patch, or neither" }))
+            )
+            .into_response();
+        }
+    };
+    let format = payload
+        .format
+        .unwrap_or_else(|| if patch.is_array() { "json-patch" } else { "merge-patch" }.to_string());
+    let operations_list = match (format.as_str(), &patch) {
+        ("json-patch", Value::Array(operations)) => Some(operations),
+        ("json-patch", _) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "A JSON Patch must be an array of operations" }))
+            )
+            .into_response();
+        }
+        ("merge-patch", _) => None,
+        _ => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "format must be json-patch or merge-patch" }))
+            )
+            .into_response();
+        }
+    };
+    if iterations.saturating_mul(document.to_string().len()) > MAX_WORK {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "document size times iterations is too large" }))
+        )
+        .into_response();
+    }
+
+    let start = Instant::now();
+    let mut result = Value::Null;
+    for _ in 0..iterations {
+        result = document.clone();
+        match operations_list {
+            Some(operations) => {
+                for (i, operation) in operations.iter().enumerate() {
+                    if let Err(error) = apply_operation(&mut result, operation) {
+                        return (
+                            StatusCode::BAD_REQUEST,
+                            Json(serde_json::json!({ "error": format!("Operation {} failed: {}", i, error) }))
+                        )
+                        .into_response();
+                    }
+                }
+            }
+            None => merge(&mut result, &patch),
+        }
+    }
+    let duration = start.elapsed();
+    let applied = operations_list.map(|o| o.len()).unwrap_or(1) * iterations;
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "format": format,
+            "iterations": iterations,
+            "operations": applied,
+            "result": result,
+            "duration_ms": duration.as_secs_f64() * 1000.0,
+            "operations_per_second": applied as f64 / duration.as_secs_f64(),
+        }))
+    )
+    .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn patch(mut document: Value, operations: Value) -> Result<Value, String> {
+        for operation in operations.as_array().unwrap() {
+            apply_operation(&mut document, operation)?;
+        }
+        Ok(document)
+    }
+
+    // Exemplos do apêndice A do RFC 6902
+    #[test]
+    fn rfc_6902_appendix_examples() {
+        let cases = [
+            // A.1 a A.4
+            (json!({"foo": "bar"}), json!([{"op": "add", "path": "/baz", "value": "qux"}]), json!({"baz": "qux", "foo": "bar"})),
+            (json!({"foo": ["bar", "baz"]}), json!([{"op": "add", "path": "/foo/1", "value": "qux"}]), json!({"foo": ["bar", "qux", "baz"]})),
+            (json!({"baz": "qux", "foo": "bar"}), json!([{"op": "remove", "path": "/baz"}]), json!({"foo": "bar"})),
+            (json!({"foo": ["bar", "qux", "baz"]}), json!([{"op": "remove", "path": "/foo/1"}]), json!({"foo": ["bar", "baz"]})),
+            // A.5 a A.7
+            (json!({"baz": "qux", "foo": "bar"}), json!([{"op": "replace", "path": "/baz", "value": "boo"}]), json!({"baz": "boo", "foo": "bar"})),
+            (
+                json!({"foo": {"bar": "baz", "waldo": "fred"}, "qux": {"corge": "grault"}}),
+                json!([{"op": "move", "from": "/foo/waldo", "path": "/qux/thud"}]),
+                json!({"foo": {"bar": "baz"}, "qux": {"corge": "grault", "thud": "fred"}}),
+            ),
+            (json!({"foo": ["all", "grass", "cows", "eat"]}), json!([{"op": "move", "from": "/foo/1", "path": "/foo/3"}]), json!({"foo": ["all", "cows", "eat", "grass"]})),
+            // A.8
+            (
+                json!({"baz": "qux", "foo": ["a", 2, "c"]}),
+                json!([{"op": "test", "path": "/baz", "value": "qux"}, {"op": "test", "path": "/foo/1", "value": 2}]),
+                json!({"baz": "qux", "foo": ["a", 2, "c"]}),
+            ),
+            // A.10, A.11 (membros desconhecidos são ignorados), A.14 e A.16
+            (json!({"foo": "bar"}), json!([{"op": "add", "path": "/child", "value": {"grandchild": {}}}]), json!({"foo": "bar", "child": {"grandchild": {}}})),
+            (json!({"foo": "bar"}), json!([{"op": "add", "path": "/baz", "value": "qux", "xyz": 123}]), json!({"foo": "bar", "baz": "qux"})),
+            (json!({"/": 9, "~1": 10}), json!([{"op": "test", "path": "/~01", "value": 10}]), json!({"/": 9, "~1": 10})),
+            (json!({"foo": ["bar"]}), json!([{"op": "add", "path": "/foo/-", "value": ["abc", "def"]}]), json!({"foo": ["bar", ["abc", "def"]]})),
+        ];
+        for (document, operations, expected) in cases {
+            assert_eq!(patch(document, operations.clone()).unwrap(), expected, "{}", operations);
+        }
+
+        // A.9, A.12 e A.15
+        assert_eq!(
+            patch(json!({"baz": "qux"}), json!([{"op": "test", "path": "/baz", "value": "bar"}])).unwrap_err(),
+            "Test failed at /baz"
+        );
+        assert!(patch(json!({"foo": "bar"}), json!([{"op": "add", "path": "/baz/bat", "value": "qux"}])).is_err());
+        assert!(patch(json!({"/": 9, "~1": 10}), json!([{"op": "test", "path": "/~01", "value": "10"}])).is_err());
+    }
+
+    #[test]
+    fn pointer_tokens_are_unescaped() {
+        assert_eq!(tokens("/a~1b/m~0n/~01").unwrap(), ["a/b", "m~n", "~1"]);
+        assert_eq!(tokens("").unwrap(), Vec::<String>::new());
+        assert!(tokens("a").is_err());
+
+        let document = json!({"a/b": 1, "m~n": 2});
+        assert_eq!(patch(document, json!([{"op": "remove", "path": "/a~1b"}, {"op": "replace", "path": "/m~0n", "value": 3}])).unwrap(), json!({"m~n": 3}));
+    }
+
+    #[test]
+    fn dash_appends_only_where_allowed() {
+        assert_eq!(patch(json!([1, 2]), json!([{"op": "add", "path": "/-", "value": 3}])).unwrap(), json!([1, 2, 3]));
+        assert_eq!(patch(json!([1, 2]), json!([{"op": "copy", "from": "/0", "path": "/-"}])).unwrap(), json!([1, 2, 1]));
+        assert_eq!(patch(json!([1, 2]), json!([{"op": "remove", "path": "/-"}])).unwrap_err(), "Invalid index: -");
+        assert!(patch(json!([1, 2]), json!([{"op": "replace", "path": "/-", "value": 3}])).is_err());
+    }
+
+    #[test]
+    fn indexes_out_of_range_are_rejected() {
+        assert_eq!(patch(json!([1, 2]), json!([{"op": "add", "path": "/2", "value": 3}])).unwrap(), json!([1, 2, 3]));
+        assert_eq!(patch(json!([1, 2]), json!([{"op": "add", "path": "/3", "value": 3}])).unwrap_err(), "Index out of bounds: 3");
+        assert_eq!(patch(json!([1, 2]), json!([{"op": "remove", "path": "/2"}])).unwrap_err(), "Index out of bounds: 2");
+        assert_eq!(patch(json!([1, 2]), json!([{"op": "remove", "path": "/01"}])).unwrap_err(), "Invalid index: 01");
+        assert_eq!(patch(json!([1, 2]), json!([{"op": "remove", "path": "/-1"}])).unwrap_err(), "Invalid index: -1");
+    }
+
+    #[test]
+    fn move_into_own_child_is_rejected() {
+        let error = patch(json!({"a": {"b": 1}}), json!([{"op": "move", "from": "/a", "path": "/a/b/c"}])).unwrap_err();
+        assert_eq!(error, "Cannot move /a into itself");
+    }
+
+    async fn run(payload: Value) -> (StatusCode, Value) {
+        let response = jsonpatch(Json(serde_json::from_value(payload).unwrap())).await;
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn missing_path_is_a_bad_request() {
+        let (status, body) = run(json!({
+            "document": {"foo": "bar"},
+            "patch": [{"op": "replace", "path": "/baz", "value": 1}],
+        }))
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "Operation 0 failed: Path not found: baz");
+    }
+
+    #[tokio::test]
+    async fn failed_test_leaves_no_result() {
+        let (status, body) = run(json!({
+            "document": {"foo": "bar"},
+            "patch": [{"op": "add", "path": "/baz", "value": 1}, {"op": "test", "path": "/foo", "value": "qux"}],
+        }))
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body, json!({"error": "Operation 1 failed: Test failed at /foo"}));
+    }
+
+    #[tokio::test]
+    async fn merge_patch_follows_rfc_7386() {
+        let (status, body) = run(json!({
+            "document": {"a": "b", "c": {"d": "e", "f": "g"}},
+            "patch": {"a": "z", "c": {"f": null}},
+        }))
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["format"], "merge-patch");
+        assert_eq!(body["result"], json!({"a": "z", "c": {"d": "e"}}));
+    }
+}
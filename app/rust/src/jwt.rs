@@ -0,0 +1,148 @@
+// ======================
+// JWT (HS256 E RS256)
+// ======================
+//
+// O trabalho que todo BFF faz em toda request: `action: "sign"` emite um token
+// com `claims` (ou claims geradas: sub, scope, iat e exp em uma hora) e
+// `action: "verify"` confere `token` e devolve as claims decodificadas. A
+// operação roda `iterations` vezes e só ela entra no tempo.
+//
+// HS256 usa `secret` (padrão `benchmark-secret`); RS256 usa o par de 2048
+// bits de `jwt_keys/`, gerado só para o benchmark. `exp` e `nbf` são
+// conferidos quando estão nas claims, mas nenhuma claim é obrigatória.
+
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::progress::Progress;
+
+const MAX_ITERATIONS: usize = 100_000;
+
+static RS256_ENCODING: Lazy<EncodingKey> =
+    Lazy::new(|| EncodingKey::from_rsa_pem(include_bytes!("jwt_keys/rs256_private.pem")).expect("chave RS256 inválida"));
+static RS256_DECODING: Lazy<DecodingKey> =
+    Lazy::new(|| DecodingKey::from_rsa_pem(include_bytes!("jwt_keys/rs256_public.pem")).expect("chave RS256 inválida"));
+
+#[derive(Deserialize)]
+pub struct JwtPayload {
+    action: String,
+    algorithm: Option<String>,
+    claims: Option<Value>,
+    token: Option<String>,
+    secret: Option<String>,
+    iterations: Option<usize>,
+}
+
+fn bad_request(error: String) -> Response<BoxBody> {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({ "error": error }))
+    )
+    .into_response()
+}
+
+fn default_claims() -> Value {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    serde_json::json!({
+        "sub": "user-42",
+        "scope": "orders:read orders:write",
+        "iat": now,
+        "exp": now + 3600,
+    })
+}
+
+pub async fn jwt(Json(payload): Json<JwtPayload>) -> Response<BoxBody> {
+    let iterations = payload.iterations.unwrap_or(1);
+    if iterations == 0 || iterations > MAX_ITERATIONS {
+        return bad_request(format!("iterations must be between 1 and {}", MAX_ITERATIONS));
+    }
+    let name = payload.algorithm.unwrap_or_else(|| "HS256".to_string());
+    let algorithm = match name.as_str() {
+        "HS256" => Algorithm::HS256,
+        "RS256" => Algorithm::RS256,
+        other => return bad_request(format!("Unsupported algorithm: {}", other)),
+    };
+    let secret = payload.secret.unwrap_or_else(|| "benchmark-secret".to_string());
+
+    let mut progress = Progress::new(iterations as u64);
+    let start = Instant::now();
+    let mut body = match payload.action.as_str() {
+        "sign" => {
+            let claims = payload.claims.unwrap_or_else(default_claims);
+            if !claims.is_object() {
+                return bad_request("claims must be an object".to_string());
+            }
+            let hs256;
+            let key = match algorithm {
+                Algorithm::HS256 => {
+                    hs256 = EncodingKey::from_secret(secret.as_bytes());
+                    &hs256
+                }
+                _ => &*RS256_ENCODING,
+            };
+            let header = Header::new(algorithm);
+            let mut token = String::new();
+            for i in 0..iterations {
+                token = match jsonwebtoken::encode(&header, &claims, key) {
+                    Ok(token) => token,
+                    Err(error) => return bad_request(format!("Signing failed: {}", error)),
+                };
+                progress.tick(i as u64 + 1);
+            }
+            serde_json::json!({ "token": token })
+        }
+        "verify" => {
+            let Some(token) = payload.token else {
+                return bad_request("verify needs a token".to_string());
+            };
+            let hs256;
+            let key = match algorithm {
+                Algorithm::HS256 => {
+                    hs256 = DecodingKey::from_secret(secret.as_bytes());
+                    &hs256
+                }
+                _ => &*RS256_DECODING,
+            };
+            let mut validation = Validation::new(algorithm);
+            validation.required_spec_claims.clear();
+            validation.validate_nbf = true;
+            let mut claims = Value::Null;
+            for i in 0..iterations {
+                claims = match jsonwebtoken::decode::<Value>(&token, key, &validation) {
+                    Ok(data) => data.claims,
+                    Err(error) => {
+                        return (
+                            StatusCode::UNAUTHORIZED,
+                            Json(serde_json::json!({ "error": format!("Invalid token: {}", error) }))
+                        )
+                        .into_response();
+                    }
+                };
+                progress.tick(i as u64 + 1);
+            }
+            serde_json::json!({ "claims": claims })
+        }
+        other => return bad_request(format!("action must be sign or verify, got {}", other)),
+    };
+    let duration = start.elapsed();
+
+    body["action"] = serde_json::json!(payload.action);
+    body["algorithm"] = serde_json::json!(name);
+    body["iterations"] = serde_json::json!(iterations);
+    body["duration_ms"] = serde_json::json!(duration.as_secs_f64() * 1000.0);
+    body["operations_per_second"] = serde_json::json!(iterations as f64 / duration.as_secs_f64());
+    if let Some(report) = progress.report() {
+        body["progress"] = report;
+    }
+    (StatusCode::OK, Json(body)).into_response()
+}
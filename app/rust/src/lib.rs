@@ -0,0 +1,752 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::{Instant, UNIX_EPOCH},
+};
+
+use axum::{
+    body::{boxed, BoxBody, Full},
+    http::{Request, Response, StatusCode, HeaderValue},
+    response::IntoResponse,
+    routing::{get, post},
+    Router,
+    extract::Json,
+};
+use tower::{Service, Layer};
+use serde::Deserialize;
+use once_cell::sync::Lazy;
+
+// Para rodar local
+#[cfg(not(feature = "lambda"))]
+use {axum::Server, std::net::SocketAddr};
+
+// Para rodar na AWS Lambda (apenas se ativar --features lambda)
+#[cfg(feature = "lambda")]
+use lambda_http::{run as lambda_run, Error as LambdaError};
+
+// Para usar write_image no encoder
+use image::ImageEncoder;
+
+mod adaptive;
+mod avro;
+#[cfg(feature = "aws")]
+mod aws;
+#[cfg(feature = "aws")]
+mod awsinit;
+mod bignum;
+mod billing;
+mod binparse;
+mod bloom;
+mod body_limit;
+mod burn;
+mod calibration;
+mod cbor;
+mod checksum;
+mod clock;
+#[cfg(feature = "aws")]
+mod cloudwatch;
+mod compare;
+mod concurrency;
+mod config;
+#[cfg(not(feature = "lambda"))]
+mod connection_policy;
+mod content_type;
+mod convert;
+mod cost;
+mod crypto;
+mod csvparse;
+mod datetime;
+mod decimal;
+mod dedup;
+#[cfg(feature = "lambda")]
+mod direct;
+mod echo;
+mod estimate;
+#[cfg(feature = "lambda")]
+mod events;
+mod eventsource;
+#[cfg(feature = "external")]
+mod external;
+mod fft;
+mod fuzzy;
+mod graph;
+mod hash;
+mod heap;
+#[cfg(all(feature = "hyper-server", not(feature = "lambda")))]
+mod hyper_server;
+mod i18n;
+mod idempotency;
+mod info;
+mod intensity;
+mod jsonparse;
+mod jsonpatch;
+mod jwt;
+#[cfg(not(feature = "lambda"))]
+mod listener;
+mod mandelbrot;
+mod matrix;
+mod memory;
+mod metrics;
+mod middleware;
+mod mixed;
+mod mock;
+mod montecarlo;
+mod msgpack;
+mod padding;
+mod paired;
+mod password;
+mod poll;
+mod random;
+#[cfg(feature = "aws")]
+mod presign;
+mod primes;
+#[cfg(not(feature = "lambda"))]
+mod profdiff;
+mod progress;
+mod protobuf;
+mod record;
+mod region;
+pub mod registry;
+#[cfg(feature = "aws")]
+mod remote_config;
+#[cfg(not(feature = "lambda"))]
+mod replay;
+mod results;
+mod rng;
+mod rules;
+mod run;
+mod samples;
+mod sanitize;
+mod scheduler;
+mod shadow;
+mod sigv4;
+mod slo;
+mod sort;
+mod stats;
+#[cfg(feature = "lambda")]
+mod stepfunctions;
+mod sweep;
+#[cfg(not(feature = "lambda"))]
+mod thread_per_core;
+mod tokenize;
+mod trailers;
+mod tuning;
+#[cfg(all(feature = "uring", not(feature = "lambda")))]
+mod uring;
+mod uuids;
+pub mod validation;
+mod vectors;
+mod workflow;
+mod xmlparse;
+
+// ======================
+// MIDDLEWARE: TimingLayer
+// ======================
+#[derive(Clone)]
+struct TimingLayer;
+
+#[derive(Clone)]
+struct TimingService<S> {
+    inner: S,
+}
+
+// Implementa a criação do service via Layer
+impl<S> Layer<S> for TimingLayer {
+    type Service = TimingService<S>;
+    fn layer(&self, inner: S) -> Self::Service {
+        TimingService { inner }
+    }
+}
+
+/// Precisamos especificar os tipos corretos para `Service<Request<ReqBody>>`.
+/// Aqui, definimos que o `Response` esperado é `Response<BoxBody>`, que é
+/// o tipo de resposta padrão do Axum quando montamos handlers.
+impl<S, ReqBody> Service<Request<ReqBody>> for TimingService<S>
+where
+    // O service interno deve aceitar Request<ReqBody> e retornar Response<BoxBody>
+    S: Service<Request<ReqBody>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    // O futuro do service também precisa ser Send + 'static
+    S::Future: Send + 'static,
+    // O body da request deve ser Send + 'static
+    ReqBody: Send + 'static,
+{
+    type Response = Response<BoxBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        let mut service = self.inner.clone();
+        let route = results::route(&req);
+        let trace_id = metrics::trace_id(req.headers());
+        // Só o que a amostra crua (samples.rs) precisa e que some com a request
+        let sampled = samples::enabled().then(|| {
+            let request_bytes = req
+                .headers()
+                .get(axum::http::header::CONTENT_LENGTH)
+                .and_then(|v| v.to_str().ok()?.parse().ok());
+            (req.method().to_string(), request_bytes)
+        });
+
+        Box::pin(async move {
+            let lambda_start = Instant::now();
+            let endpoint_start = Instant::now();
+            let invocation = clock::Invocation::start();
+            // As durações saem do CLOCK_BOOTTIME (clock.rs), não dos Instants
+            let lambda_clock = clock::Stopwatch::start();
+            let endpoint_clock = clock::Stopwatch::start();
+
+            // processa request
+            let mut response = service.call(req).await?;
+
+            let lambda_end = Instant::now();
+            let endpoint_end = Instant::now();
+
+            let lambda_duration = lambda_clock.elapsed();
+            let endpoint_duration = endpoint_clock.elapsed();
+            let wall_jump = lambda_clock.wall_jump();
+            let frozen = invocation.frozen;
+            drop(invocation);
+
+            results::record(&route, response.status().as_u16(), endpoint_duration, trace_id);
+            let cost_usd = cost::estimate(endpoint_duration);
+            let breached = slo::budget(&route).is_some_and(|budget| slo::record(&route, budget, endpoint_duration));
+            if let Some((method, request_bytes)) = sampled {
+                samples::record(samples::Sample {
+                    route,
+                    method,
+                    status: response.status().as_u16(),
+                    start: lambda_clock.started_at(),
+                    duration_ns: endpoint_duration.as_nanos() as u64,
+                    request_bytes,
+                    response_bytes: axum::body::HttpBody::size_hint(response.body()).exact(),
+                    slo_breach: breached,
+                    clock_jump: wall_jump.is_some(),
+                    frozen: frozen.is_some(),
+                    run_id: crate::run::current().id,
+                    host_generation: calibration::generation(),
+                    estimated_cost_usd: cost_usd,
+                });
+            }
+            let headers = response.headers_mut();
+            if calibration::generation() > 0 {
+                headers.insert("X-Host-Generation", HeaderValue::from(calibration::generation()));
+            }
+            if let Some(cost_usd) = cost_usd {
+                headers.insert("X-Estimated-Cost-USD", HeaderValue::from_str(&format!("{:.12}", cost_usd)).unwrap());
+            }
+            if breached {
+                headers.insert("X-SLO-Breach", HeaderValue::from_static("true"));
+            }
+            if let Some(jump_ms) = wall_jump {
+                headers.insert("X-Clock-Jump", HeaderValue::from_str(&format!("{:+.3}ms", jump_ms)).unwrap());
+            }
+            // Só aparecem depois do primeiro freeze detectado
+            if let Some(frozen) = frozen {
+                // Depois de um freeze o ambiente pode ter voltado em outro host
+                calibration::wake();
+                headers.insert(
+                    "X-Frozen-For",
+                    HeaderValue::from_str(&format!("{:?}", frozen).replace('µ', "u")).unwrap(),
+                );
+            }
+            if clock::freezes() > 0 {
+                headers.insert("X-Freeze-Count", HeaderValue::from(clock::freezes()));
+            }
+            let format = config::CONFIG.timing_format.as_str();
+            if format == "binary" || format == "both" {
+                // Relógio de parede só no início; o resto sai dos Instants
+                let lambda_start_ns =
+                    lambda_clock.started_at().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos() as u64;
+                let endpoint_start_ns = lambda_start_ns + (endpoint_start - lambda_start).as_nanos() as u64;
+                headers.insert(
+                    "X-Timing",
+                    HeaderValue::from_str(&encode_timing(wall_jump.is_some(), &[
+                        lambda_start_ns,
+                        lambda_duration.as_nanos() as u64,
+                        endpoint_start_ns,
+                        endpoint_duration.as_nanos() as u64,
+                    ]))
+                    .unwrap(),
+                );
+                if format == "binary" {
+                    return Ok(response);
+                }
+            }
+
+            // Exemplo: podemos usar debug ou epoch
+            headers.insert(
+                "X-Lambda-Start-Time",
+                HeaderValue::from_str(&format!("{:?}", lambda_start)).unwrap(),
+            );
+            headers.insert(
+                "X-Lambda-End-Time",
+                HeaderValue::from_str(&format!("{:?}", lambda_end)).unwrap(),
+            );
+            // O Debug de Duration usa "µs", que não é ASCII: o lambda_http não
+            // consegue serializar esse header, então trocamos por "us"
+            headers.insert(
+                "X-Lambda-Duration",
+                HeaderValue::from_str(&format!("{:?}", lambda_duration).replace('µ', "u")).unwrap(),
+            );
+            headers.insert(
+                "X-Endpoint-Start-Time",
+                HeaderValue::from_str(&format!("{:?}", endpoint_start)).unwrap(),
+            );
+            headers.insert(
+                "X-Endpoint-End-Time",
+                HeaderValue::from_str(&format!("{:?}", endpoint_end)).unwrap(),
+            );
+            headers.insert(
+                "X-Endpoint-Duration",
+                HeaderValue::from_str(&format!("{:?}", endpoint_duration).replace('µ', "u")).unwrap(),
+            );
+
+            Ok(response)
+        })
+    }
+}
+
+/// `X-Timing` (BFF_TIMING_FORMAT=binary): 40 bytes em base64. Byte 0 é a
+/// versão do layout (1), byte 1 são flags (bit 0: o relógio de parede saltou),
+/// bytes 2..8 são reservados (zero) e depois vêm quatro u64 big-endian em
+/// nanossegundos: início (epoch) e duração da Lambda, início (epoch) e duração
+/// do endpoint.
+fn encode_timing(wall_jump: bool, fields: &[u64; 4]) -> String {
+    use base64::{engine::general_purpose, Engine as _};
+
+    let mut blob = [0u8; 40];
+    blob[0] = 1;
+    blob[1] = wall_jump as u8;
+    for (i, value) in fields.iter().enumerate() {
+        blob[8 + i * 8..16 + i * 8].copy_from_slice(&value.to_be_bytes());
+    }
+    general_purpose::STANDARD.encode(blob)
+}
+
+// ======================
+// MODELOS de input
+// ======================
+#[derive(Deserialize)]
+struct MathPayload {
+    numbers: Vec<i64>,
+    operation: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct JsonPayload {
+    key: Option<String>,
+    value: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct StringPayload {
+    text: Option<String>,
+    pattern: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CompressPayload {
+    text: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ImagePayload {
+    text: Option<String>,
+}
+
+// Se não estiver usando, pode comentar ou remover. Ou então silenciar com:
+#[allow(dead_code)]
+static REGEX_INSTANCE: Lazy<regex::Regex> = Lazy::new(|| {
+    regex::Regex::new(r"hello").unwrap()
+});
+
+// Embutindo uma fonte TTF (opcional)
+static FONT: Lazy<Option<rusttype::Font<'static>>> = Lazy::new(|| {
+    let font_data = include_bytes!("DejaVuSans.ttf");
+    rusttype::Font::try_from_bytes(font_data as &[u8])
+});
+
+// ======================
+// HANDLERS
+// ======================
+
+// ------------
+// math_operations
+// ------------
+async fn math_operations(Json(payload): Json<MathPayload>) -> Response<BoxBody> {
+    if payload.numbers.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "No numbers provided" }))
+        )
+        .into_response();
+    }
+
+    let operation = payload.operation.unwrap_or_else(|| "sum".to_string());
+    // Checado: fora do i64 o debug entra em pânico e o release dá a volta
+    let result = match operation.as_str() {
+        "sum" => payload.numbers.iter().try_fold(0i64, |acc, n| acc.checked_add(*n)),
+        "product" => payload.numbers.iter().try_fold(1i64, |acc, n| acc.checked_mul(*n)),
+        _ => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Unsupported operation" }))
+            )
+            .into_response()
+        }
+    };
+    let Some(result) = result else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "Result does not fit in a 64-bit integer" }))
+        )
+        .into_response();
+    };
+
+    (StatusCode::OK, Json(serde_json::json!({ "result": result }))).into_response()
+}
+
+// ------------
+// json_manipulation
+// ------------
+async fn json_manipulation(Json(payload): Json<JsonPayload>) -> Response<BoxBody> {
+    let Some(key) = &payload.key else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "Key and value are required" }))
+        )
+        .into_response();
+    };
+    let Some(value) = &payload.value else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "Key and value are required" }))
+        )
+        .into_response();
+    };
+
+    let json_data = serde_json::json!({ key: value }).to_string();
+    (StatusCode::OK, Json(serde_json::json!({ "json_data": json_data }))).into_response()
+}
+
+// ------------
+// string_processing
+// ------------
+async fn string_processing(Json(payload): Json<StringPayload>) -> Response<BoxBody> {
+    let Some(text) = &payload.text else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "Text and pattern are required" }))
+        )
+        .into_response();
+    };
+    let Some(pattern) = &payload.pattern else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "Text and pattern are required" }))
+        )
+        .into_response();
+    };
+
+    let re = match regex::Regex::new(pattern) {
+        Ok(r) => r,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Invalid regex pattern" }))
+            )
+            .into_response()
+        }
+    };
+    let matches: Vec<String> = re.find_iter(text).map(|m| m.as_str().to_string()).collect();
+
+    (StatusCode::OK, Json(serde_json::json!({ "matches": matches }))).into_response()
+}
+
+// ------------
+// compress_data
+// ------------
+async fn compress_data(Json(payload): Json<CompressPayload>) -> Response<BoxBody> {
+    let Some(text) = &payload.text else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "Text is required" }))
+        )
+        .into_response();
+    };
+
+    use flate2::{Compression, write::GzEncoder};
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    std::io::Write::write_all(&mut encoder, text.as_bytes()).unwrap();
+    let compressed = encoder.finish().unwrap();
+
+    let body = boxed(Full::from(compressed));
+    let mut resp = Response::new(body);
+    *resp.status_mut() = StatusCode::OK;
+    resp.headers_mut().insert(
+        axum::http::header::CONTENT_TYPE,
+        HeaderValue::from_static("application/gzip")
+    );
+    resp
+}
+
+// ------------
+// image_processing
+// ------------
+async fn image_processing(Json(payload): Json<ImagePayload>) -> Response<BoxBody> {
+    let text = payload.text.clone().unwrap_or_else(|| "Hello, World!".to_string());
+
+    if FONT.is_none() {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({
+                "error": "Fonte não carregada. Coloque DejaVuSans.ttf ou comente."
+            }))
+        ).into_response();
+    }
+
+    let width = 200;
+    let height = 100;
+    let mut img = image::RgbaImage::from_pixel(
+        width,
+        height,
+        image::Rgba([73, 109, 137, 255])
+    );
+
+    use rusttype::Scale;
+    use imageproc::drawing::draw_text_mut;
+    use image::Rgba;
+
+    let scale = Scale { x: 20.0, y: 20.0 };
+    draw_text_mut(
+        &mut img,
+        Rgba([255, 255, 0, 255]),
+        10,
+        40,
+        scale,
+        FONT.as_ref().unwrap(),
+        &text
+    );
+
+    // Codifica em PNG sem warnings de depreciação:
+    let mut buf = Vec::new();
+    let encoder = image::codecs::png::PngEncoder::new(&mut buf);
+    encoder
+        .write_image(
+            &img,
+            width,
+            height,
+            image::ColorType::Rgba8
+        )
+        .unwrap();
+
+    // Convertemos para base64
+    use base64::{Engine as _, engine::general_purpose};
+    let encoded = general_purpose::STANDARD.encode(&buf);
+
+    (StatusCode::OK, Json(serde_json::json!({ "image": encoded }))).into_response()
+}
+
+// ======================
+// CRIA O ROUTER
+// ======================
+/// Rotas das cargas de trabalho, que também existem sem o TimingLayer em /raw.
+fn workload_routes() -> Router {
+    let router = Router::new()
+        .route("/math", post(math_operations))
+        .route("/json", post(json_manipulation))
+        // Documentos de vários MB: acima do limite padrão de 2 MB do extractor
+        .route("/json/parse", post(jsonparse::jsonparse).layer(axum::extract::DefaultBodyLimit::max(body_limit::JSON_PARSE)))
+        .route("/json/patch", post(jsonpatch::jsonpatch))
+        .route("/string", post(string_processing))
+        .route("/compress", post(compress_data))
+        .route("/image", post(image_processing))
+        .route("/tokenize", post(tokenize::tokenize))
+        .route("/vectors", post(vectors::vectors))
+        .route("/datetime", post(datetime::datetime))
+        .route("/i18n", post(i18n::i18n))
+        .route("/decimal", post(decimal::decimal))
+        .route("/rules", post(rules::rules))
+        .route("/workflow", post(workflow::workflow))
+        .route("/graph", post(graph::graph))
+        .route("/bloom", post(bloom::bloom))
+        .route("/checksum", post(checksum::checksum))
+        .route("/hash", post(hash::hash))
+        .route("/argon2", post(password::argon2))
+        .route("/bcrypt", post(password::bcrypt))
+        .route("/crypto/aes", post(crypto::aes))
+        .route("/crypto/sign", post(crypto::sign))
+        .route("/jwt", post(jwt::jwt))
+        .route("/uuid", post(uuids::uuid))
+        .route("/random", post(random::random))
+        .route("/sort", post(sort::sort))
+        .route("/matrix", post(matrix::matrix))
+        .route("/fft", post(fft::fft))
+        .route("/primes", post(primes::primes))
+        .route("/bignum", post(bignum::bignum))
+        .route("/montecarlo", post(montecarlo::montecarlo))
+        .route("/mandelbrot", post(mandelbrot::mandelbrot))
+        .route("/fuzzy", post(fuzzy::fuzzy))
+        .route("/csv", post(csvparse::csvparse))
+        .route("/xml", post(xmlparse::xmlparse))
+        .route("/convert", post(convert::convert))
+        .route("/msgpack", post(msgpack::msgpack))
+        .route("/protobuf", post(protobuf::protobuf))
+        .route("/cbor", post(cbor::cbor))
+        .route("/avro", post(avro::avro))
+        .route("/sanitize", post(sanitize::sanitize))
+        .route("/binparse", post(binparse::binparse))
+        .route("/eventsource", post(eventsource::eventsource))
+        .route("/burn", post(burn::burn))
+        .route("/sigv4", post(sigv4::sigv4))
+        .route("/echo", post(echo::echo))
+        .route("/stream", get(echo::stream))
+        .route("/mixed", post(mixed::mixed))
+        .route("/sweep", post(sweep::sweep))
+        .route("/paired", post(paired::paired))
+        .route("/power-tuning", post(tuning::power_tuning));
+
+    #[cfg(feature = "external")]
+    let router = router.route("/external", post(external::external));
+
+    #[cfg(feature = "aws")]
+    let router = router
+        .route("/awsinit", post(awsinit::awsinit))
+        .route("/presign", post(presign::presign));
+
+    router
+}
+
+pub fn create_router() -> Router {
+    let router = workload_routes()
+        .route("/mock", get(mock::list))
+        .route("/mock/:name", get(mock::mock))
+        .route("/mock/:name/schema", get(mock::schema))
+        .route("/poll", get(poll::poll).post(poll::publish))
+        .route("/stats", get(stats::stats))
+        .route("/info", get(info::info))
+        .route("/results", get(results::results))
+        .route("/results/schema", get(results::schema))
+        .route("/metrics", get(metrics::metrics))
+        .route("/samples/flush", post(samples::flush))
+        .route("/validate", post(validation::validate))
+        .route("/compare", post(compare::compare));
+
+    // /raw/<rota> é o mesmo grupo sem o TimingLayer: comparando os dois sob a
+    // mesma carga, a diferença é o custo do próprio middleware de tempo
+    middleware::build(router, workload_routes())
+}
+
+// ======================
+// MAIN LOCAL
+// ======================
+#[cfg(not(feature = "lambda"))]
+pub fn run_local() {
+    use config::CONFIG;
+
+    let args: Vec<String> = std::env::args().collect();
+    let subcommand = match args.get(1).map(String::as_str) {
+        Some("replay") => Some(replay::run(&args[2..])),
+        Some("profdiff") => Some(profdiff::run(&args[2..])),
+        _ => None,
+    };
+    if let Some(result) = subcommand {
+        if let Err(error) = result {
+            eprintln!("{}", error);
+            std::process::exit(2);
+        }
+        return;
+    }
+
+    let addr = SocketAddr::from(([0, 0, 0, 0], 3000));
+
+    #[cfg(feature = "uring")]
+    uring::spawn(SocketAddr::from(([0, 0, 0, 0], CONFIG.uring_port)));
+
+    if CONFIG.runtime == "thread-per-core" {
+        return thread_per_core::run(addr);
+    }
+
+    let listener = listener::bind(addr, false);
+    info::startup(Some(addr));
+    println!("Rodando local em http://127.0.0.1:3000");
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(serve(listener));
+}
+
+/// Serve o app no listener, dentro do runtime atual (no thread-per-core, um
+/// por worker).
+#[cfg(not(feature = "lambda"))]
+async fn serve(listener: std::net::TcpListener) {
+    #[cfg(feature = "aws")]
+    remote_config::refresh().await;
+    region::discover().await;
+    intensity::start();
+    calibration::start();
+    #[cfg(feature = "aws")]
+    cloudwatch::start();
+
+    #[cfg(feature = "hyper-server")]
+    if config::CONFIG.server == "hyper" {
+        return hyper_server::serve(listener).await;
+    }
+
+    // Cada conexão ganha o seu ConnectionPolicy (limite de requests/idade)
+    let app = create_router();
+    let make_service = hyper::service::make_service_fn(move |_| {
+        let app = app.clone();
+        async move { Ok::<_, std::convert::Infallible>(connection_policy::ConnectionPolicy::new(app)) }
+    });
+
+    Server::from_tcp(listener)
+        .unwrap()
+        .tcp_nodelay(config::CONFIG.tcp_nodelay)
+        .serve(make_service)
+        .await
+        .unwrap();
+}
+
+// ======================
+// MAIN LAMBDA
+// ======================
+#[cfg(feature = "lambda")]
+pub async fn run_lambda() -> Result<(), LambdaError> {
+    use config::CONFIG;
+
+    #[cfg(feature = "aws")]
+    remote_config::refresh().await;
+    info::startup(None);
+
+    // Modos sem HTTP: o evento vem direto do lambda_runtime
+    match CONFIG.lambda_mode.as_str() {
+        "sqs" => return events::run(events::Source::Sqs).await,
+        "kinesis" => return events::run(events::Source::Kinesis).await,
+        "stepfunctions" => return stepfunctions::run().await,
+        "direct" => return direct::run().await,
+        _ => {}
+    }
+
+    intensity::start();
+    calibration::start();
+    let app = create_router();
+
+    // O lambda_http entrega a request com o Body dele; convertemos para o
+    // body do axum antes de chegar no Router
+    let handler = tower::ServiceBuilder::new()
+        .map_request(|req: lambda_http::Request| {
+            let (parts, body) = req.into_parts();
+            Request::from_parts(parts, axum::body::Body::from(body.to_vec()))
+        })
+        .service(app);
+
+    lambda_run(handler).await?;
+    Ok(())
+}
@@ -0,0 +1,68 @@
+// ======================
+// LISTENER DO SERVIDOR LOCAL
+// ======================
+//
+// O socket é montado à mão (socket2) em vez de deixar o hyper fazer o bind,
+// para poder ligar SO_REUSEPORT (no modo thread-per-core cada worker abre o
+// seu listener na mesma porta e o kernel distribui as conexões entre eles) e
+// aplicar as opções de socket da configuração: backlog e tamanho dos buffers
+// no listener (as conexões aceitas herdam os buffers) e TCP_NODELAY em cada
+// conexão aceita.
+//
+// Os valores efetivos são lidos de volta do socket depois de aplicados (o
+// Linux, por exemplo, dobra o SO_RCVBUF pedido) e aparecem no /info.
+
+use std::net::{SocketAddr, TcpListener};
+
+use once_cell::sync::OnceCell;
+use socket2::{Domain, Protocol, Socket, Type};
+
+use crate::config::CONFIG;
+
+struct Applied {
+    reuse_port: bool,
+    recv_buffer: usize,
+    send_buffer: usize,
+}
+
+static APPLIED: OnceCell<Applied> = OnceCell::new();
+
+pub fn bind(addr: SocketAddr, reuse_port: bool) -> TcpListener {
+    let socket = Socket::new(Domain::for_address(addr), Type::STREAM, Some(Protocol::TCP)).unwrap();
+    socket.set_reuse_address(true).unwrap();
+    if reuse_port {
+        socket.set_reuse_port(true).unwrap();
+    }
+    if CONFIG.recv_buffer > 0 {
+        socket.set_recv_buffer_size(CONFIG.recv_buffer).unwrap();
+    }
+    if CONFIG.send_buffer > 0 {
+        socket.set_send_buffer_size(CONFIG.send_buffer).unwrap();
+    }
+    socket.bind(&addr.into()).unwrap_or_else(|e| panic!("Failed to bind {}: {}", addr, e));
+    socket.listen(CONFIG.listen_backlog).unwrap();
+
+    APPLIED.get_or_init(|| Applied {
+        reuse_port,
+        recv_buffer: socket.recv_buffer_size().unwrap_or(0),
+        send_buffer: socket.send_buffer_size().unwrap_or(0),
+    });
+    socket.into()
+}
+
+pub fn snapshot() -> serde_json::Value {
+    let applied = APPLIED.get();
+    serde_json::json!({
+        "tcp_nodelay": CONFIG.tcp_nodelay,
+        "listen_backlog": CONFIG.listen_backlog,
+        "reuse_port": applied.map(|a| a.reuse_port),
+        "recv_buffer": {
+            "requested": CONFIG.recv_buffer,
+            "effective": applied.map(|a| a.recv_buffer),
+        },
+        "send_buffer": {
+            "requested": CONFIG.send_buffer,
+            "effective": applied.map(|a| a.send_buffer),
+        },
+    })
+}
@@ -0,0 +1,199 @@
+// ======================
+// CONJUNTO DE MANDELBROT EM PNG
+// ======================
+//
+// Renderiza o conjunto de Mandelbrot (tempo de escape, `z = z² + c` até
+// |z| > 2 ou `iterations` passos) em `width`×`height` pixels e codifica o
+// resultado em PNG com o mesmo encoder do /image. Junta as duas coisas que
+// cada linguagem faz de jeito diferente: conta de ponto flutuante num laço
+// apertado e codificação de imagem, medidas separadamente.
+//
+// `center_x`, `center_y` e `zoom` escolhem a região (o padrão mostra o
+// conjunto inteiro, 3 unidades na horizontal). `mode: "parallel"` divide as
+// linhas entre as threads do rayon, como no /matrix. `output: "binary"`
+// devolve o PNG cru (`image/png`, tempos nos headers `X-Render-Ms` e
+// `X-Encode-Ms`); o padrão é JSON com o PNG em base64. `inside`, o número de
+// pixels que não escaparam, só depende da conta e bate entre linguagens.
+
+use std::time::Instant;
+
+use axum::{
+    body::{boxed, BoxBody, Full},
+    extract::Json,
+    http::{header, HeaderValue, Response, StatusCode},
+    response::IntoResponse,
+};
+use base64::{engine::general_purpose, Engine as _};
+use image::ImageEncoder;
+use rayon::prelude::*;
+use serde::Deserialize;
+
+use crate::estimate::{self, Estimate};
+
+const MAX_SIDE: u32 = 4096;
+const MAX_ITERATIONS: u32 = 10_000;
+
+#[derive(Deserialize)]
+pub struct MandelbrotPayload {
+    width: Option<u32>,
+    height: Option<u32>,
+    iterations: Option<u32>,
+    center_x: Option<f64>,
+    center_y: Option<f64>,
+    zoom: Option<f64>,
+    mode: Option<String>,
+    output: Option<String>,
+    dry_run: Option<bool>,
+}
+
+fn bad_request(error: String) -> Response<BoxBody> {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({ "error": error }))
+    )
+    .into_response()
+}
+
+struct View {
+    width: u32,
+    height: u32,
+    iterations: u32,
+    center_x: f64,
+    center_y: f64,
+    scale: f64,
+}
+
+/// Passos até escapar, ou `iterations` se o ponto ficou no conjunto.
+fn escape(cx: f64, cy: f64, iterations: u32) -> u32 {
+    let (mut x, mut y) = (0.0f64, 0.0f64);
+    for n in 0..iterations {
+        let (x2, y2) = (x * x, y * y);
+        if x2 + y2 > 4.0 {
+            return n;
+        }
+        y = 2.0 * x * y + cy;
+        x = x2 - y2 + cx;
+    }
+    iterations
+}
+
+/// Uma linha de pixels RGB; devolve quantos ficaram no conjunto.
+fn render_row(view: &View, py: u32, row: &mut [u8]) -> u64 {
+    let cy = view.center_y - (py as f64 + 0.5 - view.height as f64 / 2.0) * view.scale;
+    let mut inside = 0;
+    for (px, pixel) in row.chunks_exact_mut(3).enumerate() {
+        let cx = view.center_x + (px as f64 + 0.5 - view.width as f64 / 2.0) * view.scale;
+        let n = escape(cx, cy, view.iterations);
+        if n == view.iterations {
+            inside += 1;
+            pixel.fill(0);
+        } else {
+            // Paleta polinomial clássica (Bernstein), sem tabela
+            let t = n as f64 / view.iterations as f64;
+            let u = 1.0 - t;
+            pixel[0] = (9.0 * u * t * t * t * 255.0) as u8;
+            pixel[1] = (15.0 * u * u * t * t * 255.0) as u8;
+            pixel[2] = (8.5 * u * u * u * t * 255.0) as u8;
+        }
+    }
+    inside
+}
+
+pub async fn mandelbrot(Json(payload): Json<MandelbrotPayload>) -> Response<BoxBody> {
+    let width = payload.width.unwrap_or(800);
+    let height = payload.height.unwrap_or(600);
+    let iterations = payload.iterations.unwrap_or(256);
+    if width == 0 || height == 0 || width > MAX_SIDE || height > MAX_SIDE {
+        return bad_request(format!("width and height must be between 1 and {}", MAX_SIDE));
+    }
+    if iterations == 0 || iterations > MAX_ITERATIONS {
+        return bad_request(format!("iterations must be between 1 and {}", MAX_ITERATIONS));
+    }
+    let zoom = payload.zoom.unwrap_or(1.0);
+    let (center_x, center_y) = (payload.center_x.unwrap_or(-0.5), payload.center_y.unwrap_or(0.0));
+    if !zoom.is_finite() || zoom <= 0.0 || !center_x.is_finite() || !center_y.is_finite() {
+        return bad_request("zoom must be positive and the center finite".to_string());
+    }
+    let parallel = match payload.mode.as_deref().unwrap_or("single") {
+        "single" => false,
+        "parallel" => true,
+        other => return bad_request(format!("mode must be single or parallel, got {}", other)),
+    };
+    let binary = match payload.output.as_deref().unwrap_or("base64") {
+        "base64" => false,
+        "binary" => true,
+        other => return bad_request(format!("output must be base64 or binary, got {}", other)),
+    };
+
+    let pixels = width as u64 * height as u64;
+    if payload.dry_run.unwrap_or(false) {
+        // Teto: todo pixel indo até `iterations`; o PNG fica bem abaixo do cru
+        return estimate::response(Estimate {
+            iterations: pixels * iterations as u64,
+            unit: "escape-time iterations (upper bound)",
+            allocation_bytes: pixels * 3,
+            output_bytes: if binary { pixels * 3 } else { 250 + pixels * 4 },
+        });
+    }
+
+    let view = View { width, height, iterations, center_x, center_y, scale: 3.0 / zoom / width as f64 };
+    let mut buffer = vec![0u8; pixels as usize * 3];
+    let stride = width as usize * 3;
+    let start = Instant::now();
+    let inside: u64 = if parallel {
+        buffer
+            .par_chunks_mut(stride)
+            .enumerate()
+            .map(|(py, row)| render_row(&view, py as u32, row))
+            .sum()
+    } else {
+        buffer
+            .chunks_mut(stride)
+            .enumerate()
+            .map(|(py, row)| render_row(&view, py as u32, row))
+            .sum()
+    };
+    let render_duration = start.elapsed();
+
+    let start = Instant::now();
+    let mut png = Vec::new();
+    if let Err(error) =
+        image::codecs::png::PngEncoder::new(&mut png).write_image(&buffer, width, height, image::ColorType::Rgb8)
+    {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": format!("PNG encoding failed: {}", error) }))
+        )
+        .into_response();
+    }
+    let encode_duration = start.elapsed();
+    let render_ms = render_duration.as_secs_f64() * 1000.0;
+    let encode_ms = encode_duration.as_secs_f64() * 1000.0;
+
+    if binary {
+        let mut response = Response::new(boxed(Full::from(png)));
+        let headers = response.headers_mut();
+        headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("image/png"));
+        headers.insert("X-Render-Ms", HeaderValue::from_str(&format!("{:.3}", render_ms)).unwrap());
+        headers.insert("X-Encode-Ms", HeaderValue::from_str(&format!("{:.3}", encode_ms)).unwrap());
+        headers.insert("X-Mandelbrot-Inside", HeaderValue::from(inside));
+        return response;
+    }
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "width": width,
+            "height": height,
+            "iterations": iterations,
+            "mode": if parallel { "parallel" } else { "single" },
+            "inside": inside,
+            "render_ms": render_ms,
+            "encode_ms": encode_ms,
+            "megapixels_per_second": pixels as f64 / 1e6 / render_duration.as_secs_f64(),
+            "png_bytes": png.len(),
+            "image": general_purpose::STANDARD.encode(&png),
+        }))
+    )
+    .into_response()
+}
@@ -0,0 +1,152 @@
+// ======================
+// MULTIPLICAÇÃO DE MATRIZES
+// ======================
+//
+// Multiplica duas matrizes NxN de f64: `a` e `b` enviadas pelo cliente (listas
+// de linhas) ou, sem elas, geradas com a seed em [-1, 1). `size` regula o
+// trabalho (2·N³ operações) e o quanto ele sai do cache: com N = 512 cada
+// matriz tem 2 MiB.
+//
+// `mode: "single"` (padrão) roda numa thread; `"parallel"` divide as linhas
+// do resultado entre as threads do rayon (uma por vCPU), que é onde o número
+// de vCPUs da Lambda, que cresce com a memória, aparece. Os dois modos usam o
+// mesmo laço (ordem i-k-j, que percorre `b` por linha) e dão o mesmo
+// resultado, bit a bit.
+
+use std::time::Instant;
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use rayon::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    estimate::{self, Estimate},
+    progress::Progress,
+    rng::{self, SplitMix64},
+};
+
+const MAX_SIZE: usize = 2048;
+
+#[derive(Deserialize)]
+pub struct MatrixPayload {
+    size: Option<usize>,
+    a: Option<Vec<Vec<f64>>>,
+    b: Option<Vec<Vec<f64>>>,
+    mode: Option<String>,
+    return_result: Option<bool>,
+    seed: Option<u64>,
+    dry_run: Option<bool>,
+}
+
+fn bad_request(error: String) -> Response<BoxBody> {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({ "error": error }))
+    )
+    .into_response()
+}
+
+/// Matriz enviada em linhas, achatada; precisa ser n×n.
+fn flatten(rows: Vec<Vec<f64>>, n: usize) -> Option<Vec<f64>> {
+    if rows.len() != n || rows.iter().any(|row| row.len() != n) {
+        return None;
+    }
+    Some(rows.into_iter().flatten().collect())
+}
+
+/// Uma linha de `c = a × b`.
+fn multiply_row(a_row: &[f64], b: &[f64], c_row: &mut [f64], n: usize) {
+    for (k, &a_ik) in a_row.iter().enumerate() {
+        let b_row = &b[k * n..(k + 1) * n];
+        for (c, &b_kj) in c_row.iter_mut().zip(b_row) {
+            *c += a_ik * b_kj;
+        }
+    }
+}
+
+fn dry_run(n: usize, return_result: bool) -> Response<BoxBody> {
+    let n = n as u64;
+    estimate::response(Estimate {
+        iterations: 2 * n * n * n,
+        unit: "floating-point operations",
+        allocation_bytes: 3 * n * n * 8,
+        // Até ~22 caracteres por f64 no JSON
+        output_bytes: 250 + if return_result { n * n * 22 } else { 0 },
+    })
+}
+
+pub async fn matrix(Json(payload): Json<MatrixPayload>) -> Response<BoxBody> {
+    let parallel = match payload.mode.as_deref().unwrap_or("single") {
+        "single" => false,
+        "parallel" => true,
+        other => return bad_request(format!("mode must be single or parallel, got {}", other)),
+    };
+
+    let dry = payload.dry_run.unwrap_or(false);
+    let return_result = payload.return_result.unwrap_or(false);
+    let (n, a, b) = match (payload.a, payload.b) {
+        (Some(a), Some(b)) => {
+            let n = a.len();
+            if n == 0 || n > MAX_SIZE {
+                return bad_request(format!("matrices must be between 1x1 and {}x{}", MAX_SIZE, MAX_SIZE));
+            }
+            match (flatten(a, n), flatten(b, n)) {
+                (Some(_), Some(_)) if dry => return dry_run(n, return_result),
+                (Some(a), Some(b)) => (n, a, b),
+                _ => return bad_request(format!("a and b must both be {}x{}", n, n)),
+            }
+        }
+        (None, None) => {
+            let n = payload.size.unwrap_or(256);
+            if n == 0 || n > MAX_SIZE {
+                return bad_request(format!("size must be between 1 and {}", MAX_SIZE));
+            }
+            if dry {
+                return dry_run(n, return_result);
+            }
+            let mut rng = SplitMix64::new(rng::seed(payload.seed));
+            let a = (0..n * n).map(|_| rng.next_f64() * 2.0 - 1.0).collect();
+            let b = (0..n * n).map(|_| rng.next_f64() * 2.0 - 1.0).collect();
+            (n, a, b)
+        }
+        _ => return bad_request("send both a and b, or neither".to_string()),
+    };
+
+    let mut c = vec![0.0; n * n];
+    // Só o modo single marca fatias; no parallel as linhas terminam fora de ordem
+    let mut progress = Progress::new(if parallel { 0 } else { n as u64 });
+    let start = Instant::now();
+    if parallel {
+        c.par_chunks_mut(n)
+            .zip(a.par_chunks(n))
+            .for_each(|(c_row, a_row)| multiply_row(a_row, &b, c_row, n));
+    } else {
+        for (i, (c_row, a_row)) in c.chunks_mut(n).zip(a.chunks(n)).enumerate() {
+            multiply_row(a_row, &b, c_row, n);
+            progress.tick(i as u64 + 1);
+        }
+    }
+    let elapsed = start.elapsed();
+
+    let mut body = serde_json::json!({
+        "size": n,
+        "mode": if parallel { "parallel" } else { "single" },
+        "threads": if parallel { rayon::current_num_threads() } else { 1 },
+        "multiply_ms": elapsed.as_secs_f64() * 1000.0,
+        "gflops": 2.0 * (n as f64).powi(3) / elapsed.as_secs_f64() / 1e9,
+        "sum": c.iter().sum::<f64>(),
+        "trace": (0..n).map(|i| c[i * n + i]).sum::<f64>(),
+    });
+    if return_result {
+        body["result"] = serde_json::json!(c.chunks(n).collect::<Vec<_>>());
+    }
+    if let Some(report) = progress.report() {
+        body["progress"] = report;
+    }
+    (StatusCode::OK, Json(body)).into_response()
+}
@@ -0,0 +1,156 @@
+// ======================
+// MIDDLEWARE: PRESSÃO DE MEMÓRIA
+// ======================
+//
+// Um OOM kill no meio de uma execução derruba a janela de medição inteira.
+// Com `BFF_MEMORY_THRESHOLD_PERCENT` > 0, antes de cada request das rotas de
+// `BFF_MEMORY_GUARDED_ROUTES` (padrão `/image`) o RSS do processo é comparado
+// com o limite de memória; acima do limiar, a request leva 507 sem rodar.
+//
+// O limite vem, nesta ordem, de `AWS_LAMBDA_FUNCTION_MEMORY_SIZE` (MB), do
+// cgroup v2 (`memory.max`), do cgroup v1 (`memory.limit_in_bytes`) e, sem
+// limite de cgroup, da memória total da máquina. O RSS é o `VmRSS` de
+// `/proc/self/status`, lido a cada request (é barato). Fora do Linux não há
+// como ler nenhum dos dois e o guarda fica desligado.
+//
+// Com o guarda ligado, toda resposta leva `X-Memory-Rss-Bytes`,
+// `X-Memory-Limit-Bytes` e `X-Memory-Headroom-Bytes` (quanto falta para o
+// limite), para o gerador de carga acompanhar a folga.
+
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::atomic::{AtomicU64, Ordering},
+    task::{Context, Poll},
+};
+
+use axum::{
+    body::BoxBody,
+    extract::{Json, MatchedPath},
+    http::{HeaderValue, Request, Response, StatusCode},
+    response::IntoResponse,
+};
+use once_cell::sync::Lazy;
+use tower::{Layer, Service};
+
+use crate::config::CONFIG;
+
+static REJECTED: AtomicU64 = AtomicU64::new(0);
+
+// cgroup v1 sem limite reporta um valor perto de i64::MAX
+const CGROUP_UNLIMITED: u64 = 1 << 60;
+
+/// Limite de memória em bytes e de onde ele veio.
+static LIMIT: Lazy<Option<(u64, &'static str)>> = Lazy::new(|| {
+    if let Some(mb) = std::env::var("AWS_LAMBDA_FUNCTION_MEMORY_SIZE").ok().and_then(|v| v.parse::<u64>().ok()) {
+        return Some((mb * 1024 * 1024, "lambda"));
+    }
+    let read = |path: &str| std::fs::read_to_string(path).ok().and_then(|v| v.trim().parse::<u64>().ok());
+    if let Some(bytes) = read("/sys/fs/cgroup/memory.max") {
+        return Some((bytes, "cgroup_v2"));
+    }
+    if let Some(bytes) = read("/sys/fs/cgroup/memory/memory.limit_in_bytes").filter(|b| *b < CGROUP_UNLIMITED) {
+        return Some((bytes, "cgroup_v1"));
+    }
+    kib_field("/proc/meminfo", "MemTotal:").map(|bytes| (bytes, "meminfo"))
+});
+
+/// Campo em kB de um arquivo do /proc, em bytes.
+fn kib_field(path: &str, name: &str) -> Option<u64> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let line = content.lines().find(|line| line.starts_with(name))?;
+    let kib: u64 = line[name.len()..].trim().trim_end_matches("kB").trim().parse().ok()?;
+    Some(kib * 1024)
+}
+
+fn current_rss() -> Option<u64> {
+    kib_field("/proc/self/status", "VmRSS:")
+}
+
+fn enabled() -> bool {
+    CONFIG.memory_threshold_percent > 0.0
+}
+
+pub fn snapshot() -> serde_json::Value {
+    let rss = current_rss();
+    serde_json::json!({
+        "threshold_percent": CONFIG.memory_threshold_percent,
+        "guarded_routes": CONFIG.memory_guarded_routes,
+        "limit_bytes": LIMIT.map(|(bytes, _)| bytes),
+        "limit_source": LIMIT.map(|(_, source)| source),
+        "rss_bytes": rss,
+        "headroom_bytes": rss.zip(*LIMIT).map(|(rss, (limit, _))| limit.saturating_sub(rss)),
+        "rejected": REJECTED.load(Ordering::Relaxed),
+    })
+}
+
+fn tag(response: &mut Response<BoxBody>, rss: u64, limit: u64) {
+    let headers = response.headers_mut();
+    headers.insert("X-Memory-Rss-Bytes", HeaderValue::from(rss));
+    headers.insert("X-Memory-Limit-Bytes", HeaderValue::from(limit));
+    headers.insert("X-Memory-Headroom-Bytes", HeaderValue::from(limit.saturating_sub(rss)));
+}
+
+#[derive(Clone)]
+pub struct MemoryLayer;
+
+#[derive(Clone)]
+pub struct MemoryService<S> {
+    inner: S,
+}
+
+impl<S> Layer<S> for MemoryLayer {
+    type Service = MemoryService<S>;
+    fn layer(&self, inner: S) -> Self::Service {
+        MemoryService { inner }
+    }
+}
+
+impl<S, ReqBody> Service<Request<ReqBody>> for MemoryService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+{
+    type Response = Response<BoxBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        let mut service = self.inner.clone();
+        let (true, Some(rss), Some((limit, _))) = (enabled(), current_rss(), *LIMIT) else {
+            return Box::pin(service.call(req));
+        };
+
+        let route = match req.extensions().get::<MatchedPath>() {
+            Some(path) => path.as_str(),
+            None => req.uri().path(),
+        };
+        let used_percent = rss as f64 * 100.0 / limit as f64;
+        if used_percent > CONFIG.memory_threshold_percent && CONFIG.memory_guarded_routes.iter().any(|r| r == route) {
+            REJECTED.fetch_add(1, Ordering::Relaxed);
+            let mut response = (
+                StatusCode::INSUFFICIENT_STORAGE,
+                Json(serde_json::json!({
+                    "error": format!(
+                        "Memory use at {:.1}% of the limit, above the {}% threshold",
+                        used_percent, CONFIG.memory_threshold_percent
+                    )
+                }))
+            )
+            .into_response();
+            tag(&mut response, rss, limit);
+            return Box::pin(async move { Ok(response) });
+        }
+
+        Box::pin(async move {
+            let mut response = service.call(req).await?;
+            tag(&mut response, current_rss().unwrap_or(rss), limit);
+            Ok(response)
+        })
+    }
+}
@@ -0,0 +1,103 @@
+// ======================
+// MÉTRICAS OPENMETRICS COM EXEMPLARS (GET /metrics)
+// ======================
+//
+// Expõe os histogramas do /results (duração do endpoint por rota, mesmos
+// baldes 1-2-5) no formato texto do OpenMetrics, para Prometheus e afins. A
+// diferença é o exemplar: quando a request chega com contexto de trace, o
+// TimingLayer guarda o trace ID dela no balde em que caiu (só o mais recente
+// de cada balde), e a linha do balde sai com `# {trace_id="..."} valor
+// timestamp`. Do ponto alto de um painel de latência dá para ir direto ao
+// trace da invocação lenta.
+//
+// O trace ID vem do header `traceparent` (W3C Trace Context), do
+// `X-Amzn-Trace-Id` (o `Root=` do X-Ray) ou, na Lambda com tracing ativo, da
+// variável `_X_AMZN_TRACE_ID` que o runtime define a cada invocação. Sem
+// nenhum deles a request conta no histograma, mas não vira exemplar.
+
+use std::{fmt::Write as _, time::UNIX_EPOCH};
+
+use axum::{
+    body::{boxed, BoxBody, Full},
+    http::{header, HeaderMap, HeaderValue, Response},
+};
+
+use crate::results::{self, BOUNDS_MS};
+
+const CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// `Root=1-5759e988-bd862e3fe1be46a994272793;Parent=...` → o valor do Root.
+fn xray_root(value: &str) -> Option<String> {
+    value
+        .split(';')
+        .find_map(|part| part.trim().strip_prefix("Root="))
+        .map(str::to_string)
+}
+
+/// Trace ID da request, se ela tiver contexto de trace.
+pub fn trace_id(headers: &HeaderMap) -> Option<String> {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    // version-traceid-parentid-flags; um trace ID todo zero é inválido
+    if let Some(trace) = header("traceparent").and_then(|v| v.split('-').nth(1)) {
+        if trace.len() == 32 && trace.bytes().all(|b| b.is_ascii_hexdigit()) && trace.bytes().any(|b| b != b'0') {
+            return Some(trace.to_ascii_lowercase());
+        }
+    }
+    if let Some(root) = header("x-amzn-trace-id").and_then(xray_root) {
+        return Some(root);
+    }
+    #[cfg(feature = "lambda")]
+    if let Some(root) = std::env::var("_X_AMZN_TRACE_ID").ok().as_deref().and_then(xray_root) {
+        return Some(root);
+    }
+    None
+}
+
+/// Valor de label com `\`, `"` e quebra de linha escapados.
+fn label(value: &str) -> String {
+    value.replace('\\', r"\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn render() -> String {
+    let mut text = String::new();
+    let name = "bff_endpoint_duration_seconds";
+    let _ = writeln!(text, "# TYPE {} histogram\n# UNIT {} seconds", name, name);
+    let _ = writeln!(text, "# HELP {} Endpoint duration, as in X-Endpoint-Duration.", name);
+    let mut errors = Vec::new();
+    results::visit(|route, histogram| {
+        let route = label(route);
+        let mut cumulative = 0;
+        for (i, count) in histogram.buckets.iter().enumerate() {
+            cumulative += count;
+            let le = BOUNDS_MS.get(i).map_or("+Inf".to_string(), |ms| format!("{}", ms / 1000.0));
+            let _ = write!(text, "{}_bucket{{route=\"{}\",le=\"{}\"}} {}", name, route, le, cumulative);
+            if let Some(exemplar) = &histogram.exemplars[i] {
+                let at = exemplar.at.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs_f64();
+                let _ = write!(
+                    text,
+                    " # {{trace_id=\"{}\"}} {} {:.3}",
+                    label(&exemplar.trace_id),
+                    exemplar.ms / 1000.0,
+                    at
+                );
+            }
+            text.push('\n');
+        }
+        let _ = writeln!(text, "{}_count{{route=\"{}\"}} {}", name, route, histogram.requests);
+        let _ = writeln!(text, "{}_sum{{route=\"{}\"}} {}", name, route, histogram.sum_ms / 1000.0);
+        errors.push((route, histogram.errors));
+    });
+    text.push_str("# TYPE bff_endpoint_errors counter\n");
+    text.push_str("# HELP bff_endpoint_errors Responses with a 5xx status.\n");
+    for (route, count) in errors {
+        let _ = writeln!(text, "bff_endpoint_errors_total{{route=\"{}\"}} {}", route, count);
+    }
+    text.push_str("# EOF\n");
+    text
+}
+
+pub async fn metrics() -> Response<BoxBody> {
+    let mut response = Response::new(boxed(Full::from(render())));
+    response.headers_mut().insert(header::CONTENT_TYPE, HeaderValue::from_static(CONTENT_TYPE));
+    response
+}
@@ -0,0 +1,122 @@
+// ======================
+// PILHA DE MIDDLEWARE CONFIGURÁVEL
+// ======================
+//
+// `BFF_MIDDLEWARE` é a lista, da camada mais externa para a mais interna, dos
+// middlewares que envolvem as rotas. Vazia, vale `DEFAULT`, a pilha de sempre.
+// Assim dá para medir outra composição (sem dedup, o TimingLayer mais para
+// dentro, só o timing) sem recompilar. Nomes desconhecidos, repetidos ou
+// que não existem nesta build (por exemplo `cloudwatch` sem a feature "aws")
+// são ignorados com um aviso no stderr; o `/info` mostra a pilha que valeu.
+//
+// As camadas depois de `timing` também envolvem o grupo `/raw`, que é o mesmo
+// conjunto de rotas sem o TimingLayer; as de antes envolvem os dois grupos
+// juntos. Sem `timing` na lista, tudo envolve os dois grupos por dentro.
+
+use axum::Router;
+use once_cell::sync::Lazy;
+use tower::{layer::layer_fn, util::MapResponseLayer, Layer};
+
+use crate::config::CONFIG;
+
+/// A pilha padrão, da mais externa para a mais interna.
+pub const DEFAULT: &[&str] = &[
+    "billing",
+    "run",
+    "cloudwatch",
+    "trailers",
+    "shadow",
+    "record",
+    "info",
+    "timing",
+    "idempotency",
+    "dedup",
+    "thread-per-core",
+    "remote-config",
+    "intensity",
+    "scheduler",
+    "memory",
+    "adaptive",
+    "concurrency",
+    "padding",
+    "validation",
+    "seed",
+    "content-type",
+];
+
+/// Se a camada existe nesta build.
+fn available(name: &str) -> bool {
+    match name {
+        "cloudwatch" | "remote-config" => cfg!(feature = "aws"),
+        "thread-per-core" => cfg!(not(feature = "lambda")),
+        _ => DEFAULT.contains(&name),
+    }
+}
+
+pub static STACK: Lazy<Vec<&'static str>> = Lazy::new(|| {
+    if CONFIG.middleware.is_empty() {
+        return DEFAULT.iter().copied().filter(|name| available(name)).collect();
+    }
+    let mut stack = Vec::new();
+    for name in &CONFIG.middleware {
+        match DEFAULT.iter().find(|known| *known == name) {
+            Some(known) if !available(known) => eprintln!("BFF_MIDDLEWARE: {} não existe nesta build", name),
+            Some(known) if stack.contains(known) => eprintln!("BFF_MIDDLEWARE: {} repetido", name),
+            Some(known) => stack.push(*known),
+            None => eprintln!("BFF_MIDDLEWARE: middleware desconhecido {}", name),
+        }
+    }
+    stack
+});
+
+fn apply(router: Router, name: &str) -> Router {
+    match name {
+        "billing" => router.layer(crate::billing::BillingLayer),
+        "run" => router.layer(crate::run::RunLayer),
+        #[cfg(feature = "aws")]
+        "cloudwatch" => router.layer(crate::cloudwatch::CloudWatchLayer),
+        "trailers" => router.layer(crate::trailers::TrailersLayer),
+        "shadow" => router.layer(crate::shadow::ShadowLayer),
+        "record" => router.layer(crate::record::RecordLayer),
+        "info" => router.layer(MapResponseLayer::new(crate::info::tag)),
+        "timing" => router.layer(layer_fn(|service| crate::TimingLayer.layer(service))),
+        "idempotency" => router.layer(crate::idempotency::IdempotencyLayer),
+        "dedup" => router.layer(crate::dedup::DedupLayer),
+        #[cfg(not(feature = "lambda"))]
+        "thread-per-core" => router.layer(MapResponseLayer::new(crate::thread_per_core::tag)),
+        #[cfg(feature = "aws")]
+        "remote-config" => router.layer(crate::remote_config::RemoteConfigLayer),
+        "intensity" => router.layer(crate::intensity::IntensityLayer),
+        "scheduler" => router.layer(crate::scheduler::SchedulerLayer),
+        "memory" => router.layer(crate::memory::MemoryLayer),
+        "adaptive" => router.layer(crate::adaptive::AdaptiveLayer),
+        "concurrency" => router.layer(crate::concurrency::ConcurrencyLayer),
+        "padding" => router.layer(crate::padding::PaddingLayer),
+        "validation" => router.layer(crate::validation::ValidationLayer),
+        "seed" => router.layer(crate::rng::SeedLayer),
+        "content-type" => router.layer(crate::content_type::ContentTypeLayer),
+        _ => router,
+    }
+}
+
+/// Aplica `layers` (da mais externa para a mais interna) ao router.
+fn wrap(router: Router, layers: &[&str]) -> Router {
+    layers.iter().rev().fold(router, |router, name| apply(router, name))
+}
+
+/// Monta `routes` e o grupo `/raw` (`raw`, sem o TimingLayer) com a pilha.
+pub fn build(routes: Router, raw: Router) -> Router {
+    let (outer, inner) = match STACK.iter().position(|name| *name == "timing") {
+        Some(timing) => (&STACK[..timing], &STACK[timing + 1..]),
+        None => (&STACK[..0], &STACK[..]),
+    };
+    let mut router = wrap(routes, inner);
+    if STACK.contains(&"timing") {
+        router = apply(router, "timing");
+    }
+    wrap(router.nest("/raw", wrap(raw, inner)), outer)
+}
+
+pub fn snapshot() -> serde_json::Value {
+    serde_json::json!(*STACK)
+}
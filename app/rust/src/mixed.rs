@@ -0,0 +1,158 @@
+// ======================
+// MIX PONDERADO DE WORKLOADS
+// ======================
+//
+// Executa `requests` chamadas sorteadas (com seed) entre os workloads do
+// registro, de acordo com os pesos da distribuição. Assim um único endpoint
+// emula uma mistura de tráfego realista durante um teste de carga. As
+// chamadas são sequenciais e o corpo de cada resposta é consumido (e só
+// contado), para incluir o custo de serialização.
+
+use std::{collections::BTreeMap, time::Instant};
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+
+use crate::{
+    registry::{self, Workload},
+    rng::{self, SplitMix64},
+};
+
+const MAX_REQUESTS: usize = 10_000;
+
+#[derive(Deserialize)]
+pub struct MixedPayload {
+    requests: Option<usize>,
+    seed: Option<u64>,
+    mix: Option<Vec<MixEntry>>,
+}
+
+#[derive(Deserialize)]
+struct MixEntry {
+    workload: String,
+    weight: Option<f64>,
+    payload: Option<serde_json::Value>,
+}
+
+#[derive(Default)]
+struct WorkloadStats {
+    calls: usize,
+    errors: usize,
+    bytes: usize,
+    total_ms: f64,
+    max_ms: f64,
+}
+
+pub async fn mixed(Json(payload): Json<MixedPayload>) -> Response<BoxBody> {
+    let requests = payload.requests.unwrap_or(20);
+    if requests == 0 || requests > MAX_REQUESTS {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "requests must be between 1 and 10000" }))
+        )
+        .into_response();
+    }
+
+    // Sem `mix`, todos os workloads entram com peso 1 e payload padrão
+    let entries = payload.mix.unwrap_or_else(|| {
+        registry::WORKLOADS
+            .iter()
+            .map(|w| MixEntry { workload: w.name.to_string(), weight: None, payload: None })
+            .collect()
+    });
+
+    let mut mix: Vec<(&'static Workload, f64, serde_json::Value)> = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let Some(workload) = registry::find(&entry.workload) else {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": format!("Unknown workload: {}", entry.workload) }))
+            )
+            .into_response();
+        };
+        let weight = entry.weight.unwrap_or(1.0);
+        if !weight.is_finite() || weight < 0.0 {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "weight must be a non-negative number" }))
+            )
+            .into_response();
+        }
+        let body = entry
+            .payload
+            .unwrap_or_else(|| serde_json::from_str(workload.default_payload).unwrap());
+        mix.push((workload, weight, body));
+    }
+
+    let total_weight: f64 = mix.iter().map(|(_, weight, _)| weight).sum();
+    if total_weight <= 0.0 {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "mix must have at least one positive weight" }))
+        )
+        .into_response();
+    }
+
+    let mut rng = SplitMix64::new(rng::seed(payload.seed));
+    let mut stats: BTreeMap<&'static str, WorkloadStats> = BTreeMap::new();
+    let start = Instant::now();
+    for _ in 0..requests {
+        // Sorteio pela soma acumulada dos pesos
+        let mut pick = rng.next_f64() * total_weight;
+        let (workload, _, body) = mix
+            .iter()
+            .find(|(_, weight, _)| {
+                pick -= weight;
+                pick < 0.0
+            })
+            .unwrap_or_else(|| mix.iter().rev().find(|(_, weight, _)| *weight > 0.0).unwrap());
+
+        let call_start = Instant::now();
+        let response = (workload.run)(body.clone()).await;
+        let status = response.status();
+        let bytes = hyper::body::to_bytes(response.into_body()).await.map(|b| b.len()).unwrap_or(0);
+        let call_ms = call_start.elapsed().as_secs_f64() * 1000.0;
+
+        let entry = stats.entry(workload.name).or_default();
+        entry.calls += 1;
+        entry.errors += usize::from(!status.is_success());
+        entry.bytes += bytes;
+        entry.total_ms += call_ms;
+        entry.max_ms = entry.max_ms.max(call_ms);
+    }
+    let duration = start.elapsed();
+
+    let workloads: serde_json::Map<String, serde_json::Value> = stats
+        .iter()
+        .map(|(name, s)| {
+            (
+                name.to_string(),
+                serde_json::json!({
+                    "calls": s.calls,
+                    "errors": s.errors,
+                    "bytes": s.bytes,
+                    "total_ms": s.total_ms,
+                    "mean_ms": s.total_ms / s.calls as f64,
+                    "max_ms": s.max_ms,
+                }),
+            )
+        })
+        .collect();
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "requests": requests,
+            "errors": stats.values().map(|s| s.errors).sum::<usize>(),
+            "workloads": workloads,
+            "duration_ms": duration.as_secs_f64() * 1000.0,
+            "requests_per_second": requests as f64 / duration.as_secs_f64(),
+        }))
+    )
+    .into_response()
+}
@@ -0,0 +1,303 @@
+// ======================
+// RESPOSTAS MOCK A PARTIR DE JSON SCHEMA
+// ======================
+//
+// `GET /mock/:name` gera um documento falso (mas plausível) a partir de um dos
+// schemas em `mock_schemas/`, embutidos no binário. Serve de upstream para
+// o /external (`BFF_EXTERNAL_URL=http://127.0.0.1:3000/mock/user`) e para os
+// testes das outras variantes, sem depender de nenhuma API de fora.
+//
+// - `count`: devolve um array com `count` documentos em vez de um só
+// - `seed`: mesma seed, mesmos documentos (o `X-Benchmark-Seed` também vale)
+// - `delay_ms`: espera antes de responder, como um upstream lento
+//
+// Do JSON Schema só entra o que precisamos para gerar: `type` (inclusive
+// lista de tipos), `properties`/`required` (opcionais aparecem em 70% das
+// vezes), `items`/`minItems`/`maxItems`, `enum`, `const`, `minimum`/
+// `maximum`/`multipleOf`, `format` (uuid, email, date, date-time, uri),
+// `pattern` simples (literais, classes como `[A-Z0-9]` e `{n}`) e a extensão
+// `x-faker` para textos (name, phone, street, city, product, word, words,
+// sentence, paragraph).
+
+use std::time::Duration;
+
+use axum::{
+    body::BoxBody,
+    extract::{Json, Path, Query},
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::rng::{self, SplitMix64};
+
+const MAX_COUNT: usize = 10_000;
+const MAX_DELAY_MS: u64 = 10_000;
+const MAX_DEPTH: usize = 16;
+
+static SCHEMAS: Lazy<Vec<(&'static str, Value)>> = Lazy::new(|| {
+    [
+        ("order", include_str!("mock_schemas/order.json")),
+        ("product", include_str!("mock_schemas/product.json")),
+        ("review", include_str!("mock_schemas/review.json")),
+        ("user", include_str!("mock_schemas/user.json")),
+    ]
+    .into_iter()
+    .map(|(name, text)| (name, serde_json::from_str(text).expect("schema mock inválido")))
+    .collect()
+});
+
+#[derive(Deserialize)]
+pub struct MockQuery {
+    count: Option<usize>,
+    seed: Option<u64>,
+    delay_ms: Option<u64>,
+}
+
+const FIRST_NAMES: &[&str] = &["Ana", "Bruno", "Carla", "Diego", "Elisa", "Fábio", "Gabriela", "Hugo", "Isabel", "João"];
+const LAST_NAMES: &[&str] = &["Souza", "Lima", "Dias", "Alves", "Rocha", "Nunes", "Costa", "Pereira", "Martins", "Ribeiro"];
+const STREETS: &[&str] = &["Rua das Flores", "Avenida Paulista", "Rua Augusta", "Alameda Santos", "Rua XV de Novembro"];
+const CITIES: &[&str] = &["São Paulo", "Lisboa", "Porto Alegre", "Recife", "Curitiba", "Belo Horizonte"];
+const PRODUCTS: &[&str] = &["Cadeira", "Mesa", "Luminária", "Caneca", "Mochila", "Teclado", "Monitor", "Garrafa"];
+const ADJECTIVES: &[&str] = &["Compacta", "Premium", "Clássica", "Ergonômica", "Portátil", "Sem Fio"];
+const WORDS: &[&str] = &[
+    "lorem", "ipsum", "dolor", "sit", "amet", "consectetur", "adipiscing", "elit", "sed", "do", "eiusmod", "tempor",
+    "incididunt", "ut", "labore", "et", "dolore", "magna", "aliqua",
+];
+
+fn pick<'a>(rng: &mut SplitMix64, items: &'a [&'a str]) -> &'a str {
+    items[rng.next_below(items.len() as u64) as usize]
+}
+
+fn words(rng: &mut SplitMix64, count: u64) -> String {
+    (0..count).map(|_| pick(rng, WORDS)).collect::<Vec<_>>().join(" ")
+}
+
+fn sentence(rng: &mut SplitMix64) -> String {
+    let count = rng.next_below(10) + 5;
+    let text = words(rng, count);
+    let mut chars = text.chars();
+    let first = chars.next().map(|c| c.to_uppercase().collect::<String>()).unwrap_or_default();
+    format!("{}{}.", first, chars.as_str())
+}
+
+fn faker(rng: &mut SplitMix64, kind: &str) -> String {
+    match kind {
+        "name" => format!("{} {}", pick(rng, FIRST_NAMES), pick(rng, LAST_NAMES)),
+        "phone" => format!("+55 11 9{:04}-{:04}", rng.next_below(10_000), rng.next_below(10_000)),
+        "street" => format!("{}, {}", pick(rng, STREETS), rng.next_below(2000) + 1),
+        "city" => pick(rng, CITIES).to_string(),
+        "product" => format!("{} {}", pick(rng, PRODUCTS), pick(rng, ADJECTIVES)),
+        "word" => pick(rng, WORDS).to_string(),
+        "sentence" => sentence(rng),
+        "paragraph" => (0..rng.next_below(3) + 2).map(|_| sentence(rng)).collect::<Vec<_>>().join(" "),
+        _ => {
+            let count = rng.next_below(3) + 1;
+            words(rng, count)
+        }
+    }
+}
+
+/// Gera texto para um pattern simples: literais, `[...]` com faixas e `{n}`.
+fn from_pattern(rng: &mut SplitMix64, pattern: &str) -> String {
+    let chars: Vec<char> = pattern.trim_start_matches('^').trim_end_matches('$').chars().collect();
+    let mut out = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let alphabet: Vec<char> = if chars[i] == '[' {
+            let end = chars[i..].iter().position(|c| *c == ']').map(|p| i + p).unwrap_or(chars.len());
+            let class = &chars[i + 1..end.min(chars.len())];
+            i = end + 1;
+            let mut alphabet = Vec::new();
+            let mut j = 0;
+            while j < class.len() {
+                if j + 2 < class.len() && class[j + 1] == '-' {
+                    alphabet.extend(class[j]..=class[j + 2]);
+                    j += 3;
+                } else {
+                    alphabet.push(class[j]);
+                    j += 1;
+                }
+            }
+            alphabet
+        } else {
+            i += 1;
+            vec![chars[i - 1]]
+        };
+
+        let mut repeat = 1;
+        if i < chars.len() && chars[i] == '{' {
+            if let Some(end) = chars[i..].iter().position(|c| *c == '}').map(|p| i + p) {
+                repeat = chars[i + 1..end].iter().collect::<String>().parse().unwrap_or(1).min(256);
+                i = end + 1;
+            }
+        }
+        for _ in 0..repeat {
+            if !alphabet.is_empty() {
+                out.push(alphabet[rng.next_below(alphabet.len() as u64) as usize]);
+            }
+        }
+    }
+    out
+}
+
+fn uuid(rng: &mut SplitMix64) -> String {
+    let (high, low) = (rng.next_u64(), rng.next_u64());
+    // Versão 4, variante RFC 4122
+    let high = (high & !0xF000) | 0x4000;
+    let low = (low & !(0b11 << 62)) | (0b10 << 62);
+    format!(
+        "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+        high >> 32,
+        (high >> 16) & 0xFFFF,
+        high & 0xFFFF,
+        low >> 48,
+        low & 0xFFFF_FFFF_FFFF
+    )
+}
+
+fn timestamp(rng: &mut SplitMix64) -> DateTime<Utc> {
+    // Entre 2020-01-01 e 2025-01-01
+    DateTime::from_timestamp(1_577_836_800 + rng.next_below(5 * 365 * 86_400) as i64, 0).unwrap()
+}
+
+fn string(rng: &mut SplitMix64, schema: &Value) -> String {
+    if let Some(kind) = schema.get("x-faker").and_then(Value::as_str) {
+        return faker(rng, kind);
+    }
+    match schema.get("format").and_then(Value::as_str) {
+        Some("uuid") => uuid(rng),
+        Some("email") => format!(
+            "{}.{}{}@example.com",
+            pick(rng, FIRST_NAMES).to_lowercase(),
+            pick(rng, LAST_NAMES).to_lowercase(),
+            rng.next_below(100)
+        ),
+        Some("date-time") => timestamp(rng).format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+        Some("date") => timestamp(rng).format("%Y-%m-%d").to_string(),
+        Some("uri") => format!("https://cdn.example.com/{}/{}.jpg", pick(rng, WORDS), rng.next_below(1_000_000)),
+        _ => match schema.get("pattern").and_then(Value::as_str) {
+            Some(pattern) => from_pattern(rng, pattern),
+            None => faker(rng, "words"),
+        },
+    }
+}
+
+fn number(rng: &mut SplitMix64, schema: &Value, integer: bool) -> Value {
+    let bound = |name: &str, default: f64| schema.get(name).and_then(Value::as_f64).unwrap_or(default);
+    let (min, max) = (bound("minimum", 0.0), bound("maximum", 1000.0).max(bound("minimum", 0.0)));
+    if integer {
+        let (min, max) = (min.ceil() as i64, max.floor() as i64);
+        return serde_json::json!(min + rng.next_below((max - min + 1).max(1) as u64) as i64);
+    }
+    let value = min + rng.next_f64() * (max - min);
+    match schema.get("multipleOf").and_then(Value::as_f64).filter(|m| *m > 0.0) {
+        Some(step) => {
+            // Arredonda também as casas decimais, para não sair 12.340000000000002
+            let decimals = 10f64.powi((-step.log10()).ceil().max(0.0) as i32);
+            serde_json::json!(((value / step).round() * step * decimals).round() / decimals)
+        }
+        None => serde_json::json!(value),
+    }
+}
+
+fn generate(rng: &mut SplitMix64, schema: &Value, depth: usize) -> Value {
+    if depth > MAX_DEPTH {
+        return Value::Null;
+    }
+    if let Some(value) = schema.get("const") {
+        return value.clone();
+    }
+    if let Some(Value::Array(options)) = schema.get("enum") {
+        return options.get(rng.next_below(options.len().max(1) as u64) as usize).cloned().unwrap_or_default();
+    }
+
+    let kind = match schema.get("type") {
+        Some(Value::String(kind)) => kind.as_str(),
+        Some(Value::Array(kinds)) if !kinds.is_empty() => {
+            kinds[rng.next_below(kinds.len() as u64) as usize].as_str().unwrap_or("null")
+        }
+        _ if schema.get("properties").is_some() => "object",
+        _ => "null",
+    };
+    match kind {
+        "object" => {
+            let required: Vec<&str> = schema
+                .get("required")
+                .and_then(Value::as_array)
+                .map(|r| r.iter().filter_map(Value::as_str).collect())
+                .unwrap_or_default();
+            let mut object = serde_json::Map::new();
+            if let Some(Value::Object(properties)) = schema.get("properties") {
+                for (name, property) in properties {
+                    if required.contains(&name.as_str()) || rng.next_f64() < 0.7 {
+                        object.insert(name.clone(), generate(rng, property, depth + 1));
+                    }
+                }
+            }
+            Value::Object(object)
+        }
+        "array" => {
+            let min = schema.get("minItems").and_then(Value::as_u64).unwrap_or(0);
+            let max = schema.get("maxItems").and_then(Value::as_u64).unwrap_or(min + 5).clamp(min, min + 1000);
+            let len = min + rng.next_below(max - min + 1);
+            let items = schema.get("items").cloned().unwrap_or_default();
+            Value::Array((0..len).map(|_| generate(rng, &items, depth + 1)).collect())
+        }
+        "string" => Value::String(string(rng, schema)),
+        "integer" => number(rng, schema, true),
+        "number" => number(rng, schema, false),
+        "boolean" => Value::Bool(rng.next_below(2) == 0),
+        _ => Value::Null,
+    }
+}
+
+fn find(name: &str) -> Option<&'static Value> {
+    SCHEMAS.iter().find(|(n, _)| *n == name).map(|(_, schema)| schema)
+}
+
+fn unknown(name: &str) -> Response<BoxBody> {
+    (
+        StatusCode::NOT_FOUND,
+        Json(serde_json::json!({ "error": format!("Unknown mock: {}", name) }))
+    )
+    .into_response()
+}
+
+pub async fn list() -> Response<BoxBody> {
+    let names: Vec<&str> = SCHEMAS.iter().map(|(name, _)| *name).collect();
+    (StatusCode::OK, Json(serde_json::json!({ "mocks": names }))).into_response()
+}
+
+pub async fn schema(Path(name): Path<String>) -> Response<BoxBody> {
+    match find(&name) {
+        Some(schema) => (StatusCode::OK, Json(schema.clone())).into_response(),
+        None => unknown(&name),
+    }
+}
+
+pub async fn mock(Path(name): Path<String>, Query(query): Query<MockQuery>) -> Response<BoxBody> {
+    let Some(schema) = find(&name) else {
+        return unknown(&name);
+    };
+    if query.count.is_some_and(|count| count > MAX_COUNT) || query.delay_ms.is_some_and(|ms| ms > MAX_DELAY_MS) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": format!("count must be at most {} and delay_ms at most {}", MAX_COUNT, MAX_DELAY_MS) }))
+        )
+        .into_response();
+    }
+
+    if let Some(ms) = query.delay_ms.filter(|ms| *ms > 0) {
+        tokio::time::sleep(Duration::from_millis(ms)).await;
+    }
+    let mut rng = SplitMix64::new(rng::seed(query.seed));
+    let body = match query.count {
+        Some(count) => Value::Array((0..count).map(|_| generate(&mut rng, schema, 0)).collect()),
+        None => generate(&mut rng, schema, 0),
+    };
+    (StatusCode::OK, Json(body)).into_response()
+}
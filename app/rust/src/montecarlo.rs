@@ -0,0 +1,110 @@
+// ======================
+// MONTE CARLO (ESTIMATIVA DE PI)
+// ======================
+//
+// Sorteia `samples` pontos no quadrado unitário e conta os que caem dentro do
+// quarto de círculo: pi ≈ 4 · dentro / samples. É o workload paralelo mais
+// simples que existe, sem memória compartilhada nem sincronização até o fim,
+// então o speedup com `tasks` > 1 mostra direto quanta CPU a Lambda entrega
+// para aquela configuração de memória.
+//
+// Cada task roda numa thread própria (não no pool do rayon, que tem uma
+// thread por vCPU) para que pedir mais tasks que vCPUs apareça como
+// contenção. As amostras são divididas por igual e cada task tem o seu
+// SplitMix64, com seed tirada em ordem de um gerador semeado com `seed`: o
+// resultado é o mesmo entre execuções para o mesmo `samples` e `tasks`.
+
+use std::time::{Duration, Instant};
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+
+use crate::{
+    estimate::{self, Estimate},
+    rng::{self, SplitMix64},
+};
+
+const MAX_SAMPLES: u64 = 10_000_000_000;
+const MAX_TASKS: usize = 64;
+
+#[derive(Deserialize)]
+pub struct MonteCarloPayload {
+    samples: Option<u64>,
+    tasks: Option<usize>,
+    seed: Option<u64>,
+    dry_run: Option<bool>,
+}
+
+fn inside(samples: u64, seed: u64) -> (u64, Duration) {
+    let start = Instant::now();
+    let mut rng = SplitMix64::new(seed);
+    let mut hits = 0;
+    for _ in 0..samples {
+        let (x, y) = (rng.next_f64(), rng.next_f64());
+        if x * x + y * y <= 1.0 {
+            hits += 1;
+        }
+    }
+    (hits, start.elapsed())
+}
+
+pub async fn montecarlo(Json(payload): Json<MonteCarloPayload>) -> Response<BoxBody> {
+    let samples = payload.samples.unwrap_or(1_000_000);
+    let tasks = payload.tasks.unwrap_or(1);
+    if samples == 0 || samples > MAX_SAMPLES || tasks == 0 || tasks > MAX_TASKS {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": format!("samples must be between 1 and {} and tasks between 1 and {}", MAX_SAMPLES, MAX_TASKS)
+            }))
+        )
+        .into_response();
+    }
+
+    if payload.dry_run.unwrap_or(false) {
+        return estimate::response(Estimate {
+            iterations: samples,
+            unit: "samples",
+            allocation_bytes: 0,
+            output_bytes: 250 + 30 * tasks as u64,
+        });
+    }
+
+    let mut seeder = SplitMix64::new(rng::seed(payload.seed));
+    let shares: Vec<(u64, u64)> = (0..tasks as u64)
+        .map(|i| (samples / tasks as u64 + u64::from(i < samples % tasks as u64), seeder.next_u64()))
+        .collect();
+
+    let start = Instant::now();
+    let results: Vec<(u64, Duration)> = std::thread::scope(|scope| {
+        let handles: Vec<_> = shares
+            .iter()
+            .map(|&(samples, seed)| scope.spawn(move || inside(samples, seed)))
+            .collect();
+        handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+    });
+    let elapsed = start.elapsed();
+
+    let hits: u64 = results.iter().map(|(hits, _)| hits).sum();
+    let pi = 4.0 * hits as f64 / samples as f64;
+    let task_ms: Vec<f64> = results.iter().map(|(_, duration)| duration.as_secs_f64() * 1000.0).collect();
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "samples": samples,
+            "tasks": tasks,
+            "inside": hits,
+            "pi": pi,
+            "absolute_error": (pi - std::f64::consts::PI).abs(),
+            "duration_ms": elapsed.as_secs_f64() * 1000.0,
+            "task_ms": task_ms,
+            "samples_per_second": samples as f64 / elapsed.as_secs_f64(),
+        }))
+    )
+    .into_response()
+}
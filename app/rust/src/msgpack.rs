@@ -0,0 +1,92 @@
+// ======================
+// IDA E VOLTA POR MESSAGEPACK
+// ======================
+//
+// Codifica `document` (qualquer JSON) em MessagePack e decodifica de volta,
+// `iterations` vezes, para comparar o custo de um formato binário entre as
+// implementações. Devolve o tamanho em JSON compacto e em MessagePack e os
+// tempos de codificação e decodificação (total e por iteração). `round_trip`
+// confere que o documento que voltou é igual ao original.
+
+use std::time::{Duration, Instant};
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use serde_json::Value;
+
+const MAX_ITERATIONS: usize = 100_000;
+/// Bytes de MessagePack codificados no total (iterations x tamanho)
+const MAX_WORK: usize = 2_000_000_000;
+
+const SAMPLE: &str = r#"{"order":{"id":48213,"customer":{"id":912,"name":"Ana Souza","email":"ana@example.com"},"status":"paid","created_at":"2024-05-14T09:21:00Z","items":[{"sku":"SKU-00417","quantity":2,"price":19.9},{"sku":"SKU-08812","quantity":1,"price":249.0}],"tags":["gift","express"],"gift_message":null,"paid":true}}"#;
+
+#[derive(Deserialize)]
+pub struct MsgpackPayload {
+    document: Option<Value>,
+    iterations: Option<usize>,
+}
+
+fn bad_request(error: String) -> Response<BoxBody> {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({ "error": error }))
+    )
+    .into_response()
+}
+
+fn ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+pub async fn msgpack(Json(payload): Json<MsgpackPayload>) -> Response<BoxBody> {
+    let iterations = payload.iterations.unwrap_or(1);
+    if iterations == 0 || iterations > MAX_ITERATIONS {
+        return bad_request(format!("iterations must be between 1 and {}", MAX_ITERATIONS));
+    }
+    let document = payload.document.unwrap_or_else(|| serde_json::from_str(SAMPLE).unwrap());
+    let json_bytes = serde_json::to_vec(&document).map_or(0, |json| json.len());
+
+    let mut encoded = match rmp_serde::to_vec(&document) {
+        Ok(encoded) => encoded,
+        Err(error) => return bad_request(format!("Cannot encode MessagePack: {}", error)),
+    };
+    if encoded.len().saturating_mul(iterations) > MAX_WORK {
+        return bad_request("document size * iterations exceeds 2 GB".to_string());
+    }
+
+    let mut encode_duration = Duration::ZERO;
+    let mut decode_duration = Duration::ZERO;
+    let mut decoded = Value::Null;
+    for _ in 0..iterations {
+        let start = Instant::now();
+        encoded = rmp_serde::to_vec(&document).expect("já codificou uma vez");
+        encode_duration += start.elapsed();
+        let start = Instant::now();
+        decoded = match rmp_serde::from_slice(&encoded) {
+            Ok(decoded) => decoded,
+            Err(error) => return bad_request(format!("Cannot decode MessagePack: {}", error)),
+        };
+        decode_duration += start.elapsed();
+    }
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "iterations": iterations,
+            "json_bytes": json_bytes,
+            "msgpack_bytes": encoded.len(),
+            "size_ratio": encoded.len() as f64 / json_bytes.max(1) as f64,
+            "round_trip": decoded == document,
+            "encode_ms": ms(encode_duration),
+            "decode_ms": ms(decode_duration),
+            "encode_ms_per_iteration": ms(encode_duration) / iterations as f64,
+            "decode_ms_per_iteration": ms(decode_duration) / iterations as f64,
+        }))
+    )
+    .into_response()
+}
@@ -0,0 +1,222 @@
+// ======================
+// MIDDLEWARE: PADDING NA RESPOSTA
+// ======================
+//
+// Para separar tempo de cálculo de tempo de transferência é preciso controlar
+// o tamanho da resposta independente do workload. Qualquer endpoint aceita
+// `response_padding_bytes` na query string (ou o header
+// `X-Response-Padding-Bytes`): a resposta JSON ganha um campo `"padding"` com
+// um texto que faz o corpo crescer exatamente N bytes.
+//
+// O campo custa 13 bytes (`,"padding":""`), então pedidos menores que isso
+// crescem 13 bytes mesmo; `X-Response-Padding` diz quanto foi acrescentado.
+// O texto são caracteres alfanuméricos de um SplitMix64 com seed fixa:
+// determinístico entre execuções e linguagens, e sem comprimir como uma
+// sequência de espaços comprimiria. Só objetos JSON recebem padding; PNG,
+// gzip e streams passam intactos, com `X-Response-Padding: 0`.
+
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use axum::{
+    body::{boxed, BoxBody, Full},
+    extract::Json,
+    http::{header, HeaderValue, Request, Response, StatusCode},
+    response::IntoResponse,
+};
+use tower::{Layer, Service};
+
+use crate::rng::SplitMix64;
+
+const QUERY_PARAM: &str = "response_padding_bytes";
+const REQUEST_HEADER: &str = "X-Response-Padding-Bytes";
+const MAX_PADDING_BYTES: usize = 64 * 1024 * 1024;
+const ALPHABET: &[u8; 62] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+const PADDING_SEED: u64 = 42;
+
+/// N pedido, da query string ou do header; `Err` se o valor não for um número.
+fn requested<B>(req: &Request<B>) -> Result<Option<usize>, ()> {
+    let from_query = req.uri().query().and_then(|query| {
+        query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix(QUERY_PARAM)?.strip_prefix('='))
+    });
+    let from_header = req.headers().get(REQUEST_HEADER).and_then(|v| v.to_str().ok());
+    match from_query.or(from_header) {
+        Some(value) => value.trim().parse().map(Some).map_err(|_| ()),
+        None => Ok(None),
+    }
+}
+
+fn filler(len: usize) -> Vec<u8> {
+    let mut rng = SplitMix64::new(PADDING_SEED);
+    (0..len).map(|_| ALPHABET[rng.next_below(ALPHABET.len() as u64) as usize]).collect()
+}
+
+/// Acrescenta o campo antes do `}` final; devolve o corpo e os bytes somados.
+fn pad(body: &[u8], bytes: usize) -> Option<(Vec<u8>, usize)> {
+    let end = body.iter().rposition(|b| !b.is_ascii_whitespace())?;
+    if body.first() != Some(&b'{') || body[end] != b'}' {
+        return None;
+    }
+    let empty = body[1..end].iter().all(u8::is_ascii_whitespace);
+    let prefix: &[u8] = if empty { b"\"padding\":\"" } else { b",\"padding\":\"" };
+    let overhead = prefix.len() + 1;
+    let text = filler(bytes.saturating_sub(overhead));
+
+    let mut padded = Vec::with_capacity(body.len() + overhead + text.len());
+    padded.extend_from_slice(&body[..end]);
+    padded.extend_from_slice(prefix);
+    padded.extend_from_slice(&text);
+    padded.push(b'"');
+    padded.extend_from_slice(&body[end..]);
+    Some((padded, overhead + text.len()))
+}
+
+fn is_json<B>(response: &Response<B>) -> bool {
+    response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"))
+}
+
+#[derive(Clone)]
+pub struct PaddingLayer;
+
+#[derive(Clone)]
+pub struct PaddingService<S> {
+    inner: S,
+}
+
+impl<S> Layer<S> for PaddingLayer {
+    type Service = PaddingService<S>;
+    fn layer(&self, inner: S) -> Self::Service {
+        PaddingService { inner }
+    }
+}
+
+impl<S, ReqBody> Service<Request<ReqBody>> for PaddingService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+{
+    type Response = Response<BoxBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        let mut service = self.inner.clone();
+
+        let bytes = match requested(&req) {
+            Ok(Some(bytes)) if bytes <= MAX_PADDING_BYTES => bytes,
+            Ok(None) => return Box::pin(service.call(req)),
+            _ => {
+                return Box::pin(async {
+                    Ok((
+                        StatusCode::BAD_REQUEST,
+                        Json(serde_json::json!({
+                            "error": format!("{} must be an integer between 0 and {}", QUERY_PARAM, MAX_PADDING_BYTES)
+                        }))
+                    )
+                    .into_response())
+                });
+            }
+        };
+
+        Box::pin(async move {
+            let response = service.call(req).await?;
+            if bytes == 0 || !is_json(&response) {
+                let mut response = response;
+                response.headers_mut().insert("X-Response-Padding", HeaderValue::from(0));
+                return Ok(response);
+            }
+
+            let (mut parts, body) = response.into_parts();
+            let Ok(body) = hyper::body::to_bytes(body).await else {
+                return Ok((
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(serde_json::json!({ "error": "Failed to read response for padding" }))
+                )
+                .into_response());
+            };
+            let (body, added) = pad(&body, bytes).unwrap_or_else(|| (body.to_vec(), 0));
+            parts.headers.remove(header::CONTENT_LENGTH);
+            parts.headers.insert("X-Response-Padding", HeaderValue::from(added));
+            Ok(Response::from_parts(parts, boxed(Full::from(body))))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BODY: &[u8] = br#"{"result":15,"operation":"sum"}"#;
+
+    fn padded_len(body: &[u8], bytes: usize) -> usize {
+        let (padded, added) = pad(body, bytes).unwrap();
+        let value: serde_json::Value = serde_json::from_slice(&padded).expect("padded body is JSON");
+        assert!(value["padding"].is_string());
+        assert_eq!(padded.len(), body.len() + added);
+        padded.len()
+    }
+
+    #[test]
+    fn body_grows_by_exactly_the_requested_bytes() {
+        // Abaixo, igual e acima do tamanho do corpo original
+        for bytes in [BODY.len() / 2, BODY.len(), BODY.len() * 10, 1 << 20] {
+            assert_eq!(padded_len(BODY, bytes), BODY.len() + bytes, "{} bytes", bytes);
+        }
+    }
+
+    #[test]
+    fn requests_below_the_field_overhead_overshoot() {
+        for bytes in [1, 5, 12, 13] {
+            assert_eq!(padded_len(BODY, bytes), BODY.len() + 13, "{} bytes", bytes);
+        }
+        assert_eq!(padded_len(BODY, 14), BODY.len() + 14);
+    }
+
+    #[test]
+    fn empty_object_needs_no_comma() {
+        assert_eq!(pad(b"{ }\n", 100).unwrap().0.len(), 104);
+        assert_eq!(padded_len(b"{}", 5), 2 + 12);
+    }
+
+    #[test]
+    fn only_objects_are_padded() {
+        assert!(pad(b"[1,2,3]", 100).is_none());
+        assert!(pad(b"\"text\"", 100).is_none());
+        assert!(pad(b"", 100).is_none());
+    }
+
+    #[test]
+    fn filler_is_deterministic() {
+        assert_eq!(filler(64), filler(64));
+        assert!(filler(64).iter().all(u8::is_ascii_alphanumeric));
+    }
+
+    #[test]
+    fn query_string_wins_over_header() {
+        let req = Request::builder()
+            .uri("/math?response_padding_bytes=100")
+            .header(REQUEST_HEADER, "5")
+            .body(())
+            .unwrap();
+        assert_eq!(requested(&req), Ok(Some(100)));
+        let req = Request::builder().uri("/math").header(REQUEST_HEADER, "5").body(()).unwrap();
+        assert_eq!(requested(&req), Ok(Some(5)));
+        let req = Request::builder().uri("/math?response_padding_bytes=-1").body(()).unwrap();
+        assert_eq!(requested(&req), Err(()));
+        assert_eq!(requested(&Request::new(())), Ok(None));
+    }
+}
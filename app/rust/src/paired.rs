@@ -0,0 +1,167 @@
+// ======================
+// COMPARAÇÃO PAREADA A/B (POST /paired)
+// ======================
+//
+// Compara duas variantes do mesmo workload do registro dentro de uma request
+// só, intercalando as execuções: `a` e `b` são os campos que mudam no payload
+// (por exemplo `{"algorithm": "stable"}` e `{"algorithm": "unstable"}` no
+// /sort, ou `{"parser": "dom"}` e `{"parser": "typed"}` no /json/parse),
+// aplicados sobre `payload` ou o padrão do workload. Cada par roda as duas
+// variantes em seguida, alternando a ordem (AB, BA, AB...) para que nenhuma
+// fique sempre com o cache quente ou frio.
+//
+// Em hardware compartilhado o ruído de um minuto para o outro costuma ser
+// maior que a diferença procurada; como as duas medidas de um par passam pelo
+// mesmo momento da máquina, a diferença `b - a` de cada par cancela quase tudo
+// isso. A resposta traz a distribuição dessas diferenças: média com intervalo
+// de confiança de 95%, percentis, a mediana da razão `b / a` e em quantos
+// pares cada variante ganhou. Uma chamada que falha interrompe a comparação.
+
+use std::time::Instant;
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{
+    registry::{self, Workload},
+    sweep::{percentile, spread},
+};
+
+const MAX_PAIRS: usize = 5_000;
+const MAX_WARMUP: usize = 100;
+
+#[derive(Deserialize)]
+pub struct PairedPayload {
+    workload: String,
+    a: Value,
+    b: Value,
+    payload: Option<Value>,
+    pairs: Option<usize>,
+    warmup: Option<usize>,
+}
+
+fn bad_request(error: String) -> Response<BoxBody> {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({ "error": error }))
+    )
+    .into_response()
+}
+
+/// O payload base com os campos da variante por cima.
+fn variant(base: &Value, overrides: &Value) -> Option<Value> {
+    let mut body = base.clone();
+    for (key, value) in overrides.as_object()? {
+        body[key] = value.clone();
+    }
+    Some(body)
+}
+
+/// Roda uma chamada e devolve a duração em ms, ou o status e o erro da resposta.
+pub async fn call(workload: &Workload, body: &Value) -> Result<f64, (u16, String)> {
+    let start = Instant::now();
+    let response = (workload.run)(body.clone()).await;
+    let status = response.status();
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap_or_default();
+    let ms = start.elapsed().as_secs_f64() * 1000.0;
+    if !status.is_success() {
+        let body: Value = serde_json::from_slice(&bytes).unwrap_or_default();
+        let error = body["error"].as_str().unwrap_or("no error message").to_string();
+        return Err((status.as_u16(), error));
+    }
+    Ok(ms)
+}
+
+fn summary(values: &[f64]) -> Value {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let (mean, stddev, margin) = spread(&sorted);
+    serde_json::json!({
+        "mean_ms": mean,
+        "stddev_ms": stddev,
+        "ci95_low_ms": mean - margin,
+        "ci95_high_ms": mean + margin,
+        "min_ms": sorted[0],
+        "p5_ms": percentile(&sorted, 0.05),
+        "p50_ms": percentile(&sorted, 0.5),
+        "p95_ms": percentile(&sorted, 0.95),
+        "max_ms": sorted[sorted.len() - 1],
+    })
+}
+
+pub async fn paired(Json(payload): Json<PairedPayload>) -> Response<BoxBody> {
+    let Some(workload) = registry::find(&payload.workload) else {
+        return bad_request(format!("Unknown workload: {}", payload.workload));
+    };
+    let pairs = payload.pairs.unwrap_or(30);
+    if pairs == 0 || pairs > MAX_PAIRS {
+        return bad_request(format!("pairs must be between 1 and {}", MAX_PAIRS));
+    }
+    let warmup = payload.warmup.unwrap_or(1);
+    if warmup > MAX_WARMUP {
+        return bad_request(format!("warmup must be at most {}", MAX_WARMUP));
+    }
+    let base = payload
+        .payload
+        .unwrap_or_else(|| serde_json::from_str(workload.default_payload).unwrap());
+    if !base.is_object() {
+        return bad_request("payload must be an object".to_string());
+    }
+    let (Some(body_a), Some(body_b)) = (variant(&base, &payload.a), variant(&base, &payload.b)) else {
+        return bad_request("a and b must be objects with the fields that change".to_string());
+    };
+
+    let start = Instant::now();
+    let mut a_ms = Vec::with_capacity(pairs);
+    let mut b_ms = Vec::with_capacity(pairs);
+    for round in 0..warmup + pairs {
+        // Pares ímpares rodam b primeiro
+        let order = if round % 2 == 0 { [("a", &body_a), ("b", &body_b)] } else { [("b", &body_b), ("a", &body_a)] };
+        let mut measured = [0.0; 2];
+        for (name, body) in order {
+            match call(workload, body).await {
+                Ok(ms) => measured[usize::from(name == "b")] = ms,
+                Err((status, error)) => {
+                    return bad_request(format!("Variant {} failed with status {}: {}", name, status, error));
+                }
+            }
+        }
+        if round >= warmup {
+            a_ms.push(measured[0]);
+            b_ms.push(measured[1]);
+        }
+    }
+    let duration = start.elapsed();
+
+    let differences: Vec<f64> = a_ms.iter().zip(&b_ms).map(|(a, b)| b - a).collect();
+    let mut ratios: Vec<f64> = a_ms.iter().zip(&b_ms).filter(|(a, _)| **a > 0.0).map(|(a, b)| b / a).collect();
+    ratios.sort_by(f64::total_cmp);
+    let difference = summary(&differences);
+    // O intervalo não cruza o zero: a diferença não é só ruído
+    let low = difference["ci95_low_ms"].as_f64().unwrap_or(0.0);
+    let high = difference["ci95_high_ms"].as_f64().unwrap_or(0.0);
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "workload": workload.name,
+            "pairs": pairs,
+            "warmup": warmup,
+            "a": summary(&a_ms),
+            "b": summary(&b_ms),
+            "difference": difference,
+            "ratio_p50": (!ratios.is_empty()).then(|| percentile(&ratios, 0.5)),
+            "a_faster": differences.iter().filter(|d| **d > 0.0).count(),
+            "b_faster": differences.iter().filter(|d| **d < 0.0).count(),
+            "significant": pairs > 1 && (low > 0.0 || high < 0.0),
+            "duration_ms": duration.as_secs_f64() * 1000.0,
+        }))
+    )
+    .into_response()
+}
@@ -0,0 +1,226 @@
+// ======================
+// HASH DE SENHA (ARGON2 E BCRYPT)
+// ======================
+//
+// POST /argon2
+// Gera o hash Argon2 (PHC: `$argon2id$v=19$m=...,t=...,p=...$salt$hash`) de
+// `password` com o custo pedido e confere o hash logo em seguida, como num
+// cadastro seguido de login. Diferente dos outros workloads, o custo aqui é
+// memória: cada hash preenche `memory_kib` KiB, o que pesa de um jeito bem
+// diferente em cada configuração de memória da Lambda.
+//
+// O salt vem da seed (ou de `salt`, em base64 sem padding), então o hash é
+// reproduzível. As `parallelism` lanes são calculadas em sequência, numa
+// thread só: o crate não paraleliza.
+//
+// POST /bcrypt faz o mesmo com bcrypt, o benchmark clássico de CPU lenta:
+// `cost` é o log2 das rodadas (cada +1 dobra o tempo) e a memória é fixa, uns
+// 4 KiB. O bcrypt só usa os primeiros 72 bytes da senha.
+
+use std::time::Instant;
+
+use argon2::{
+    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Algorithm, Argon2, Params, Version,
+};
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+
+use crate::{
+    estimate::{self, Estimate},
+    rng::{self, SplitMix64},
+};
+
+const MAX_MEMORY_KIB: u32 = 1024 * 1024;
+const MAX_ITERATIONS: u32 = 100;
+const MAX_PARALLELISM: u32 = 16;
+// Memória preenchida no total (memory_kib × iterations), ~16 GiB
+const MAX_WORK_KIB: u64 = 16 * 1024 * 1024;
+// O crate aceita de 4 a 31; custo 16 já leva alguns segundos
+const MIN_BCRYPT_COST: u32 = 4;
+const MAX_BCRYPT_COST: u32 = 16;
+
+#[derive(Deserialize)]
+pub struct Argon2Payload {
+    password: String,
+    memory_kib: Option<u32>,
+    iterations: Option<u32>,
+    parallelism: Option<u32>,
+    variant: Option<String>,
+    salt: Option<String>,
+    seed: Option<u64>,
+    dry_run: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct BcryptPayload {
+    password: String,
+    cost: Option<u32>,
+    seed: Option<u64>,
+    dry_run: Option<bool>,
+}
+
+pub async fn argon2(Json(payload): Json<Argon2Payload>) -> Response<BoxBody> {
+    let memory = payload.memory_kib.unwrap_or(Params::DEFAULT_M_COST);
+    let iterations = payload.iterations.unwrap_or(Params::DEFAULT_T_COST);
+    let parallelism = payload.parallelism.unwrap_or(Params::DEFAULT_P_COST);
+    if memory > MAX_MEMORY_KIB
+        || iterations == 0
+        || iterations > MAX_ITERATIONS
+        || parallelism == 0
+        || parallelism > MAX_PARALLELISM
+        || memory as u64 * iterations as u64 > MAX_WORK_KIB
+    {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "memory_kib, iterations or parallelism out of range" }))
+        )
+        .into_response();
+    }
+
+    let algorithm = match payload.variant.as_deref().unwrap_or("argon2id") {
+        "argon2id" => Algorithm::Argon2id,
+        "argon2i" => Algorithm::Argon2i,
+        "argon2d" => Algorithm::Argon2d,
+        other => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": format!("Unsupported variant: {}", other) }))
+            )
+            .into_response();
+        }
+    };
+    // Params::new também recusa memória abaixo de 8 KiB por lane
+    let params = match Params::new(memory, iterations, parallelism, None) {
+        Ok(params) => params,
+        Err(error) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": format!("Invalid parameters: {}", error) }))
+            )
+            .into_response();
+        }
+    };
+
+    let salt = match payload.salt {
+        Some(salt) => SaltString::from_b64(&salt),
+        None => {
+            let mut rng = SplitMix64::new(rng::seed(payload.seed));
+            let bytes: Vec<u8> = (0..2).flat_map(|_| rng.next_u64().to_le_bytes()).collect();
+            SaltString::encode_b64(&bytes)
+        }
+    };
+    let salt = match salt {
+        Ok(salt) => salt,
+        Err(error) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": format!("Invalid salt: {}", error) }))
+            )
+            .into_response();
+        }
+    };
+    if payload.dry_run.unwrap_or(false) {
+        // Cada passada preenche toda a memória, no hash e de novo no verify
+        return estimate::response(Estimate {
+            iterations: 2 * memory as u64 * iterations as u64,
+            unit: "KiB filled",
+            allocation_bytes: memory as u64 * 1024,
+            output_bytes: 250,
+        });
+    }
+
+    let hasher = Argon2::new(algorithm, Version::V0x13, params);
+    let start = Instant::now();
+    let hash = match hasher.hash_password(payload.password.as_bytes(), &salt) {
+        Ok(hash) => hash.to_string(),
+        Err(error) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": format!("Hashing failed: {}", error) }))
+            )
+            .into_response();
+        }
+    };
+    let hash_duration = start.elapsed();
+
+    // Confere a partir do texto PHC, como faria o login com o hash guardado
+    let start = Instant::now();
+    let verified = PasswordHash::new(&hash)
+        .map(|parsed| hasher.verify_password(payload.password.as_bytes(), &parsed).is_ok())
+        .unwrap_or(false);
+    let verify_duration = start.elapsed();
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "hash": hash,
+            "verified": verified,
+            "memory_kib": memory,
+            "iterations": iterations,
+            "parallelism": parallelism,
+            "hash_ms": hash_duration.as_secs_f64() * 1000.0,
+            "verify_ms": verify_duration.as_secs_f64() * 1000.0,
+        }))
+    )
+    .into_response()
+}
+
+pub async fn bcrypt(Json(payload): Json<BcryptPayload>) -> Response<BoxBody> {
+    let cost = payload.cost.unwrap_or(bcrypt::DEFAULT_COST);
+    if !(MIN_BCRYPT_COST..=MAX_BCRYPT_COST).contains(&cost) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": format!("cost must be between {} and {}", MIN_BCRYPT_COST, MAX_BCRYPT_COST) }))
+        )
+        .into_response();
+    }
+    if payload.dry_run.unwrap_or(false) {
+        // 2^cost expansões de chave no hash e de novo no verify
+        return estimate::response(Estimate {
+            iterations: 2 << cost,
+            unit: "key expansions",
+            allocation_bytes: 4 * 1024,
+            output_bytes: 150,
+        });
+    }
+
+    let mut rng = SplitMix64::new(rng::seed(payload.seed));
+    let mut salt = [0u8; 16];
+    salt[..8].copy_from_slice(&rng.next_u64().to_le_bytes());
+    salt[8..].copy_from_slice(&rng.next_u64().to_le_bytes());
+
+    let start = Instant::now();
+    let hash = match bcrypt::hash_with_salt(payload.password.as_bytes(), cost, salt) {
+        Ok(parts) => parts.format_for_version(bcrypt::Version::TwoB),
+        Err(error) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": format!("Hashing failed: {}", error) }))
+            )
+            .into_response();
+        }
+    };
+    let hash_duration = start.elapsed();
+
+    let start = Instant::now();
+    let verified = bcrypt::verify(payload.password.as_bytes(), &hash).unwrap_or(false);
+    let verify_duration = start.elapsed();
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "hash": hash,
+            "verified": verified,
+            "cost": cost,
+            "hash_ms": hash_duration.as_secs_f64() * 1000.0,
+            "verify_ms": verify_duration.as_secs_f64() * 1000.0,
+        }))
+    )
+    .into_response()
+}
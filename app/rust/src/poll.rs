@@ -0,0 +1,181 @@
+// ======================
+// LONG POLLING
+// ======================
+//
+// GET /poll?channel=X&since=N&timeout_ms=T segura a conexão até o canal
+// receber uma mensagem com sequência maior que `since` (publicada via
+// POST /poll) ou até o timeout. Serve para comparar quantas conexões ociosas
+// cada runtime aguenta segurar num deploy em container.
+//
+// Cada canal é um `watch` do tokio: guarda só a última mensagem, então um
+// cliente que chegar atrasado recebe a mais recente (e pelo `sequence`
+// percebe se perdeu alguma).
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicI64, AtomicU64, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+
+use axum::{
+    body::BoxBody,
+    extract::{Json, Query},
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use tokio::sync::watch;
+
+const MAX_TIMEOUT_MS: u64 = 120_000;
+const MAX_CHANNELS: usize = 1_024;
+
+#[derive(Deserialize)]
+pub struct PollQuery {
+    channel: Option<String>,
+    since: Option<u64>,
+    timeout_ms: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub struct PublishPayload {
+    channel: Option<String>,
+    data: Option<serde_json::Value>,
+}
+
+#[derive(Clone)]
+struct Message {
+    sequence: u64,
+    data: serde_json::Value,
+}
+
+static CHANNELS: Lazy<Mutex<HashMap<String, watch::Sender<Message>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+static WAITING: AtomicI64 = AtomicI64::new(0);
+static DELIVERED: AtomicU64 = AtomicU64::new(0);
+static TIMEOUTS: AtomicU64 = AtomicU64::new(0);
+static PUBLISHED: AtomicU64 = AtomicU64::new(0);
+
+pub fn snapshot() -> serde_json::Value {
+    serde_json::json!({
+        "channels": CHANNELS.lock().unwrap().len(),
+        "waiting": WAITING.load(Ordering::Relaxed),
+        "delivered": DELIVERED.load(Ordering::Relaxed),
+        "timeouts": TIMEOUTS.load(Ordering::Relaxed),
+        "published": PUBLISHED.load(Ordering::Relaxed),
+    })
+}
+
+fn subscribe(channel: &str) -> Option<watch::Receiver<Message>> {
+    let mut channels = CHANNELS.lock().unwrap();
+    if let Some(sender) = channels.get(channel) {
+        return Some(sender.subscribe());
+    }
+    if channels.len() >= MAX_CHANNELS {
+        return None;
+    }
+    let (sender, receiver) = watch::channel(Message { sequence: 0, data: serde_json::Value::Null });
+    channels.insert(channel.to_string(), sender);
+    Some(receiver)
+}
+
+// Conta a conexão como esperando enquanto o guard estiver vivo, inclusive se
+// o cliente desconectar e o future for descartado no meio
+struct WaitingGuard;
+
+impl WaitingGuard {
+    fn new() -> Self {
+        WAITING.fetch_add(1, Ordering::Relaxed);
+        WaitingGuard
+    }
+}
+
+impl Drop for WaitingGuard {
+    fn drop(&mut self) {
+        WAITING.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+pub async fn poll(Query(query): Query<PollQuery>) -> Response<BoxBody> {
+    let channel = query.channel.unwrap_or_else(|| "default".to_string());
+    let since = query.since.unwrap_or(0);
+    let timeout = Duration::from_millis(query.timeout_ms.unwrap_or(30_000).min(MAX_TIMEOUT_MS));
+
+    let Some(mut receiver) = subscribe(&channel) else {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({ "error": "Too many channels" }))
+        )
+        .into_response();
+    };
+
+    let start = crate::clock::Stopwatch::start();
+    let _guard = WaitingGuard::new();
+    let wait = receiver.wait_for(|message| message.sequence > since);
+    let message = match tokio::time::timeout(timeout, wait).await {
+        Ok(Ok(message)) => Some(message.clone()),
+        // Timeout (ou o canal sumiu, o que na prática não acontece)
+        _ => None,
+    };
+    let waited_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+    match message {
+        Some(message) => {
+            DELIVERED.fetch_add(1, Ordering::Relaxed);
+            (
+                StatusCode::OK,
+                Json(serde_json::json!({
+                    "channel": channel,
+                    "sequence": message.sequence,
+                    "data": message.data,
+                    "timed_out": false,
+                    "waited_ms": waited_ms,
+                }))
+            )
+            .into_response()
+        }
+        None => {
+            TIMEOUTS.fetch_add(1, Ordering::Relaxed);
+            (
+                StatusCode::OK,
+                Json(serde_json::json!({ "channel": channel, "timed_out": true, "waited_ms": waited_ms }))
+            )
+            .into_response()
+        }
+    }
+}
+
+pub async fn publish(Json(payload): Json<PublishPayload>) -> Response<BoxBody> {
+    let channel = payload.channel.unwrap_or_else(|| "default".to_string());
+    if subscribe(&channel).is_none() {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({ "error": "Too many channels" }))
+        )
+        .into_response();
+    }
+
+    let channels = CHANNELS.lock().unwrap();
+    let sender = &channels[&channel];
+    let mut sequence = 0;
+    sender.send_modify(|message| {
+        message.sequence += 1;
+        message.data = payload.data.unwrap_or(serde_json::Value::Null);
+        sequence = message.sequence;
+    });
+    PUBLISHED.fetch_add(1, Ordering::Relaxed);
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "channel": channel,
+            "sequence": sequence,
+            "waiting": sender.receiver_count(),
+        }))
+    )
+    .into_response()
+}
@@ -0,0 +1,85 @@
+// ======================
+// CRIVO DE ERATÓSTENES
+// ======================
+//
+// Conta os primos até `limit` (inclusive) com o crivo clássico: um `Vec<bool>`
+// de limit+1 posições e um laço marcando os múltiplos de cada primo a partir
+// do quadrado dele. Um byte por número, de propósito: o mesmo crivo é trivial
+// de escrever nas outras linguagens, e o custo é alocar e percorrer a memória.
+// `last` pede os K maiores primos encontrados.
+
+use std::time::Instant;
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+
+use crate::estimate::{self, Estimate};
+
+const MAX_LIMIT: usize = 200_000_000;
+const MAX_LAST: usize = 10_000;
+
+#[derive(Deserialize)]
+pub struct PrimesPayload {
+    limit: Option<usize>,
+    last: Option<usize>,
+    dry_run: Option<bool>,
+}
+
+pub async fn primes(Json(payload): Json<PrimesPayload>) -> Response<BoxBody> {
+    let limit = payload.limit.unwrap_or(1_000_000);
+    let last = payload.last.unwrap_or(0);
+    if !(2..=MAX_LIMIT).contains(&limit) || last > MAX_LAST {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": format!("limit must be between 2 and {} and last at most {}", MAX_LIMIT, MAX_LAST)
+            }))
+        )
+        .into_response();
+    }
+
+    if payload.dry_run.unwrap_or(false) {
+        // ~n·ln(ln n) marcações
+        let n = limit as f64;
+        return estimate::response(Estimate {
+            iterations: (n * n.ln().ln().max(1.0)) as u64,
+            unit: "multiples marked",
+            allocation_bytes: limit as u64 + 1,
+            output_bytes: 150 + 11 * last as u64,
+        });
+    }
+
+    let start = Instant::now();
+    let mut composite = vec![false; limit + 1];
+    let mut p = 2;
+    while p * p <= limit {
+        if !composite[p] {
+            for multiple in (p * p..=limit).step_by(p) {
+                composite[multiple] = true;
+            }
+        }
+        p += 1;
+    }
+    let count = composite[2..].iter().filter(|c| !**c).count();
+    let elapsed = start.elapsed();
+
+    let mut largest: Vec<usize> = (2..=limit).rev().filter(|n| !composite[*n]).take(last).collect();
+    largest.reverse();
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "limit": limit,
+            "count": count,
+            "last": largest,
+            "sieve_ms": elapsed.as_secs_f64() * 1000.0,
+            "numbers_per_second": limit as f64 / elapsed.as_secs_f64(),
+        }))
+    )
+    .into_response()
+}
@@ -0,0 +1,322 @@
+// ======================
+// SUBCOMANDO profdiff
+// ======================
+//
+// `demo-lambda-axum profdiff <antes.pb[.gz]> <depois.pb[.gz]> [-o diff.svg]
+// [--sample-index N] [--normalize] [--folded]` compara dois perfis no formato
+// pprof (protobuf, gzipado ou não) e gera um flamegraph diferencial (inferno):
+// a largura de cada frame é a do perfil "depois" e a cor mostra a mudança,
+// vermelho onde cresceu e azul onde caiu. Com `--folded` sai o texto
+// `pilha antes depois`, que o `flamegraph.pl` e o `inferno-flamegraph` também
+// leem.
+//
+// O valor comparado é o `--sample-index`-ésimo de cada amostra; o padrão é o
+// último, como no `go tool pprof` (em perfis de CPU, o tempo). Os dois perfis
+// precisam ter o mesmo tipo de amostra. `--normalize` escala o "antes" para o
+// mesmo total do "depois", para comparar perfis de durações diferentes.
+//
+// O resumo (totais e as funções cujo tempo próprio mais mudou) sai como uma
+// linha JSON no stderr. Este repositório ainda não expõe um endpoint de
+// profiling; os perfis vêm de qualquer ferramenta que escreva pprof.
+//
+// Do protobuf só lemos o que o flamegraph precisa: tipos de amostra,
+// amostras, locations (com as linhas inline), funções e a tabela de strings.
+
+use std::{collections::HashMap, io::Read};
+
+// ------------
+// Leitor mínimo de protobuf
+// ------------
+enum Wire<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+    Fixed,
+}
+
+struct Message<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Message<'a> {
+    fn varint(&mut self) -> Result<u64, String> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let (&byte, rest) = self.data.split_first().ok_or("truncated varint")?;
+            self.data = rest;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err("varint too long".to_string())
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        if len > self.data.len() {
+            return Err("truncated field".to_string());
+        }
+        let (head, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(head)
+    }
+
+    /// Próximo campo: número e valor.
+    fn next_field(&mut self) -> Result<Option<(u64, Wire<'a>)>, String> {
+        if self.data.is_empty() {
+            return Ok(None);
+        }
+        let key = self.varint()?;
+        let wire = match key & 7 {
+            0 => Wire::Varint(self.varint()?),
+            1 => {
+                self.take(8)?;
+                Wire::Fixed
+            }
+            2 => {
+                let len = self.varint()? as usize;
+                Wire::Bytes(self.take(len)?)
+            }
+            5 => {
+                self.take(4)?;
+                Wire::Fixed
+            }
+            other => return Err(format!("unsupported wire type {}", other)),
+        };
+        Ok(Some((key >> 3, wire)))
+    }
+}
+
+fn fields(data: &[u8], mut each: impl FnMut(u64, Wire) -> Result<(), String>) -> Result<(), String> {
+    let mut message = Message { data };
+    while let Some((number, wire)) = message.next_field()? {
+        each(number, wire)?;
+    }
+    Ok(())
+}
+
+/// Campo `repeated` de inteiros, empacotado ou não.
+fn push_varints(wire: Wire, values: &mut Vec<u64>) -> Result<(), String> {
+    match wire {
+        Wire::Varint(value) => values.push(value),
+        Wire::Bytes(data) => {
+            let mut packed = Message { data };
+            while !packed.data.is_empty() {
+                values.push(packed.varint()?);
+            }
+        }
+        Wire::Fixed => return Err("unexpected fixed-width field".to_string()),
+    }
+    Ok(())
+}
+
+// ------------
+// Perfil
+// ------------
+struct Profile {
+    sample_types: Vec<String>,
+    /// Pilha (da raiz para a folha, separada por `;`) → valor por tipo.
+    stacks: HashMap<String, Vec<i64>>,
+}
+
+fn decode(data: &[u8]) -> Result<Profile, String> {
+    let mut strings = Vec::new();
+    let mut sample_types = Vec::new();
+    let mut samples = Vec::new();
+    // location → ids das funções, da mais interna (inline) para fora
+    let mut locations: HashMap<u64, (Vec<u64>, u64)> = HashMap::new();
+    let mut functions: HashMap<u64, u64> = HashMap::new();
+
+    fields(data, |number, wire| {
+        match (number, wire) {
+            (1, Wire::Bytes(data)) => {
+                let mut kind = 0;
+                fields(data, |number, wire| {
+                    if let (1, Wire::Varint(value)) = (number, wire) {
+                        kind = value;
+                    }
+                    Ok(())
+                })?;
+                sample_types.push(kind);
+            }
+            (2, Wire::Bytes(data)) => {
+                let (mut location_ids, mut values) = (Vec::new(), Vec::new());
+                fields(data, |number, wire| match number {
+                    1 => push_varints(wire, &mut location_ids),
+                    2 => push_varints(wire, &mut values),
+                    _ => Ok(()),
+                })?;
+                samples.push((location_ids, values));
+            }
+            (4, Wire::Bytes(data)) => {
+                let (mut id, mut address, mut lines) = (0, 0, Vec::new());
+                fields(data, |number, wire| {
+                    match (number, wire) {
+                        (1, Wire::Varint(value)) => id = value,
+                        (3, Wire::Varint(value)) => address = value,
+                        (4, Wire::Bytes(data)) => fields(data, |number, wire| {
+                            if let (1, Wire::Varint(function)) = (number, wire) {
+                                lines.push(function);
+                            }
+                            Ok(())
+                        })?,
+                        _ => {}
+                    }
+                    Ok(())
+                })?;
+                locations.insert(id, (lines, address));
+            }
+            (5, Wire::Bytes(data)) => {
+                let (mut id, mut name) = (0, 0);
+                fields(data, |number, wire| {
+                    match (number, wire) {
+                        (1, Wire::Varint(value)) => id = value,
+                        (2, Wire::Varint(value)) => name = value,
+                        _ => {}
+                    }
+                    Ok(())
+                })?;
+                functions.insert(id, name);
+            }
+            (6, Wire::Bytes(data)) => strings.push(String::from_utf8_lossy(data).into_owned()),
+            _ => {}
+        }
+        Ok(())
+    })?;
+
+    let string = |index: u64| strings.get(index as usize).cloned().unwrap_or_default();
+    let mut stacks: HashMap<String, Vec<i64>> = HashMap::new();
+    for (location_ids, values) in samples {
+        // O primeiro location é a folha; dentro de um location as linhas
+        // inline vêm antes de quem as chamou
+        let mut frames = Vec::new();
+        for id in location_ids.iter().rev() {
+            match locations.get(id) {
+                Some((lines, _)) if !lines.is_empty() => {
+                    frames.extend(lines.iter().rev().map(|f| functions.get(f).map_or_else(String::new, |n| string(*n))))
+                }
+                Some((_, address)) => frames.push(format!("{:#x}", address)),
+                None => frames.push("[unknown]".to_string()),
+            }
+        }
+        let stack = frames.iter().map(|f| if f.is_empty() { "[unknown]" } else { f }).collect::<Vec<_>>().join(";");
+        let entry = stacks.entry(stack).or_insert_with(|| vec![0; values.len()]);
+        for (total, value) in entry.iter_mut().zip(&values) {
+            *total += *value as i64;
+        }
+    }
+    Ok(Profile { sample_types: sample_types.into_iter().map(string).collect(), stacks })
+}
+
+fn load(path: &str) -> Result<Profile, String> {
+    let raw = std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let data = if raw.starts_with(&[0x1f, 0x8b]) {
+        let mut data = Vec::new();
+        flate2::read::GzDecoder::new(raw.as_slice())
+            .read_to_end(&mut data)
+            .map_err(|e| format!("Failed to gunzip {}: {}", path, e))?;
+        data
+    } else {
+        raw
+    };
+    decode(&data).map_err(|e| format!("{} is not a valid pprof profile: {}", path, e))
+}
+
+// ------------
+// Comparação
+// ------------
+const TOP_FUNCTIONS: usize = 10;
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    let usage = "usage: demo-lambda-axum profdiff <before> <after> [-o FILE] [--sample-index N] [--normalize] [--folded]";
+    let mut files = Vec::new();
+    let mut output = "profdiff.svg".to_string();
+    let mut sample_index = None;
+    let mut normalize = false;
+    let mut folded = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-o" | "--output" => output = args.next().ok_or("-o needs a file")?.clone(),
+            "--sample-index" => {
+                sample_index = Some(args.next().and_then(|v| v.parse().ok()).ok_or("--sample-index needs a number")?)
+            }
+            "--normalize" => normalize = true,
+            "--folded" => folded = true,
+            _ if files.len() < 2 => files.push(arg.clone()),
+            _ => return Err(format!("Unexpected argument: {}", arg)),
+        }
+    }
+    let [before_path, after_path] = <[String; 2]>::try_from(files).map_err(|_| usage)?;
+    let (before, after) = (load(&before_path)?, load(&after_path)?);
+    if before.sample_types != after.sample_types {
+        return Err(format!(
+            "Profiles have different sample types: {:?} and {:?}",
+            before.sample_types, after.sample_types
+        ));
+    }
+    let index = sample_index.unwrap_or(after.sample_types.len().saturating_sub(1));
+    let Some(sample_type) = after.sample_types.get(index) else {
+        return Err(format!("--sample-index must be below {}", after.sample_types.len()));
+    };
+
+    let value = |profile: &Profile, stack: &str| profile.stacks.get(stack).and_then(|v| v.get(index)).copied().unwrap_or(0);
+    let total = |profile: &Profile| profile.stacks.values().filter_map(|v| v.get(index)).sum::<i64>();
+    let (before_total, after_total) = (total(&before), total(&after));
+    let scale = if normalize && before_total > 0 { after_total as f64 / before_total as f64 } else { 1.0 };
+
+    let mut stacks: Vec<&String> = before.stacks.keys().chain(after.stacks.keys()).collect();
+    stacks.sort();
+    stacks.dedup();
+    let lines: Vec<String> = stacks
+        .iter()
+        .map(|stack| {
+            let old = (value(&before, stack) as f64 * scale).round() as i64;
+            format!("{} {} {}", stack, old, value(&after, stack))
+        })
+        .collect();
+
+    if folded {
+        std::fs::write(&output, lines.join("\n") + "\n").map_err(|e| format!("Failed to write {}: {}", output, e))?;
+    } else {
+        let mut options = inferno::flamegraph::Options::default();
+        options.title = "Differential flame graph".to_string();
+        options.subtitle = Some(format!("{} → {} ({})", before_path, after_path, sample_type));
+        options.count_name = sample_type.clone();
+        let file = std::fs::File::create(&output).map_err(|e| format!("Failed to create {}: {}", output, e))?;
+        inferno::flamegraph::from_lines(&mut options, lines.iter().map(String::as_str), std::io::BufWriter::new(file))
+            .map_err(|e| format!("Failed to render the flame graph: {}", e))?;
+    }
+
+    // Tempo próprio (folha) por função, para o resumo
+    let mut self_values: HashMap<&str, (f64, i64)> = HashMap::new();
+    for stack in &stacks {
+        let leaf = stack.rsplit(';').next().unwrap_or(stack);
+        let entry = self_values.entry(leaf).or_default();
+        entry.0 += value(&before, stack) as f64 * scale;
+        entry.1 += value(&after, stack);
+    }
+    let mut changes: Vec<_> = self_values.into_iter().collect();
+    changes.sort_by(|a, b| {
+        let delta = |(before, after): (f64, i64)| (after as f64 - before).abs();
+        delta(b.1).total_cmp(&delta(a.1)).then(a.0.cmp(b.0))
+    });
+    let functions: Vec<_> = changes
+        .into_iter()
+        .take(TOP_FUNCTIONS)
+        .map(|(function, (before, after))| {
+            serde_json::json!({ "function": function, "before": before.round(), "after": after, "delta": after as f64 - before.round() })
+        })
+        .collect();
+    let summary = serde_json::json!({
+        "event": "profdiff",
+        "sample_type": sample_type,
+        "before_total": before_total,
+        "after_total": after_total,
+        "normalized": normalize,
+        "stacks": stacks.len(),
+        "output": output,
+        "top_self_changes": functions,
+    });
+    eprintln!("{}", summary);
+    Ok(())
+}
@@ -0,0 +1,83 @@
+// ======================
+// PROGRESSO EM FATIAS DOS WORKLOADS LONGOS
+// ======================
+//
+// Um tempo total alto não diz se o workload foi lento por igual ou se parou no
+// meio (throttling da Lambda, CPU roubada por um vizinho). Os laços de
+// iterações marcam o relógio a cada `BFF_PROGRESS_SLICES` avos do trabalho
+// (padrão 10, então a cada 10%) e, se o laço passou de
+// `BFF_PROGRESS_THRESHOLD_MS`, a resposta ganha `progress` com o tempo de cada
+// fatia. Fatias parecidas: lentidão uniforme; uma fatia muito maior que as
+// outras: uma parada.
+//
+// Marcar custa uma multiplicação e uma comparação por iteração e no máximo
+// uma leitura do relógio por fatia, então fica ligado sempre; o limiar só
+// decide se o resultado aparece.
+
+use std::time::{Duration, Instant};
+
+use crate::config::CONFIG;
+
+pub struct Progress {
+    start: Instant,
+    total: u64,
+    slices: u64,
+    next: u64,
+    marks: Vec<(u64, Duration)>,
+}
+
+impl Progress {
+    /// `total` é o número de iterações que o laço vai fazer.
+    pub fn new(total: u64) -> Self {
+        let slices = CONFIG.progress_slices.min(total.max(1));
+        Progress { start: Instant::now(), total, slices, next: 1, marks: Vec::with_capacity(slices as usize) }
+    }
+
+    /// Chamado com o número de iterações já concluídas.
+    #[inline]
+    pub fn tick(&mut self, done: u64) {
+        while self.next <= self.slices && done * self.slices >= self.next * self.total {
+            self.marks.push((done, self.start.elapsed()));
+            self.next += 1;
+        }
+    }
+
+    /// As fatias, se o laço passou do limiar.
+    pub fn report(&self) -> Option<serde_json::Value> {
+        let &(_, total) = self.marks.last()?;
+        if total < CONFIG.progress_threshold {
+            return None;
+        }
+        let mut previous = Duration::ZERO;
+        let durations: Vec<f64> = self
+            .marks
+            .iter()
+            .map(|(_, elapsed)| {
+                let slice = *elapsed - previous;
+                previous = *elapsed;
+                slice.as_secs_f64() * 1000.0
+            })
+            .collect();
+        let slices: Vec<_> = self
+            .marks
+            .iter()
+            .zip(&durations)
+            .map(|((done, elapsed), slice_ms)| {
+                serde_json::json!({
+                    "iterations": done,
+                    "percent": *done as f64 * 100.0 / self.total as f64,
+                    "elapsed_ms": elapsed.as_secs_f64() * 1000.0,
+                    "slice_ms": slice_ms,
+                })
+            })
+            .collect();
+        let min = durations.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max = durations.iter().cloned().fold(0.0, f64::max);
+        Some(serde_json::json!({
+            "slices": slices,
+            "min_slice_ms": min,
+            "max_slice_ms": max,
+            "max_over_min": if min > 0.0 { Some(max / min) } else { None },
+        }))
+    }
+}
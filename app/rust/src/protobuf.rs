@@ -0,0 +1,158 @@
+// ======================
+// IDA E VOLTA POR PROTOBUF
+// ======================
+//
+// Muito BFF fala JSON com o cliente e protobuf com o backend. Aqui o
+// documento de pedidos do /json/parse (`{"orders": [...]}`, em `document` ou
+// gerado a partir da seed com `orders` pedidos) é lido do JSON para as
+// mensagens de order.proto, codificado em protobuf e decodificado de volta,
+// `iterations` vezes. Devolve os tamanhos em JSON e em protobuf e os tempos
+// de cada etapa; `round_trip` confere que a lista decodificada é igual à
+// lida do JSON.
+//
+// As structs abaixo são as que o prost-build gera para order.proto, escritas
+// à mão para a build não depender do protoc; com elas o JSON entra direto
+// pelo serde. Campos ausentes no JSON ficam com o padrão do proto3.
+
+use std::time::{Duration, Instant};
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use prost::Message;
+use serde::Deserialize;
+
+use crate::{jsonparse, rng};
+
+const MAX_ORDERS: usize = 100_000;
+const MAX_ITERATIONS: usize = 10_000;
+/// Bytes de protobuf codificados no total (iterations x tamanho)
+const MAX_WORK: usize = 2_000_000_000;
+
+#[derive(Clone, PartialEq, Message, Deserialize)]
+#[serde(default)]
+pub struct Customer {
+    #[prost(uint64, tag = "1")]
+    pub id: u64,
+    #[prost(string, tag = "2")]
+    pub name: String,
+    #[prost(string, tag = "3")]
+    pub email: String,
+}
+
+#[derive(Clone, PartialEq, Message, Deserialize)]
+#[serde(default)]
+pub struct Item {
+    #[prost(string, tag = "1")]
+    pub sku: String,
+    #[prost(uint32, tag = "2")]
+    pub quantity: u32,
+    #[prost(double, tag = "3")]
+    pub price: f64,
+}
+
+#[derive(Clone, PartialEq, Message, Deserialize)]
+#[serde(default)]
+pub struct Order {
+    #[prost(uint64, tag = "1")]
+    pub id: u64,
+    #[prost(message, optional, tag = "2")]
+    pub customer: Option<Customer>,
+    #[prost(string, tag = "3")]
+    pub status: String,
+    #[prost(string, tag = "4")]
+    pub created_at: String,
+    #[prost(message, repeated, tag = "5")]
+    pub items: Vec<Item>,
+    #[prost(string, repeated, tag = "6")]
+    pub tags: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, Message, Deserialize)]
+#[serde(default)]
+pub struct OrderList {
+    #[prost(message, repeated, tag = "1")]
+    pub orders: Vec<Order>,
+}
+
+#[derive(Deserialize)]
+pub struct ProtobufPayload {
+    document: Option<String>,
+    orders: Option<usize>,
+    seed: Option<u64>,
+    iterations: Option<usize>,
+}
+
+fn bad_request(error: String) -> Response<BoxBody> {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({ "error": error }))
+    )
+    .into_response()
+}
+
+fn ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+pub async fn protobuf(Json(payload): Json<ProtobufPayload>) -> Response<BoxBody> {
+    let iterations = payload.iterations.unwrap_or(1);
+    if iterations == 0 || iterations > MAX_ITERATIONS {
+        return bad_request(format!("iterations must be between 1 and {}", MAX_ITERATIONS));
+    }
+    let text = match payload.document {
+        Some(document) => document,
+        None => {
+            let count = payload.orders.unwrap_or(100);
+            if count > MAX_ORDERS {
+                return bad_request(format!("orders must be at most {}", MAX_ORDERS));
+            }
+            jsonparse::generate_document(count, rng::seed(payload.seed))
+        }
+    };
+
+    let start = Instant::now();
+    let list: OrderList = match serde_json::from_str(&text) {
+        Ok(list) => list,
+        Err(error) => return bad_request(format!("Invalid document: {}", error)),
+    };
+    let json_parse_duration = start.elapsed();
+    if list.encoded_len().saturating_mul(iterations) > MAX_WORK {
+        return bad_request("document size * iterations exceeds 2 GB".to_string());
+    }
+
+    let mut encode_duration = Duration::ZERO;
+    let mut decode_duration = Duration::ZERO;
+    let mut encoded = Vec::new();
+    let mut decoded = OrderList::default();
+    for _ in 0..iterations {
+        let start = Instant::now();
+        encoded = list.encode_to_vec();
+        encode_duration += start.elapsed();
+        let start = Instant::now();
+        decoded = OrderList::decode(encoded.as_slice()).expect("acabou de ser codificada");
+        decode_duration += start.elapsed();
+    }
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "orders": list.orders.len(),
+            "items": list.orders.iter().map(|order| order.items.len()).sum::<usize>(),
+            "iterations": iterations,
+            "json_bytes": text.len(),
+            "protobuf_bytes": encoded.len(),
+            "size_ratio": encoded.len() as f64 / text.len().max(1) as f64,
+            "round_trip": decoded == list,
+            "json_parse_ms": ms(json_parse_duration),
+            "encode_ms": ms(encode_duration),
+            "decode_ms": ms(decode_duration),
+            "encode_ms_per_iteration": ms(encode_duration) / iterations as f64,
+            "decode_ms_per_iteration": ms(decode_duration) / iterations as f64,
+        }))
+    )
+    .into_response()
+}
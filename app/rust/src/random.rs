@@ -0,0 +1,131 @@
+// ======================
+// DADOS ALEATÓRIOS
+// ======================
+//
+// Devolve `count` bytes aleatórios (`kind: "bytes"`, em base64) ou `count`
+// inteiros (`kind: "integers"`, em [0, `bound`) se houver `bound`). A fonte é
+// `source`:
+//
+// - `prng` (padrão): SplitMix64 com a seed (rng.rs), reproduzível entre as
+//   linguagens. Os bytes são os u64 gerados em little-endian, em sequência
+// - `os`: o gerador do sistema (getrandom), o que um serviço usaria para
+//   chaves e tokens; a seed é ignorada
+//
+// Só a geração entra no tempo, não a codificação da resposta.
+
+use std::time::Instant;
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use base64::{engine::general_purpose, Engine as _};
+use rand_core::{OsRng, RngCore};
+use serde::Deserialize;
+
+use crate::{
+    estimate::{self, Estimate},
+    rng::{self, SplitMix64},
+};
+
+const MAX_BYTES: usize = 16 * 1024 * 1024;
+const MAX_INTEGERS: usize = 1_000_000;
+
+#[derive(Deserialize)]
+pub struct RandomPayload {
+    kind: Option<String>,
+    count: Option<usize>,
+    source: Option<String>,
+    bound: Option<u64>,
+    seed: Option<u64>,
+    dry_run: Option<bool>,
+}
+
+fn bad_request(error: String) -> Response<BoxBody> {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({ "error": error }))
+    )
+    .into_response()
+}
+
+// Genérico na fonte para o gerador não passar por uma chamada dinâmica a cada
+// u64, o que pesaria mais que o próprio SplitMix64
+fn generate(kind: &str, count: usize, bound: Option<u64>, mut next: impl FnMut() -> u64) -> serde_json::Value {
+    let start = Instant::now();
+    if kind == "bytes" {
+        let mut bytes = Vec::with_capacity(count + 8);
+        while bytes.len() < count {
+            bytes.extend_from_slice(&next().to_le_bytes());
+        }
+        bytes.truncate(count);
+        let elapsed = start.elapsed();
+        return serde_json::json!({
+            "data": general_purpose::STANDARD.encode(&bytes),
+            "generate_ms": elapsed.as_secs_f64() * 1000.0,
+            "bytes_per_second": count as f64 / elapsed.as_secs_f64(),
+        });
+    }
+    let values: Vec<u64> = match bound {
+        Some(bound) => (0..count).map(|_| next() % bound).collect(),
+        None => (0..count).map(|_| next()).collect(),
+    };
+    let elapsed = start.elapsed();
+    serde_json::json!({
+        "values": values,
+        "bound": bound,
+        "generate_ms": elapsed.as_secs_f64() * 1000.0,
+        "values_per_second": count as f64 / elapsed.as_secs_f64(),
+    })
+}
+
+pub async fn random(Json(payload): Json<RandomPayload>) -> Response<BoxBody> {
+    let kind = payload.kind.unwrap_or_else(|| "bytes".to_string());
+    let max = match kind.as_str() {
+        "bytes" => MAX_BYTES,
+        "integers" => MAX_INTEGERS,
+        other => return bad_request(format!("kind must be bytes or integers, got {}", other)),
+    };
+    let count = payload.count.unwrap_or(1024);
+    if count == 0 || count > max {
+        return bad_request(format!("count must be between 1 and {} for {}", max, kind));
+    }
+    if payload.bound == Some(0) {
+        return bad_request("bound must be positive".to_string());
+    }
+    let source = payload.source.unwrap_or_else(|| "prng".to_string());
+    if payload.dry_run.unwrap_or(false) {
+        let count = count as u64;
+        let (allocation_bytes, output_bytes) = if kind == "bytes" {
+            (count, count.div_ceil(3) * 4)
+        } else {
+            // Até 20 dígitos e a vírgula por inteiro
+            (count * 8, count * 21)
+        };
+        return estimate::response(Estimate {
+            iterations: count,
+            unit: if kind == "bytes" { "bytes" } else { "integers" },
+            allocation_bytes,
+            output_bytes: 200 + output_bytes,
+        });
+    }
+    let seed = rng::seed(payload.seed);
+    let mut body = match source.as_str() {
+        "prng" => {
+            let mut prng = SplitMix64::new(seed);
+            generate(&kind, count, payload.bound, || prng.next_u64())
+        }
+        "os" => generate(&kind, count, payload.bound, || OsRng.next_u64()),
+        other => return bad_request(format!("source must be prng or os, got {}", other)),
+    };
+
+    body["kind"] = serde_json::json!(kind);
+    body["source"] = serde_json::json!(source);
+    body["count"] = serde_json::json!(count);
+    if source == "prng" {
+        body["seed"] = serde_json::json!(seed);
+    }
+    (StatusCode::OK, Json(body)).into_response()
+}
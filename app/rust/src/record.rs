@@ -0,0 +1,227 @@
+// ======================
+// MIDDLEWARE: GRAVAÇÃO DE REQUESTS
+// ======================
+//
+// Com `BFF_RECORD_FILE` (e/ou `BFF_RECORD_S3_BUCKET`, feature "aws"), cada
+// request que chega vira uma linha JSON com método, path (com query),
+// Content-Type, corpo, `X-Benchmark-Seed` (se veio) e o horário de parede de
+// chegada. O subcomando `replay` (replay.rs) reenvia o arquivo na mesma ordem
+// e com os mesmos intervalos, contra qualquer variante.
+//
+// O arquivo recebe uma linha por request, na hora. Para o S3 as linhas são
+// juntadas em lotes de `BFF_RECORD_S3_BATCH` e cada lote vira um objeto
+// `<prefixo><unix_ms>-<pid>-<seq>.jsonl`; na Lambda o lote que estiver
+// incompleto quando o ambiente é reciclado se perde.
+
+use std::{
+    fs::File,
+    future::Future,
+    io::Write,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    task::{Context, Poll},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use axum::{
+    body::{Body, BoxBody},
+    http::{header, Request, Response},
+};
+use once_cell::sync::Lazy;
+use tower::{Layer, Service};
+
+use crate::{body_limit, config::CONFIG};
+
+static RECORDED: AtomicU64 = AtomicU64::new(0);
+static FILE_ERRORS: AtomicU64 = AtomicU64::new(0);
+
+static FILE: Lazy<Mutex<Option<File>>> = Lazy::new(|| {
+    let file = std::fs::OpenOptions::new().create(true).append(true).open(&CONFIG.record_file);
+    Mutex::new(match file {
+        Ok(file) => Some(file),
+        Err(error) => {
+            eprintln!("não foi possível abrir {} para gravação: {}", CONFIG.record_file, error);
+            None
+        }
+    })
+});
+
+pub fn enabled() -> bool {
+    #[cfg(feature = "aws")]
+    if !CONFIG.record_s3_bucket.is_empty() {
+        return true;
+    }
+    !CONFIG.record_file.is_empty()
+}
+
+pub fn snapshot() -> serde_json::Value {
+    #[allow(unused_mut)]
+    let mut body = serde_json::json!({
+        "file": CONFIG.record_file,
+        "recorded": RECORDED.load(Ordering::Relaxed),
+        "file_errors": FILE_ERRORS.load(Ordering::Relaxed),
+    });
+    #[cfg(feature = "aws")]
+    {
+        body["s3"] = s3::snapshot();
+    }
+    body
+}
+
+/// Linha gravada; o corpo vai como texto quando é UTF-8 e em base64 quando não.
+fn line(req: &Request<Body>, body: &[u8]) -> String {
+    let mut entry = serde_json::json!({
+        "unix_ms": SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs_f64() * 1000.0,
+        "method": req.method().as_str(),
+        "path": req.uri().path_and_query().map(|p| p.as_str()).unwrap_or("/"),
+        "content_type": req.headers().get(header::CONTENT_TYPE).and_then(|v| v.to_str().ok()),
+    });
+    if let Some(seed) = req.headers().get("X-Benchmark-Seed").and_then(|v| v.to_str().ok()) {
+        entry["seed"] = serde_json::json!(seed);
+    }
+    crate::run::annotate(&mut entry);
+    match std::str::from_utf8(body) {
+        Ok(text) => entry["body"] = serde_json::json!(text),
+        Err(_) => {
+            use base64::{engine::general_purpose, Engine as _};
+            entry["body_base64"] = serde_json::json!(general_purpose::STANDARD.encode(body));
+        }
+    }
+    entry.to_string()
+}
+
+fn record(line: String) {
+    RECORDED.fetch_add(1, Ordering::Relaxed);
+    if !CONFIG.record_file.is_empty() {
+        let mut file = FILE.lock().unwrap();
+        let written = match file.as_mut() {
+            Some(file) => writeln!(file, "{}", line).is_ok(),
+            None => false,
+        };
+        if !written {
+            FILE_ERRORS.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+    #[cfg(feature = "aws")]
+    if !CONFIG.record_s3_bucket.is_empty() {
+        s3::push(line);
+    }
+}
+
+#[cfg(feature = "aws")]
+mod s3 {
+    use std::sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    };
+
+    use aws_sdk_s3::primitives::ByteStream;
+
+    use crate::{aws, config::CONFIG};
+
+    static BATCH: Mutex<Vec<String>> = Mutex::new(Vec::new());
+    static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+    static UPLOADS: AtomicU64 = AtomicU64::new(0);
+    static ERRORS: AtomicU64 = AtomicU64::new(0);
+
+    pub fn snapshot() -> serde_json::Value {
+        serde_json::json!({
+            "bucket": CONFIG.record_s3_bucket,
+            "prefix": CONFIG.record_s3_prefix,
+            "pending": BATCH.lock().unwrap().len(),
+            "uploads": UPLOADS.load(Ordering::Relaxed),
+            "errors": ERRORS.load(Ordering::Relaxed),
+        })
+    }
+
+    pub fn push(line: String) {
+        let lines = {
+            let mut batch = BATCH.lock().unwrap();
+            batch.push(line);
+            if batch.len() < CONFIG.record_s3_batch.max(1) {
+                return;
+            }
+            std::mem::take(&mut *batch)
+        };
+
+        let key = format!(
+            "{}{}-{}-{}.jsonl",
+            CONFIG.record_s3_prefix,
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis(),
+            std::process::id(),
+            SEQUENCE.fetch_add(1, Ordering::Relaxed)
+        );
+        tokio::spawn(async move {
+            let client = aws_sdk_s3::Client::new(aws::shared_config().await);
+            let body = lines.join("\n") + "\n";
+            let result = client
+                .put_object()
+                .bucket(&CONFIG.record_s3_bucket)
+                .key(&key)
+                .content_type("application/x-ndjson")
+                .body(ByteStream::from(body.into_bytes()))
+                .send()
+                .await;
+            match result {
+                Ok(_) => UPLOADS.fetch_add(1, Ordering::Relaxed),
+                Err(error) => {
+                    eprintln!("falha ao enviar {} para o S3: {}", key, aws::describe(error));
+                    ERRORS.fetch_add(1, Ordering::Relaxed)
+                }
+            };
+        });
+    }
+}
+
+#[derive(Clone)]
+pub struct RecordLayer;
+
+#[derive(Clone)]
+pub struct RecordService<S> {
+    inner: S,
+}
+
+impl<S> Layer<S> for RecordLayer {
+    type Service = RecordService<S>;
+    fn layer(&self, inner: S) -> Self::Service {
+        RecordService { inner }
+    }
+}
+
+impl<S> Service<Request<Body>> for RecordService<S>
+where
+    S: Service<Request<Body>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<BoxBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let mut service = self.inner.clone();
+
+        if !enabled() {
+            return Box::pin(service.call(req));
+        }
+
+        Box::pin(async move {
+            let (parts, bytes) = match body_limit::read(req).await {
+                Ok(read) => read,
+                Err(response) => return Ok(response),
+            };
+            let req = Request::from_parts(parts, Body::from(bytes.clone()));
+            record(line(&req, &bytes));
+            service.call(req).await
+        })
+    }
+}
@@ -0,0 +1,113 @@
+// ======================
+// REGIÃO E ZONA DE DISPONIBILIDADE
+// ======================
+//
+// Numa execução em várias regiões, cada resultado precisa dizer de onde veio
+// sem anotação manual. A região sai de `BFF_REGION` ou, sem ela, de
+// `AWS_REGION`/`AWS_DEFAULT_REGION`, que a Lambda e o ECS já definem. A zona
+// sai de `BFF_AVAILABILITY_ZONE` ou, no ECS, do endpoint de metadados da task
+// (`ECS_CONTAINER_METADATA_URI_V4`), lido uma vez na subida do servidor. A
+// Lambda não expõe a zona em lugar nenhum; lá ela fica de fora.
+//
+// Quando há algum valor, ele vai como `"region": {...}` em tudo que leva os
+// metadados da execução (run.rs faz as duas anotações juntas), como headers
+// `X-Region` e `X-Availability-Zone` em toda resposta e como dimensões das
+// métricas do CloudWatch. Como os metadados da execução, não entra no
+// `X-Config-Hash`: a mesma configuração em duas regiões dá o mesmo hash.
+
+use axum::http::{HeaderMap, HeaderValue};
+use once_cell::sync::{Lazy, OnceCell};
+use serde::Serialize;
+
+use crate::config::CONFIG;
+
+pub const ENV_VARS: [&str; 2] = ["BFF_REGION", "BFF_AVAILABILITY_ZONE"];
+pub const HEADERS: [&str; 2] = ["X-Region", "X-Availability-Zone"];
+
+#[derive(Clone, Default, PartialEq, Eq, Hash, Serialize)]
+pub struct Region {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub availability_zone: Option<String>,
+}
+
+impl Region {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.availability_zone.is_none()
+    }
+
+    /// Os campos na ordem de `HEADERS`.
+    pub fn values(&self) -> [Option<&str>; 2] {
+        [self.name.as_deref(), self.availability_zone.as_deref()]
+    }
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+static FROM_ENV: Lazy<Region> = Lazy::new(|| Region {
+    name: non_empty(&CONFIG.region),
+    availability_zone: non_empty(&CONFIG.availability_zone),
+});
+
+static DISCOVERED: OnceCell<Region> = OnceCell::new();
+
+/// Região em vigor: a descoberta nos metadados, se houve, senão a do ambiente.
+pub fn current() -> &'static Region {
+    DISCOVERED.get().unwrap_or(&FROM_ENV)
+}
+
+/// Acrescenta `"region"` a um objeto JSON, se houver algum valor.
+pub fn annotate(value: &mut serde_json::Value) {
+    let region = current();
+    if !region.is_empty() && value.is_object() {
+        value["region"] = serde_json::json!(region);
+    }
+}
+
+/// Marca a resposta com `X-Region` e `X-Availability-Zone`.
+pub fn tag(headers: &mut HeaderMap) {
+    for (name, value) in HEADERS.iter().zip(current().values()) {
+        if let Some(value) = value.and_then(|v| HeaderValue::from_str(v).ok()) {
+            headers.insert(*name, value);
+        }
+    }
+}
+
+/// Lê a zona dos metadados da task do ECS, se o processo roda lá e ela não
+/// veio do ambiente. Falhas só deixam a zona de fora.
+#[cfg(not(feature = "lambda"))]
+pub async fn discover() {
+    if DISCOVERED.get().is_some() || FROM_ENV.availability_zone.is_some() {
+        return;
+    }
+    let Ok(base) = std::env::var("ECS_CONTAINER_METADATA_URI_V4") else {
+        return;
+    };
+    let Ok(uri) = format!("{}/task", base.trim_end_matches('/')).parse() else {
+        return;
+    };
+    let result = tokio::time::timeout(std::time::Duration::from_secs(1), async {
+        let response = hyper::Client::new().get(uri).await?;
+        hyper::body::to_bytes(response.into_body()).await
+    })
+    .await;
+    let zone = match result {
+        Ok(Ok(bytes)) => serde_json::from_slice::<serde_json::Value>(&bytes)
+            .ok()
+            .and_then(|task| task["AvailabilityZone"].as_str().and_then(non_empty)),
+        Ok(Err(error)) => {
+            eprintln!("falha ao ler os metadados do ECS: {}", error);
+            None
+        }
+        Err(_) => {
+            eprintln!("timeout ao ler os metadados do ECS");
+            None
+        }
+    };
+    if let Some(zone) = zone {
+        let _ = DISCOVERED.set(Region { name: FROM_ENV.name.clone(), availability_zone: Some(zone) });
+    }
+}
@@ -0,0 +1,104 @@
+// ======================
+// REGISTRO DE WORKLOADS
+// ======================
+//
+// Lista dos workloads que podem ser chamados internamente (por exemplo pelo
+// /mixed), sem passar pelo router. Cada entrada embrulha o handler axum
+// original: desserializa o `serde_json::Value` no payload do handler e chama
+// a mesma função que a rota HTTP usa. A última coluna diz quais campos da
+// resposta entram no checksum de validação (validation.rs).
+
+use std::{future::Future, pin::Pin};
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+
+use crate::validation::Validation::{self, Fields, Gunzip};
+
+pub type WorkloadFuture = Pin<Box<dyn Future<Output = Response<BoxBody>> + Send>>;
+
+pub struct Workload {
+    pub name: &'static str,
+    pub run: fn(serde_json::Value) -> WorkloadFuture,
+    /// Payload usado quando quem chama não manda um (JSON).
+    pub default_payload: &'static str,
+    pub validation: Validation,
+}
+
+macro_rules! workload {
+    ($name:literal, $handler:path, $default:literal, $validation:expr) => {
+        Workload {
+            name: $name,
+            run: |value| {
+                Box::pin(async move {
+                    match serde_json::from_value(value) {
+                        Ok(payload) => crate::validation::attach(&$validation, $handler(Json(payload)).await).await,
+                        Err(error) => (
+                            StatusCode::BAD_REQUEST,
+                            Json(serde_json::json!({ "error": format!("Invalid payload: {}", error) }))
+                        )
+                        .into_response(),
+                    }
+                })
+            },
+            default_payload: $default,
+            validation: $validation,
+        }
+    };
+}
+
+pub static WORKLOADS: &[Workload] = &[
+    workload!("math", crate::math_operations, r#"{"numbers":[1,2,3,4,5],"operation":"sum"}"#, Fields(&["result"])),
+    workload!("json", crate::json_manipulation, r#"{"key":"name","value":"benchmark"}"#, Fields(&["json_data"])),
+    workload!("string", crate::string_processing, r#"{"text":"hello world, hello rust","pattern":"hel+o"}"#, Fields(&["matches"])),
+    workload!("compress", crate::compress_data, r#"{"text":"lorem ipsum dolor sit amet lorem ipsum dolor sit amet"}"#, Gunzip),
+    workload!("image", crate::image_processing, r#"{"text":"Hello, World!"}"#, Validation::None),
+    workload!("tokenize", crate::tokenize::tokenize, r#"{"text":"The quick brown fox jumps over the lazy dog. Tokenizers split text into subword units."}"#, Fields(&["byte_count", "token_count", "vocab_size"])),
+    workload!("vectors", crate::vectors::vectors, r#"{}"#, Fields(&["count", "dimensions", "results"])),
+    workload!("datetime", crate::datetime::datetime, r#"{}"#, Fields(&["conversions", "failed", "output_bytes", "parsed", "sample"])),
+    workload!("i18n", crate::i18n::i18n, r#"{}"#, Fields(&["formatted", "output_bytes", "sample"])),
+    workload!("decimal", crate::decimal::decimal, r#"{}"#, Fields(&["converted", "count", "gross", "net", "vat"])),
+    workload!("rules", crate::rules::rules, r#"{}"#, Fields(&["evaluations", "matched", "rules"])),
+    workload!("workflow", crate::workflow::workflow, r#"{}"#, Fields(&["completed_runs", "events", "final_state", "outcomes"])),
+    workload!("graph", crate::graph::graph, r#"{}"#, Fields(&["distance", "edges", "hops", "nodes", "reachable", "visited"])),
+    workload!("bloom", crate::bloom::bloom, r#"{}"#, Fields(&["bits", "false_negatives", "false_positives", "hashes", "items"])),
+    workload!("checksum", crate::checksum::checksum, r#"{}"#, Fields(&["iterations", "results.adler32.checksum", "results.crc32.checksum", "results.crc32c.checksum", "results.xxhash64.checksum"])),
+    workload!("hash", crate::hash::hash, r#"{"text":"hello world","iterations":1000}"#, Fields(&["algorithm", "digest", "iterations"])),
+    workload!("argon2", crate::password::argon2, r#"{"password":"correct horse battery staple"}"#, Fields(&["hash", "verified"])),
+    workload!("bcrypt", crate::password::bcrypt, r#"{"password":"correct horse battery staple"}"#, Fields(&["hash", "verified"])),
+    workload!("crypto/aes", crate::crypto::aes, r#"{}"#, Fields(&["bytes", "iterations", "verified"])),
+    workload!("crypto/sign", crate::crypto::sign, r#"{"message":"hello world"}"#, Fields(&["iterations", "verified"])),
+    workload!("jwt", crate::jwt::jwt, r#"{"action":"sign"}"#, Fields(&["action", "algorithm", "iterations"])),
+    workload!("uuid", crate::uuids::uuid, r#"{}"#, Fields(&["count", "serialized_bytes", "version"])),
+    workload!("random", crate::random::random, r#"{}"#, Fields(&["count", "data", "kind", "seed", "source", "values"])),
+    workload!("sort", crate::sort::sort, r#"{"size":10000}"#, Fields(&["max", "median", "min", "size", "type"])),
+    workload!("matrix", crate::matrix::matrix, r#"{"size":64}"#, Fields(&["size", "sum", "trace"])),
+    workload!("fft", crate::fft::fft, r#"{}"#, Fields(&["iterations", "length", "peaks"])),
+    workload!("primes", crate::primes::primes, r#"{"limit":100000}"#, Fields(&["count", "last", "limit"])),
+    workload!("bignum", crate::bignum::bignum, r#"{}"#, Fields(&["digits", "leading", "n", "operation", "trailing"])),
+    workload!("montecarlo", crate::montecarlo::montecarlo, r#"{}"#, Fields(&["inside", "pi", "samples", "tasks"])),
+    workload!("mandelbrot", crate::mandelbrot::mandelbrot, r#"{"width":160,"height":120}"#, Fields(&["height", "inside", "iterations", "width"])),
+    workload!("fuzzy", crate::fuzzy::fuzzy, r#"{"a":"kitten","b":"sitting"}"#, Fields(&["distance", "mode", "similarity"])),
+    workload!("csv", crate::csvparse::csvparse, r#"{}"#, Fields(&["aggregate", "columns", "matched", "result", "rows"])),
+    workload!("xml", crate::xmlparse::xmlparse, r#"{}"#, Fields(&["count", "elements", "result", "values"])),
+    workload!("convert", crate::convert::convert, r#"{}"#, Fields(&["from", "output", "to"])),
+    workload!("msgpack", crate::msgpack::msgpack, r#"{}"#, Fields(&["iterations", "json_bytes", "msgpack_bytes", "round_trip"])),
+    workload!("protobuf", crate::protobuf::protobuf, r#"{}"#, Fields(&["items", "json_bytes", "orders", "protobuf_bytes", "round_trip"])),
+    workload!("cbor", crate::cbor::cbor, r#"{"id":48213,"status":"paid","items":[{"sku":"SKU-00417","quantity":2,"price":19.9}],"tags":["gift"],"note":null}"#, Fields(&["cbor_bytes", "json_bytes", "round_trip"])),
+    workload!("avro", crate::avro::avro, r#"{}"#, Fields(&["bytes", "records", "round_trip"])),
+    workload!("sanitize", crate::sanitize::sanitize, r#"{}"#, Fields(&["output", "output_bytes"])),
+    workload!("binparse", crate::binparse::binparse, r#"{}"#, Fields(&["bytes", "invalid", "max_timestamp", "min_timestamp", "totals", "valid"])),
+    workload!("eventsource", crate::eventsource::eventsource, r#"{}"#, Fields(&["accounts", "applied", "events", "open_accounts", "rejected", "total_balance"])),
+    workload!("burn", crate::burn::burn, r#"{"duration_ms":10}"#, Fields(&["requested_ms"])),
+    workload!("sigv4", crate::sigv4::sigv4, r#"{"count":1000}"#, Fields(&["count"])),
+    workload!("json/parse", crate::jsonparse::jsonparse, r#"{}"#, Fields(&["amount", "items", "orders", "quantity"])),
+    workload!("json/patch", crate::jsonpatch::jsonpatch, r#"{}"#, Fields(&["operations", "result"])),
+];
+
+pub fn find(name: &str) -> Option<&'static Workload> {
+    WORKLOADS.iter().find(|workload| workload.name == name)
+}
@@ -0,0 +1,218 @@
+// ======================
+// CONFIG REMOTA: SSM PARAMETER STORE / SECRETS MANAGER (feature "aws")
+// ======================
+//
+// Busca os parâmetros de `BFF_SSM_PARAMETERS` e os segredos de
+// `BFF_SECRET_IDS` e guarda em memória por `BFF_REMOTE_CONFIG_TTL_SECS`. A
+// primeira carga acontece no startup (entra no cold start, como num BFF real);
+// depois disso, cada request passa pelo `RemoteConfigLayer`, que recarrega se
+// o cache venceu e responde com `X-Config-Source: cache` ou `remote`.
+//
+// O cache fica atrás de um mutex assíncrono: se várias requests encontram o
+// cache vencido ao mesmo tempo, só a primeira busca, as outras esperam por ela.
+// Os valores nunca saem do processo; o /stats só mostra nomes, contagens e tempos.
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    sync::atomic::{AtomicU64, Ordering},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use axum::http::{HeaderValue, Request, Response};
+use once_cell::sync::Lazy;
+use tower::{Layer, Service};
+
+use crate::{aws, config::CONFIG};
+
+// Depois de uma falha, espera esse tempo antes de buscar de novo, para não
+// pagar um timeout de rede em toda request
+const RETRY_BACKOFF: Duration = Duration::from_secs(30);
+
+struct Cached {
+    fetched_at: Instant,
+    values: HashMap<String, String>,
+}
+
+#[derive(Default)]
+struct State {
+    cached: Option<Cached>,
+    failed_at: Option<Instant>,
+}
+
+#[derive(Clone, Copy)]
+pub enum Source {
+    Cache,
+    Remote,
+    // Nada configurado (ou a busca falhou)
+    Unavailable,
+}
+
+impl Source {
+    fn as_str(self) -> &'static str {
+        match self {
+            Source::Cache => "cache",
+            Source::Remote => "remote",
+            Source::Unavailable => "unavailable",
+        }
+    }
+}
+
+static STATE: Lazy<tokio::sync::Mutex<State>> = Lazy::new(|| tokio::sync::Mutex::new(State::default()));
+
+static FETCHES: AtomicU64 = AtomicU64::new(0);
+static CACHE_HITS: AtomicU64 = AtomicU64::new(0);
+static ERRORS: AtomicU64 = AtomicU64::new(0);
+static LAST_FETCH_US: AtomicU64 = AtomicU64::new(0);
+
+pub fn snapshot() -> serde_json::Value {
+    // Só os nomes; se uma busca estiver em andamento, fica de fora
+    let loaded: Option<Vec<String>> = STATE
+        .try_lock()
+        .ok()
+        .and_then(|state| state.cached.as_ref().map(|c| c.values.keys().cloned().collect()));
+    serde_json::json!({
+        "ssm_parameters": CONFIG.ssm_parameters.len(),
+        "secrets": CONFIG.secret_ids.len(),
+        "loaded": loaded,
+        "fetches": FETCHES.load(Ordering::Relaxed),
+        "cache_hits": CACHE_HITS.load(Ordering::Relaxed),
+        "errors": ERRORS.load(Ordering::Relaxed),
+        "last_fetch_ms": LAST_FETCH_US.load(Ordering::Relaxed) as f64 / 1000.0,
+    })
+}
+
+fn enabled() -> bool {
+    !CONFIG.ssm_parameters.is_empty() || !CONFIG.secret_ids.is_empty()
+}
+
+async fn fetch() -> Result<HashMap<String, String>, String> {
+    let config = aws::shared_config().await;
+    let mut values = HashMap::new();
+
+    if !CONFIG.ssm_parameters.is_empty() {
+        let ssm = aws_sdk_ssm::Client::new(config);
+        // GetParameters aceita no máximo 10 nomes por chamada
+        for names in CONFIG.ssm_parameters.chunks(10) {
+            let output = ssm
+                .get_parameters()
+                .set_names(Some(names.to_vec()))
+                .with_decryption(true)
+                .send()
+                .await
+                .map_err(aws::describe)?;
+            if let Some(invalid) = output.invalid_parameters().first() {
+                return Err(format!("SSM parameter not found: {}", invalid));
+            }
+            for parameter in output.parameters() {
+                if let (Some(name), Some(value)) = (parameter.name(), parameter.value()) {
+                    values.insert(name.to_string(), value.to_string());
+                }
+            }
+        }
+    }
+
+    if !CONFIG.secret_ids.is_empty() {
+        let secrets = aws_sdk_secretsmanager::Client::new(config);
+        for id in &CONFIG.secret_ids {
+            let output = secrets
+                .get_secret_value()
+                .secret_id(id)
+                .send()
+                .await
+                .map_err(aws::describe)?;
+            if let Some(value) = output.secret_string() {
+                values.insert(id.clone(), value.to_string());
+            }
+        }
+    }
+
+    Ok(values)
+}
+
+/// Garante que a config está carregada e dentro do TTL, e diz de onde veio.
+pub async fn refresh() -> Source {
+    if !enabled() {
+        return Source::Unavailable;
+    }
+
+    let mut state = STATE.lock().await;
+    if state.cached.as_ref().is_some_and(|c| c.fetched_at.elapsed() < CONFIG.remote_config_ttl) {
+        CACHE_HITS.fetch_add(1, Ordering::Relaxed);
+        return Source::Cache;
+    }
+    // Em backoff depois de uma falha: serve o que tiver (mesmo vencido)
+    if state.failed_at.is_some_and(|t| t.elapsed() < RETRY_BACKOFF) {
+        return if state.cached.is_some() { Source::Cache } else { Source::Unavailable };
+    }
+
+    let start = Instant::now();
+    FETCHES.fetch_add(1, Ordering::Relaxed);
+    let result = fetch().await;
+    LAST_FETCH_US.store(start.elapsed().as_micros() as u64, Ordering::Relaxed);
+    match result {
+        Ok(values) => {
+            state.cached = Some(Cached { fetched_at: Instant::now(), values });
+            state.failed_at = None;
+            Source::Remote
+        }
+        Err(error) => {
+            ERRORS.fetch_add(1, Ordering::Relaxed);
+            eprintln!("remote config fetch failed: {}", error);
+            state.failed_at = Some(Instant::now());
+            if state.cached.is_some() {
+                Source::Cache
+            } else {
+                Source::Unavailable
+            }
+        }
+    }
+}
+
+// ------------
+// layer
+// ------------
+#[derive(Clone)]
+pub struct RemoteConfigLayer;
+
+#[derive(Clone)]
+pub struct RemoteConfigService<S> {
+    inner: S,
+}
+
+impl<S> Layer<S> for RemoteConfigLayer {
+    type Service = RemoteConfigService<S>;
+    fn layer(&self, inner: S) -> Self::Service {
+        RemoteConfigService { inner }
+    }
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for RemoteConfigService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+{
+    type Response = Response<ResBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        let mut service = self.inner.clone();
+
+        Box::pin(async move {
+            let source = refresh().await;
+            let mut response = service.call(req).await?;
+            response
+                .headers_mut()
+                .insert("X-Config-Source", HeaderValue::from_static(source.as_str()));
+            Ok(response)
+        })
+    }
+}
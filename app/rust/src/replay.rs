@@ -0,0 +1,184 @@
+// ======================
+// SUBCOMANDO replay
+// ======================
+//
+// `demo-lambda-axum replay <arquivo> [--target URL] [--speed N]` reenvia as
+// requests gravadas pelo record.rs, na ordem do horário de chegada e com os
+// mesmos intervalos entre elas (divididos por `--speed`; `--speed 0` manda
+// tudo de uma vez). Cada request sai no seu horário sem esperar as
+// anteriores terminarem, como no tráfego original.
+//
+// Cada resposta vira uma linha JSON no stdout (para comparar variantes) e o
+// resumo sai no stderr no fim, as duas com os metadados da execução do
+// ambiente (run.rs), que também seguem nos headers `X-Run-*` de cada request.
+// Só HTTP simples: para HTTPS, ponha um proxy na
+// frente ou rode contra a porta local.
+
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
+
+use hyper::{Body, Client, Method, Request};
+
+use crate::run;
+
+struct Entry {
+    unix_ms: f64,
+    method: Method,
+    path: String,
+    content_type: Option<String>,
+    seed: Option<String>,
+    body: Vec<u8>,
+}
+
+fn parse(line: &str) -> Result<Entry, String> {
+    use base64::{engine::general_purpose, Engine as _};
+
+    let value: serde_json::Value = serde_json::from_str(line).map_err(|e| e.to_string())?;
+    let text = |field: &str| value.get(field).and_then(|v| v.as_str());
+    let body = match (text("body"), text("body_base64")) {
+        (Some(body), _) => body.as_bytes().to_vec(),
+        (None, Some(encoded)) => general_purpose::STANDARD.decode(encoded).map_err(|e| e.to_string())?,
+        (None, None) => Vec::new(),
+    };
+    Ok(Entry {
+        unix_ms: value.get("unix_ms").and_then(|v| v.as_f64()).ok_or("missing unix_ms")?,
+        method: text("method").ok_or("missing method")?.parse().map_err(|_| "invalid method")?,
+        path: text("path").ok_or("missing path")?.to_string(),
+        content_type: text("content_type").map(String::from),
+        seed: text("seed").map(String::from),
+        body,
+    })
+}
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    let mut file = None;
+    let mut target = "http://127.0.0.1:3000".to_string();
+    let mut speed = 1.0;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--target" => target = args.next().ok_or("--target needs a URL")?.clone(),
+            "--speed" => {
+                speed = args
+                    .next()
+                    .and_then(|v| v.parse().ok())
+                    .filter(|s: &f64| s.is_finite() && *s >= 0.0)
+                    .ok_or("--speed needs a non-negative number")?
+            }
+            _ if file.is_none() => file = Some(arg.clone()),
+            _ => return Err(format!("Unexpected argument: {}", arg)),
+        }
+    }
+    let file = file.ok_or("usage: demo-lambda-axum replay <file> [--target URL] [--speed N]")?;
+
+    let content = std::fs::read_to_string(&file).map_err(|e| format!("Failed to read {}: {}", file, e))?;
+    let mut entries = content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| parse(line).map_err(|e| format!("{}:{}: {}", file, i + 1, e)))
+        .collect::<Result<Vec<_>, _>>()?;
+    // Arquivos de vários ambientes podem ter sido concatenados
+    entries.sort_by(|a, b| a.unix_ms.total_cmp(&b.unix_ms));
+
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(replay(entries, target.trim_end_matches('/').to_string(), speed));
+    Ok(())
+}
+
+async fn replay(entries: Vec<Entry>, target: String, speed: f64) {
+    let client = Client::new();
+    let first = entries.first().map(|e| e.unix_ms).unwrap_or_default();
+    let start = Instant::now();
+
+    let mut tasks = Vec::with_capacity(entries.len());
+    for (index, entry) in entries.into_iter().enumerate() {
+        let due = if speed == 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64((entry.unix_ms - first).max(0.0) / 1000.0 / speed)
+        };
+        tokio::time::sleep_until((start + due).into()).await;
+
+        let client = client.clone();
+        let run = run::current();
+        let uri = format!("{}{}", target, entry.path);
+        tasks.push(tokio::spawn(async move {
+            let lag_ms = start.elapsed().saturating_sub(due).as_secs_f64() * 1000.0;
+            let mut request = Request::builder().method(entry.method.clone()).uri(&uri);
+            if let Some(content_type) = &entry.content_type {
+                request = request.header("Content-Type", content_type);
+            }
+            if let Some(seed) = &entry.seed {
+                request = request.header("X-Benchmark-Seed", seed);
+            }
+            for (name, value) in run::HEADERS.iter().zip(run.values()) {
+                if let Some(value) = value {
+                    request = request.header(*name, value);
+                }
+            }
+            let sent = Instant::now();
+            let result = match request.body(Body::from(entry.body)) {
+                Ok(request) => client.request(request).await.map_err(|e| e.to_string()),
+                Err(e) => Err(e.to_string()),
+            };
+            // Lê o corpo inteiro: a latência é até o último byte
+            let status = match result {
+                Ok(response) => {
+                    let status = response.status().as_u16();
+                    hyper::body::to_bytes(response.into_body()).await.map(|_| status).map_err(|e| e.to_string())
+                }
+                Err(e) => Err(e),
+            };
+            let latency_ms = sent.elapsed().as_secs_f64() * 1000.0;
+
+            let mut line = serde_json::json!({
+                "index": index,
+                "method": entry.method.as_str(),
+                "path": entry.path,
+                "latency_ms": latency_ms,
+                "lag_ms": lag_ms,
+            });
+            match &status {
+                Ok(status) => line["status"] = serde_json::json!(status),
+                Err(error) => line["error"] = serde_json::json!(error),
+            }
+            run.annotate(&mut line);
+            println!("{}", line);
+            (status.ok(), latency_ms, lag_ms)
+        }));
+    }
+
+    let mut statuses: BTreeMap<String, u64> = BTreeMap::new();
+    let (mut errors, mut total_latency, mut max_latency, mut max_lag) = (0, 0.0, 0.0f64, 0.0f64);
+    let requests = tasks.len();
+    for task in tasks {
+        let Ok((status, latency_ms, lag_ms)) = task.await else { continue };
+        match status {
+            Some(status) => *statuses.entry(status.to_string()).or_default() += 1,
+            None => errors += 1,
+        }
+        total_latency += latency_ms;
+        max_latency = max_latency.max(latency_ms);
+        max_lag = max_lag.max(lag_ms);
+    }
+
+    let mut summary = serde_json::json!({
+        "target": target,
+        "speed": speed,
+        "requests": requests,
+        "errors": errors,
+        "statuses": statuses,
+        "duration_ms": start.elapsed().as_secs_f64() * 1000.0,
+        "mean_latency_ms": if requests == 0 { 0.0 } else { total_latency / requests as f64 },
+        "max_latency_ms": max_latency,
+        "max_lag_ms": max_lag,
+    });
+    run::annotate(&mut summary);
+    eprintln!("{}", summary);
+}
@@ -0,0 +1,311 @@
+// ======================
+// DOCUMENTO DE RESULTADOS (GET /results)
+// ======================
+//
+// O /stats muda de forma a cada subsistema novo, o que quebra os notebooks de
+// análise. O /results é o formato estável para exportar: um documento com
+// versão (`schema_version`, hoje 1), descrito pelo JSON Schema em
+// `results_schema.json` (servido em `/results/schema`), com os metadados da
+// execução, a região, o documento do /info e, por rota, um histograma da
+// duração do endpoint (a mesma de `X-Endpoint-Duration`) e, com o modelo de
+// custo (cost.rs), o custo estimado. Requests que não casam com nenhuma rota
+// ficam todas em `<unmatched>`: com o path cru, cada 404 com um path novo
+// virava um histograma a mais, para sempre.
+//
+// O histograma tem baldes fixos na série 1-2-5, de 0,05 ms a 50 s, mais um
+// balde aberto; só os baldes com contagem aparecem. Os percentis são o limite
+// superior do balde em que caem (limitados ao máximo), então têm a precisão
+// dos baldes. Mudança que quebre o formato sobe `schema_version`.
+//
+// Com `BFF_RESULTS_SECRET`, a resposta leva `X-Results-Signature`: HMAC-SHA256,
+// em hex, dos bytes exatos do corpo. Reserializar o documento do outro lado
+// não serve: o Python escreve `1.6e-08` onde o serde_json escreve `1.6e-8`, e
+// os preços do modelo de custo são todos números assim.
+
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, SystemTime},
+};
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    body::{boxed, Full},
+    extract::MatchedPath,
+    http::{header, HeaderValue, Request, Response, StatusCode},
+    response::IntoResponse,
+};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use once_cell::sync::Lazy;
+use sha2::Sha256;
+
+use crate::config::CONFIG;
+
+pub const SCHEMA_VERSION: u64 = 1;
+
+// Limites superiores dos baldes, em ms; o último balde não tem limite
+pub const BOUNDS_MS: [f64; 19] = [
+    0.05, 0.1, 0.2, 0.5, 1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0, 1000.0, 2000.0, 5000.0, 10000.0,
+    20000.0, 50000.0,
+];
+
+static SCHEMA: Lazy<serde_json::Value> =
+    Lazy::new(|| serde_json::from_str(include_str!("results_schema.json")).expect("results_schema.json inválido"));
+
+static STARTED_AT: Lazy<DateTime<Utc>> = Lazy::new(Utc::now);
+
+/// A última request com trace ID que caiu num balde (metrics.rs).
+#[derive(Clone)]
+pub struct Exemplar {
+    pub trace_id: String,
+    pub ms: f64,
+    pub at: SystemTime,
+}
+
+#[derive(Default)]
+pub struct Histogram {
+    pub requests: u64,
+    pub errors: u64,
+    pub sum_ms: f64,
+    cost_usd: f64,
+    min_ms: f64,
+    max_ms: f64,
+    pub buckets: [u64; BOUNDS_MS.len() + 1],
+    pub exemplars: [Option<Exemplar>; BOUNDS_MS.len() + 1],
+}
+
+impl Histogram {
+    /// Limite superior do balde do percentil `p` (0..1), limitado ao máximo.
+    fn percentile(&self, p: f64) -> f64 {
+        let rank = ((self.requests as f64 * p).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (i, count) in self.buckets.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return BOUNDS_MS.get(i).map_or(self.max_ms, |bound| bound.min(self.max_ms));
+            }
+        }
+        self.max_ms
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        let buckets: Vec<_> = self
+            .buckets
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(i, count)| serde_json::json!({ "le": BOUNDS_MS.get(i), "count": count }))
+            .collect();
+        let mut histogram = serde_json::json!({
+            "requests": self.requests,
+            "errors": self.errors,
+            "latency_ms": {
+                "min": self.min_ms,
+                "max": self.max_ms,
+                "mean": self.sum_ms / self.requests as f64,
+                "p50": self.percentile(0.5),
+                "p90": self.percentile(0.9),
+                "p99": self.percentile(0.99),
+                "buckets": buckets,
+            },
+        });
+        if crate::cost::MODEL.is_some() {
+            histogram["estimated_cost_usd"] = serde_json::json!({
+                "total": self.cost_usd,
+                "per_request": self.cost_usd / self.requests as f64,
+            });
+        }
+        histogram
+    }
+}
+
+static ROUTES: Lazy<Mutex<HashMap<String, Histogram>>> = Lazy::new(Default::default);
+
+/// Rota das requests que não casaram com nenhuma
+pub const UNMATCHED: &str = "<unmatched>";
+
+/// A rota da request, como é agregada nas métricas.
+pub fn route<B>(req: &Request<B>) -> String {
+    match req.extensions().get::<MatchedPath>() {
+        Some(path) => path.as_str().to_string(),
+        None => UNMATCHED.to_string(),
+    }
+}
+
+/// Conta uma request na rota (chamado pelo TimingLayer).
+pub fn record(route: &str, status: u16, duration: Duration, trace_id: Option<String>) {
+    Lazy::force(&STARTED_AT);
+    let ms = duration.as_secs_f64() * 1000.0;
+    let mut routes = ROUTES.lock().unwrap();
+    let histogram = match routes.get_mut(route) {
+        Some(histogram) => histogram,
+        None => routes.entry(route.to_string()).or_default(),
+    };
+    if histogram.requests == 0 || ms < histogram.min_ms {
+        histogram.min_ms = ms;
+    }
+    histogram.max_ms = histogram.max_ms.max(ms);
+    histogram.sum_ms += ms;
+    histogram.cost_usd += crate::cost::estimate(duration).unwrap_or(0.0);
+    histogram.requests += 1;
+    if status >= 500 {
+        histogram.errors += 1;
+    }
+    let bucket = BOUNDS_MS.iter().position(|bound| ms <= *bound).unwrap_or(BOUNDS_MS.len());
+    histogram.buckets[bucket] += 1;
+    if let Some(trace_id) = trace_id {
+        histogram.exemplars[bucket] = Some(Exemplar { trace_id, ms, at: SystemTime::now() });
+    }
+}
+
+/// Percorre os histogramas, em ordem de rota.
+pub fn visit(mut each: impl FnMut(&str, &Histogram)) {
+    let routes = ROUTES.lock().unwrap();
+    let mut names: Vec<&String> = routes.keys().collect();
+    names.sort();
+    for name in names {
+        each(name, &routes[name]);
+    }
+}
+
+/// HMAC-SHA256 em hex dos bytes do corpo.
+fn sign(body: &[u8], secret: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC aceita chave de qualquer tamanho");
+    mac.update(body);
+    mac.finalize().into_bytes().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// O corpo serializado e, com segredo, a assinatura dele.
+fn signed_body(document: &serde_json::Value, secret: &str) -> (Vec<u8>, Option<String>) {
+    let body = serde_json::to_vec(document).unwrap();
+    let signature = (!secret.is_empty()).then(|| sign(&body, secret));
+    (body, signature)
+}
+
+pub fn document() -> serde_json::Value {
+    let routes: serde_json::Map<String, serde_json::Value> = ROUTES
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(route, histogram)| (route.clone(), histogram.to_json()))
+        .collect();
+    serde_json::json!({
+        "schema": "bff-benchmark-results",
+        "schema_version": SCHEMA_VERSION,
+        "generated_at": Utc::now().to_rfc3339(),
+        "started_at": STARTED_AT.to_rfc3339(),
+        "run": crate::run::current(),
+        "region": crate::region::current(),
+        "environment": crate::info::document(),
+        "cost_model": crate::cost::snapshot(),
+        "routes": routes,
+    })
+}
+
+pub async fn results() -> Response<BoxBody> {
+    let (body, signature) = signed_body(&document(), &CONFIG.results_secret);
+    let mut response = Response::new(boxed(Full::from(body)));
+    response.headers_mut().insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    if let Some(signature) = signature {
+        response.headers_mut().insert("X-Results-Signature", HeaderValue::from_str(&signature).unwrap());
+    }
+    response
+}
+
+pub async fn schema() -> Response<BoxBody> {
+    (StatusCode::OK, Json(SCHEMA.clone())).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Documento com os números pequenos do modelo de custo, que quebravam a
+    // verificação por reserialização
+    fn cost_document() -> serde_json::Value {
+        serde_json::json!({
+            "schema": "bff-benchmark-results",
+            "schema_version": SCHEMA_VERSION,
+            "cost_model": {
+                "memory_mb": 1769,
+                "architecture": "x86_64",
+                "usd_per_gb_second": 0.000_016_666_7,
+                "usd_per_request": 0.000_000_2,
+                "pricing_region": "us-east-1",
+            },
+            "routes": {
+                "/math": {
+                    "requests": 3,
+                    "estimated_cost_usd": { "total": 0.000_000_016, "per_request": 0.000_05 },
+                },
+            },
+        })
+    }
+
+    #[test]
+    fn signature_covers_the_exact_body() {
+        let document = cost_document();
+        let (body, signature) = signed_body(&document, "secret");
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"secret").unwrap();
+        mac.update(&body);
+        mac.verify_slice(&hex_decode(&signature.unwrap())).expect("signature matches the bytes");
+
+        let parsed: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(parsed, document);
+        assert_eq!(parsed["cost_model"]["usd_per_gb_second"], 0.000_016_666_7);
+        assert_eq!(parsed["routes"]["/math"]["estimated_cost_usd"]["total"], 0.000_000_016);
+    }
+
+    #[test]
+    fn changed_body_fails_verification() {
+        let (body, signature) = signed_body(&cost_document(), "secret");
+        let text = String::from_utf8(body).unwrap();
+        assert!(text.contains("1.6e-8"));
+        let reformatted = text.replace("1.6e-8", "1.6e-08");
+        assert_ne!(sign(reformatted.as_bytes(), "secret"), signature.unwrap());
+    }
+
+    #[test]
+    fn no_secret_means_no_signature() {
+        let (body, signature) = signed_body(&cost_document(), "");
+        assert!(signature.is_none());
+        assert!(!body.is_empty());
+    }
+
+    fn histogram(latencies_ms: &[f64]) -> Histogram {
+        let mut histogram = Histogram::default();
+        for &ms in latencies_ms {
+            let bucket = BOUNDS_MS.iter().position(|bound| ms <= *bound).unwrap_or(BOUNDS_MS.len());
+            histogram.buckets[bucket] += 1;
+            histogram.requests += 1;
+            histogram.max_ms = histogram.max_ms.max(ms);
+        }
+        histogram
+    }
+
+    #[test]
+    fn percentile_is_the_bucket_bound_capped_at_the_max() {
+        assert_eq!(histogram(&[]).percentile(0.5), 0.0);
+        assert_eq!(histogram(&[3.0]).percentile(0.5), 3.0);
+
+        let latencies = histogram(&[0.3, 0.3, 0.3, 0.3, 0.7, 1.5, 1.5, 8.0, 40.0, 45.0]);
+        assert_eq!(latencies.percentile(0.0), 0.5);
+        assert_eq!(latencies.percentile(0.5), 1.0);
+        assert_eq!(latencies.percentile(0.6), 2.0);
+        assert_eq!(latencies.percentile(0.8), 10.0);
+        assert_eq!(latencies.percentile(0.99), 45.0);
+    }
+
+    #[test]
+    fn percentile_above_the_last_bound_is_the_max() {
+        assert_eq!(histogram(&[1.0, 80_000.0]).percentile(0.99), 80_000.0);
+        assert_eq!(histogram(&[1.0, 80_000.0]).percentile(0.5), 1.0);
+    }
+
+    fn hex_decode(hex: &str) -> Vec<u8> {
+        (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap()).collect()
+    }
+}
@@ -0,0 +1,127 @@
+// ======================
+// PRNG determinístico (SplitMix64)
+// ======================
+//
+// Usamos SplitMix64 em vez do crate `rand` porque o algoritmo cabe em poucas
+// linhas e é trivial de reproduzir nas versões Go e Python: com a mesma seed,
+// todas as linguagens geram exatamente os mesmos dados de entrada.
+//
+// A seed de cada workload vem do campo `seed` do payload, ou 42. O header
+// `X-Benchmark-Seed` tem prioridade sobre os dois e vale para a request
+// inteira (inclusive os workloads sorteados pelo /mixed), e volta na resposta:
+// reenviar o mesmo payload com o header devolvido reproduz a execução.
+
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{HeaderValue, Request, Response, StatusCode},
+    response::IntoResponse,
+};
+use tower::{Layer, Service};
+
+const SEED_HEADER: &str = "X-Benchmark-Seed";
+
+tokio::task_local! {
+    static REQUEST_SEED: u64;
+}
+
+/// Seed efetiva do workload: header da request, depois payload, depois 42.
+pub fn seed(payload: Option<u64>) -> u64 {
+    REQUEST_SEED.try_with(|seed| *seed).ok().or(payload).unwrap_or(42)
+}
+
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> Self {
+        SplitMix64 { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniforme em [0, 1) usando os 53 bits altos.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+
+    /// Uniforme em [-1, 1).
+    pub fn next_signed_f32(&mut self) -> f32 {
+        (self.next_f64() * 2.0 - 1.0) as f32
+    }
+
+    /// Inteiro em [0, bound). O viés do módulo é desprezível para os tamanhos
+    /// usados aqui e mantém a fórmula idêntica entre as linguagens.
+    pub fn next_below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+}
+
+// ------------
+// MIDDLEWARE: `X-Benchmark-Seed`
+// ------------
+#[derive(Clone)]
+pub struct SeedLayer;
+
+#[derive(Clone)]
+pub struct SeedService<S> {
+    inner: S,
+}
+
+impl<S> Layer<S> for SeedLayer {
+    type Service = SeedService<S>;
+    fn layer(&self, inner: S) -> Self::Service {
+        SeedService { inner }
+    }
+}
+
+impl<S, ReqBody> Service<Request<ReqBody>> for SeedService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+{
+    type Response = Response<BoxBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        let mut service = self.inner.clone();
+
+        let Some(header) = req.headers().get(SEED_HEADER) else {
+            return Box::pin(service.call(req));
+        };
+        let Some(seed) = header.to_str().ok().and_then(|v| v.trim().parse::<u64>().ok()) else {
+            return Box::pin(async {
+                Ok((
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({ "error": "X-Benchmark-Seed must be an unsigned 64-bit integer" }))
+                )
+                .into_response())
+            });
+        };
+
+        Box::pin(async move {
+            let mut response = REQUEST_SEED.scope(seed, service.call(req)).await?;
+            response.headers_mut().insert(SEED_HEADER, HeaderValue::from(seed));
+            Ok(response)
+        })
+    }
+}
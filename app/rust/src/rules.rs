@@ -0,0 +1,457 @@
+// ======================
+// MOTOR DE REGRAS
+// ======================
+//
+// Avalia expressões booleanas contra o documento `data` da request, com um
+// interpretador pequeno (tokenizer + parser Pratt). Exemplo de expressão:
+//
+//   order.total >= 100 && customer.country in ["BR", "PT"] && !customer.blocked
+//
+// Operadores: || && ! == != < <= > >= in + - * / e funções len, contains,
+// starts_with e lower. Caminhos com ponto navegam pelo JSON.
+
+use std::time::Instant;
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use serde_json::Value;
+
+const MAX_ITERATIONS: usize = 100_000;
+// Iterações × bytes de expressão: limita o custo de muitas regras (ou uma
+// regra gigante) repetidas muitas vezes
+const MAX_WORK: usize = 200_000_000;
+
+// Regras usadas quando a request não manda as suas
+const DEFAULT_RULES: &[(&str, &str)] = &[
+    ("free_shipping", "order.total >= 100 && customer.country in [\"BR\", \"PT\"]"),
+    ("vip_discount", "customer.tier == \"gold\" || customer.orders > 50"),
+    ("fraud_review", "order.total > 5000 || (customer.age < 18 && order.total > 500)"),
+    ("bulk_order", "len(order.items) >= 10"),
+    ("beta_checkout", "!customer.blocked && contains(customer.flags, \"beta\")"),
+    ("corporate_email", "!starts_with(lower(customer.email), \"test\") && contains(customer.email, \"@corp.\")"),
+    ("high_margin", "order.total - order.cost > order.total * 0.4"),
+];
+
+#[derive(Deserialize)]
+pub struct RuleDefinition {
+    name: String,
+    expression: String,
+}
+
+#[derive(Deserialize)]
+pub struct RulesPayload {
+    data: Option<Value>,
+    rules: Option<Vec<RuleDefinition>>,
+    iterations: Option<usize>,
+}
+
+// ------------
+// tokenizer
+// ------------
+#[derive(Clone, PartialEq, Debug)]
+enum Token {
+    Number(f64),
+    Str(String),
+    Ident(String),
+    Op(&'static str),
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            '[' => {
+                tokens.push(Token::LBracket);
+                i += 1;
+            }
+            ']' => {
+                tokens.push(Token::RBracket);
+                i += 1;
+            }
+            ',' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            '"' | '\'' => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|&ch| ch == c)
+                    .ok_or("Unterminated string")?;
+                tokens.push(Token::Str(chars[i + 1..i + 1 + end].iter().collect()));
+                i += end + 2;
+            }
+            c if c.is_ascii_digit() => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                tokens.push(Token::Number(text.parse().map_err(|_| format!("Invalid number: {}", text))?));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.') {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                tokens.push(if word == "in" { Token::Op("in") } else { Token::Ident(word) });
+            }
+            _ => {
+                let two: String = chars[i..(i + 2).min(chars.len())].iter().collect();
+                let op = ["||", "&&", "==", "!=", "<=", ">="]
+                    .into_iter()
+                    .find(|op| *op == two)
+                    .or_else(|| ["!", "<", ">", "+", "-", "*", "/"].into_iter().find(|op| op.starts_with(c)))
+                    .ok_or_else(|| format!("Unexpected character: {}", c))?;
+                tokens.push(Token::Op(op));
+                i += op.len();
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+// ------------
+// parser (Pratt)
+// ------------
+pub enum Expr {
+    Literal(Value),
+    Path(Vec<String>),
+    Array(Vec<Expr>),
+    Not(Box<Expr>),
+    Neg(Box<Expr>),
+    Binary(&'static str, Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+}
+
+fn binding_power(op: &str) -> u8 {
+    match op {
+        "||" => 1,
+        "&&" => 2,
+        "==" | "!=" => 3,
+        "<" | "<=" | ">" | ">=" | "in" => 4,
+        "+" | "-" => 5,
+        "*" | "/" => 6,
+        _ => 0,
+    }
+}
+
+// Limita a altura da árvore para uma expressão maliciosa não estourar a pilha
+// (no parse e no eval, que são recursivos)
+const MAX_DEPTH: usize = 64;
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), String> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            other => Err(format!("Expected {:?}, found {:?}", expected, other)),
+        }
+    }
+
+    fn list(&mut self, close: Token) -> Result<Vec<Expr>, String> {
+        let mut items = Vec::new();
+        if self.tokens.get(self.pos) == Some(&close) {
+            self.pos += 1;
+            return Ok(items);
+        }
+        loop {
+            items.push(self.expression(0)?);
+            match self.next() {
+                Some(Token::Comma) => continue,
+                Some(token) if token == close => return Ok(items),
+                other => return Err(format!("Expected , or {:?}, found {:?}", close, other)),
+            }
+        }
+    }
+
+    fn prefix(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Expr::Literal(Value::from(n))),
+            Some(Token::Str(s)) => Ok(Expr::Literal(Value::from(s))),
+            Some(Token::Op("!")) => Ok(Expr::Not(Box::new(self.expression(7)?))),
+            Some(Token::Op("-")) => Ok(Expr::Neg(Box::new(self.expression(7)?))),
+            Some(Token::LParen) => {
+                let expr = self.expression(0)?;
+                self.expect(Token::RParen)?;
+                Ok(expr)
+            }
+            Some(Token::LBracket) => Ok(Expr::Array(self.list(Token::RBracket)?)),
+            Some(Token::Ident(name)) => match name.as_str() {
+                "true" => Ok(Expr::Literal(Value::Bool(true))),
+                "false" => Ok(Expr::Literal(Value::Bool(false))),
+                "null" => Ok(Expr::Literal(Value::Null)),
+                _ if self.tokens.get(self.pos) == Some(&Token::LParen) => {
+                    self.pos += 1;
+                    Ok(Expr::Call(name, self.list(Token::RParen)?))
+                }
+                _ => Ok(Expr::Path(name.split('.').map(String::from).collect())),
+            },
+            other => Err(format!("Unexpected token: {:?}", other)),
+        }
+    }
+
+    fn expression(&mut self, min_power: u8) -> Result<Expr, String> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err("Expression nested too deeply".into());
+        }
+        let mut left = self.prefix()?;
+        // A cadeia associativa à esquerda cresce no loop, não na recursão:
+        // cada Binary montado aqui conta como mais um nível
+        let mut chained = 0;
+        while let Some(Token::Op(op)) = self.tokens.get(self.pos).cloned() {
+            let power = binding_power(op);
+            if power == 0 || power <= min_power {
+                break;
+            }
+            self.pos += 1;
+            chained += 1;
+            self.depth += 1;
+            if self.depth > MAX_DEPTH {
+                return Err("Expression nested too deeply".into());
+            }
+            let right = self.expression(power)?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+        self.depth -= chained + 1;
+        Ok(left)
+    }
+}
+
+pub fn parse(source: &str) -> Result<Expr, String> {
+    let mut parser = Parser { tokens: tokenize(source)?, pos: 0, depth: 0 };
+    let expr = parser.expression(0)?;
+    if parser.pos < parser.tokens.len() {
+        return Err(format!("Unexpected trailing token: {:?}", parser.tokens[parser.pos]));
+    }
+    Ok(expr)
+}
+
+// ------------
+// avaliação
+// ------------
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64() != Some(0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(a) => !a.is_empty(),
+        Value::Object(_) => true,
+    }
+}
+
+fn equals(a: &Value, b: &Value) -> bool {
+    match (a.as_f64(), b.as_f64()) {
+        (Some(x), Some(y)) => x == y,
+        _ => a == b,
+    }
+}
+
+fn number(value: &Value) -> Result<f64, String> {
+    value.as_f64().ok_or_else(|| format!("Expected a number, found {}", value))
+}
+
+fn eval(expr: &Expr, data: &Value) -> Result<Value, String> {
+    match expr {
+        Expr::Literal(value) => Ok(value.clone()),
+        Expr::Path(segments) => Ok(segments
+            .iter()
+            .try_fold(data, |value, segment| value.get(segment.as_str()))
+            .cloned()
+            .unwrap_or(Value::Null)),
+        Expr::Array(items) => items.iter().map(|item| eval(item, data)).collect::<Result<_, _>>().map(Value::Array),
+        Expr::Not(inner) => Ok(Value::Bool(!truthy(&eval(inner, data)?))),
+        Expr::Neg(inner) => Ok(Value::from(-number(&eval(inner, data)?)?)),
+        Expr::Binary(op, left, right) => {
+            let left = eval(left, data)?;
+            // Curto-circuito, como em qualquer linguagem
+            match *op {
+                "&&" if !truthy(&left) => return Ok(Value::Bool(false)),
+                "||" if truthy(&left) => return Ok(Value::Bool(true)),
+                _ => {}
+            }
+            let right = eval(right, data)?;
+            let result = match *op {
+                "&&" | "||" => Value::Bool(truthy(&right)),
+                "==" => Value::Bool(equals(&left, &right)),
+                "!=" => Value::Bool(!equals(&left, &right)),
+                "in" => match &right {
+                    Value::Array(items) => Value::Bool(items.iter().any(|item| equals(&left, item))),
+                    Value::String(s) => Value::Bool(left.as_str().is_some_and(|l| s.contains(l))),
+                    _ => return Err("Right side of 'in' must be an array or string".into()),
+                },
+                "<" | "<=" | ">" | ">=" => {
+                    let ordering = match (&left, &right) {
+                        (Value::String(a), Value::String(b)) => a.partial_cmp(b),
+                        _ => number(&left)?.partial_cmp(&number(&right)?),
+                    };
+                    let Some(ordering) = ordering else { return Ok(Value::Bool(false)) };
+                    Value::Bool(match *op {
+                        "<" => ordering.is_lt(),
+                        "<=" => ordering.is_le(),
+                        ">" => ordering.is_gt(),
+                        _ => ordering.is_ge(),
+                    })
+                }
+                "+" => Value::from(number(&left)? + number(&right)?),
+                "-" => Value::from(number(&left)? - number(&right)?),
+                "*" => Value::from(number(&left)? * number(&right)?),
+                "/" => Value::from(number(&left)? / number(&right)?),
+                _ => return Err(format!("Unknown operator: {}", op)),
+            };
+            Ok(result)
+        }
+        Expr::Call(name, args) => {
+            let args: Vec<Value> = args.iter().map(|arg| eval(arg, data)).collect::<Result<_, _>>()?;
+            match (name.as_str(), args.as_slice()) {
+                ("len", [Value::Array(a)]) => Ok(Value::from(a.len())),
+                ("len", [Value::String(s)]) => Ok(Value::from(s.chars().count())),
+                ("len", [Value::Null]) => Ok(Value::from(0)),
+                ("contains", [Value::Array(a), needle]) => Ok(Value::Bool(a.iter().any(|item| equals(item, needle)))),
+                ("contains", [Value::String(s), Value::String(needle)]) => Ok(Value::Bool(s.contains(needle.as_str()))),
+                ("contains", [Value::Null, _]) => Ok(Value::Bool(false)),
+                ("starts_with", [Value::String(s), Value::String(prefix)]) => Ok(Value::Bool(s.starts_with(prefix.as_str()))),
+                ("starts_with", [Value::Null, _]) => Ok(Value::Bool(false)),
+                ("lower", [Value::String(s)]) => Ok(Value::from(s.to_lowercase())),
+                ("lower", [Value::Null]) => Ok(Value::Null),
+                _ => Err(format!("Invalid call: {}/{}", name, args.len())),
+            }
+        }
+    }
+}
+
+/// Avalia a expressão e reduz o resultado a verdadeiro/falso.
+pub fn matches(expr: &Expr, data: &Value) -> Result<bool, String> {
+    eval(expr, data).map(|value| truthy(&value))
+}
+
+fn sample_data() -> Value {
+    serde_json::json!({
+        "customer": {
+            "age": 34,
+            "country": "BR",
+            "tier": "gold",
+            "orders": 12,
+            "blocked": false,
+            "flags": ["beta", "newsletter"],
+            "email": "ana@corp.example",
+        },
+        "order": {
+            "total": 349.9,
+            "cost": 180.0,
+            "items": [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],
+        },
+    })
+}
+
+pub async fn rules(Json(payload): Json<RulesPayload>) -> Response<BoxBody> {
+    let iterations = payload.iterations.unwrap_or(1);
+    if iterations == 0 || iterations > MAX_ITERATIONS {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "Iterations out of range" }))
+        )
+        .into_response();
+    }
+    let data = payload.data.unwrap_or_else(sample_data);
+    let definitions: Vec<(String, String)> = match payload.rules {
+        Some(rules) => rules.into_iter().map(|r| (r.name, r.expression)).collect(),
+        None => DEFAULT_RULES
+            .iter()
+            .map(|(name, expression)| (name.to_string(), expression.to_string()))
+            .collect(),
+    };
+
+    let expression_bytes: usize = definitions.iter().map(|(_, expression)| expression.len()).sum();
+    if expression_bytes.saturating_mul(iterations) > MAX_WORK {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "Too many rules for this many iterations" }))
+        )
+        .into_response();
+    }
+
+    let parse_start = Instant::now();
+    let mut compiled = Vec::with_capacity(definitions.len());
+    for (name, expression) in &definitions {
+        match parse(expression) {
+            Ok(expr) => compiled.push((name, expr)),
+            Err(error) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({ "error": format!("Rule {}: {}", name, error) }))
+                )
+                .into_response()
+            }
+        }
+    }
+    let parse_duration = parse_start.elapsed();
+
+    let start = Instant::now();
+    let mut matched = Vec::new();
+    for i in 0..iterations {
+        for (name, expr) in &compiled {
+            let result = match matches(expr, &data) {
+                Ok(result) => result,
+                Err(error) => {
+                    return (
+                        StatusCode::BAD_REQUEST,
+                        Json(serde_json::json!({ "error": format!("Rule {}: {}", name, error) }))
+                    )
+                    .into_response()
+                }
+            };
+            if result && i == 0 {
+                matched.push(name.as_str());
+            }
+        }
+    }
+    let duration = start.elapsed();
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "matched": matched,
+            "rules": compiled.len(),
+            "evaluations": compiled.len() * iterations,
+            "parse_ms": parse_duration.as_secs_f64() * 1000.0,
+            "duration_ms": duration.as_secs_f64() * 1000.0,
+        }))
+    )
+    .into_response()
+}
@@ -0,0 +1,150 @@
+// ======================
+// METADADOS DA EXECUÇÃO DO BENCHMARK
+// ======================
+//
+// Identificam a execução a que cada resultado pertence, para juntar a saída
+// de ferramentas diferentes (carga, logs, métricas) sem depender só do
+// horário: `BFF_RUN_ID`, `BFF_RUN_SCENARIO` e `BFF_HARNESS_SHA` (commit do
+// harness). Os headers `X-Run-Id`, `X-Run-Scenario` e `X-Harness-Sha`
+// sobrescrevem o valor do ambiente só naquela request, campo a campo.
+//
+// Quando há algum valor, ele vai como `"run": {...}` em toda linha JSON de
+// log, no /info e no /stats, nas linhas gravadas (record.rs) e nas do replay.
+// As dimensões das métricas do CloudWatch (cloudwatch.rs) vêm só do
+// ambiente: os headers não chegam lá, porque cada valor novo seria mais uma
+// métrica cobrada. Não entram no `X-Config-Hash`: são metadados, não
+// configuração. A região (region.rs) vai junto em toda anotação, mesmo sem
+// metadados da execução.
+
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use axum::{
+    body::BoxBody,
+    http::{Request, Response},
+};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use tower::{Layer, Service};
+
+use crate::config::CONFIG;
+
+pub const ENV_VARS: [&str; 3] = ["BFF_RUN_ID", "BFF_RUN_SCENARIO", "BFF_HARNESS_SHA"];
+pub const HEADERS: [&str; 3] = ["X-Run-Id", "X-Run-Scenario", "X-Harness-Sha"];
+
+#[derive(Clone, Default, PartialEq, Eq, Hash, Serialize)]
+pub struct Run {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scenario: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub harness_sha: Option<String>,
+}
+
+impl Run {
+    pub fn is_empty(&self) -> bool {
+        self.id.is_none() && self.scenario.is_none() && self.harness_sha.is_none()
+    }
+
+    /// Os campos na ordem de `HEADERS`.
+    // Usado pelo replay (fora da Lambda) e pelas dimensões do CloudWatch
+    #[cfg(any(not(feature = "lambda"), feature = "aws"))]
+    pub fn values(&self) -> [Option<&str>; 3] {
+        [self.id.as_deref(), self.scenario.as_deref(), self.harness_sha.as_deref()]
+    }
+
+    /// Acrescenta `"run"` a um objeto JSON, se houver algum valor, e a região.
+    pub fn annotate(&self, value: &mut serde_json::Value) {
+        if !self.is_empty() && value.is_object() {
+            value["run"] = serde_json::json!(self);
+        }
+        crate::region::annotate(value);
+    }
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+static FROM_ENV: Lazy<Run> = Lazy::new(|| Run {
+    id: non_empty(&CONFIG.run_id),
+    scenario: non_empty(&CONFIG.run_scenario),
+    harness_sha: non_empty(&CONFIG.harness_sha),
+});
+
+tokio::task_local! {
+    static REQUEST_RUN: Run;
+}
+
+/// Metadados em vigor: os da request, se vieram headers, senão os do ambiente.
+pub fn current() -> Run {
+    REQUEST_RUN.try_with(Clone::clone).unwrap_or_else(|_| FROM_ENV.clone())
+}
+
+/// Só os metadados do ambiente, ignorando os headers da request.
+#[cfg(feature = "aws")]
+pub fn from_env() -> &'static Run {
+    &FROM_ENV
+}
+
+/// Atalho para `current().annotate(value)`.
+pub fn annotate(value: &mut serde_json::Value) {
+    current().annotate(value);
+}
+
+// ------------
+// MIDDLEWARE: headers `X-Run-*`
+// ------------
+#[derive(Clone)]
+pub struct RunLayer;
+
+#[derive(Clone)]
+pub struct RunService<S> {
+    inner: S,
+}
+
+impl<S> Layer<S> for RunLayer {
+    type Service = RunService<S>;
+    fn layer(&self, inner: S) -> Self::Service {
+        RunService { inner }
+    }
+}
+
+impl<S, ReqBody> Service<Request<ReqBody>> for RunService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+{
+    type Response = Response<BoxBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        let mut service = self.inner.clone();
+
+        let [id, scenario, harness_sha] =
+            HEADERS.map(|name| req.headers().get(name).and_then(|v| v.to_str().ok()).and_then(|v| non_empty(v.trim())));
+        if id.is_none() && scenario.is_none() && harness_sha.is_none() {
+            return Box::pin(service.call(req));
+        }
+        let run = Run {
+            id: id.or_else(|| FROM_ENV.id.clone()),
+            scenario: scenario.or_else(|| FROM_ENV.scenario.clone()),
+            harness_sha: harness_sha.or_else(|| FROM_ENV.harness_sha.clone()),
+        };
+
+        // O call interno também roda no escopo: os layers de fora do handler
+        // (trailers) leem o run já no call. O CloudWatch não lê daqui, só do
+        // ambiente (`from_env`)
+        Box::pin(REQUEST_RUN.scope(run, async move { service.call(req).await }))
+    }
+}
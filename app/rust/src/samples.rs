@@ -0,0 +1,290 @@
+// ======================
+// AMOSTRAS CRUAS EM PARQUET
+// ======================
+//
+// Percentis e histogramas (/results) já são um resumo; para análise estatística
+// de verdade os notebooks precisam de cada request. Com `BFF_SAMPLES_DIR` (e/ou
+// `BFF_SAMPLES_S3_BUCKET`, feature "aws"), o TimingLayer guarda uma linha por
+// request: rota, método, status, início (relógio de parede), duração do
+// endpoint, tamanhos do corpo da request e da resposta (quando conhecidos),
+// as marcas de SLO estourado, salto de relógio e freeze, o run ID, a geração
+// do host (calibration.rs) e o custo estimado (cost.rs).
+//
+// As linhas ficam em memória até somarem `BFF_SAMPLES_BATCH` e então viram um
+// arquivo Parquet (colunar, Snappy) `<unix_ms>-<pid>-<seq>.parquet`, gravado no
+// diretório e/ou enviado ao bucket com `BFF_SAMPLES_S3_PREFIX`. A codificação
+// roda fora do caminho da request. `POST /samples/flush` grava o que estiver
+// pendente na hora, para fechar uma rodada; na Lambda o que ficou pendente
+// quando o ambiente é reciclado se perde.
+
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use parquet::{
+    basic::Compression,
+    data_type::{BoolType, ByteArray, ByteArrayType, DoubleType, Int32Type, Int64Type},
+    errors::Result as ParquetResult,
+    file::{properties::WriterProperties, writer::SerializedFileWriter},
+    schema::parser::parse_message_type,
+};
+
+use crate::config::CONFIG;
+
+const SCHEMA: &str = "
+message sample {
+    required binary route (UTF8);
+    required binary method (UTF8);
+    required int32 status;
+    required int64 start_unix_us;
+    required int64 duration_ns;
+    optional int64 request_bytes;
+    optional int64 response_bytes;
+    required boolean slo_breach;
+    required boolean clock_jump;
+    required boolean frozen;
+    optional binary run_id (UTF8);
+    required int32 host_generation;
+    optional double estimated_cost_usd;
+}
+";
+
+pub struct Sample {
+    pub route: String,
+    pub method: String,
+    pub status: u16,
+    pub start: SystemTime,
+    pub duration_ns: u64,
+    pub request_bytes: Option<u64>,
+    pub response_bytes: Option<u64>,
+    pub slo_breach: bool,
+    pub clock_jump: bool,
+    pub frozen: bool,
+    pub run_id: Option<String>,
+    pub host_generation: u32,
+    pub estimated_cost_usd: Option<f64>,
+}
+
+static PENDING: Mutex<Vec<Sample>> = Mutex::new(Vec::new());
+static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+static ROWS: AtomicU64 = AtomicU64::new(0);
+static FILES: AtomicU64 = AtomicU64::new(0);
+static ERRORS: AtomicU64 = AtomicU64::new(0);
+
+pub fn enabled() -> bool {
+    #[cfg(feature = "aws")]
+    if !CONFIG.samples_s3_bucket.is_empty() {
+        return true;
+    }
+    !CONFIG.samples_dir.is_empty()
+}
+
+pub fn snapshot() -> serde_json::Value {
+    #[allow(unused_mut)]
+    let mut body = serde_json::json!({
+        "dir": CONFIG.samples_dir,
+        "batch": CONFIG.samples_batch,
+        "pending": PENDING.lock().unwrap().len(),
+        "rows_written": ROWS.load(Ordering::Relaxed),
+        "files": FILES.load(Ordering::Relaxed),
+        "errors": ERRORS.load(Ordering::Relaxed),
+    });
+    #[cfg(feature = "aws")]
+    {
+        body["s3"] = serde_json::json!({
+            "bucket": CONFIG.samples_s3_bucket,
+            "prefix": CONFIG.samples_s3_prefix,
+        });
+    }
+    body
+}
+
+/// Guarda a amostra; com o lote cheio, grava o arquivo em segundo plano.
+pub fn record(sample: Sample) {
+    let batch = {
+        let mut pending = PENDING.lock().unwrap();
+        pending.push(sample);
+        if pending.len() < CONFIG.samples_batch.max(1) {
+            return;
+        }
+        std::mem::take(&mut *pending)
+    };
+    tokio::spawn(write(batch));
+}
+
+fn column<T: parquet::data_type::DataType>(
+    row_group: &mut parquet::file::writer::SerializedRowGroupWriter<'_, &mut Vec<u8>>,
+    values: &[T::T],
+    definitions: Option<&[i16]>,
+) -> ParquetResult<()> {
+    let mut column = row_group.next_column()?.expect("coluna a mais no schema");
+    column.typed::<T>().write_batch(values, definitions, None)?;
+    column.close()
+}
+
+/// Valores presentes e níveis de definição de uma coluna opcional.
+fn optional<'a, T, U>(
+    samples: &'a [Sample],
+    field: impl Fn(&'a Sample) -> Option<T>,
+    map: impl Fn(T) -> U,
+) -> (Vec<U>, Vec<i16>) {
+    let mut values = Vec::new();
+    let definitions = samples
+        .iter()
+        .map(|sample| match field(sample) {
+            Some(value) => {
+                values.push(map(value));
+                1
+            }
+            None => 0,
+        })
+        .collect();
+    (values, definitions)
+}
+
+fn encode(samples: &[Sample]) -> ParquetResult<Vec<u8>> {
+    let schema = Arc::new(parse_message_type(SCHEMA)?);
+    let properties = Arc::new(WriterProperties::builder().set_compression(Compression::SNAPPY).build());
+    let mut buffer = Vec::new();
+    let mut writer = SerializedFileWriter::new(&mut buffer, schema, properties)?;
+    let mut row_group = writer.next_row_group()?;
+
+    let text = |value: &str| ByteArray::from(value);
+    let routes: Vec<_> = samples.iter().map(|s| text(&s.route)).collect();
+    column::<ByteArrayType>(&mut row_group, &routes, None)?;
+    let methods: Vec<_> = samples.iter().map(|s| text(&s.method)).collect();
+    column::<ByteArrayType>(&mut row_group, &methods, None)?;
+    let statuses: Vec<_> = samples.iter().map(|s| s.status as i32).collect();
+    column::<Int32Type>(&mut row_group, &statuses, None)?;
+    let starts: Vec<_> = samples
+        .iter()
+        .map(|s| s.start.duration_since(UNIX_EPOCH).unwrap_or_default().as_micros() as i64)
+        .collect();
+    column::<Int64Type>(&mut row_group, &starts, None)?;
+    let durations: Vec<_> = samples.iter().map(|s| s.duration_ns as i64).collect();
+    column::<Int64Type>(&mut row_group, &durations, None)?;
+    let (values, definitions) = optional(samples, |s| s.request_bytes, |v| v as i64);
+    column::<Int64Type>(&mut row_group, &values, Some(&definitions))?;
+    let (values, definitions) = optional(samples, |s| s.response_bytes, |v| v as i64);
+    column::<Int64Type>(&mut row_group, &values, Some(&definitions))?;
+    let flags: [fn(&Sample) -> bool; 3] = [|s| s.slo_breach, |s| s.clock_jump, |s| s.frozen];
+    for flag in flags {
+        let values: Vec<_> = samples.iter().map(flag).collect();
+        column::<BoolType>(&mut row_group, &values, None)?;
+    }
+    let (values, definitions) = optional(samples, |s| s.run_id.as_deref(), text);
+    column::<ByteArrayType>(&mut row_group, &values, Some(&definitions))?;
+    let generations: Vec<_> = samples.iter().map(|s| s.host_generation as i32).collect();
+    column::<Int32Type>(&mut row_group, &generations, None)?;
+    let (values, definitions) = optional(samples, |s| s.estimated_cost_usd, |v| v);
+    column::<DoubleType>(&mut row_group, &values, Some(&definitions))?;
+
+    row_group.close()?;
+    writer.close()?;
+    Ok(buffer)
+}
+
+/// Codifica e grava um lote; devolve o nome do arquivo.
+async fn write(samples: Vec<Sample>) -> Option<String> {
+    let rows = samples.len() as u64;
+    let name = format!(
+        "{}-{}-{}.parquet",
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis(),
+        std::process::id(),
+        SEQUENCE.fetch_add(1, Ordering::Relaxed)
+    );
+    // Codificação e arquivo são bloqueantes: ficam fora das threads do runtime
+    let path = (!CONFIG.samples_dir.is_empty()).then(|| std::path::Path::new(&CONFIG.samples_dir).join(&name));
+    let encoded = tokio::task::spawn_blocking(move || {
+        let bytes = encode(&samples)?;
+        let saved = path.map(|path| std::fs::write(&path, &bytes).map_err(|error| (path, error)));
+        Ok::<_, parquet::errors::ParquetError>((bytes, saved))
+    })
+    .await;
+
+    let mut written = false;
+    #[allow(unused_variables)]
+    let bytes = match encoded {
+        Ok(Ok((bytes, saved))) => {
+            match saved {
+                Some(Ok(())) => written = true,
+                Some(Err((path, error))) => {
+                    eprintln!("falha ao gravar {}: {}", path.display(), error);
+                    ERRORS.fetch_add(1, Ordering::Relaxed);
+                }
+                None => {}
+            }
+            bytes
+        }
+        Ok(Err(error)) => {
+            eprintln!("falha ao codificar as amostras em Parquet: {}", error);
+            ERRORS.fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+        Err(_) => {
+            ERRORS.fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+    };
+    #[cfg(feature = "aws")]
+    if !CONFIG.samples_s3_bucket.is_empty() {
+        use aws_sdk_s3::primitives::ByteStream;
+
+        let key = format!("{}{}", CONFIG.samples_s3_prefix, name);
+        let client = aws_sdk_s3::Client::new(crate::aws::shared_config().await);
+        let result = client
+            .put_object()
+            .bucket(&CONFIG.samples_s3_bucket)
+            .key(&key)
+            .content_type("application/vnd.apache.parquet")
+            .body(ByteStream::from(bytes))
+            .send()
+            .await;
+        match result {
+            Ok(_) => written = true,
+            Err(error) => {
+                eprintln!("falha ao enviar {} para o S3: {}", key, crate::aws::describe(error));
+                ERRORS.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    if written {
+        FILES.fetch_add(1, Ordering::Relaxed);
+        ROWS.fetch_add(rows, Ordering::Relaxed);
+    }
+    written.then_some(name)
+}
+
+pub async fn flush() -> Response<BoxBody> {
+    if !enabled() {
+        return (
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({ "error": "Sample export is disabled; set BFF_SAMPLES_DIR" }))
+        )
+        .into_response();
+    }
+    let batch = std::mem::take(&mut *PENDING.lock().unwrap());
+    let rows = batch.len();
+    if rows == 0 {
+        return (StatusCode::OK, Json(serde_json::json!({ "rows": 0, "file": null }))).into_response();
+    }
+    match write(batch).await {
+        Some(file) => (StatusCode::OK, Json(serde_json::json!({ "rows": rows, "file": file }))).into_response(),
+        None => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": "Failed to write samples; see logs" }))
+        )
+        .into_response(),
+    }
+}
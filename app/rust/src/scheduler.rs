@@ -0,0 +1,256 @@
+// ======================
+// MIDDLEWARE: ESCALONADOR POR PRIORIDADE
+// ======================
+//
+// Experimento opcional (`BFF_SCHEDULER_SLOTS` > 0): só N requests executam ao
+// mesmo tempo, e quando uma vaga abre ela vai para quem está esperando com a
+// maior prioridade (FIFO dentro da mesma prioridade). A prioridade vem do
+// header `X-Priority: high | normal | low` (padrão `normal`).
+//
+// Os handlers são síncronos e não cedem a CPU no meio; o que o escalonador
+// faz é furar a fila na admissão: sob carga mista, uma request `high` espera
+// só a próxima vaga, em vez de esperar todo o trabalho pesado que chegou
+// antes. O /stats mostra espera e latência total por prioridade, para medir o
+// ganho, e quem esperou recebe `X-Scheduler-Wait`.
+//
+// Só passam pelo escalonador métodos que não são GET: GET /poll, por exemplo,
+// seguraria uma vaga durante todo o long poll.
+
+use std::{
+    cmp::Ordering as CmpOrdering,
+    collections::BinaryHeap,
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    task::{Context, Poll},
+    time::Instant,
+};
+
+use axum::{
+    body::{Body, BoxBody},
+    http::{HeaderValue, Method, Request, Response},
+};
+use once_cell::sync::Lazy;
+use tokio::sync::oneshot;
+use tower::{Layer, Service};
+
+use crate::config::CONFIG;
+
+const CLASSES: [&str; 3] = ["low", "normal", "high"];
+
+struct Waiter {
+    priority: usize,
+    sequence: u64,
+    wake: oneshot::Sender<()>,
+}
+
+// BinaryHeap é de máximo: maior prioridade primeiro, e dentro dela o menor
+// número de sequência (quem chegou antes)
+impl Ord for Waiter {
+    fn cmp(&self, other: &Self) -> CmpOrdering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
+impl PartialOrd for Waiter {
+    fn partial_cmp(&self, other: &Self) -> Option<CmpOrdering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Waiter {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == CmpOrdering::Equal
+    }
+}
+
+impl Eq for Waiter {}
+
+struct State {
+    available: usize,
+    sequence: u64,
+    waiting: BinaryHeap<Waiter>,
+}
+
+static STATE: Lazy<Mutex<State>> = Lazy::new(|| {
+    Mutex::new(State { available: CONFIG.scheduler_slots, sequence: 0, waiting: BinaryHeap::new() })
+});
+
+#[derive(Default)]
+struct ClassStats {
+    requests: AtomicU64,
+    waited: AtomicU64,
+    total_wait_us: AtomicU64,
+    max_wait_us: AtomicU64,
+    total_latency_us: AtomicU64,
+    max_latency_us: AtomicU64,
+}
+
+static STATS: Lazy<[ClassStats; 3]> = Lazy::new(Default::default);
+
+pub fn snapshot() -> serde_json::Value {
+    let state = STATE.lock().unwrap();
+    let classes: serde_json::Map<String, serde_json::Value> = CLASSES
+        .iter()
+        .zip(STATS.iter())
+        .map(|(name, stats)| {
+            let requests = stats.requests.load(Ordering::Relaxed);
+            let mean = |total: &AtomicU64| {
+                if requests == 0 { 0.0 } else { total.load(Ordering::Relaxed) as f64 / requests as f64 / 1000.0 }
+            };
+            let value = serde_json::json!({
+                "requests": requests,
+                "waited": stats.waited.load(Ordering::Relaxed),
+                "mean_wait_ms": mean(&stats.total_wait_us),
+                "max_wait_ms": stats.max_wait_us.load(Ordering::Relaxed) as f64 / 1000.0,
+                "mean_latency_ms": mean(&stats.total_latency_us),
+                "max_latency_ms": stats.max_latency_us.load(Ordering::Relaxed) as f64 / 1000.0,
+            });
+            (name.to_string(), value)
+        })
+        .collect();
+    serde_json::json!({
+        "slots": CONFIG.scheduler_slots,
+        "available": state.available,
+        "waiting": state.waiting.len(),
+        "classes": classes,
+    })
+}
+
+/// Vaga de execução; devolvida (ou repassada a quem espera) no drop.
+struct Slot;
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        release();
+    }
+}
+
+fn release() {
+    let mut state = STATE.lock().unwrap();
+    // Repassa direto para o próximo da fila; quem desistiu (request
+    // cancelada) já soltou o receiver e é pulado
+    while let Some(waiter) = state.waiting.pop() {
+        if waiter.wake.send(()).is_ok() {
+            return;
+        }
+    }
+    state.available += 1;
+}
+
+// Espera na fila; se a request for cancelada depois de já ter recebido a
+// vaga, ela é devolvida aqui
+struct Waiting {
+    receiver: Option<oneshot::Receiver<()>>,
+}
+
+impl Drop for Waiting {
+    fn drop(&mut self) {
+        if let Some(mut receiver) = self.receiver.take() {
+            if receiver.try_recv().is_ok() {
+                release();
+            }
+        }
+    }
+}
+
+/// Vaga, e se foi preciso entrar na fila para consegui-la.
+async fn acquire(priority: usize) -> (Slot, bool) {
+    let receiver = {
+        let mut state = STATE.lock().unwrap();
+        if state.available > 0 && state.waiting.is_empty() {
+            state.available -= 1;
+            return (Slot, false);
+        }
+        state.sequence += 1;
+        let (wake, receiver) = oneshot::channel();
+        let sequence = state.sequence;
+        state.waiting.push(Waiter { priority, sequence, wake });
+        receiver
+    };
+
+    let mut waiting = Waiting { receiver: Some(receiver) };
+    // O sender só some depois de mandar a vaga
+    let _ = waiting.receiver.as_mut().unwrap().await;
+    waiting.receiver = None;
+    (Slot, true)
+}
+
+fn priority(req: &Request<Body>) -> usize {
+    match req.headers().get("X-Priority").and_then(|v| v.to_str().ok()) {
+        Some(value) if value.eq_ignore_ascii_case("high") => 2,
+        Some(value) if value.eq_ignore_ascii_case("low") => 0,
+        _ => 1,
+    }
+}
+
+#[derive(Clone)]
+pub struct SchedulerLayer;
+
+#[derive(Clone)]
+pub struct SchedulerService<S> {
+    inner: S,
+}
+
+impl<S> Layer<S> for SchedulerLayer {
+    type Service = SchedulerService<S>;
+    fn layer(&self, inner: S) -> Self::Service {
+        SchedulerService { inner }
+    }
+}
+
+impl<S> Service<Request<Body>> for SchedulerService<S>
+where
+    S: Service<Request<Body>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<BoxBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let mut service = self.inner.clone();
+
+        if CONFIG.scheduler_slots == 0 || req.method() == Method::GET {
+            return Box::pin(service.call(req));
+        }
+
+        let priority = priority(&req);
+        Box::pin(async move {
+            let start = Instant::now();
+            let (slot, queued) = acquire(priority).await;
+            let wait = start.elapsed();
+            let wait_us = wait.as_micros() as u64;
+
+            let response = service.call(req).await;
+            drop(slot);
+
+            let stats = &STATS[priority];
+            let latency_us = start.elapsed().as_micros() as u64;
+            stats.requests.fetch_add(1, Ordering::Relaxed);
+            stats.total_latency_us.fetch_add(latency_us, Ordering::Relaxed);
+            stats.max_latency_us.fetch_max(latency_us, Ordering::Relaxed);
+            stats.total_wait_us.fetch_add(wait_us, Ordering::Relaxed);
+            stats.max_wait_us.fetch_max(wait_us, Ordering::Relaxed);
+
+            let mut response = response?;
+            if queued {
+                stats.waited.fetch_add(1, Ordering::Relaxed);
+                response.headers_mut().insert(
+                    "X-Scheduler-Wait",
+                    HeaderValue::from_str(&format!("{:?}", wait).replace('µ', "u")).unwrap(),
+                );
+            }
+            Ok(response)
+        })
+    }
+}
@@ -0,0 +1,210 @@
+// ======================
+// MIDDLEWARE: TRÁFEGO ESPELHADO (shadow)
+// ======================
+//
+// Com `BFF_SHADOW_URL`, uma fração `BFF_SHADOW_SAMPLE` das requests que chegam
+// é copiada (método, path com query, headers e corpo) para outra variante, em
+// segundo plano. A resposta ao cliente é sempre a do primário: o espelho não
+// atrasa nada além de bufferizar o corpo, e erros do espelho só viram
+// contadores.
+//
+// Cada espelho é comparado com o primário: status diferentes contam como
+// divergência e a diferença de latência (espelho - primário) é acumulada.
+// O espelho leva `X-Shadow: 1`, e requests com esse header nunca são
+// espelhadas de novo (duas variantes espelhando uma na outra não entram em
+// loop). Só HTTP simples.
+
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::atomic::{AtomicU64, Ordering},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    body::{Body, BoxBody},
+    http::{header, HeaderValue, Request, Response},
+};
+use once_cell::sync::Lazy;
+use tokio::sync::oneshot;
+use tower::{Layer, Service};
+
+use crate::{body_limit, config::CONFIG, rng::SplitMix64};
+
+static CLIENT: Lazy<hyper::Client<hyper::client::HttpConnector>> = Lazy::new(hyper::Client::new);
+
+static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+static IN_FLIGHT: AtomicU64 = AtomicU64::new(0);
+static SENT: AtomicU64 = AtomicU64::new(0);
+static SKIPPED: AtomicU64 = AtomicU64::new(0);
+static SUCCEEDED: AtomicU64 = AtomicU64::new(0);
+static NON_SUCCESS: AtomicU64 = AtomicU64::new(0);
+static ERRORS: AtomicU64 = AtomicU64::new(0);
+static STATUS_MISMATCHES: AtomicU64 = AtomicU64::new(0);
+static COMPLETED: AtomicU64 = AtomicU64::new(0);
+static LATENCY_US: AtomicU64 = AtomicU64::new(0);
+static MAX_LATENCY_US: AtomicU64 = AtomicU64::new(0);
+static COMPARED: AtomicU64 = AtomicU64::new(0);
+/// Soma das diferenças de latência, deslocada para caber num u64 sem sinal.
+static DELTA_US: AtomicU64 = AtomicU64::new(1 << 62);
+
+pub fn snapshot() -> serde_json::Value {
+    let completed = COMPLETED.load(Ordering::Relaxed);
+    let compared = COMPARED.load(Ordering::Relaxed);
+    let delta_us = DELTA_US.load(Ordering::Relaxed) as i64 - (1 << 62);
+    serde_json::json!({
+        "url": CONFIG.shadow_url,
+        "sample": CONFIG.shadow_sample,
+        "sent": SENT.load(Ordering::Relaxed),
+        "skipped": SKIPPED.load(Ordering::Relaxed),
+        "in_flight": IN_FLIGHT.load(Ordering::Relaxed),
+        "succeeded": SUCCEEDED.load(Ordering::Relaxed),
+        "non_success": NON_SUCCESS.load(Ordering::Relaxed),
+        "errors": ERRORS.load(Ordering::Relaxed),
+        "status_mismatches": STATUS_MISMATCHES.load(Ordering::Relaxed),
+        "mean_latency_ms": if completed == 0 { 0.0 } else { LATENCY_US.load(Ordering::Relaxed) as f64 / completed as f64 / 1000.0 },
+        "max_latency_ms": MAX_LATENCY_US.load(Ordering::Relaxed) as f64 / 1000.0,
+        "mean_delta_ms": if compared == 0 { 0.0 } else { delta_us as f64 / compared as f64 / 1000.0 },
+    })
+}
+
+fn sampled() -> bool {
+    if CONFIG.shadow_sample >= 1.0 {
+        return true;
+    }
+    SplitMix64::new(SEQUENCE.fetch_add(1, Ordering::Relaxed)).next_f64() < CONFIG.shadow_sample
+}
+
+/// Monta a cópia; `Host` e `Content-Length` ficam para o cliente preencher.
+fn mirror_request(req: &Request<Body>, body: hyper::body::Bytes) -> Option<Request<Body>> {
+    let path = req.uri().path_and_query().map(|p| p.as_str()).unwrap_or("/");
+    let mut mirror = Request::builder()
+        .method(req.method().clone())
+        .uri(format!("{}{}", CONFIG.shadow_url.trim_end_matches('/'), path))
+        .body(Body::from(body))
+        .ok()?;
+    for (name, value) in req.headers() {
+        if name != header::HOST && name != header::CONTENT_LENGTH {
+            mirror.headers_mut().append(name, value.clone());
+        }
+    }
+    mirror.headers_mut().insert("X-Shadow", HeaderValue::from_static("1"));
+    Some(mirror)
+}
+
+/// Envia o espelho e, quando o primário terminar, compara os dois.
+async fn send(mirror: Request<Body>, primary: oneshot::Receiver<(u16, Duration)>) {
+    let start = Instant::now();
+    let result = tokio::time::timeout(CONFIG.shadow_timeout, async {
+        let response = CLIENT.request(mirror).await?;
+        let status = response.status().as_u16();
+        hyper::body::to_bytes(response.into_body()).await.map(|_| status)
+    })
+    .await;
+    let latency = start.elapsed();
+    IN_FLIGHT.fetch_sub(1, Ordering::Relaxed);
+
+    let micros = latency.as_micros() as u64;
+    COMPLETED.fetch_add(1, Ordering::Relaxed);
+    LATENCY_US.fetch_add(micros, Ordering::Relaxed);
+    MAX_LATENCY_US.fetch_max(micros, Ordering::Relaxed);
+
+    let status = match result {
+        Ok(Ok(status)) => status,
+        Ok(Err(error)) => {
+            ERRORS.fetch_add(1, Ordering::Relaxed);
+            eprintln!("falha no espelho para {}: {}", CONFIG.shadow_url, error);
+            return;
+        }
+        Err(_) => {
+            ERRORS.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+    };
+    if (200..300).contains(&status) {
+        SUCCEEDED.fetch_add(1, Ordering::Relaxed);
+    } else {
+        NON_SUCCESS.fetch_add(1, Ordering::Relaxed);
+    }
+
+    // Sem resposta do primário (handler falhou) não há o que comparar
+    let Ok((primary_status, primary_latency)) = primary.await else { return };
+    if primary_status != status {
+        STATUS_MISMATCHES.fetch_add(1, Ordering::Relaxed);
+    }
+    let delta = micros as i64 - primary_latency.as_micros() as i64;
+    DELTA_US.fetch_add(delta as u64, Ordering::Relaxed);
+    COMPARED.fetch_add(1, Ordering::Relaxed);
+}
+
+#[derive(Clone)]
+pub struct ShadowLayer;
+
+#[derive(Clone)]
+pub struct ShadowService<S> {
+    inner: S,
+}
+
+impl<S> Layer<S> for ShadowLayer {
+    type Service = ShadowService<S>;
+    fn layer(&self, inner: S) -> Self::Service {
+        ShadowService { inner }
+    }
+}
+
+impl<S> Service<Request<Body>> for ShadowService<S>
+where
+    S: Service<Request<Body>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<BoxBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let mut service = self.inner.clone();
+
+        if CONFIG.shadow_url.is_empty() || req.headers().contains_key("X-Shadow") || !sampled() {
+            return Box::pin(service.call(req));
+        }
+        // Um espelho lento não pode acumular tasks sem limite
+        if IN_FLIGHT.fetch_add(1, Ordering::Relaxed) >= CONFIG.shadow_max_in_flight as u64 {
+            IN_FLIGHT.fetch_sub(1, Ordering::Relaxed);
+            SKIPPED.fetch_add(1, Ordering::Relaxed);
+            return Box::pin(service.call(req));
+        }
+
+        Box::pin(async move {
+            let (parts, bytes) = match body_limit::read(req).await {
+                Ok(read) => read,
+                Err(response) => {
+                    IN_FLIGHT.fetch_sub(1, Ordering::Relaxed);
+                    return Ok(response);
+                }
+            };
+            let req = Request::from_parts(parts, Body::from(bytes.clone()));
+
+            let (done, primary) = oneshot::channel();
+            match mirror_request(&req, bytes) {
+                Some(mirror) => {
+                    SENT.fetch_add(1, Ordering::Relaxed);
+                    tokio::spawn(send(mirror, primary));
+                }
+                None => {
+                    IN_FLIGHT.fetch_sub(1, Ordering::Relaxed);
+                    ERRORS.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+
+            let start = Instant::now();
+            let response = service.call(req).await?;
+            let _ = done.send((response.status().as_u16(), start.elapsed()));
+            Ok(response)
+        })
+    }
+}
@@ -0,0 +1,67 @@
+// ======================
+// ORÇAMENTO DE LATÊNCIA (SLO)
+// ======================
+//
+// `BFF_LATENCY_BUDGETS=/image=200,/compress=50` define o orçamento, em ms, de
+// cada rota; `*` vale para as rotas que não estão na lista. O TimingLayer
+// compara a duração do endpoint (a mesma de `X-Endpoint-Duration`) com o
+// orçamento e marca as respostas que estouraram com `X-SLO-Breach: true`.
+// O /stats mostra, por rota, requests, estouros e a taxa de estouro, que é
+// como o resultado de um benchmark costuma ser lido.
+//
+// A rota é o padrão do router (ex. `/mock/:name`), como no CloudWatch.
+
+use std::{collections::HashMap, sync::Mutex, time::Duration};
+
+use once_cell::sync::Lazy;
+
+use crate::config::CONFIG;
+
+#[derive(Default)]
+struct Counters {
+    requests: u64,
+    breaches: u64,
+    max_over_us: u64,
+}
+
+static ROUTES: Lazy<Mutex<HashMap<String, Counters>>> = Lazy::new(Default::default);
+
+/// Orçamento da rota: o dela, senão o de `*`.
+pub fn budget(route: &str) -> Option<Duration> {
+    let find = |key: &str| CONFIG.latency_budgets.iter().find(|(path, _)| path == key).map(|(_, budget)| *budget);
+    find(route).or_else(|| find("*"))
+}
+
+/// Conta a request e diz se ela estourou o orçamento.
+pub fn record(route: &str, budget: Duration, duration: Duration) -> bool {
+    let breached = duration > budget;
+    let mut routes = ROUTES.lock().unwrap();
+    let counters = match routes.get_mut(route) {
+        Some(counters) => counters,
+        None => routes.entry(route.to_string()).or_default(),
+    };
+    counters.requests += 1;
+    if breached {
+        counters.breaches += 1;
+        counters.max_over_us = counters.max_over_us.max((duration - budget).as_micros() as u64);
+    }
+    breached
+}
+
+pub fn snapshot() -> serde_json::Value {
+    let routes = ROUTES.lock().unwrap();
+    let routes: serde_json::Map<String, serde_json::Value> = routes
+        .iter()
+        .map(|(route, counters)| {
+            let value = serde_json::json!({
+                "budget_ms": budget(route).map(|b| b.as_secs_f64() * 1000.0),
+                "requests": counters.requests,
+                "breaches": counters.breaches,
+                "breach_rate": if counters.requests == 0 { 0.0 } else { counters.breaches as f64 / counters.requests as f64 },
+                "max_over_budget_ms": counters.max_over_us as f64 / 1000.0,
+            });
+            (route.clone(), value)
+        })
+        .collect();
+    serde_json::Value::Object(routes)
+}
@@ -0,0 +1,155 @@
+// ======================
+// ORDENAÇÃO DE ARRAYS GRANDES
+// ======================
+//
+// Ordena `values` (números enviados pelo cliente) ou, sem eles, `size`
+// elementos gerados com a seed: inteiros em [0, 2^31) (`type: "int"`, padrão)
+// ou floats em [0, 1) (`type: "float"`). Valores enviados que são todos
+// inteiros são ordenados como i64; havendo algum float, como f64.
+//
+// `algorithm: "unstable"` (padrão, pdqsort) ou `"stable"` (merge sort da
+// std). Só a ordenação entra em `sort_ms`; a geração tem o próprio tempo. O
+// array ordenado só volta com `return_result: true`, porque com milhões de
+// elementos a serialização pesaria mais que a ordenação.
+
+use std::{cmp::Ordering, time::Instant};
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use serde_json::{Number, Value};
+
+use crate::{
+    estimate::{self, Estimate},
+    rng::{self, SplitMix64},
+};
+
+const MAX_SIZE: usize = 10_000_000;
+
+#[derive(Deserialize)]
+pub struct SortPayload {
+    values: Option<Vec<Number>>,
+    size: Option<usize>,
+    #[serde(rename = "type")]
+    element_type: Option<String>,
+    algorithm: Option<String>,
+    return_result: Option<bool>,
+    seed: Option<u64>,
+    dry_run: Option<bool>,
+}
+
+fn bad_request(error: String) -> Response<BoxBody> {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({ "error": error }))
+    )
+    .into_response()
+}
+
+/// Ordena e devolve o tempo da ordenação, mais os campos do resultado.
+fn run<T: Copy + Into<Value>>(mut values: Vec<T>, stable: bool, compare: fn(&T, &T) -> Ordering, return_result: bool) -> Value {
+    let start = Instant::now();
+    if stable {
+        values.sort_by(compare);
+    } else {
+        values.sort_unstable_by(compare);
+    }
+    let elapsed = start.elapsed();
+
+    let mut body = serde_json::json!({
+        "size": values.len(),
+        "sort_ms": elapsed.as_secs_f64() * 1000.0,
+        "elements_per_second": values.len() as f64 / elapsed.as_secs_f64(),
+        "min": values.first().map(|v| (*v).into()),
+        "median": values.get(values.len() / 2).map(|v| (*v).into()),
+        "max": values.last().map(|v| (*v).into()),
+    });
+    if return_result {
+        body["sorted"] = Value::Array(values.into_iter().map(Into::into).collect());
+    }
+    body
+}
+
+fn dry_run(payload: &SortPayload, stable: bool, return_result: bool) -> Response<BoxBody> {
+    let size = match &payload.values {
+        Some(values) => values.len(),
+        None => payload.size.unwrap_or(100_000),
+    };
+    if size == 0 || size > MAX_SIZE {
+        return bad_request(format!("size must be between 1 and {}", MAX_SIZE));
+    }
+    if payload.values.is_none() && !matches!(payload.element_type.as_deref().unwrap_or("int"), "int" | "float") {
+        return bad_request("type must be int or float".to_string());
+    }
+    // n·log2(n) comparações; o merge sort estável ainda aloca um buffer de n/2
+    let size = size as u64;
+    let comparisons = size * (64 - size.leading_zeros() as u64);
+    estimate::response(Estimate {
+        iterations: comparisons,
+        unit: "comparisons",
+        allocation_bytes: size * 8 + if stable { size * 4 } else { 0 },
+        output_bytes: 200 + if return_result { size * 21 } else { 0 },
+    })
+}
+
+pub async fn sort(Json(payload): Json<SortPayload>) -> Response<BoxBody> {
+    let algorithm = payload.algorithm.as_deref().unwrap_or("unstable").to_string();
+    let stable = match algorithm.as_str() {
+        "unstable" => false,
+        "stable" => true,
+        other => return bad_request(format!("algorithm must be stable or unstable, got {}", other)),
+    };
+    let return_result = payload.return_result.unwrap_or(false);
+    if payload.dry_run.unwrap_or(false) {
+        return dry_run(&payload, stable, return_result);
+    }
+
+    let (element_type, mut body) = match payload.values {
+        Some(values) => {
+            if values.is_empty() || values.len() > MAX_SIZE {
+                return bad_request(format!("values must have between 1 and {} elements", MAX_SIZE));
+            }
+            let ints: Option<Vec<i64>> = values.iter().map(Number::as_i64).collect();
+            match ints {
+                Some(ints) => ("int", run(ints, stable, i64::cmp, return_result)),
+                None => {
+                    let floats = values.iter().filter_map(Number::as_f64).collect();
+                    ("float", run(floats, stable, f64::total_cmp, return_result))
+                }
+            }
+        }
+        None => {
+            let size = payload.size.unwrap_or(100_000);
+            if size == 0 || size > MAX_SIZE {
+                return bad_request(format!("size must be between 1 and {}", MAX_SIZE));
+            }
+            let start = Instant::now();
+            let mut rng = SplitMix64::new(rng::seed(payload.seed));
+            match payload.element_type.as_deref().unwrap_or("int") {
+                "int" => {
+                    let ints = (0..size).map(|_| rng.next_below(1 << 31) as i64).collect();
+                    let generate_ms = start.elapsed().as_secs_f64() * 1000.0;
+                    let mut body = run(ints, stable, i64::cmp, return_result);
+                    body["generate_ms"] = serde_json::json!(generate_ms);
+                    ("int", body)
+                }
+                "float" => {
+                    let floats = (0..size).map(|_| rng.next_f64()).collect();
+                    let generate_ms = start.elapsed().as_secs_f64() * 1000.0;
+                    let mut body = run(floats, stable, f64::total_cmp, return_result);
+                    body["generate_ms"] = serde_json::json!(generate_ms);
+                    ("float", body)
+                }
+                other => return bad_request(format!("type must be int or float, got {}", other)),
+            }
+        }
+    };
+
+    body["type"] = serde_json::json!(element_type);
+    body["algorithm"] = serde_json::json!(algorithm);
+    (StatusCode::OK, Json(body)).into_response()
+}
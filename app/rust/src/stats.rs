@@ -0,0 +1,58 @@
+// ======================
+// GET /stats
+// ======================
+//
+// Cada subsistema mantém os próprios contadores e expõe um `snapshot()`;
+// aqui só juntamos tudo num único documento.
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+
+use crate::{adaptive, clock, concurrency, dedup, idempotency, intensity, memory, poll, record, samples, scheduler, shadow, slo, trailers};
+
+pub async fn stats() -> Response<BoxBody> {
+    #[allow(unused_mut)]
+    let mut body = serde_json::json!({
+        "idempotency": idempotency::snapshot(),
+        "dedup": dedup::snapshot(),
+        "poll": poll::snapshot(),
+        "concurrency": concurrency::snapshot(),
+        "adaptive": adaptive::snapshot(),
+        "slo": slo::snapshot(),
+        "memory": memory::snapshot(),
+        "scheduler": scheduler::snapshot(),
+        "intensity": intensity::snapshot(),
+        "clock": clock::snapshot(),
+        "record": record::snapshot(),
+        "samples": samples::snapshot(),
+        "shadow": shadow::snapshot(),
+        "response_metrics": trailers::snapshot(),
+    });
+
+    // Subsistemas atrás de feature só aparecem quando compilados
+    #[cfg(not(feature = "lambda"))]
+    {
+        body["connections"] = crate::connection_policy::snapshot();
+    }
+    #[cfg(feature = "external")]
+    {
+        body["external"] = crate::external::snapshot();
+    }
+    #[cfg(feature = "lambda")]
+    {
+        body["events"] = crate::events::snapshot();
+    }
+    #[cfg(feature = "aws")]
+    {
+        body["remote_config"] = crate::remote_config::snapshot();
+        body["aws_credentials"] = crate::aws::snapshot();
+        body["cloudwatch"] = crate::cloudwatch::snapshot();
+    }
+    crate::run::annotate(&mut body);
+
+    (StatusCode::OK, Json(body)).into_response()
+}
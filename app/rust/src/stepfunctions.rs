@@ -0,0 +1,78 @@
+// ======================
+// MODO TASK DO STEP FUNCTIONS (feature "lambda")
+// ======================
+//
+// Com `BFF_LAMBDA_MODE=stepfunctions`, a Lambda é um Task state: o input da
+// execução chega direto do lambda_runtime (sem lambda_http) no formato
+// `{"workload": "...", "payload": {...}}` e o output volta estruturado, com o
+// corpo da resposta do workload e os tempos, para o próximo state usar.
+//
+// Falha do workload (status fora de 2xx) vira erro da task, para que o
+// `Retry`/`Catch` da máquina de estados entre em ação.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use lambda_runtime::{service_fn, Error, LambdaEvent};
+use serde::Deserialize;
+
+use crate::{
+    clock::{self, Stopwatch},
+    registry,
+};
+
+#[derive(Deserialize)]
+struct TaskInput {
+    workload: String,
+    #[serde(default)]
+    payload: Option<serde_json::Value>,
+}
+
+static COLD: AtomicBool = AtomicBool::new(true);
+
+async fn handle(event: LambdaEvent<serde_json::Value>) -> Result<serde_json::Value, Error> {
+    let invocation = clock::Invocation::start();
+    let start = Stopwatch::start();
+    let cold_start = COLD.swap(false, Ordering::Relaxed);
+    let (workload, status, output, workload_ms) = run_task(event.payload).await?;
+
+    Ok(serde_json::json!({
+        "workload": workload,
+        "status": status,
+        "output": output,
+        "timings": {
+            "workload_ms": workload_ms,
+            "total_ms": start.elapsed().as_secs_f64() * 1000.0,
+            "cold_start": cold_start,
+            "frozen_ms": invocation.frozen.map(|frozen| frozen.as_secs_f64() * 1000.0),
+        },
+        "request_id": event.context.request_id,
+    }))
+}
+
+async fn run_task(input: serde_json::Value) -> Result<(String, u16, serde_json::Value, f64), Error> {
+    let input: TaskInput = serde_json::from_value(input).map_err(|e| format!("Invalid task input: {}", e))?;
+    let workload = registry::find(&input.workload).ok_or_else(|| format!("Unknown workload: {}", input.workload))?;
+    let payload = input
+        .payload
+        .unwrap_or_else(|| serde_json::from_str(workload.default_payload).unwrap());
+
+    let start = Stopwatch::start();
+    let response = (workload.run)(payload).await;
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body())
+        .await
+        .map_err(|e| format!("Failed to read workload response: {}", e))?;
+    let workload_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+    // Workloads que não respondem JSON (ex.: imagem) vão como string
+    let output = serde_json::from_slice(&body)
+        .unwrap_or_else(|_| serde_json::Value::String(String::from_utf8_lossy(&body).into_owned()));
+    if !status.is_success() {
+        return Err(format!("Workload {} returned {}: {}", workload.name, status, output).into());
+    }
+    Ok((workload.name.to_string(), status.as_u16(), output, workload_ms))
+}
+
+pub async fn run() -> Result<(), Error> {
+    lambda_runtime::run(service_fn(handle)).await
+}
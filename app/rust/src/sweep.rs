@@ -0,0 +1,354 @@
+// ======================
+// VARREDURA DE TAMANHOS (POST /sweep)
+// ======================
+//
+// Roda um workload do registro (registry.rs) para cada valor de `sizes`,
+// gravado no campo `parameter` do payload (ex.: `orders` do /json/parse,
+// `size` do /matrix), e devolve a tabela tamanho → latência numa chamada só,
+// em vez de uma rodada orquestrada por ponto. Cada ponto tem `warmup`
+// chamadas descartadas e `repetitions` medidas, em sequência, com o corpo da
+// resposta consumido como no /mixed. O resto do payload vem de `payload` ou
+// do padrão do workload.
+//
+// Se uma chamada falha, o ponto conta o erro e segue; o status e a mensagem
+// da primeira falha vão junto, para distinguir um tamanho acima do limite do
+// workload de um problema no meio da série. As estatísticas são só das
+// chamadas que deram certo (um 400 rápido não é um ponto rápido) e ficam null
+// se nenhuma deu.
+//
+// Uma média sozinha esconde o ruído, então cada ponto traz também a dispersão
+// das repetições: desvio padrão (amostral), coeficiente de variação, o
+// intervalo de confiança de 95% da média (t de Student, com n - 1 graus de
+// liberdade) e quantas repetições são outliers pelo z modificado (mediana e
+// MAD, |z| > 3,5, como em Iglewicz e Hoaglin). Com `format: "csv"` a tabela
+// sai em CSV, uma linha por ponto, com as mesmas colunas.
+
+use std::time::Instant;
+
+use axum::{
+    body::{boxed, BoxBody, Full},
+    extract::Json,
+    http::{header, HeaderValue, Response, StatusCode},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+
+use crate::registry;
+
+const MAX_SIZES: usize = 100;
+const MAX_REPETITIONS: usize = 1_000;
+/// Chamadas no total, aquecimento incluído
+const MAX_CALLS: usize = 10_000;
+/// Limite do z modificado a partir do qual uma repetição é outlier
+const OUTLIER_Z: f64 = 3.5;
+
+/// t de Student bicaudal de 95% para 1 a 30 graus de liberdade
+const T_95: [f64; 30] = [
+    12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160, 2.145, 2.131,
+    2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056, 2.052, 2.048, 2.045, 2.042,
+];
+
+const CSV_COLUMNS: [&str; 14] = [
+    "size",
+    "calls",
+    "errors",
+    "response_bytes",
+    "min_ms",
+    "mean_ms",
+    "p50_ms",
+    "p95_ms",
+    "max_ms",
+    "stddev_ms",
+    "cv",
+    "ci95_low_ms",
+    "ci95_high_ms",
+    "outliers",
+];
+
+#[derive(Deserialize)]
+pub struct SweepPayload {
+    workload: String,
+    parameter: String,
+    sizes: Vec<serde_json::Number>,
+    payload: Option<serde_json::Value>,
+    repetitions: Option<usize>,
+    warmup: Option<usize>,
+    format: Option<String>,
+}
+
+fn bad_request(error: String) -> Response<BoxBody> {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({ "error": error }))
+    )
+    .into_response()
+}
+
+/// Percentil `p` (0..1) por posição, sobre latências já ordenadas.
+pub fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = ((sorted.len() as f64 * p).ceil() as usize).max(1);
+    sorted[rank.min(sorted.len()) - 1]
+}
+
+/// Média, desvio padrão amostral e a meia largura do intervalo de confiança
+/// de 95% da média.
+pub fn spread(values: &[f64]) -> (f64, f64, f64) {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    if values.len() < 2 {
+        return (mean, 0.0, 0.0);
+    }
+    let stddev = (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt();
+    let t = T_95.get(values.len() - 2).copied().unwrap_or(1.96);
+    (mean, stddev, t * stddev / n.sqrt())
+}
+
+/// Quantas latências têm |z modificado| acima de `OUTLIER_Z`.
+fn outliers(sorted: &[f64]) -> usize {
+    let median = percentile(sorted, 0.5);
+    let mut deviations: Vec<f64> = sorted.iter().map(|ms| (ms - median).abs()).collect();
+    deviations.sort_by(f64::total_cmp);
+    let mad = percentile(&deviations, 0.5);
+    if mad == 0.0 {
+        // Mais da metade das repetições é igual à mediana: o resto destoa
+        return deviations.iter().filter(|d| **d > 0.0).count();
+    }
+    deviations.iter().filter(|d| 0.6745 * **d / mad > OUTLIER_Z).count()
+}
+
+struct Point {
+    size: serde_json::Number,
+    latencies_ms: Vec<f64>,
+    errors: usize,
+    response_bytes: usize,
+    first_error: Option<serde_json::Value>,
+}
+
+impl Point {
+    fn to_json(&self) -> serde_json::Value {
+        let mut sorted = self.latencies_ms.clone();
+        sorted.sort_by(f64::total_cmp);
+        // Sem nenhuma chamada boa, as estatísticas ficam null
+        let measured = !sorted.is_empty();
+        let stats = measured.then(|| spread(&sorted));
+        let p = |q: f64| measured.then(|| percentile(&sorted, q));
+        serde_json::json!({
+            "size": self.size,
+            "calls": sorted.len() + self.errors,
+            "errors": self.errors,
+            "response_bytes": self.response_bytes,
+            "min_ms": sorted.first(),
+            "mean_ms": stats.map(|(mean, _, _)| mean),
+            "p50_ms": p(0.5),
+            "p95_ms": p(0.95),
+            "max_ms": sorted.last(),
+            "stddev_ms": stats.map(|(_, stddev, _)| stddev),
+            "cv": stats.map(|(mean, stddev, _)| if mean > 0.0 { stddev / mean } else { 0.0 }),
+            "ci95_low_ms": stats.map(|(mean, _, margin)| mean - margin),
+            "ci95_high_ms": stats.map(|(mean, _, margin)| mean + margin),
+            "outliers": measured.then(|| outliers(&sorted)),
+            "first_error": self.first_error,
+        })
+    }
+}
+
+pub async fn sweep(Json(payload): Json<SweepPayload>) -> Response<BoxBody> {
+    let Some(workload) = registry::find(&payload.workload) else {
+        return bad_request(format!("Unknown workload: {}", payload.workload));
+    };
+    if payload.sizes.is_empty() || payload.sizes.len() > MAX_SIZES {
+        return bad_request(format!("sizes must have between 1 and {} values", MAX_SIZES));
+    }
+    let repetitions = payload.repetitions.unwrap_or(5);
+    if repetitions == 0 || repetitions > MAX_REPETITIONS {
+        return bad_request(format!("repetitions must be between 1 and {}", MAX_REPETITIONS));
+    }
+    let warmup = payload.warmup.unwrap_or(1);
+    let csv = match payload.format.as_deref().unwrap_or("json") {
+        "json" => false,
+        "csv" => true,
+        other => return bad_request(format!("format must be json or csv, got {}", other)),
+    };
+    if payload.sizes.len().saturating_mul(repetitions.saturating_add(warmup)) > MAX_CALLS {
+        return bad_request(format!("sizes * (repetitions + warmup) must be at most {}", MAX_CALLS));
+    }
+    let base = payload
+        .payload
+        .unwrap_or_else(|| serde_json::from_str(workload.default_payload).unwrap());
+    if !base.is_object() {
+        return bad_request("payload must be an object".to_string());
+    }
+
+    let start = Instant::now();
+    let mut points = Vec::with_capacity(payload.sizes.len());
+    for size in payload.sizes {
+        let mut body = base.clone();
+        body[payload.parameter.as_str()] = serde_json::Value::Number(size.clone());
+        let mut point = Point { size, latencies_ms: Vec::with_capacity(repetitions), errors: 0, response_bytes: 0, first_error: None };
+        for call in 0..warmup + repetitions {
+            let call_start = Instant::now();
+            let response = (workload.run)(body.clone()).await;
+            let status = response.status();
+            let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap_or_default();
+            let call_ms = call_start.elapsed().as_secs_f64() * 1000.0;
+            if call < warmup {
+                continue;
+            }
+            if status.is_success() {
+                point.latencies_ms.push(call_ms);
+                point.response_bytes = bytes.len();
+            } else {
+                point.errors += 1;
+                point.first_error.get_or_insert_with(|| {
+                    let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap_or_default();
+                    serde_json::json!({ "status": status.as_u16(), "error": body.get("error") })
+                });
+            }
+        }
+        points.push(point);
+    }
+    let duration = start.elapsed();
+    let errors: usize = points.iter().map(|p| p.errors).sum();
+    let points: Vec<serde_json::Value> = points.iter().map(Point::to_json).collect();
+
+    if csv {
+        return csv_response(&points);
+    }
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "workload": workload.name,
+            "parameter": payload.parameter,
+            "repetitions": repetitions,
+            "warmup": warmup,
+            "errors": errors,
+            "points": points,
+            "duration_ms": duration.as_secs_f64() * 1000.0,
+        }))
+    )
+    .into_response()
+}
+
+fn csv_response(points: &[serde_json::Value]) -> Response<BoxBody> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let _ = writer.write_record(CSV_COLUMNS);
+    for point in points {
+        // Estatística sem chamadas boas fica vazia, não "null"
+        let _ = writer.write_record(CSV_COLUMNS.iter().map(|column| match &point[*column] {
+            serde_json::Value::Null => String::new(),
+            value => value.to_string(),
+        }));
+    }
+    let mut response = Response::new(boxed(Full::from(writer.into_inner().unwrap_or_default())));
+    response.headers_mut().insert(header::CONTENT_TYPE, HeaderValue::from_static("text/csv; charset=utf-8"));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn point(latencies_ms: &[f64], errors: usize) -> serde_json::Value {
+        Point {
+            size: 1.into(),
+            latencies_ms: latencies_ms.to_vec(),
+            errors,
+            response_bytes: 0,
+            first_error: None,
+        }
+        .to_json()
+    }
+
+    #[test]
+    fn no_successful_calls_leave_null_stats() {
+        let point = point(&[], 3);
+        assert_eq!(point["calls"], 3);
+        for column in &CSV_COLUMNS[4..] {
+            assert!(point[*column].is_null(), "{}", column);
+        }
+    }
+
+    #[test]
+    fn single_call_has_no_spread() {
+        assert_eq!(spread(&[4.0]), (4.0, 0.0, 0.0));
+        assert_eq!(percentile(&[4.0], 0.5), 4.0);
+        assert_eq!(percentile(&[4.0], 0.95), 4.0);
+        assert_eq!(outliers(&[4.0]), 0);
+    }
+
+    #[test]
+    fn two_calls_use_one_degree_of_freedom() {
+        let (mean, stddev, margin) = spread(&[1.0, 3.0]);
+        assert_eq!(mean, 2.0);
+        assert!((stddev - 2f64.sqrt()).abs() < 1e-12);
+        assert!((margin - 12.706 * stddev / 2f64.sqrt()).abs() < 1e-12);
+        assert_eq!(percentile(&[1.0, 3.0], 0.5), 1.0);
+        assert_eq!(percentile(&[1.0, 3.0], 0.95), 3.0);
+    }
+
+    #[test]
+    fn t_table_ends_at_thirty_degrees_of_freedom() {
+        let t = |n: usize| {
+            let values: Vec<f64> = (0..n).map(|i| (i % 2) as f64).collect();
+            let (_, stddev, margin) = spread(&values);
+            margin * (n as f64).sqrt() / stddev
+        };
+        assert!((t(3) - 4.303).abs() < 1e-9);
+        assert!((t(31) - 2.042).abs() < 1e-9);
+        assert!((t(32) - 1.96).abs() < 1e-9);
+    }
+
+    #[test]
+    fn equal_latencies_have_zero_mad() {
+        assert_eq!(outliers(&[5.0; 6]), 0);
+        // Com MAD zero, tudo que não é a mediana conta
+        assert_eq!(outliers(&[5.0, 5.0, 5.0, 9.0]), 1);
+        assert_eq!(point(&[5.0; 6], 0)["stddev_ms"], 0.0);
+        assert_eq!(point(&[5.0; 6], 0)["cv"], 0.0);
+    }
+
+    #[test]
+    fn modified_z_flags_far_latencies() {
+        assert_eq!(outliers(&[10.0, 10.5, 11.0, 11.5, 12.0, 50.0]), 1);
+        assert_eq!(outliers(&[10.0, 10.5, 11.0, 11.5, 12.0, 13.0]), 0);
+    }
+
+    #[test]
+    fn failed_calls_are_counted_but_not_measured() {
+        let point = point(&[1.0, 2.0, 3.0], 2);
+        assert_eq!(point["calls"], 5);
+        assert_eq!(point["errors"], 2);
+        assert_eq!(point["min_ms"], 1.0);
+        assert_eq!(point["mean_ms"], 2.0);
+        assert_eq!(point["max_ms"], 3.0);
+    }
+
+    #[tokio::test]
+    async fn sweep_excludes_failing_sizes_from_stats() {
+        let payload = json!({
+            "workload": "json/patch",
+            "parameter": "iterations",
+            "sizes": [1, 0],
+            "payload": { "users": 2, "operations": 2 },
+            "repetitions": 3,
+            "warmup": 0,
+        });
+        let response = sweep(Json(serde_json::from_value(payload).unwrap())).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value =
+            serde_json::from_slice(&hyper::body::to_bytes(response.into_body()).await.unwrap()).unwrap();
+
+        assert_eq!(body["errors"], 3);
+        let (ok, failed) = (&body["points"][0], &body["points"][1]);
+        assert_eq!(ok["errors"], 0);
+        assert!(ok["mean_ms"].is_number());
+        assert_eq!(failed["calls"], 3);
+        assert_eq!(failed["errors"], 3);
+        assert!(failed["mean_ms"].is_null());
+        assert!(failed["p95_ms"].is_null());
+        assert_eq!(failed["first_error"]["status"], 400);
+        assert_eq!(failed["first_error"]["error"], "iterations, users or operations out of range");
+    }
+}
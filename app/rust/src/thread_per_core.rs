@@ -0,0 +1,84 @@
+// ======================
+// RUNTIME THREAD-PER-CORE
+// ======================
+//
+// Com `BFF_RUNTIME=thread-per-core`, o servidor local não usa o runtime
+// multi-thread (work-stealing) do tokio: sobe `BFF_WORKERS` threads, cada uma
+// presa a um core e com o seu próprio runtime single-thread e o seu próprio
+// listener com SO_REUSEPORT. Uma conexão fica do começo ao fim no worker que
+// o kernel escolheu, sem roubo de tarefas entre threads.
+//
+// Toda resposta leva `X-Runtime`: `work-stealing` ou `thread-per-core/<n>`,
+// com o número do worker que atendeu.
+
+use std::{cell::Cell, net::SocketAddr};
+
+use axum::http::{HeaderValue, Response};
+
+use crate::{config::CONFIG, listener};
+
+thread_local! {
+    static WORKER: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// Marca a resposta com o modo de runtime (e o worker) que a atendeu.
+pub fn tag<B>(mut response: Response<B>) -> Response<B> {
+    let value = match WORKER.with(Cell::get) {
+        Some(worker) => HeaderValue::from_str(&format!("thread-per-core/{}", worker)).unwrap(),
+        None => HeaderValue::from_static("work-stealing"),
+    };
+    response.headers_mut().insert("X-Runtime", value);
+    response
+}
+
+/// Quantos workers sobem: `BFF_WORKERS`, ou um por core se for 0.
+pub fn workers() -> usize {
+    match CONFIG.workers {
+        0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    }
+}
+
+pub fn run(addr: SocketAddr) {
+    let workers = workers();
+    let cores = core_affinity::get_core_ids().unwrap_or_default();
+    println!(
+        "Rodando local (thread-per-core, {} workers) em http://127.0.0.1:{}",
+        workers,
+        addr.port()
+    );
+
+    // Todos os listeners abrem aqui, antes dos workers, para o evento de
+    // startup já sair com as opções de socket efetivas
+    let listeners: Vec<_> = (0..workers).map(|_| listener::bind(addr, true)).collect();
+    crate::info::startup(Some(addr));
+
+    let threads: Vec<_> = listeners
+        .into_iter()
+        .enumerate()
+        .map(|(worker, listener)| {
+            let core = (!cores.is_empty()).then(|| cores[worker % cores.len()]);
+            std::thread::Builder::new()
+                .name(format!("worker-{}", worker))
+                .spawn(move || {
+                    if let Some(core) = core {
+                        if !core_affinity::set_for_current(core) {
+                            eprintln!("worker {}: não foi possível fixar no core {}", worker, core.id);
+                        }
+                    }
+                    WORKER.with(|w| w.set(Some(worker)));
+
+                    let runtime = tokio::runtime::Builder::new_current_thread()
+                        .enable_all()
+                        .build()
+                        .unwrap();
+                    runtime.block_on(crate::serve(listener));
+                })
+                .unwrap()
+        })
+        .collect();
+
+    for thread in threads {
+        let _ = thread.join();
+    }
+}
@@ -0,0 +1,134 @@
+// ======================
+// TOKENIZER BPE (estilo tiktoken)
+// ======================
+//
+// Vocabulário embutido em `bpe_ranks.tiktoken` no mesmo formato do tiktoken
+// (token em base64 + rank por linha). Os 256 primeiros ranks são os bytes
+// crus, então qualquer texto UTF-8 é tokenizável.
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    time::Instant,
+};
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct TokenizePayload {
+    text: Option<String>,
+    include_tokens: Option<bool>,
+}
+
+static RANKS: Lazy<HashMap<Vec<u8>, u32>> = Lazy::new(|| {
+    use base64::{engine::general_purpose, Engine as _};
+
+    include_str!("bpe_ranks.tiktoken")
+        .lines()
+        .filter_map(|line| {
+            let (token, rank) = line.split_once(' ')?;
+            let token = general_purpose::STANDARD.decode(token).ok()?;
+            Some((token, rank.parse().ok()?))
+        })
+        .collect()
+});
+
+// Pré-tokenização no estilo GPT-2. O crate `regex` não suporta lookahead,
+// então o `\s+(?!\S)` original virou apenas `\s+`.
+static PRETOKENIZER: Lazy<regex::Regex> = Lazy::new(|| {
+    regex::Regex::new(r"'s|'t|'re|'ve|'m|'ll|'d| ?\p{L}+| ?\p{N}+| ?[^\s\p{L}\p{N}]+|\s+")
+        .unwrap()
+});
+
+/// Aplica os merges de menor rank até não restar nenhum par conhecido,
+/// igual ao `byte_pair_merge` do tiktoken: a cada passo junta o par de menor
+/// rank, e no empate o mais à esquerda.
+///
+/// Em vez de reavaliar todos os pares a cada merge (quadrático: uma palavra
+/// de 160 KB sem espaços levava minutos), os candidatos ficam num heap e só os
+/// vizinhos do merge são recalculados. Entradas que ficaram velhas são
+/// descartadas quando saem do heap.
+fn byte_pair_encode(piece: &[u8], ranks: &HashMap<Vec<u8>, u32>) -> Vec<u32> {
+    if let Some(rank) = ranks.get(piece) {
+        return vec![*rank];
+    }
+
+    // Cada parte é identificada pelo offset onde começa; vai até next[start]
+    let len = piece.len();
+    let mut next: Vec<usize> = (1..=len).collect();
+    let mut prev: Vec<usize> = (0..len).map(|i| i.wrapping_sub(1)).collect();
+    let mut heap = BinaryHeap::new();
+
+    // Par formado pela parte `start` e a seguinte, se existir e tiver rank
+    let pair = |next: &[usize], start: usize| {
+        let end = *next.get(*next.get(start)?)?;
+        ranks.get(&piece[start..end]).map(|rank| Reverse((*rank, start, end)))
+    };
+    heap.extend((0..len).filter_map(|start| pair(&next, start)));
+
+    while let Some(Reverse((_, start, end))) = heap.pop() {
+        // Velha: a parte sumiu ou o vizinho já mudou
+        if next[start] == usize::MAX || next.get(next[start]) != Some(&end) {
+            continue;
+        }
+        let right = next[start];
+        next[start] = end;
+        next[right] = usize::MAX;
+        if end < len {
+            prev[end] = start;
+        }
+        heap.extend(pair(&next, start));
+        if prev[start] != usize::MAX {
+            heap.extend(pair(&next, prev[start]));
+        }
+    }
+
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    while start < len {
+        tokens.push(ranks[&piece[start..next[start]]]);
+        start = next[start];
+    }
+    tokens
+}
+
+pub fn encode(text: &str) -> Vec<u32> {
+    let ranks = &*RANKS;
+    PRETOKENIZER
+        .find_iter(text)
+        .flat_map(|m| byte_pair_encode(m.as_str().as_bytes(), ranks))
+        .collect()
+}
+
+pub async fn tokenize(Json(payload): Json<TokenizePayload>) -> Response<BoxBody> {
+    let Some(text) = &payload.text else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "Text is required" }))
+        )
+        .into_response();
+    };
+
+    let start = Instant::now();
+    let tokens = encode(text);
+    let duration = start.elapsed();
+
+    let mut body = serde_json::json!({
+        "token_count": tokens.len(),
+        "byte_count": text.len(),
+        "vocab_size": RANKS.len(),
+        "duration_ms": duration.as_secs_f64() * 1000.0,
+    });
+    if payload.include_tokens.unwrap_or(false) {
+        body["tokens"] = serde_json::json!(tokens);
+    }
+
+    (StatusCode::OK, Json(body)).into_response()
+}
@@ -0,0 +1,204 @@
+// ======================
+// MIDDLEWARE: MÉTRICAS PÓS-RESPOSTA (trailers)
+// ======================
+//
+// Os headers de tempo (TimingLayer) saem antes do corpo, então não contam o
+// envio dele. Com `BFF_RESPONSE_METRICS=true`, o corpo de cada resposta passa
+// por um contador e, quando termina, as métricas finais saem:
+//
+// - como trailers (`X-Response-Duration-Ms`, `X-Response-Bytes`), se a conexão
+//   é HTTP/2 e o cliente mandou `TE: trailers`. O servidor local aceita h2c
+//   (ex.: `curl --http2-prior-knowledge`); o HTTP/1 do hyper 0.14 não escreve
+//   trailers e o API Gateway bufferiza a resposta inteira
+// - senão, numa linha JSON (`"event": "response_complete"`) no stdout, que na
+//   Lambda vai para o CloudWatch Logs
+//
+// A duração vai da chegada da request até o último byte entregue ao hyper
+// (não até o cliente confirmar). Corpo que não chegou ao fim, porque o
+// cliente desconectou, sai no log com `"complete": false`.
+
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::atomic::{AtomicU64, Ordering},
+    task::{Context, Poll},
+    time::Instant,
+};
+
+use axum::{
+    body::{boxed, BoxBody, Bytes, HttpBody},
+    http::{header, HeaderMap, HeaderValue, Method, Request, Response, Version},
+};
+use tower::{Layer, Service};
+
+use crate::{config::CONFIG, run::Run};
+
+static TRAILERS: AtomicU64 = AtomicU64::new(0);
+static LOGGED: AtomicU64 = AtomicU64::new(0);
+static INCOMPLETE: AtomicU64 = AtomicU64::new(0);
+
+pub fn snapshot() -> serde_json::Value {
+    serde_json::json!({
+        "enabled": CONFIG.response_metrics,
+        "trailers": TRAILERS.load(Ordering::Relaxed),
+        "logged": LOGGED.load(Ordering::Relaxed),
+        "incomplete": INCOMPLETE.load(Ordering::Relaxed),
+    })
+}
+
+struct MetricsBody {
+    inner: BoxBody,
+    start: Instant,
+    bytes: u64,
+    complete: bool,
+    // Só com HTTP/2 + `TE: trailers`
+    trailers: bool,
+    sent: bool,
+    method: Method,
+    path: String,
+    status: u16,
+    version: Version,
+    // O corpo termina fora do escopo da request; o run é capturado antes
+    run: Run,
+}
+
+impl MetricsBody {
+    fn duration_ms(&self) -> f64 {
+        self.start.elapsed().as_secs_f64() * 1000.0
+    }
+}
+
+impl HttpBody for MetricsBody {
+    type Data = Bytes;
+    type Error = axum::Error;
+
+    fn poll_data(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Bytes, axum::Error>>> {
+        let poll = Pin::new(&mut self.inner).poll_data(cx);
+        match &poll {
+            Poll::Ready(Some(Ok(data))) => self.bytes += data.len() as u64,
+            Poll::Ready(None) => self.complete = true,
+            _ => {}
+        }
+        poll
+    }
+
+    fn poll_trailers(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<Option<HeaderMap>, axum::Error>> {
+        let mut trailers = match Pin::new(&mut self.inner).poll_trailers(cx) {
+            Poll::Ready(Ok(trailers)) => trailers.unwrap_or_default(),
+            other => return other,
+        };
+        if !self.trailers || self.sent {
+            return Poll::Ready(Ok((!trailers.is_empty()).then_some(trailers)));
+        }
+        self.sent = true;
+        self.complete = true;
+        trailers.insert("X-Response-Duration-Ms", HeaderValue::from_str(&format!("{:.3}", self.duration_ms())).unwrap());
+        trailers.insert("X-Response-Bytes", HeaderValue::from(self.bytes));
+        TRAILERS.fetch_add(1, Ordering::Relaxed);
+        Poll::Ready(Ok(Some(trailers)))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        // Com trailers pendentes o hyper precisa chamar poll_trailers
+        (!self.trailers || self.sent) && self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> hyper::body::SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+impl Drop for MetricsBody {
+    fn drop(&mut self) {
+        // Trailers já mandados: nada a logar
+        if self.sent {
+            return;
+        }
+        // Full e Empty terminam sem que o hyper peça o próximo chunk
+        let complete = self.complete || self.inner.is_end_stream();
+        if !complete {
+            INCOMPLETE.fetch_add(1, Ordering::Relaxed);
+        }
+        LOGGED.fetch_add(1, Ordering::Relaxed);
+        let mut event = serde_json::json!({
+            "event": "response_complete",
+            "method": self.method.as_str(),
+            "path": self.path,
+            "status": self.status,
+            "protocol": format!("{:?}", self.version),
+            "bytes": self.bytes,
+            "duration_ms": self.duration_ms(),
+            "complete": complete,
+        });
+        self.run.annotate(&mut event);
+        println!("{}", event);
+    }
+}
+
+#[derive(Clone)]
+pub struct TrailersLayer;
+
+#[derive(Clone)]
+pub struct TrailersService<S> {
+    inner: S,
+}
+
+impl<S> Layer<S> for TrailersLayer {
+    type Service = TrailersService<S>;
+    fn layer(&self, inner: S) -> Self::Service {
+        TrailersService { inner }
+    }
+}
+
+impl<S, ReqBody> Service<Request<ReqBody>> for TrailersService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+{
+    type Response = Response<BoxBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        let mut service = self.inner.clone();
+        if !CONFIG.response_metrics {
+            return Box::pin(async move { service.call(req).await });
+        }
+
+        let start = Instant::now();
+        let method = req.method().clone();
+        let path = req.uri().path().to_string();
+        let version = req.version();
+        let run = crate::run::current();
+        let wants_trailers = version == Version::HTTP_2
+            && req
+                .headers()
+                .get(header::TE)
+                .and_then(|v| v.to_str().ok())
+                .is_some_and(|v| v.split(',').any(|t| t.trim().eq_ignore_ascii_case("trailers")));
+
+        Box::pin(async move {
+            let response = service.call(req).await?;
+            let (parts, body) = response.into_parts();
+            let body = MetricsBody {
+                inner: body,
+                start,
+                bytes: 0,
+                complete: false,
+                trailers: wants_trailers,
+                sent: false,
+                method,
+                path,
+                status: parts.status.as_u16(),
+                version,
+                run,
+            };
+            Ok(Response::from_parts(parts, boxed(body)))
+        })
+    }
+}
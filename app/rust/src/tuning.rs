@@ -0,0 +1,194 @@
+// ======================
+// RECOMENDAÇÃO DE MEMÓRIA (POST /power-tuning)
+// ======================
+//
+// No estilo do AWS Lambda Power Tuning, mas sem state machine nem um deploy
+// por tamanho: mede o workload uma vez, nesta instância, e projeta a latência
+// e o custo em cada tamanho de `memory_mb`. A projeção usa a regra da Lambda
+// de que a CPU é proporcional à memória, com um vCPU inteiro em 1769 MB:
+//
+//   latência(m) = medida × (f × cpu(atual) / cpu(m) + 1 - f)
+//
+// onde `cpu(m) = min(m / 1769, 1)` e `f` (`cpu_bound_fraction`, padrão 1) é a
+// parte da latência que depende de CPU; o resto (rede, espera) não muda. A
+// CPU atual é a da memória desta Lambda ou, fora dela, um núcleo inteiro. O
+// custo vem da conta do cost.rs (duração cobrada × GB × preço, mais a
+// request), no preço da arquitetura em que o processo roda.
+//
+// `strategy` escolhe a recomendação: `cost` (a mais barata), `speed` (a mais
+// rápida, com o custo desempatando) ou `balanced`, que minimiza
+// `w × custo / menor custo + (1 - w) × latência / menor latência` com
+// `w = balanced_weight` (padrão 0,5). A projeção supõe um workload de uma
+// thread: acima de 1769 MB só melhora o que usa vários núcleos, e essa parte
+// não é modelada.
+
+use std::time::Duration;
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{
+    cost::{self, Model},
+    paired, registry,
+    sweep::{percentile, spread},
+};
+
+/// Memória com que a Lambda dá um vCPU inteiro
+const FULL_CPU_MB: f64 = 1769.0;
+const MIN_MEMORY_MB: u64 = 128;
+const MAX_MEMORY_MB: u64 = 10_240;
+const MAX_REPETITIONS: usize = 1_000;
+const DEFAULT_MEMORY_MB: &[u64] = &[128, 256, 512, 1024, 1536, 1769, 2048, 3008];
+
+#[derive(Deserialize)]
+pub struct TuningPayload {
+    workload: String,
+    payload: Option<Value>,
+    memory_mb: Option<Vec<u64>>,
+    repetitions: Option<usize>,
+    strategy: Option<String>,
+    balanced_weight: Option<f64>,
+    cpu_bound_fraction: Option<f64>,
+}
+
+fn bad_request(error: String) -> Response<BoxBody> {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({ "error": error }))
+    )
+    .into_response()
+}
+
+fn cpu_share(memory_mb: f64) -> f64 {
+    (memory_mb / FULL_CPU_MB).min(1.0)
+}
+
+struct Candidate {
+    memory_mb: u64,
+    projected_ms: f64,
+    billed_ms: u64,
+    cost_usd: f64,
+}
+
+impl Candidate {
+    fn to_json(&self) -> Value {
+        serde_json::json!({
+            "memory_mb": self.memory_mb,
+            "cpu_share": cpu_share(self.memory_mb as f64),
+            "projected_ms": self.projected_ms,
+            "billed_ms": self.billed_ms,
+            "cost_usd": self.cost_usd,
+            "cost_per_million_usd": self.cost_usd * 1e6,
+        })
+    }
+}
+
+pub async fn power_tuning(Json(payload): Json<TuningPayload>) -> Response<BoxBody> {
+    let Some(workload) = registry::find(&payload.workload) else {
+        return bad_request(format!("Unknown workload: {}", payload.workload));
+    };
+    let mut sizes = payload.memory_mb.unwrap_or_else(|| DEFAULT_MEMORY_MB.to_vec());
+    sizes.sort_unstable();
+    sizes.dedup();
+    if sizes.is_empty() || sizes.iter().any(|mb| !(MIN_MEMORY_MB..=MAX_MEMORY_MB).contains(mb)) {
+        return bad_request(format!("memory_mb must be sizes between {} and {}", MIN_MEMORY_MB, MAX_MEMORY_MB));
+    }
+    let repetitions = payload.repetitions.unwrap_or(10);
+    if repetitions == 0 || repetitions > MAX_REPETITIONS {
+        return bad_request(format!("repetitions must be between 1 and {}", MAX_REPETITIONS));
+    }
+    let strategy = payload.strategy.as_deref().unwrap_or("balanced");
+    if !["cost", "speed", "balanced"].contains(&strategy) {
+        return bad_request(format!("strategy must be cost, speed or balanced, got {}", strategy));
+    }
+    let weight = payload.balanced_weight.unwrap_or(0.5);
+    let cpu_bound = payload.cpu_bound_fraction.unwrap_or(1.0);
+    if !(0.0..=1.0).contains(&weight) || !(0.0..=1.0).contains(&cpu_bound) {
+        return bad_request("balanced_weight and cpu_bound_fraction must be between 0 and 1".to_string());
+    }
+    let body = payload
+        .payload
+        .unwrap_or_else(|| serde_json::from_str(workload.default_payload).unwrap());
+
+    // Uma chamada de aquecimento, descartada
+    let mut latencies = Vec::with_capacity(repetitions);
+    for call in 0..=repetitions {
+        match paired::call(workload, &body).await {
+            Ok(ms) if call > 0 => latencies.push(ms),
+            Ok(_) => {}
+            Err((status, error)) => {
+                return bad_request(format!("Workload failed with status {}: {}", status, error));
+            }
+        }
+    }
+    latencies.sort_by(f64::total_cmp);
+    let measured_ms = percentile(&latencies, 0.5);
+    let (mean, stddev, margin) = spread(&latencies);
+
+    let lambda_memory = cost::MODEL.as_ref().filter(|model| model.source == "lambda").map(|model| model.memory_mb);
+    let current_share = lambda_memory.map_or(1.0, |mb| cpu_share(mb as f64));
+    let pricing = cost::pricing();
+    let candidates: Vec<Candidate> = sizes
+        .iter()
+        .map(|&memory_mb| {
+            let scale = cpu_bound * current_share / cpu_share(memory_mb as f64) + 1.0 - cpu_bound;
+            let projected_ms = measured_ms * scale;
+            let billed_ms = cost::billed_ms(Duration::from_secs_f64(projected_ms / 1000.0));
+            let model = Model { memory_mb, source: "tuning", pricing };
+            Candidate { memory_mb, projected_ms, billed_ms, cost_usd: model.invocation_usd(billed_ms) }
+        })
+        .collect();
+
+    let cheapest = candidates.iter().min_by(|a, b| a.cost_usd.total_cmp(&b.cost_usd)).unwrap();
+    let fastest = candidates
+        .iter()
+        .min_by(|a, b| a.projected_ms.total_cmp(&b.projected_ms).then(a.cost_usd.total_cmp(&b.cost_usd)))
+        .unwrap();
+    let score = |c: &Candidate| {
+        weight * c.cost_usd / cheapest.cost_usd + (1.0 - weight) * c.projected_ms / fastest.projected_ms.max(f64::MIN_POSITIVE)
+    };
+    let recommended = match strategy {
+        "cost" => cheapest,
+        "speed" => fastest,
+        _ => candidates.iter().min_by(|a, b| score(a).total_cmp(&score(b))).unwrap(),
+    };
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "workload": workload.name,
+            "repetitions": repetitions,
+            "measured": {
+                "memory_mb": lambda_memory,
+                "cpu_share": current_share,
+                "p50_ms": measured_ms,
+                "mean_ms": mean,
+                "stddev_ms": stddev,
+                "ci95_low_ms": mean - margin,
+                "ci95_high_ms": mean + margin,
+            },
+            "assumptions": {
+                "cpu_bound_fraction": cpu_bound,
+                "full_cpu_memory_mb": FULL_CPU_MB,
+                "architecture": pricing.architecture,
+                "billing_granularity_ms": crate::config::CONFIG.billing_granularity_ms.max(1),
+            },
+            "candidates": candidates.iter().map(Candidate::to_json).collect::<Vec<_>>(),
+            "cheapest_mb": cheapest.memory_mb,
+            "fastest_mb": fastest.memory_mb,
+            "recommendation": {
+                "strategy": strategy,
+                "memory_mb": recommended.memory_mb,
+                "projected_ms": recommended.projected_ms,
+                "cost_usd": recommended.cost_usd,
+            },
+        }))
+    )
+    .into_response()
+}
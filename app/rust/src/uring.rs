@@ -0,0 +1,335 @@
+// ======================
+// SERVIDOR IO_URING (feature "uring", experimental, só Linux)
+// ======================
+//
+// Um segundo listener, em `BFF_URING_PORT`, cujo accept/read/write passa pelo
+// io_uring (tokio-uring) em vez do epoll do tokio. Roda numa thread própria,
+// com o runtime single-thread do tokio-uring, ao lado do servidor axum.
+//
+// Só existem POST /echo e GET /stream, com a mesma semântica e os mesmos
+// limites do echo.rs, e o HTTP/1.1 é o mínimo para eles: Content-Length
+// obrigatório no corpo (sem chunked na request) e keep-alive por padrão.
+// Toda resposta leva `X-Server: uring` e o `X-Config-Hash`.
+//
+// Clientes HTTP/1.0 (alguns geradores de carga antigos) recebem o que o hyper
+// daria a eles: keep-alive só com `Connection: keep-alive`, confirmado na
+// resposta, e o /stream sem chunked, terminado pelo fechamento da conexão. O
+// alvo em forma absoluta (`POST http://host:porta/echo`) vale o mesmo que o
+// caminho; outra versão do protocolo recebe 505.
+
+use std::{io, net::SocketAddr};
+
+use tokio_uring::net::{TcpListener, TcpStream};
+
+use crate::{
+    config::CONFIG_HASH,
+    echo::{self, StreamQuery},
+};
+
+const READ_BUFFER: usize = 16 * 1024;
+const MAX_HEAD: usize = 16 * 1024;
+// Mesmo limite padrão de corpo do axum
+const MAX_BODY: usize = 2 << 20;
+const CLOSE: &str = "Connection: close\r\n";
+
+struct RequestHead {
+    method: String,
+    path: String,
+    query: String,
+    content_length: usize,
+    keep_alive: bool,
+    http10: bool,
+}
+
+impl RequestHead {
+    /// Cabeçalho Connection da resposta, se o padrão da versão não bastar.
+    fn connection(&self) -> &'static str {
+        match (self.keep_alive, self.http10) {
+            (false, _) => CLOSE,
+            (true, true) => "Connection: keep-alive\r\n",
+            (true, false) => "",
+        }
+    }
+}
+
+pub fn spawn(addr: SocketAddr) {
+    std::thread::Builder::new()
+        .name("uring".to_string())
+        .spawn(move || {
+            let runtime = match tokio_uring::Runtime::new(&tokio_uring::builder()) {
+                Ok(runtime) => runtime,
+                Err(error) => {
+                    eprintln!("io_uring indisponível, servidor uring desativado: {}", error);
+                    return;
+                }
+            };
+            runtime.block_on(async move {
+                let listener = match TcpListener::bind(addr) {
+                    Ok(listener) => listener,
+                    Err(error) => {
+                        eprintln!("servidor uring não conseguiu abrir {}: {}", addr, error);
+                        return;
+                    }
+                };
+                println!("Rodando io_uring em http://127.0.0.1:{} (/echo, /stream)", addr.port());
+                loop {
+                    match listener.accept().await {
+                        Ok((stream, _)) => {
+                            let _ = stream.set_nodelay(crate::config::CONFIG.tcp_nodelay);
+                            tokio_uring::spawn(async move {
+                                // Erro de I/O só encerra a conexão
+                                let _ = connection(stream).await;
+                            });
+                        }
+                        Err(error) => eprintln!("accept no servidor uring falhou: {}", error),
+                    }
+                }
+            });
+        })
+        .unwrap();
+}
+
+async fn connection(stream: TcpStream) -> io::Result<()> {
+    let mut pending: Vec<u8> = Vec::new();
+    let mut read_buffer: Vec<u8> = Vec::with_capacity(READ_BUFFER);
+
+    loop {
+        // Cabeçalho: lê até o \r\n\r\n
+        let head_end = loop {
+            if let Some(pos) = pending.windows(4).position(|w| w == b"\r\n\r\n") {
+                break pos + 4;
+            }
+            if pending.len() > MAX_HEAD {
+                return write_error(&stream, "431 Request Header Fields Too Large", "Request head too large", CLOSE).await;
+            }
+            if read_more(&stream, &mut pending, &mut read_buffer).await? == 0 {
+                return Ok(());
+            }
+        };
+        let head = match parse_head(&pending[..head_end]) {
+            Ok(head) => head,
+            Err((status, error)) => return write_error(&stream, status, error, CLOSE).await,
+        };
+        pending.drain(..head_end);
+
+        if head.content_length > MAX_BODY {
+            return write_error(&stream, "413 Payload Too Large", "Body larger than 2 MiB", CLOSE).await;
+        }
+        while pending.len() < head.content_length {
+            if read_more(&stream, &mut pending, &mut read_buffer).await? == 0 {
+                return Ok(());
+            }
+        }
+        let body: Vec<u8> = pending.drain(..head.content_length).collect();
+
+        match (head.method.as_str(), head.path.as_str()) {
+            ("POST", "/echo") => {
+                let mut response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/octet-stream\r\nContent-Length: {}\r\nX-Server: uring\r\nX-Config-Hash: {}\r\n{}\r\n",
+                    body.len(),
+                    *CONFIG_HASH,
+                    head.connection()
+                )
+                .into_bytes();
+                response.extend_from_slice(&body);
+                stream.write_all(response).await.0?;
+            }
+            // HTTP/1.0 não tem chunked: o corpo vai cru e termina quando a conexão fecha
+            ("GET", "/stream") if head.http10 => match parse_query(&head.query).resolve() {
+                Ok((chunks, chunk_size)) => {
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/octet-stream\r\nX-Server: uring\r\nX-Config-Hash: {}\r\n{}\r\n",
+                        *CONFIG_HASH, CLOSE
+                    );
+                    stream.write_all(response.into_bytes()).await.0?;
+                    for i in 0..chunks {
+                        stream.write_all(echo::chunk(i, chunk_size).to_vec()).await.0?;
+                    }
+                    return Ok(());
+                }
+                Err(error) => write_error(&stream, "400 Bad Request", error, head.connection()).await?,
+            },
+            ("GET", "/stream") => match parse_query(&head.query).resolve() {
+                Ok((chunks, chunk_size)) => {
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/octet-stream\r\nTransfer-Encoding: chunked\r\nX-Server: uring\r\nX-Config-Hash: {}\r\n{}\r\n",
+                        *CONFIG_HASH,
+                        head.connection()
+                    );
+                    stream.write_all(response.into_bytes()).await.0?;
+                    for i in 0..chunks {
+                        let mut frame = format!("{:x}\r\n", chunk_size).into_bytes();
+                        frame.extend_from_slice(&echo::chunk(i, chunk_size));
+                        frame.extend_from_slice(b"\r\n");
+                        stream.write_all(frame).await.0?;
+                    }
+                    stream.write_all(b"0\r\n\r\n".to_vec()).await.0?;
+                }
+                Err(error) => write_error(&stream, "400 Bad Request", error, head.connection()).await?,
+            },
+            (_, "/echo") | (_, "/stream") => {
+                write_error(&stream, "405 Method Not Allowed", "Method not allowed", head.connection()).await?
+            }
+            _ => write_error(&stream, "404 Not Found", "Only /echo and /stream are served here", head.connection()).await?,
+        }
+
+        if !head.keep_alive {
+            return Ok(());
+        }
+    }
+}
+
+async fn read_more(stream: &TcpStream, pending: &mut Vec<u8>, read_buffer: &mut Vec<u8>) -> io::Result<usize> {
+    // O tokio-uring fica com o buffer durante a operação e o devolve no fim
+    let (result, buffer) = stream.read(std::mem::take(read_buffer)).await;
+    *read_buffer = buffer;
+    let n = result?;
+    pending.extend_from_slice(&read_buffer[..n]);
+    read_buffer.clear();
+    Ok(n)
+}
+
+async fn write_error(stream: &TcpStream, status: &str, message: &str, connection: &str) -> io::Result<()> {
+    let body = serde_json::json!({ "error": message }).to_string();
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nX-Server: uring\r\nX-Config-Hash: {}\r\n{}\r\n{}",
+        status,
+        body.len(),
+        *CONFIG_HASH,
+        connection,
+        body
+    );
+    stream.write_all(response.into_bytes()).await.0
+}
+
+/// Tira `esquema://autoridade` de um alvo em forma absoluta; o resto fica como está.
+fn origin_form(target: &str) -> &str {
+    let Some(scheme_end) = target.find("://") else {
+        return target;
+    };
+    if !target[..scheme_end].chars().all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c)) {
+        return target;
+    }
+    let rest = &target[scheme_end + 3..];
+    match rest.find(['/', '?']) {
+        Some(pos) if rest.as_bytes()[pos] == b'/' => &rest[pos..],
+        _ => "/",
+    }
+}
+
+fn parse_head(head: &[u8]) -> Result<RequestHead, (&'static str, &'static str)> {
+    const BAD: &str = "400 Bad Request";
+    let head = std::str::from_utf8(head).map_err(|_| (BAD, "Request head is not UTF-8"))?;
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split(' ');
+    let (method, target, version) = match (request_line.next(), request_line.next(), request_line.next()) {
+        (Some(method), Some(target), Some(version)) => (method, target, version),
+        _ => return Err((BAD, "Malformed request line")),
+    };
+    if version != "HTTP/1.0" && version != "HTTP/1.1" {
+        return Err(("505 HTTP Version Not Supported", "Only HTTP/1.0 and HTTP/1.1 are served here"));
+    }
+    let target = origin_form(target);
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    let mut content_length = 0;
+    let mut keep_alive = version == "HTTP/1.1";
+    for line in lines.filter(|line| !line.is_empty()) {
+        let (name, value) = line.split_once(':').ok_or((BAD, "Malformed header"))?;
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            content_length = value.parse().map_err(|_| (BAD, "Invalid Content-Length"))?;
+        } else if name.eq_ignore_ascii_case("transfer-encoding") {
+            return Err((BAD, "Chunked request bodies are not supported; send Content-Length"));
+        } else if name.eq_ignore_ascii_case("connection") {
+            keep_alive = !value.eq_ignore_ascii_case("close")
+                && (version == "HTTP/1.1" || value.eq_ignore_ascii_case("keep-alive"));
+        }
+    }
+
+    Ok(RequestHead {
+        method: method.to_string(),
+        path: path.to_string(),
+        query: query.to_string(),
+        content_length,
+        keep_alive,
+        http10: version == "HTTP/1.0",
+    })
+}
+
+// Valor que não é número fica de fora e cai no padrão, como um parâmetro ausente
+fn parse_query(query: &str) -> StreamQuery {
+    let mut params = StreamQuery { chunks: None, chunk_size: None };
+    for pair in query.split('&') {
+        match pair.split_once('=') {
+            Some(("chunks", value)) => params.chunks = value.parse().ok(),
+            Some(("chunk_size", value)) => params.chunk_size = value.parse().ok(),
+            _ => {}
+        }
+    }
+    params
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn head(raw: &str) -> Result<RequestHead, (&'static str, &'static str)> {
+        parse_head(raw.as_bytes())
+    }
+
+    #[test]
+    fn http10_closes_unless_keep_alive_is_asked() {
+        let plain = head("POST /echo HTTP/1.0\r\nContent-Length: 5\r\n\r\n").unwrap();
+        assert!(plain.http10);
+        assert!(!plain.keep_alive);
+        assert_eq!(plain.connection(), CLOSE);
+        assert_eq!((plain.method.as_str(), plain.path.as_str(), plain.content_length), ("POST", "/echo", 5));
+
+        let kept = head("GET /stream HTTP/1.0\r\nConnection: Keep-Alive\r\n\r\n").unwrap();
+        assert!(kept.keep_alive);
+        assert_eq!(kept.connection(), "Connection: keep-alive\r\n");
+    }
+
+    #[test]
+    fn http11_keeps_alive_by_default() {
+        let head = head("GET /stream HTTP/1.1\r\nHost: x\r\n\r\n").unwrap();
+        assert!(!head.http10);
+        assert!(head.keep_alive);
+        assert_eq!(head.connection(), "");
+    }
+
+    #[test]
+    fn absolute_form_target_is_reduced_to_the_path() {
+        let head = head("GET http://127.0.0.1:3001/stream?chunks=2 HTTP/1.0\r\n\r\n").unwrap();
+        assert_eq!(head.path, "/stream");
+        assert_eq!(head.query, "chunks=2");
+
+        assert_eq!(origin_form("HTTPS://example.com/echo"), "/echo");
+        assert_eq!(origin_form("http://example.com"), "/");
+        assert_eq!(origin_form("http://example.com?x=1"), "/");
+        assert_eq!(origin_form("/echo"), "/echo");
+        assert_eq!(origin_form("/redirect?to=http://x/y"), "/redirect?to=http://x/y");
+    }
+
+    #[test]
+    fn other_versions_get_505() {
+        for version in ["HTTP/2.0", "HTTP/0.9", "http/1.1"] {
+            let Err((status, _)) = head(&format!("GET /echo {}\r\n\r\n", version)) else {
+                panic!("{} was accepted", version);
+            };
+            assert_eq!(status, "505 HTTP Version Not Supported");
+        }
+    }
+
+    #[test]
+    fn chunked_bodies_and_bad_lengths_are_rejected() {
+        let chunked = head("POST /echo HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n");
+        assert!(matches!(chunked, Err(("400 Bad Request", message)) if message.contains("Chunked")));
+
+        for length in ["abc", "-1", ""] {
+            let bad = head(&format!("POST /echo HTTP/1.1\r\nContent-Length: {}\r\n\r\n", length));
+            assert!(matches!(bad, Err(("400 Bad Request", "Invalid Content-Length"))), "{:?}", length);
+        }
+    }
+}
@@ -0,0 +1,105 @@
+// ======================
+// GERAÇÃO DE UUIDS EM LOTE
+// ======================
+//
+// Gera `count` UUIDs (`version` v4, aleatório, ou v7, ordenado pelo horário)
+// e devolve o array. A geração e a serialização do array para JSON são medidas
+// separadamente: com um lote grande, o tempo passa a ser quase todo alocação
+// e serializador, não o gerador. O array serializado entra na resposta como
+// está (RawValue), sem ser serializado de novo.
+//
+// Os UUIDs vêm do gerador do sistema, não da seed: é o que um serviço de
+// verdade usaria.
+
+use std::time::Instant;
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
+use uuid::Uuid;
+
+use crate::estimate::{self, Estimate};
+
+const MAX_COUNT: usize = 1_000_000;
+
+#[derive(Deserialize)]
+pub struct UuidPayload {
+    count: Option<usize>,
+    version: Option<String>,
+    dry_run: Option<bool>,
+}
+
+#[derive(Serialize)]
+struct UuidResponse {
+    version: String,
+    count: usize,
+    generate_ms: f64,
+    serialize_ms: f64,
+    serialized_bytes: usize,
+    ids_per_second: f64,
+    ids: Box<RawValue>,
+}
+
+fn bad_request(error: String) -> Response<BoxBody> {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({ "error": error }))
+    )
+    .into_response()
+}
+
+pub async fn uuid(Json(payload): Json<UuidPayload>) -> Response<BoxBody> {
+    let count = payload.count.unwrap_or(1000);
+    if count == 0 || count > MAX_COUNT {
+        return bad_request(format!("count must be between 1 and {}", MAX_COUNT));
+    }
+    let version = payload.version.unwrap_or_else(|| "v4".to_string());
+    let generate: fn() -> Uuid = match version.as_str() {
+        "v4" => Uuid::new_v4,
+        "v7" => Uuid::now_v7,
+        other => return bad_request(format!("version must be v4 or v7, got {}", other)),
+    };
+
+    if payload.dry_run.unwrap_or(false) {
+        // 16 bytes por UUID e 39 no JSON (36 caracteres, aspas e vírgula)
+        return estimate::response(Estimate {
+            iterations: count as u64,
+            unit: "UUIDs",
+            allocation_bytes: count as u64 * (16 + 39),
+            output_bytes: 200 + count as u64 * 39,
+        });
+    }
+
+    let start = Instant::now();
+    let ids: Vec<Uuid> = (0..count).map(|_| generate()).collect();
+    let generate_duration = start.elapsed();
+
+    let start = Instant::now();
+    let ids = match serde_json::value::to_raw_value(&ids) {
+        Ok(ids) => ids,
+        Err(error) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": format!("Serialization failed: {}", error) }))
+            )
+            .into_response();
+        }
+    };
+    let serialize_duration = start.elapsed();
+
+    let response = UuidResponse {
+        version,
+        count,
+        generate_ms: generate_duration.as_secs_f64() * 1000.0,
+        serialize_ms: serialize_duration.as_secs_f64() * 1000.0,
+        serialized_bytes: ids.get().len(),
+        ids_per_second: count as f64 / generate_duration.as_secs_f64(),
+        ids,
+    };
+    (StatusCode::OK, Json(response)).into_response()
+}
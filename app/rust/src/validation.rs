@@ -0,0 +1,237 @@
+// ======================
+// VALIDAÇÃO DE RESULTADOS ENTRE AS LINGUAGENS
+// ======================
+//
+// Cada workload do registro declara quais campos da resposta são o resultado
+// do cálculo (os determinísticos: nada de `duration_ms`, taxas por segundo ou
+// relógio). A resposta ganha um campo `validation` com o checksum canônico
+// desses campos, e o mesmo checksum em outra linguagem quer dizer o mesmo
+// resultado.
+//
+// O documento canônico é um objeto JSON com um par `caminho: valor` por campo
+// declarado (caminhos com `.` descem em objetos), chaves ordenadas, sem espaços
+// e sem escapar o que não é ASCII; números não inteiros são arredondados para
+// 6 casas decimais. O checksum são os 16 primeiros dígitos hex do SHA-256
+// desse texto. Respostas que não são JSON (o gzip do /compress) usam
+// `X-Validation`, calculado sobre o conteúdo descomprimido.
+//
+// POST /validate compara dois documentos de resultado campo a campo.
+
+use std::{
+    future::Future,
+    io::Read,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use axum::{
+    body::{boxed, BoxBody, Full},
+    extract::Json,
+    http::{header, HeaderValue, Request, Response, StatusCode},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use tower::{Layer, Service};
+
+use crate::registry;
+
+pub enum Validation {
+    /// Campos (caminhos separados por `.`) que formam o resultado.
+    Fields(&'static [&'static str]),
+    /// Corpo gzip: o resultado é o conteúdo descomprimido.
+    Gunzip,
+    /// Sem resultado comparável (ex.: PNG, que muda com o encoder).
+    None,
+}
+
+fn digest(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)[..8].iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn lookup<'a>(document: &'a serde_json::Value, path: &str) -> Option<&'a serde_json::Value> {
+    path.split('.').try_fold(document, |value, key| value.get(key))
+}
+
+pub fn round(value: &serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Number(n) if n.is_f64() => {
+            let rounded = (n.as_f64().unwrap() * 1e6).round() / 1e6;
+            if rounded.fract() == 0.0 && rounded.abs() < 9e15 {
+                serde_json::json!(rounded as i64)
+            } else {
+                serde_json::json!(rounded)
+            }
+        }
+        serde_json::Value::Array(items) => items.iter().map(round).collect(),
+        serde_json::Value::Object(map) => map.iter().map(|(k, v)| (k.clone(), round(v))).collect(),
+        other => other.clone(),
+    }
+}
+
+/// Valores canônicos de cada campo; campo ausente vira `null`.
+fn canonical(document: &serde_json::Value, fields: &[&str]) -> serde_json::Map<String, serde_json::Value> {
+    fields
+        .iter()
+        .map(|field| (field.to_string(), lookup(document, field).map(round).unwrap_or_default()))
+        .collect()
+}
+
+pub fn checksum(document: &serde_json::Value, fields: &[&str]) -> String {
+    digest(serde_json::Value::Object(canonical(document, fields)).to_string().as_bytes())
+}
+
+/// Acrescenta a validação a uma resposta de sucesso do workload.
+pub async fn attach(validation: &Validation, response: Response<BoxBody>) -> Response<BoxBody> {
+    if !response.status().is_success() || matches!(validation, Validation::None) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = hyper::body::to_bytes(body).await else {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": "Failed to read workload response" }))
+        )
+        .into_response();
+    };
+
+    let body = match validation {
+        Validation::Fields(fields) => match serde_json::from_slice::<serde_json::Value>(&bytes) {
+            Ok(mut document) if document.is_object() => {
+                document["validation"] = serde_json::json!({
+                    "checksum": checksum(&document, fields),
+                    "fields": fields,
+                });
+                parts.headers.remove(header::CONTENT_LENGTH);
+                document.to_string().into_bytes()
+            }
+            _ => bytes.to_vec(),
+        },
+        Validation::Gunzip => {
+            let mut content = Vec::new();
+            if flate2::read::GzDecoder::new(&bytes[..]).read_to_end(&mut content).is_ok() {
+                parts.headers.insert("X-Validation", HeaderValue::from_str(&digest(&content)).unwrap());
+            }
+            bytes.to_vec()
+        }
+        Validation::None => unreachable!(),
+    };
+    Response::from_parts(parts, boxed(Full::from(body)))
+}
+
+// ------------
+// MIDDLEWARE: as rotas HTTP chamam os handlers direto, sem passar pelo
+// registro, então a validação entra aqui para elas
+// ------------
+#[derive(Clone)]
+pub struct ValidationLayer;
+
+#[derive(Clone)]
+pub struct ValidationService<S> {
+    inner: S,
+}
+
+impl<S> Layer<S> for ValidationLayer {
+    type Service = ValidationService<S>;
+    fn layer(&self, inner: S) -> Self::Service {
+        ValidationService { inner }
+    }
+}
+
+impl<S, ReqBody> Service<Request<ReqBody>> for ValidationService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+{
+    type Response = Response<BoxBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        let mut service = self.inner.clone();
+
+        let Some(workload) = registry::find(req.uri().path().trim_start_matches('/')) else {
+            return Box::pin(service.call(req));
+        };
+
+        Box::pin(async move {
+            let response = service.call(req).await?;
+            Ok(attach(&workload.validation, response).await)
+        })
+    }
+}
+
+// ------------
+// POST /validate
+// ------------
+#[derive(Deserialize)]
+pub struct ValidatePayload {
+    /// Workload dos documentos; sem ele vale o `validation.fields` de `a`.
+    workload: Option<String>,
+    a: serde_json::Value,
+    b: serde_json::Value,
+}
+
+pub async fn validate(Json(payload): Json<ValidatePayload>) -> Response<BoxBody> {
+    let declared: Vec<String>;
+    let fields: Vec<&str> = match &payload.workload {
+        Some(name) => match registry::find(name).map(|workload| &workload.validation) {
+            Some(Validation::Fields(fields)) => fields.to_vec(),
+            Some(_) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({ "error": format!("Workload {} has no JSON result to compare", name) }))
+                )
+                .into_response();
+            }
+            None => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({ "error": format!("Unknown workload: {}", name) }))
+                )
+                .into_response();
+            }
+        },
+        None => {
+            declared = lookup(&payload.a, "validation.fields")
+                .and_then(|fields| serde_json::from_value(fields.clone()).ok())
+                .unwrap_or_default();
+            if declared.is_empty() {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({ "error": "Pass workload, or documents that carry validation.fields" }))
+                )
+                .into_response();
+            }
+            declared.iter().map(String::as_str).collect()
+        }
+    };
+
+    let a = canonical(&payload.a, &fields);
+    let b = canonical(&payload.b, &fields);
+    let differences: Vec<serde_json::Value> = fields
+        .iter()
+        .filter(|field| a[**field] != b[**field])
+        .map(|field| serde_json::json!({ "field": field, "a": a[*field], "b": b[*field] }))
+        .collect();
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "equal": differences.is_empty(),
+            "fields": fields,
+            "checksums": {
+                "a": checksum(&payload.a, &fields),
+                "b": checksum(&payload.b, &fields),
+            },
+            "differences": differences,
+        }))
+    )
+    .into_response()
+}
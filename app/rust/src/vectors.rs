@@ -0,0 +1,271 @@
+// ======================
+// BUSCA POR SIMILARIDADE DE VETORES
+// ======================
+//
+// Gera `count` vetores de `dimensions` floats a partir da seed e compara cada
+// um com o vetor de consulta. O caminho "scalar" é um loop simples; o "simd"
+// usa AVX2/FMA (x86_64) ou NEON (aarch64) quando disponíveis, com fallback
+// para um loop de 8 lanes que o compilador consegue vetorizar sozinho.
+
+use std::{cmp::Ordering, collections::BinaryHeap, time::Instant};
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+
+use crate::{
+    estimate::{self, Estimate},
+    progress::Progress,
+    rng::{self, SplitMix64},
+};
+
+// Limite de floats gerados por request (64 MiB de dados)
+const MAX_ELEMENTS: usize = 16 * 1024 * 1024;
+
+#[derive(Deserialize)]
+pub struct VectorsPayload {
+    dimensions: Option<usize>,
+    count: Option<usize>,
+    seed: Option<u64>,
+    query: Option<Vec<f32>>,
+    metric: Option<String>,
+    mode: Option<String>,
+    top_k: Option<usize>,
+    dry_run: Option<bool>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Metric {
+    Cosine,
+    Dot,
+}
+
+// ------------
+// kernels de produto escalar
+// ------------
+type DotKernel = fn(&[f32], &[f32]) -> f32;
+
+fn dot_scalar(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn dot_lanes(a: &[f32], b: &[f32]) -> f32 {
+    let mut acc = [0.0f32; 8];
+    let chunks_a = a.chunks_exact(8);
+    let chunks_b = b.chunks_exact(8);
+    let tail: f32 = chunks_a
+        .remainder()
+        .iter()
+        .zip(chunks_b.remainder())
+        .map(|(x, y)| x * y)
+        .sum();
+    for (ca, cb) in chunks_a.zip(chunks_b) {
+        for (lane, (x, y)) in acc.iter_mut().zip(ca.iter().zip(cb)) {
+            *lane += x * y;
+        }
+    }
+    acc.iter().sum::<f32>() + tail
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2,fma")]
+unsafe fn dot_avx2(a: &[f32], b: &[f32]) -> f32 {
+    use std::arch::x86_64::*;
+
+    let n = a.len() / 8 * 8;
+    let mut acc = _mm256_setzero_ps();
+    let mut i = 0;
+    while i < n {
+        let va = _mm256_loadu_ps(a.as_ptr().add(i));
+        let vb = _mm256_loadu_ps(b.as_ptr().add(i));
+        acc = _mm256_fmadd_ps(va, vb, acc);
+        i += 8;
+    }
+    let mut lanes = [0.0f32; 8];
+    _mm256_storeu_ps(lanes.as_mut_ptr(), acc);
+    lanes.iter().sum::<f32>() + dot_scalar(&a[n..], &b[n..])
+}
+
+#[cfg(target_arch = "aarch64")]
+fn dot_neon(a: &[f32], b: &[f32]) -> f32 {
+    use std::arch::aarch64::*;
+
+    let n = a.len() / 4 * 4;
+    // NEON faz parte da base do aarch64, então não precisamos de detecção
+    unsafe {
+        let mut acc = vdupq_n_f32(0.0);
+        let mut i = 0;
+        while i < n {
+            let va = vld1q_f32(a.as_ptr().add(i));
+            let vb = vld1q_f32(b.as_ptr().add(i));
+            acc = vfmaq_f32(acc, va, vb);
+            i += 4;
+        }
+        vaddvq_f32(acc) + dot_scalar(&a[n..], &b[n..])
+    }
+}
+
+/// Escolhe o melhor kernel SIMD disponível nesta máquina.
+fn simd_kernel() -> (&'static str, DotKernel) {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma") {
+            // Seguro: acabamos de confirmar que a CPU suporta avx2 e fma
+            return ("avx2", |a, b| unsafe { dot_avx2(a, b) });
+        }
+    }
+    #[cfg(target_arch = "aarch64")]
+    {
+        return ("neon", dot_neon);
+    }
+    #[allow(unreachable_code)]
+    ("lanes", dot_lanes)
+}
+
+// ------------
+// top-k
+// ------------
+struct Hit {
+    score: f32,
+    index: usize,
+}
+
+impl PartialEq for Hit {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Hit {}
+
+impl PartialOrd for Hit {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// Invertido para o BinaryHeap virar um min-heap: o topo é o pior resultado do top-k
+impl Ord for Hit {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .score
+            .total_cmp(&self.score)
+            .then_with(|| self.index.cmp(&other.index))
+    }
+}
+
+pub async fn vectors(Json(payload): Json<VectorsPayload>) -> Response<BoxBody> {
+    let dimensions = payload
+        .query
+        .as_ref()
+        .map(|q| q.len())
+        .or(payload.dimensions)
+        .unwrap_or(128);
+    let count = payload.count.unwrap_or(10_000);
+    let top_k = payload.top_k.unwrap_or(10).min(count);
+
+    if dimensions == 0 || count == 0 {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "Dimensions and count must be positive" }))
+        )
+        .into_response();
+    }
+    if dimensions.saturating_mul(count) > MAX_ELEMENTS {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "dimensions * count exceeds the limit" }))
+        )
+        .into_response();
+    }
+
+    let metric = match payload.metric.as_deref().unwrap_or("cosine") {
+        "cosine" => Metric::Cosine,
+        "dot" => Metric::Dot,
+        _ => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Unsupported metric" }))
+            )
+            .into_response()
+        }
+    };
+    let (kernel_name, kernel): (&str, DotKernel) = match payload.mode.as_deref().unwrap_or("simd") {
+        "scalar" => ("scalar", dot_scalar),
+        "simd" => simd_kernel(),
+        _ => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": "Unsupported mode" }))
+            )
+            .into_response()
+        }
+    };
+
+    if payload.dry_run.unwrap_or(false) {
+        return estimate::response(Estimate {
+            iterations: count as u64,
+            unit: "vectors",
+            allocation_bytes: ((count + 1) * dimensions * 4) as u64,
+            output_bytes: 200 + 50 * top_k as u64,
+        });
+    }
+
+    // Geração dos dados (medida separadamente da busca)
+    let generate_start = Instant::now();
+    let mut rng = SplitMix64::new(rng::seed(payload.seed));
+    let query = payload
+        .query
+        .clone()
+        .unwrap_or_else(|| (0..dimensions).map(|_| rng.next_signed_f32()).collect());
+    let stored: Vec<f32> = (0..dimensions * count)
+        .map(|_| rng.next_signed_f32())
+        .collect();
+    let generate_duration = generate_start.elapsed();
+
+    let mut progress = Progress::new(count as u64);
+    let search_start = Instant::now();
+    let query_norm = kernel(&query, &query).sqrt();
+    let mut heap = BinaryHeap::with_capacity(top_k + 1);
+    for (index, vector) in stored.chunks_exact(dimensions).enumerate() {
+        let dot = kernel(&query, vector);
+        let score = match metric {
+            Metric::Dot => dot,
+            Metric::Cosine => {
+                let denom = query_norm * kernel(vector, vector).sqrt();
+                if denom == 0.0 { 0.0 } else { dot / denom }
+            }
+        };
+        heap.push(Hit { score, index });
+        if heap.len() > top_k {
+            heap.pop();
+        }
+        progress.tick(index as u64 + 1);
+    }
+    // into_sorted_vec usa a ordem invertida, então já sai do melhor para o pior
+    let results: Vec<_> = heap
+        .into_sorted_vec()
+        .into_iter()
+        .map(|hit| serde_json::json!({ "index": hit.index, "score": hit.score }))
+        .collect();
+    let search_duration = search_start.elapsed();
+
+    let flops = (if metric == Metric::Cosine { 4 } else { 2 }) * dimensions * count;
+    let mut body = serde_json::json!({
+        "results": results,
+        "kernel": kernel_name,
+        "dimensions": dimensions,
+        "count": count,
+        "generate_ms": generate_duration.as_secs_f64() * 1000.0,
+        "duration_ms": search_duration.as_secs_f64() * 1000.0,
+        "gflops": flops as f64 / search_duration.as_secs_f64() / 1e9,
+    });
+    if let Some(report) = progress.report() {
+        body["progress"] = report;
+    }
+    (StatusCode::OK, Json(body)).into_response()
+}
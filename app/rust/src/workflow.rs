@@ -0,0 +1,271 @@
+// ======================
+// MÁQUINA DE ESTADOS / WORKFLOW
+// ======================
+//
+// A máquina vem no payload (ou usa o ciclo de vida de pedido padrão) e é
+// compilada para índices antes de processar os eventos. Transições podem ter
+// uma `guard` escrita na mesma linguagem do /rules, avaliada contra `context`.
+
+use std::{collections::HashMap, time::Instant};
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{
+    rng::{self, SplitMix64},
+    rules,
+};
+
+const MAX_EVENTS: usize = 1_000_000;
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum TransitionDefinition {
+    Target(String),
+    Guarded { target: String, guard: Option<String> },
+}
+
+#[derive(Deserialize)]
+pub struct StateDefinition {
+    #[serde(default)]
+    on: HashMap<String, TransitionDefinition>,
+    #[serde(default, rename = "final")]
+    is_final: bool,
+}
+
+#[derive(Deserialize)]
+pub struct MachineDefinition {
+    initial: String,
+    states: HashMap<String, StateDefinition>,
+}
+
+#[derive(Deserialize)]
+pub struct WorkflowPayload {
+    machine: Option<MachineDefinition>,
+    events: Option<Vec<String>>,
+    count: Option<usize>,
+    seed: Option<u64>,
+    context: Option<Value>,
+    strict: Option<bool>,
+    restart_on_final: Option<bool>,
+}
+
+struct Transition {
+    target: usize,
+    guard: Option<rules::Expr>,
+}
+
+struct State {
+    name: String,
+    is_final: bool,
+    // Indexado pelo id do evento
+    transitions: Vec<Option<Transition>>,
+}
+
+struct Machine {
+    initial: usize,
+    states: Vec<State>,
+    events: HashMap<String, usize>,
+}
+
+enum Outcome {
+    Transitioned(usize),
+    GuardRejected,
+    NoTransition,
+    UnknownEvent,
+    AlreadyFinal,
+}
+
+impl Outcome {
+    fn label(&self) -> &'static str {
+        match self {
+            Outcome::Transitioned(_) => "transitioned",
+            Outcome::GuardRejected => "guard_rejected",
+            Outcome::NoTransition => "no_transition",
+            Outcome::UnknownEvent => "unknown_event",
+            Outcome::AlreadyFinal => "already_final",
+        }
+    }
+}
+
+fn default_machine() -> MachineDefinition {
+    serde_json::from_value(serde_json::json!({
+        "initial": "created",
+        "states": {
+            "created": { "on": { "pay": { "target": "paid", "guard": "amount > 0" }, "cancel": "cancelled" } },
+            "paid": { "on": { "ship": "shipped", "refund": "refunded" } },
+            "shipped": { "on": { "deliver": "delivered", "return": "returned" } },
+            "returned": { "on": { "refund": "refunded" } },
+            "delivered": { "final": true },
+            "cancelled": { "final": true },
+            "refunded": { "final": true },
+        }
+    }))
+    .unwrap()
+}
+
+fn compile(definition: MachineDefinition) -> Result<Machine, String> {
+    // Ordena os nomes para os índices não dependerem da ordem do HashMap
+    let mut names: Vec<&String> = definition.states.keys().collect();
+    names.sort();
+    let index: HashMap<&str, usize> = names.iter().enumerate().map(|(i, n)| (n.as_str(), i)).collect();
+
+    let mut event_names: Vec<&String> = definition.states.values().flat_map(|s| s.on.keys()).collect();
+    event_names.sort();
+    event_names.dedup();
+    let events: HashMap<String, usize> = event_names
+        .iter()
+        .enumerate()
+        .map(|(i, e)| ((*e).clone(), i))
+        .collect();
+
+    let initial = *index
+        .get(definition.initial.as_str())
+        .ok_or_else(|| format!("Unknown initial state: {}", definition.initial))?;
+
+    let mut states = Vec::with_capacity(names.len());
+    for name in &names {
+        let definition = &definition.states[*name];
+        let mut transitions: Vec<Option<Transition>> = (0..events.len()).map(|_| None).collect();
+        for (event, transition) in &definition.on {
+            let (target, guard) = match transition {
+                TransitionDefinition::Target(target) => (target, None),
+                TransitionDefinition::Guarded { target, guard } => (target, guard.as_deref()),
+            };
+            let target = *index
+                .get(target.as_str())
+                .ok_or_else(|| format!("Unknown target state: {}", target))?;
+            let guard = guard
+                .map(rules::parse)
+                .transpose()
+                .map_err(|e| format!("Invalid guard on {}.{}: {}", name, event, e))?;
+            transitions[events[event]] = Some(Transition { target, guard });
+        }
+        states.push(State { name: (*name).clone(), is_final: definition.is_final, transitions });
+    }
+
+    Ok(Machine { initial, states, events })
+}
+
+fn step(machine: &Machine, current: usize, event: &str, context: &Value) -> Result<Outcome, String> {
+    let state = &machine.states[current];
+    if state.is_final {
+        return Ok(Outcome::AlreadyFinal);
+    }
+    let Some(&event) = machine.events.get(event) else {
+        return Ok(Outcome::UnknownEvent);
+    };
+    let Some(transition) = &state.transitions[event] else {
+        return Ok(Outcome::NoTransition);
+    };
+    if let Some(guard) = &transition.guard {
+        if !rules::matches(guard, context)? {
+            return Ok(Outcome::GuardRejected);
+        }
+    }
+    Ok(Outcome::Transitioned(transition.target))
+}
+
+pub async fn workflow(Json(payload): Json<WorkflowPayload>) -> Response<BoxBody> {
+    let machine = match compile(payload.machine.unwrap_or_else(default_machine)) {
+        Ok(machine) => machine,
+        Err(error) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": error }))
+            )
+            .into_response()
+        }
+    };
+
+    let count = payload
+        .events
+        .as_ref()
+        .map(|e| e.len())
+        .unwrap_or(payload.count.unwrap_or(10_000));
+    if count > MAX_EVENTS {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "Too many events" }))
+        )
+        .into_response();
+    }
+    let events = payload.events.unwrap_or_else(|| {
+        let mut names: Vec<&String> = machine.events.keys().collect();
+        names.sort();
+        if names.is_empty() {
+            return Vec::new();
+        }
+        let mut rng = SplitMix64::new(rng::seed(payload.seed));
+        (0..count)
+            .map(|_| names[(rng.next_f64() * names.len() as f64) as usize].clone())
+            .collect()
+    });
+
+    let context = payload.context.unwrap_or_else(|| serde_json::json!({ "amount": 120 }));
+    let strict = payload.strict.unwrap_or(false);
+    let restart_on_final = payload.restart_on_final.unwrap_or(true);
+
+    let start = Instant::now();
+    let mut current = machine.initial;
+    let mut outcomes: HashMap<&'static str, usize> = HashMap::new();
+    let mut completed_runs = 0usize;
+    for (position, event) in events.iter().enumerate() {
+        let outcome = match step(&machine, current, event, &context) {
+            Ok(outcome) => outcome,
+            Err(error) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({ "error": error }))
+                )
+                .into_response()
+            }
+        };
+        *outcomes.entry(outcome.label()).or_default() += 1;
+        match outcome {
+            Outcome::Transitioned(target) => {
+                current = target;
+                if machine.states[current].is_final {
+                    completed_runs += 1;
+                    if restart_on_final {
+                        current = machine.initial;
+                    }
+                }
+            }
+            rejected if strict => {
+                return (
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    Json(serde_json::json!({
+                        "error": "Invalid transition",
+                        "reason": rejected.label(),
+                        "state": machine.states[current].name,
+                        "event": event,
+                        "position": position,
+                    }))
+                )
+                .into_response()
+            }
+            _ => {}
+        }
+    }
+    let duration = start.elapsed();
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "final_state": machine.states[current].name,
+            "events": events.len(),
+            "completed_runs": completed_runs,
+            "outcomes": outcomes,
+            "duration_ms": duration.as_secs_f64() * 1000.0,
+            "events_per_second": events.len() as f64 / duration.as_secs_f64(),
+        }))
+    )
+    .into_response()
+}
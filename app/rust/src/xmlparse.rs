@@ -0,0 +1,224 @@
+// ======================
+// PARSING DE XML
+// ======================
+//
+// Muito backend ainda fala XML/SOAP, e traduzir para JSON é trabalho típico
+// de um BFF. `text` é um documento XML, lido evento a evento pelo quick-xml.
+//
+// `mode: "convert"` (padrão) devolve o documento como JSON: cada elemento
+// vira um objeto com os atributos em `@nome` e o texto em `#text`; elemento
+// só com texto vira a string; filhos com o mesmo nome viram um array. Os
+// nomes ficam como estão no documento, com o prefixo de namespace
+// (`soap:Body`). `mode: "extract"` devolve só o texto dos elementos `tag`, na
+// ordem do documento, sem montar a árvore. O texto de cada elemento é o que
+// está diretamente nele, com as entidades resolvidas e sem os espaços das
+// pontas.
+
+use std::time::Instant;
+
+use axum::{
+    body::BoxBody,
+    extract::Json,
+    http::{Response, StatusCode},
+    response::IntoResponse,
+};
+use quick_xml::{escape::resolve_predefined_entity, events::Event, Reader, XmlVersion};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+// A árvore é montada sem recursão, mas serializá-la não é
+const MAX_DEPTH: usize = 128;
+
+const SAMPLE: &str = r#"<?xml version="1.0"?>
+<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/">
+  <soap:Body>
+    <GetOrdersResponse>
+      <Order id="1001" status="shipped"><Item sku="KB-01">Keyboard</Item><Total currency="EUR">49.90</Total></Order>
+      <Order id="1002" status="pending"><Item sku="MS-02">Mouse</Item><Item sku="PD-07">Mouse pad</Item><Total currency="EUR">27.80</Total></Order>
+    </GetOrdersResponse>
+  </soap:Body>
+</soap:Envelope>"#;
+
+#[derive(Deserialize)]
+pub struct XmlPayload {
+    text: Option<String>,
+    mode: Option<String>,
+    tag: Option<String>,
+}
+
+fn bad_request(error: String) -> Response<BoxBody> {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({ "error": error }))
+    )
+    .into_response()
+}
+
+struct Node {
+    name: String,
+    fields: Map<String, Value>,
+    text: String,
+}
+
+impl Node {
+    fn into_value(mut self) -> Value {
+        let text = self.text.trim();
+        if self.fields.is_empty() {
+            return Value::String(text.to_string());
+        }
+        if !text.is_empty() {
+            self.fields.insert("#text".to_string(), Value::String(text.to_string()));
+        }
+        Value::Object(self.fields)
+    }
+}
+
+/// Filho repetido vira array.
+fn insert(fields: &mut Map<String, Value>, name: String, value: Value) {
+    match fields.get_mut(&name) {
+        Some(Value::Array(items)) => items.push(value),
+        Some(existing) => *existing = Value::Array(vec![existing.take(), value]),
+        None => {
+            fields.insert(name, value);
+        }
+    }
+}
+
+struct Parsed {
+    root: Option<(String, Value)>,
+    extracted: Vec<String>,
+    elements: u64,
+    max_depth: usize,
+}
+
+fn parse(text: &str, extract: Option<&str>) -> Result<Parsed, String> {
+    let mut reader = Reader::from_str(text);
+    let mut stack: Vec<Node> = Vec::new();
+    let mut parsed = Parsed { root: None, extracted: Vec::new(), elements: 0, max_depth: 0 };
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("Invalid XML at byte {}: {}", reader.error_position(), e))?;
+        // Elemento aberto (Start) ou que já se fecha (Empty)
+        let (start, closes) = match &event {
+            Event::Start(start) => (Some(start), false),
+            Event::Empty(start) => (Some(start), true),
+            _ => (None, false),
+        };
+        if let Some(start) = start {
+            if stack.is_empty() && parsed.root.is_some() {
+                return Err("Invalid XML: more than one root element".to_string());
+            }
+            let name = String::from_utf8_lossy(start.name().as_ref()).into_owned();
+            let mut fields = Map::new();
+            if extract.is_none() {
+                for attribute in start.attributes() {
+                    let attribute = attribute.map_err(|e| format!("Invalid XML attribute: {}", e))?;
+                    let value = attribute
+                        .normalized_value(XmlVersion::Implicit1_0)
+                        .map_err(|e| format!("Invalid XML attribute: {}", e))?;
+                    let key = format!("@{}", String::from_utf8_lossy(attribute.key.as_ref()));
+                    fields.insert(key, Value::String(value.into_owned()));
+                }
+            }
+            stack.push(Node { name, fields, text: String::new() });
+            parsed.elements += 1;
+            parsed.max_depth = parsed.max_depth.max(stack.len());
+            if stack.len() > MAX_DEPTH {
+                return Err(format!("XML nesting is deeper than {} elements", MAX_DEPTH));
+            }
+        }
+
+        let text = match &event {
+            Event::Text(text) => Some(text.decode().map_err(|e| format!("Invalid XML text: {}", e))?.into_owned()),
+            Event::CData(data) => Some(data.decode().map_err(|e| format!("Invalid XML text: {}", e))?.into_owned()),
+            Event::GeneralRef(reference) => {
+                let resolved = match reference.resolve_char_ref().map_err(|e| format!("Invalid XML: {}", e))? {
+                    Some(ch) => ch.to_string(),
+                    None => {
+                        let name = reference.decode().map_err(|e| format!("Invalid XML: {}", e))?;
+                        match resolve_predefined_entity(&name) {
+                            Some(value) => value.to_string(),
+                            None => return Err(format!("Invalid XML: unknown entity &{};", name)),
+                        }
+                    }
+                };
+                Some(resolved)
+            }
+            _ => None,
+        };
+        if let (Some(text), Some(node)) = (text, stack.last_mut()) {
+            node.text.push_str(&text);
+        }
+
+        if closes || matches!(event, Event::End(_)) {
+            let node = stack.pop().expect("o reader confere os nomes de fechamento");
+            if extract == Some(node.name.as_str()) {
+                parsed.extracted.push(node.text.trim().to_string());
+            }
+            if extract.is_none() {
+                let name = node.name.clone();
+                let value = node.into_value();
+                match stack.last_mut() {
+                    Some(parent) => insert(&mut parent.fields, name, value),
+                    None => parsed.root = Some((name, value)),
+                }
+            } else if stack.is_empty() {
+                parsed.root = Some((node.name, Value::Null));
+            }
+        }
+        if matches!(event, Event::Eof) {
+            break;
+        }
+    }
+    if let Some(node) = stack.last() {
+        return Err(format!("Invalid XML: <{}> is never closed", node.name));
+    }
+    if parsed.root.is_none() {
+        return Err("Invalid XML: no root element".to_string());
+    }
+    Ok(parsed)
+}
+
+pub async fn xmlparse(Json(payload): Json<XmlPayload>) -> Response<BoxBody> {
+    let text = payload.text.as_deref().unwrap_or(SAMPLE);
+    let extract = match payload.mode.as_deref().unwrap_or("convert") {
+        "convert" => None,
+        "extract" => match payload.tag.as_deref() {
+            Some(tag) => Some(tag),
+            None => return bad_request("extract mode needs a tag".to_string()),
+        },
+        other => return bad_request(format!("mode must be convert or extract, got {}", other)),
+    };
+
+    let start = Instant::now();
+    let parsed = match parse(text, extract) {
+        Ok(parsed) => parsed,
+        Err(error) => return bad_request(error),
+    };
+    let elapsed = start.elapsed();
+
+    let mut body = serde_json::json!({
+        "mode": if extract.is_some() { "extract" } else { "convert" },
+        "bytes": text.len(),
+        "elements": parsed.elements,
+        "depth": parsed.max_depth,
+        "parse_ms": elapsed.as_secs_f64() * 1000.0,
+        "bytes_per_second": text.len() as f64 / elapsed.as_secs_f64(),
+    });
+    match (extract, parsed.root) {
+        (Some(tag), _) => {
+            body["tag"] = serde_json::json!(tag);
+            body["count"] = serde_json::json!(parsed.extracted.len());
+            body["values"] = serde_json::json!(parsed.extracted);
+        }
+        (None, Some((name, value))) => {
+            let mut document = Map::new();
+            document.insert(name, value);
+            body["result"] = Value::Object(document);
+        }
+        (None, None) => {}
+    }
+    (StatusCode::OK, Json(body)).into_response()
+}
@@ -0,0 +1,69 @@
+// Caminhos de erro do ContentTypeLayer no modo padrão (`BFF_CONTENT_TYPE=strict`,
+// 422 para payload que não bate), pelo router completo.
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+};
+use serde_json::Value;
+use tower::ServiceExt;
+
+const MATH: &str = r#"{"numbers": [1, 2, 3], "operation": "sum"}"#;
+
+async fn post(content_type: Option<&str>, body: &str) -> (StatusCode, Option<String>, Value) {
+    let mut request = Request::post("/math");
+    if let Some(content_type) = content_type {
+        request = request.header(header::CONTENT_TYPE, content_type);
+    }
+    let request = request.body(Body::from(body.to_string())).unwrap();
+    let response = demo_lambda_axum::create_router().oneshot(request).await.unwrap();
+    let status = response.status();
+    let content_type = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .map(|value| value.to_str().unwrap().to_string());
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    (status, content_type, serde_json::from_slice(&bytes).unwrap())
+}
+
+fn assert_error_body(content_type: Option<String>, body: &Value) {
+    assert_eq!(content_type.as_deref(), Some("application/json"));
+    let object = body.as_object().expect("error body is a JSON object");
+    assert_eq!(object.len(), 1, "only the error field: {}", body);
+    assert!(object["error"].as_str().is_some_and(|error| !error.is_empty()));
+}
+
+#[tokio::test]
+async fn json_body_is_accepted() {
+    let (status, _, body) = post(Some("application/json"), MATH).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["result"], 6.0);
+}
+
+#[tokio::test]
+async fn other_content_type_is_415_with_json_error() {
+    let (status, content_type, body) = post(Some("text/plain"), MATH).await;
+    assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    assert_error_body(content_type, &body);
+}
+
+#[tokio::test]
+async fn missing_content_type_is_415() {
+    let (status, content_type, body) = post(None, MATH).await;
+    assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    assert_error_body(content_type, &body);
+}
+
+#[tokio::test]
+async fn invalid_json_is_400_with_json_error() {
+    let (status, content_type, body) = post(Some("application/json"), "{\"numbers\": [1,").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_error_body(content_type, &body);
+}
+
+#[tokio::test]
+async fn mismatched_payload_is_422_with_json_error() {
+    let (status, content_type, body) = post(Some("application/json"), r#"{"numbers": "one"}"#).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_error_body(content_type, &body);
+}
@@ -0,0 +1,53 @@
+// Caminhos de erro do ContentTypeLayer com `BFF_CONTENT_TYPE=lenient` e
+// `BFF_PAYLOAD_ERROR_STATUS=400`. Fica num arquivo próprio porque a config é
+// lida uma vez por processo, e cada arquivo em tests/ roda no seu.
+
+use std::sync::Once;
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    Router,
+};
+use serde_json::Value;
+use tower::ServiceExt;
+
+const MATH: &str = r#"{"numbers": [1, 2, 3], "operation": "sum"}"#;
+
+fn router() -> Router {
+    static ENV: Once = Once::new();
+    ENV.call_once(|| {
+        std::env::set_var("BFF_CONTENT_TYPE", "lenient");
+        std::env::set_var("BFF_PAYLOAD_ERROR_STATUS", "400");
+    });
+    demo_lambda_axum::create_router()
+}
+
+async fn post(content_type: Option<&str>, body: &str) -> (StatusCode, Value) {
+    let mut request = Request::post("/math");
+    if let Some(content_type) = content_type {
+        request = request.header(header::CONTENT_TYPE, content_type);
+    }
+    let request = request.body(Body::from(body.to_string())).unwrap();
+    let response = router().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+#[tokio::test]
+async fn any_content_type_is_parsed_as_json() {
+    for content_type in [Some("text/plain"), None] {
+        let (status, body) = post(content_type, MATH).await;
+        assert_eq!(status, StatusCode::OK, "{:?}: {}", content_type, body);
+        assert_eq!(body["result"], 6.0);
+    }
+}
+
+#[tokio::test]
+async fn mismatched_payload_uses_configured_status() {
+    let (status, body) = post(Some("text/plain"), r#"{"numbers": "one"}"#).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["error"].as_str().is_some_and(|error| !error.is_empty()));
+    assert_eq!(body.as_object().unwrap().len(), 1);
+}